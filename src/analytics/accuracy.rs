@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use super::roi::UNKNOWN;
+use crate::models::{LiveMatchState, PositionSide, Signal, SignalStrength};
+use crate::trading::stop_loss::side_price;
+
+/// Width of a `team_a_win_prob` calibration bucket
+pub const CALIBRATION_BUCKET: f64 = 0.1;
+
+/// A signal whose match has a result
+#[derive(Debug, Clone)]
+pub struct SettledSignal {
+    pub signal: Signal,
+
+    /// Whether the market's team A won the match
+    pub team_a_won: bool,
+}
+
+impl SettledSignal {
+    /// Settle a signal against its match's `radiant_win`; None for signals
+    /// stored before their side was recorded
+    pub fn new(signal: Signal, radiant_win: bool) -> Option<Self> {
+        let team_a_is_radiant = signal.team_a_is_radiant?;
+        Some(Self {
+            team_a_won: radiant_win == team_a_is_radiant,
+            signal,
+        })
+    }
+
+    /// League name from the match snapshot
+    pub fn league(&self) -> String {
+        serde_json::from_str::<LiveMatchState>(&self.signal.match_snapshot)
+            .ok()
+            .and_then(|state| state.league_name)
+            .unwrap_or_else(|| UNKNOWN.to_string())
+    }
+
+    /// Side the net edge favoured; None if it favoured neither
+    pub fn side(&self) -> Option<PositionSide> {
+        let net_edge = self.signal.net_edge.filter(|e| *e != 0.0)?;
+        Some(PositionSide::from_edge(net_edge))
+    }
+
+    /// Whether the favoured side won
+    pub fn hit(&self) -> Option<bool> {
+        Some(match self.side()? {
+            PositionSide::TeamA => self.team_a_won,
+            PositionSide::TeamB => !self.team_a_won,
+        })
+    }
+
+    /// Model probability minus price on the favoured side, before fees
+    pub fn predicted_edge(&self) -> Option<f64> {
+        let edge = self.signal.edge?;
+        Some(match self.side()? {
+            PositionSide::TeamA => edge,
+            PositionSide::TeamB => -edge,
+        })
+    }
+
+    /// Payout minus price on the favoured side, before fees
+    pub fn realized_edge(&self) -> Option<f64> {
+        let side = self.side()?;
+        let payout = if self.hit()? { 1.0 } else { 0.0 };
+        Some(payout - side_price(side, self.signal.market_team_a_odds))
+    }
+}
+
+/// Precision and edge of one group of signals at one strength
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccuracyStats {
+    pub key: String,
+    pub strength: SignalStrength,
+
+    /// Signals that favoured a side
+    pub signals: usize,
+
+    /// Fraction of those whose favoured side won
+    pub precision: f64,
+
+    /// Mean edge the model saw on the favoured side
+    pub avg_predicted_edge: Option<f64>,
+
+    /// Mean payout minus price on the favoured side
+    pub avg_realized_edge: f64,
+}
+
+/// Precision and edge by a grouping key and strength, sorted by key then
+/// strength; signals that favoured neither side are left out
+pub fn summarize_accuracy(
+    settled: &[SettledSignal],
+    key: impl Fn(&SettledSignal) -> String,
+) -> Vec<AccuracyStats> {
+    let mut groups: BTreeMap<(String, SignalStrength), Vec<&SettledSignal>> = BTreeMap::new();
+    for s in settled.iter().filter(|s| s.side().is_some()) {
+        groups
+            .entry((key(s), s.signal.strength))
+            .or_default()
+            .push(s);
+    }
+
+    groups
+        .into_iter()
+        .map(|((key, strength), signals)| {
+            let n = signals.len() as f64;
+            let hits = signals.iter().filter(|s| s.hit() == Some(true)).count();
+            let predicted: Vec<f64> = signals.iter().filter_map(|s| s.predicted_edge()).collect();
+
+            AccuracyStats {
+                key,
+                strength,
+                signals: signals.len(),
+                precision: hits as f64 / n,
+                avg_predicted_edge: (!predicted.is_empty())
+                    .then(|| predicted.iter().sum::<f64>() / predicted.len() as f64),
+                avg_realized_edge: signals
+                    .iter()
+                    .filter_map(|s| s.realized_edge())
+                    .sum::<f64>()
+                    / n,
+            }
+        })
+        .collect()
+}
+
+/// Observed team A win rate for one bucket of predicted probabilities
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationBucket {
+    pub key: String,
+
+    /// Bucket covers `[lower, lower + CALIBRATION_BUCKET)`
+    pub lower: f64,
+
+    pub signals: usize,
+
+    /// Mean `team_a_win_prob` in the bucket
+    pub avg_predicted: f64,
+
+    /// Fraction of the bucket's signals team A won
+    pub observed: f64,
+}
+
+/// Calibration of `team_a_win_prob` by a grouping key, sorted by key then
+/// bucket; signals without a prediction are left out
+pub fn calibration(
+    settled: &[SettledSignal],
+    key: impl Fn(&SettledSignal) -> String,
+) -> Vec<CalibrationBucket> {
+    let buckets = (1.0 / CALIBRATION_BUCKET).round() as i64;
+
+    let mut groups: BTreeMap<(String, i64), Vec<(f64, bool)>> = BTreeMap::new();
+    for s in settled {
+        let Some(prob) = s.signal.team_a_win_prob else {
+            continue;
+        };
+        // Nudge so 0.7 / 0.1 lands in bucket 7, not 6.999…
+        let bucket = ((prob / CALIBRATION_BUCKET + 1e-9).floor() as i64).clamp(0, buckets - 1);
+        groups
+            .entry((key(s), bucket))
+            .or_default()
+            .push((prob, s.team_a_won));
+    }
+
+    groups
+        .into_iter()
+        .map(|((key, bucket), rows)| {
+            let n = rows.len() as f64;
+            CalibrationBucket {
+                key,
+                lower: bucket as f64 * CALIBRATION_BUCKET,
+                signals: rows.len(),
+                avg_predicted: rows.iter().map(|(p, _)| p).sum::<f64>() / n,
+                observed: rows.iter().filter(|(_, won)| *won).count() as f64 / n,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SignalType;
+
+    fn signal(prob: f64, odds: f64, strength: SignalStrength) -> Signal {
+        let edge = prob - odds;
+        Signal {
+            market_condition_id: "m".to_string(),
+            match_id: 1,
+            market_team_a_odds: odds,
+            team_a_win_prob: Some(prob),
+            edge: Some(edge),
+            net_edge: Some(edge),
+            strength,
+            signal_type: SignalType::TowerKill,
+            team_a_is_radiant: Some(false),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_precision_and_realized_edge_by_strength() {
+        let settled = vec![
+            // Backs team A (Dire), Dire wins
+            SettledSignal::new(signal(0.7, 0.5, SignalStrength::Strong), false).unwrap(),
+            // Backs team B, Dire wins
+            SettledSignal::new(signal(0.2, 0.4, SignalStrength::Strong), false).unwrap(),
+            // Backs team A, Radiant wins
+            SettledSignal::new(signal(0.62, 0.6, SignalStrength::Weak), true).unwrap(),
+        ];
+        assert!(settled[0].team_a_won);
+        assert_eq!(settled[1].hit(), Some(false));
+
+        let stats = summarize_accuracy(&settled, |_| "all".to_string());
+        assert_eq!(stats.len(), 2);
+
+        let strong = stats
+            .iter()
+            .find(|s| s.strength == SignalStrength::Strong)
+            .unwrap();
+        assert_eq!(strong.signals, 2);
+        assert_eq!(strong.precision, 0.5);
+        assert!((strong.avg_predicted_edge.unwrap() - 0.2).abs() < 1e-9);
+        // +0.5 on team A at 0.5, -0.6 on team B at 0.6
+        assert!((strong.avg_realized_edge - -0.05).abs() < 1e-9);
+
+        let buckets = calibration(&settled, |_| "all".to_string());
+        let lower: Vec<f64> = buckets.iter().map(|b| (b.lower * 10.0).round()).collect();
+        assert_eq!(lower, [2.0, 6.0, 7.0]);
+        assert_eq!(buckets[1].observed, 0.0);
+
+        // Older signals don't know their side
+        let mut unknown = signal(0.7, 0.5, SignalStrength::Strong);
+        unknown.team_a_is_radiant = None;
+        assert!(SettledSignal::new(unknown, true).is_none());
+    }
+}
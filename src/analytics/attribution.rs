@@ -0,0 +1,247 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+use super::roi::{GroupStats, Trade};
+use crate::models::{ExitSignal, Position, PositionSide, Signal};
+use crate::trading::exit::ENTRY_MIN_STRENGTH;
+use crate::trading::stop_loss::side_price;
+
+/// Realized and simulated results for one signal type and strength
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution {
+    pub signal_type: String,
+    pub strength: String,
+
+    /// Positions actually taken on these signals
+    pub realized: Option<GroupStats>,
+
+    /// Hypothetical entry-to-exit trades on every signaled edge
+    pub simulated: Option<GroupStats>,
+}
+
+/// Paper trade that backs an entry signal's edge and sells at its exit signal
+///
+/// Buys `stake` USD of the favoured side at the entry quote and closes at the
+/// exit quote. Returns None if the entry had no edge or an unusable price.
+pub fn simulate_exit(entry: Signal, exit: &ExitSignal, stake: f64) -> Option<Trade> {
+    paper_trade(
+        entry,
+        |side| side_price(side, exit.market_team_a_odds),
+        exit.created_at,
+        stake,
+    )
+}
+
+/// Paper trade that backs an entry signal nothing exited
+///
+/// Settles at 1 or 0 once the entry is graded against the result, and
+/// otherwise marks to `last`, the market's latest signal, so entries held
+/// to the end count alongside exited ones.
+pub fn simulate_hold(entry: Signal, last: &Signal, stake: f64) -> Option<Trade> {
+    let closed_at = last.created_at.max(entry.created_at);
+    match entry.correct {
+        Some(won) => paper_trade(entry, |_| if won { 1.0 } else { 0.0 }, closed_at, stake),
+        None => paper_trade(
+            entry,
+            |side| side_price(side, last.market_team_a_odds),
+            closed_at,
+            stake,
+        ),
+    }
+}
+
+/// Entries the processor would still hold when the signals end, oldest
+/// first: per market, the first Strong+ signal with an edge from its last
+/// exit's trigger on
+///
+/// `signals` must be oldest first; entries are tracked in memory, so one
+/// dropped by a restart still counts here.
+pub fn unexited_entries(signals: &[Signal], exits: &[ExitSignal]) -> Vec<Signal> {
+    let mut last_trigger: HashMap<&str, i64> = HashMap::new();
+    for exit in exits {
+        let trigger = last_trigger
+            .entry(exit.market_condition_id.as_str())
+            .or_default();
+        *trigger = (*trigger).max(exit.trigger_signal_id);
+    }
+
+    let mut entries: BTreeMap<&str, &Signal> = BTreeMap::new();
+    for signal in signals {
+        let market = signal.market_condition_id.as_str();
+        let is_entry = signal.strength >= ENTRY_MIN_STRENGTH
+            && signal.net_edge.is_some_and(|e| e != 0.0)
+            && signal.id.unwrap_or_default() >= last_trigger.get(market).copied().unwrap_or(0);
+        if is_entry {
+            entries.entry(market).or_insert(signal);
+        }
+    }
+
+    let mut entries: Vec<Signal> = entries.into_values().cloned().collect();
+    entries.sort_by_key(|signal| signal.created_at);
+    entries
+}
+
+/// Buy `stake` USD of the side the entry favours at its quote and sell at
+/// `exit_price(side)`
+fn paper_trade(
+    entry: Signal,
+    exit_price: impl Fn(PositionSide) -> f64,
+    closed_at: DateTime<Utc>,
+    stake: f64,
+) -> Option<Trade> {
+    let net_edge = entry.net_edge.filter(|e| *e != 0.0)?;
+    let side = PositionSide::from_edge(net_edge);
+
+    let entry_price = side_price(side, entry.market_team_a_odds);
+    if entry_price <= 0.0 || entry_price >= 1.0 {
+        return None;
+    }
+
+    let mut position = Position::open(
+        &entry.market_condition_id,
+        side,
+        entry_price,
+        stake / entry_price,
+    );
+    position.signal_id = entry.id;
+    position.match_id = Some(entry.match_id);
+    position.opened_at = entry.created_at;
+    position
+        .close(position.remaining_shares, exit_price(side))
+        .ok()?;
+    position.closed_at = Some(closed_at);
+
+    Some(Trade {
+        position,
+        signal: Some(entry),
+    })
+}
+
+/// Realized and simulated trades keyed by (signal type, strength)
+type AttributionGroups<'a> = BTreeMap<(String, String), (Vec<&'a Trade>, Vec<&'a Trade>)>;
+
+/// Attribute realized and simulated PnL to signal type and strength
+pub fn attribute(realized: &[Trade], simulated: &[Trade]) -> Vec<Attribution> {
+    let mut groups = AttributionGroups::new();
+
+    for trade in realized {
+        groups.entry(key(trade)).or_default().0.push(trade);
+    }
+    for trade in simulated {
+        groups.entry(key(trade)).or_default().1.push(trade);
+    }
+
+    groups
+        .into_iter()
+        .map(|((signal_type, strength), (realized, simulated))| {
+            let label = format!("{}/{}", signal_type, strength);
+            let stats = |trades: &[&Trade]| {
+                (!trades.is_empty()).then(|| GroupStats::from_trades(label.clone(), trades))
+            };
+
+            Attribution {
+                realized: stats(&realized),
+                simulated: stats(&simulated),
+                signal_type,
+                strength,
+            }
+        })
+        .collect()
+}
+
+fn key(trade: &Trade) -> (String, String) {
+    (trade.signal_type(), trade.strength())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::models::{ExitReason, SignalStrength, SignalType};
+
+    fn signal(team_a_odds: f64, net_edge: f64) -> Signal {
+        Signal {
+            id: Some(1),
+            market_condition_id: "0xa".to_string(),
+            match_id: 7,
+            market_team_a_odds: team_a_odds,
+            edge: Some(net_edge),
+            net_edge: Some(net_edge),
+            strength: SignalStrength::Strong,
+            signal_type: SignalType::BarracksKill,
+            ..Default::default()
+        }
+    }
+
+    fn exit(trigger_signal_id: i64, team_a_odds: f64) -> ExitSignal {
+        ExitSignal {
+            id: None,
+            entry_signal_id: 1,
+            trigger_signal_id,
+            market_condition_id: "0xa".to_string(),
+            match_id: 7,
+            reason: ExitReason::Collapsed,
+            entry_net_edge: -0.12,
+            current_net_edge: -0.005,
+            market_team_a_odds: team_a_odds,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_simulated_team_b_trade() {
+        let exit = exit(2, 0.40);
+
+        // Bought team B at 0.50, sold at 0.60
+        let trade = simulate_exit(signal(0.50, -0.12), &exit, 50.0).unwrap();
+        assert_eq!(trade.position.side, PositionSide::TeamB);
+        assert!((trade.position.realized_pnl - 10.0).abs() < 1e-9);
+
+        let rows = attribute(&[], &[trade]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].signal_type, "BarracksKill");
+        assert_eq!(rows[0].strength, "Strong");
+        assert!(rows[0].realized.is_none());
+        assert!((rows[0].simulated.as_ref().unwrap().roi() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unexited_entries_are_held_to_the_end() {
+        let numbered = |id, net_edge| Signal {
+            id: Some(id),
+            ..signal(0.50, net_edge)
+        };
+        let weak = Signal {
+            strength: SignalStrength::Weak,
+            ..numbered(3, 0.10)
+        };
+        // 1 was exited by 3, which was too weak to enter; 4 is still open
+        let signals = [
+            numbered(1, -0.12),
+            weak,
+            numbered(4, 0.10),
+            numbered(5, 0.10),
+        ];
+        let entries = unexited_entries(&signals, &[exit(3, 0.40)]);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, Some(4));
+
+        // Marked to the last price: team A bought at 0.50, last at 0.60
+        let last = Signal {
+            market_team_a_odds: 0.60,
+            ..numbered(9, 0.0)
+        };
+        let marked = simulate_hold(entries[0].clone(), &last, 50.0).unwrap();
+        assert!((marked.position.realized_pnl - 10.0).abs() < 1e-9);
+
+        // Settled once graded, whatever the last price
+        let lost = Signal {
+            correct: Some(false),
+            ..entries[0].clone()
+        };
+        let settled = simulate_hold(lost, &last, 50.0).unwrap();
+        assert!((settled.position.realized_pnl + 50.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use crate::models::{PositionSide, Signal};
+use crate::trading::stop_loss::side_price;
+
+/// Closing line value of one signal
+#[derive(Debug, Clone)]
+pub struct SignalClv {
+    pub signal: Signal,
+
+    /// Side the signal's net edge favoured
+    pub side: PositionSide,
+
+    /// Price of that side when the signal fired
+    pub entry_price: f64,
+
+    /// Price of that side at close
+    pub closing_price: f64,
+}
+
+impl SignalClv {
+    /// CLV for an actionable signal; None if it favoured neither side
+    pub fn new(signal: Signal, closing_team_a_odds: f64) -> Option<Self> {
+        let net_edge = signal.net_edge.filter(|e| *e != 0.0)?;
+        let side = PositionSide::from_edge(net_edge);
+
+        Some(Self {
+            entry_price: side_price(side, signal.market_team_a_odds),
+            closing_price: side_price(side, closing_team_a_odds),
+            side,
+            signal,
+        })
+    }
+
+    /// Closing price minus entry price on the favoured side (positive = beat the close)
+    pub fn clv(&self) -> f64 {
+        self.closing_price - self.entry_price
+    }
+}
+
+/// Aggregated CLV for one group of signals
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClvSummary {
+    pub key: String,
+    pub signals: usize,
+    pub avg_clv: f64,
+
+    /// Fraction of signals whose price moved their way by the close
+    pub beat_close_rate: f64,
+}
+
+/// Aggregate CLV by a grouping key, sorted by key
+pub fn summarize_clv(rows: &[SignalClv], key: impl Fn(&SignalClv) -> String) -> Vec<ClvSummary> {
+    let mut groups: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for row in rows {
+        groups.entry(key(row)).or_default().push(row.clv());
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, clvs)| {
+            let n = clvs.len() as f64;
+            ClvSummary {
+                key,
+                signals: clvs.len(),
+                avg_clv: clvs.iter().sum::<f64>() / n,
+                beat_close_rate: clvs.iter().filter(|c| **c > 0.0).count() as f64 / n,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SignalStrength;
+
+    #[test]
+    fn test_clv_follows_favoured_side() {
+        let signal = |odds: f64, net_edge: f64| Signal {
+            market_condition_id: "0xa".to_string(),
+            match_id: 1,
+            market_team_a_odds: odds,
+            edge: Some(net_edge),
+            net_edge: Some(net_edge),
+            strength: SignalStrength::Moderate,
+            ..Default::default()
+        };
+
+        // Backed team B at 0.60; team A closed at 0.30, so B closed at 0.70
+        let row = SignalClv::new(signal(0.40, -0.08), 0.30).unwrap();
+        assert!((row.clv() - 0.10).abs() < 1e-9);
+
+        let rows = vec![row, SignalClv::new(signal(0.50, 0.06), 0.45).unwrap()];
+        let summary = summarize_clv(&rows, |_| "all".to_string());
+        assert!((summary[0].avg_clv - 0.025).abs() < 1e-9);
+        assert_eq!(summary[0].beat_close_rate, 0.5);
+
+        assert!(SignalClv::new(signal(0.50, 0.0), 0.45).is_none());
+    }
+}
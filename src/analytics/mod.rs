@@ -0,0 +1,14 @@
+pub mod accuracy;
+pub mod attribution;
+pub mod clv;
+pub mod roi;
+pub mod stats;
+
+pub use accuracy::{
+    calibration, summarize_accuracy, AccuracyStats, CalibrationBucket, SettledSignal,
+    CALIBRATION_BUCKET,
+};
+pub use attribution::{attribute, simulate_exit, simulate_hold, unexited_entries, Attribution};
+pub use clv::{summarize_clv, ClvSummary, SignalClv};
+pub use roi::{max_drawdown, summarize, GroupStats, Trade};
+pub use stats::{SignalStats, StatsGroup};
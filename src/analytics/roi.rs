@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use crate::models::{LiveMatchState, Position, PositionSide, Signal};
+
+/// Group key used when a position has no originating signal
+pub const MANUAL: &str = "Manual";
+
+/// Group key used when a value isn't known
+pub const UNKNOWN: &str = "unknown";
+
+/// A finished position joined with the signal that prompted it
+#[derive(Debug, Clone)]
+pub struct Trade {
+    pub position: Position,
+    pub signal: Option<Signal>,
+}
+
+impl Trade {
+    /// ISO week the trade finished in, e.g. `2026-W07`
+    pub fn week(&self) -> String {
+        let at = self.position.closed_at.unwrap_or(self.position.opened_at);
+        at.format("%G-W%V").to_string()
+    }
+
+    /// League name from the signal's match snapshot
+    pub fn league(&self) -> String {
+        self.signal
+            .as_ref()
+            .and_then(|s| serde_json::from_str::<LiveMatchState>(&s.match_snapshot).ok())
+            .and_then(|state| state.league_name)
+            .unwrap_or_else(|| UNKNOWN.to_string())
+    }
+
+    /// Type of the originating signal
+    pub fn signal_type(&self) -> String {
+        self.signal
+            .as_ref()
+            .map(|s| s.signal_type.to_string())
+            .unwrap_or_else(|| MANUAL.to_string())
+    }
+
+    /// Strength of the originating signal
+    pub fn strength(&self) -> String {
+        self.signal
+            .as_ref()
+            .map(|s| s.strength.to_string())
+            .unwrap_or_else(|| MANUAL.to_string())
+    }
+
+    /// Net edge the signal showed for the side that was bought
+    pub fn entry_edge(&self) -> Option<f64> {
+        let net_edge = self.signal.as_ref()?.net_edge?;
+
+        Some(match self.position.side {
+            PositionSide::TeamA => net_edge,
+            PositionSide::TeamB => -net_edge,
+        })
+    }
+}
+
+/// Aggregated results for one group of trades
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupStats {
+    pub key: String,
+    pub trades: usize,
+
+    /// USD cost of all shares bought
+    pub staked: f64,
+
+    /// Realized profit/loss in USD
+    pub pnl: f64,
+
+    /// Mean net edge at entry over trades with a signal
+    pub avg_entry_edge: Option<f64>,
+
+    /// Realized PnL per share bought (edge actually captured)
+    pub avg_edge_captured: f64,
+
+    /// Largest peak-to-trough fall in cumulative PnL
+    pub max_drawdown: f64,
+}
+
+impl GroupStats {
+    /// Realized PnL as a fraction of stake
+    pub fn roi(&self) -> f64 {
+        if self.staked > 0.0 {
+            self.pnl / self.staked
+        } else {
+            0.0
+        }
+    }
+
+    pub(crate) fn from_trades(key: String, trades: &[&Trade]) -> Self {
+        let staked = trades.iter().map(|t| t.position.cost_basis()).sum();
+        let pnl = trades.iter().map(|t| t.position.realized_pnl).sum();
+        let shares: f64 = trades.iter().map(|t| t.position.shares).sum();
+
+        let edges: Vec<f64> = trades.iter().filter_map(|t| t.entry_edge()).collect();
+        let avg_entry_edge =
+            (!edges.is_empty()).then(|| edges.iter().sum::<f64>() / edges.len() as f64);
+
+        Self {
+            key,
+            trades: trades.len(),
+            staked,
+            pnl,
+            avg_entry_edge,
+            avg_edge_captured: if shares > 0.0 { pnl / shares } else { 0.0 },
+            max_drawdown: max_drawdown(trades.iter().map(|t| t.position.realized_pnl)),
+        }
+    }
+}
+
+/// Aggregate trades by a grouping key, sorted by key
+///
+/// Trades should be in the order they finished so drawdowns are meaningful.
+pub fn summarize(trades: &[Trade], key: impl Fn(&Trade) -> String) -> Vec<GroupStats> {
+    let mut groups: BTreeMap<String, Vec<&Trade>> = BTreeMap::new();
+    for trade in trades {
+        groups.entry(key(trade)).or_default().push(trade);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, trades)| GroupStats::from_trades(key, &trades))
+        .collect()
+}
+
+/// Largest peak-to-trough fall in the running sum of a PnL sequence
+pub fn max_drawdown(pnls: impl IntoIterator<Item = f64>) -> f64 {
+    let mut cumulative = 0.0;
+    let mut peak = 0.0_f64;
+    let mut drawdown = 0.0_f64;
+
+    for pnl in pnls {
+        cumulative += pnl;
+        peak = peak.max(cumulative);
+        drawdown = drawdown.max(peak - cumulative);
+    }
+
+    drawdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_drawdown() {
+        assert_eq!(max_drawdown([10.0, -4.0, -8.0, 5.0, -2.0]), 12.0);
+        assert_eq!(max_drawdown([-5.0, 3.0]), 5.0);
+        assert_eq!(max_drawdown([]), 0.0);
+    }
+
+    #[test]
+    fn test_summarize_roi() {
+        let trade = |side, price: f64, won| {
+            let mut position = Position::open("0xa", side, price, 100.0);
+            position.resolve(won).unwrap();
+            Trade {
+                position,
+                signal: None,
+            }
+        };
+        let trades = vec![
+            trade(PositionSide::TeamA, 0.40, true),
+            trade(PositionSide::TeamB, 0.60, false),
+        ];
+
+        let stats = summarize(&trades, |t| t.signal_type());
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].key, MANUAL);
+        assert_eq!(stats[0].trades, 2);
+        assert!((stats[0].pnl - 0.0).abs() < 1e-9);
+        assert!((stats[0].staked - 100.0).abs() < 1e-9);
+        assert!((stats[0].max_drawdown - 60.0).abs() < 1e-9);
+        assert_eq!(stats[0].avg_entry_edge, None);
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+/// Dimension signal statistics are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsGroup {
+    #[default]
+    SignalType,
+    Strength,
+    League,
+    /// UTC calendar day the signal was stored
+    Day,
+}
+
+impl StatsGroup {
+    /// Name used in query strings
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsGroup::SignalType => "signal_type",
+            StatsGroup::Strength => "strength",
+            StatsGroup::League => "league",
+            StatsGroup::Day => "day",
+        }
+    }
+}
+
+impl fmt::Display for StatsGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for StatsGroup {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "signal_type" | "type" => Ok(StatsGroup::SignalType),
+            "strength" => Ok(StatsGroup::Strength),
+            "league" => Ok(StatsGroup::League),
+            "day" => Ok(StatsGroup::Day),
+            other => anyhow::bail!("Unknown stats group: {}", other),
+        }
+    }
+}
+
+/// Signal counts and quality for one group
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalStats {
+    pub key: String,
+    pub signals: i64,
+
+    /// Signals whose net edge favoured a side
+    pub actionable: i64,
+
+    /// Mean absolute net edge over actionable signals
+    pub avg_edge: Option<f64>,
+
+    /// Mean stored confidence, over signals that recorded one
+    pub avg_confidence: Option<f64>,
+
+    /// Signals graded against their match result with a side
+    pub graded: i64,
+
+    /// Share of graded signals whose favoured side won
+    pub hit_rate: Option<f64>,
+}
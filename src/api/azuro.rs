@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, info};
+
+use crate::matching::MarketQuestion;
+use crate::models::{Market, Venue};
+
+/// Azuro sport slug for Dota 2
+const DOTA2_SPORT_SLUG: &str = "dota-2";
+
+/// Azuro dictionary outcome IDs for the full-match winner (team 1, team 2)
+pub const WINNER_OUTCOME_IDS: (&str, &str) = ("29", "30");
+
+/// Games fetched per subgraph query
+const MAX_GAMES: u32 = 100;
+
+const GAMES_QUERY: &str = r#"
+query Games($sport: String!, $first: Int!) {
+  games(
+    first: $first
+    where: { sport_: { slug: $sport }, status: Created }
+    orderBy: startsAt
+  ) {
+    gameId
+    title
+    participants { name }
+    conditions(where: { status: Created }) {
+      conditionId
+      reinforcement
+      outcomes { outcomeId currentOdds }
+    }
+  }
+}
+"#;
+
+/// Client for the Azuro protocol subgraph (on-chain bookmaker)
+pub struct AzuroClient {
+    client: Client,
+    subgraph_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse {
+    data: Option<GamesData>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GamesData {
+    #[serde(default)]
+    games: Vec<GameResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GameResponse {
+    game_id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    participants: Vec<ParticipantResponse>,
+    #[serde(default)]
+    conditions: Vec<ConditionResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParticipantResponse {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConditionResponse {
+    condition_id: String,
+    /// Liquidity backing the condition, as a BigDecimal string
+    #[serde(default)]
+    reinforcement: Option<String>,
+    #[serde(default)]
+    outcomes: Vec<OutcomeResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutcomeResponse {
+    outcome_id: String,
+    /// Decimal odds, as a BigDecimal string
+    current_odds: String,
+}
+
+impl ConditionResponse {
+    /// Decimal odds of an outcome
+    fn odds(&self, outcome_id: &str) -> Option<f64> {
+        self.outcomes
+            .iter()
+            .find(|o| o.outcome_id == outcome_id)
+            .and_then(|o| o.current_odds.parse().ok())
+            .filter(|odds: &f64| *odds > 1.0)
+    }
+}
+
+impl AzuroClient {
+    /// Create a new Azuro subgraph client
+    pub fn new(subgraph_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            subgraph_url: subgraph_url.to_string(),
+        }
+    }
+
+    /// Fetch Dota 2 games with an open match-winner condition
+    pub async fn fetch_dota2_markets(&self) -> Result<Vec<Market>> {
+        debug!("Querying Azuro subgraph: {}", self.subgraph_url);
+
+        let response = self
+            .client
+            .post(&self.subgraph_url)
+            .json(&json!({
+                "query": GAMES_QUERY,
+                "variables": { "sport": DOTA2_SPORT_SLUG, "first": MAX_GAMES },
+            }))
+            .send()
+            .await
+            .context("Failed to query Azuro subgraph")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Azuro subgraph error: {} - {}", status, text);
+        }
+
+        let body: GraphQlResponse = response
+            .json()
+            .await
+            .context("Failed to parse Azuro subgraph response")?;
+
+        if let Some(error) = body.errors.first() {
+            anyhow::bail!("Azuro subgraph query failed: {}", error.message);
+        }
+
+        let markets: Vec<Market> = body
+            .data
+            .map(|d| d.games)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|game| self.convert_game(game))
+            .collect();
+
+        info!("Total active Azuro Dota 2 markets found: {}", markets.len());
+        Ok(markets)
+    }
+
+    /// Convert a game's match-winner condition into a two-team market
+    fn convert_game(&self, game: GameResponse) -> Option<Market> {
+        let [team_a, team_b] = game.participants.as_slice() else {
+            return None;
+        };
+
+        let (team_1_outcome, team_2_outcome) = WINNER_OUTCOME_IDS;
+        let Some((condition, odds_a, odds_b)) = game
+            .conditions
+            .iter()
+            .find_map(|c| Some((c, c.odds(team_1_outcome)?, c.odds(team_2_outcome)?)))
+        else {
+            debug!("Azuro game {} has no open winner condition", game.game_id);
+            return None;
+        };
+
+        // Remove the margin by normalizing implied probabilities
+        let (implied_a, implied_b) = (1.0 / odds_a, 1.0 / odds_b);
+        let team_a_odds = implied_a / (implied_a + implied_b);
+
+        let liquidity = condition
+            .reinforcement
+            .as_deref()
+            .and_then(|r| r.parse().ok())
+            .unwrap_or(0.0);
+
+        let question = if game.title.is_empty() {
+            format!("{} vs {}", team_a.name, team_b.name)
+        } else {
+            game.title.clone()
+        };
+        let details = MarketQuestion::parse(&question);
+
+        let market = Market {
+            condition_id: condition.condition_id.clone(),
+            venue: Venue::Azuro,
+            question,
+            team_a: team_a.name.trim().to_string(),
+            team_b: team_b.name.trim().to_string(),
+            team_a_odds,
+            team_b_odds: 1.0 - team_a_odds,
+            opening_team_a_odds: team_a_odds,
+            opened_at: None,
+            // The book's own prices, margin included, are what a bet pays
+            team_a_bid: Some(1.0 - implied_b),
+            team_a_ask: Some(implied_a),
+            liquidity,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: details.series_format,
+            game_number: details.game_number,
+            qualifiers: details.qualifiers,
+            outcomes: Vec::new(),
+        };
+
+        info!(
+            "Found Azuro market: {} vs {} (odds: {:.0}% / {:.0}%)",
+            market.team_a,
+            market.team_b,
+            market.team_a_odds * 100.0,
+            market.team_b_odds * 100.0
+        );
+
+        Some(market)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_game_uses_winner_condition() {
+        let game: GameResponse = serde_json::from_value(json!({
+            "gameId": "1001",
+            "title": "Team Spirit - OG",
+            "participants": [{"name": "Team Spirit"}, {"name": "OG"}],
+            "conditions": [
+                {
+                    "conditionId": "handicap",
+                    "outcomes": [{"outcomeId": "7", "currentOdds": "1.9"}]
+                },
+                {
+                    "conditionId": "winner",
+                    "reinforcement": "5000",
+                    "outcomes": [
+                        {"outcomeId": "29", "currentOdds": "1.5"},
+                        {"outcomeId": "30", "currentOdds": "2.5"}
+                    ]
+                }
+            ]
+        }))
+        .unwrap();
+
+        let market = AzuroClient::new("http://localhost")
+            .convert_game(game)
+            .unwrap();
+        assert_eq!(market.condition_id, "winner");
+        assert_eq!(market.venue, Venue::Azuro);
+        assert_eq!(market.team_a, "Team Spirit");
+        assert!((market.team_a_odds - 0.625).abs() < 1e-9);
+        assert!((market.team_a_buy_price() - 1.0 / 1.5).abs() < 1e-9);
+        assert!((market.team_b_buy_price() - 1.0 / 2.5).abs() < 1e-9);
+        assert_eq!(market.liquidity, 5000.0);
+    }
+}
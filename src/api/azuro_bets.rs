@@ -0,0 +1,494 @@
+//! Bet placement through the Azuro relayer (needs the `azuro` feature)
+//!
+//! The relayer takes an order signed with the bettor's key as EIP-712
+//! typed data and submits it on chain, so the bettor needs no gas, only an
+//! allowance for the bet token. The signed `ClientBetData` is:
+//!
+//! ```text
+//! ClientBetData(ClientData clientData,uint256 conditionId,uint128 outcomeId,
+//!     uint64 minOdds,uint128 amount,uint256 nonce)
+//! ClientData(string attention,address affiliate,address core,uint256 expiresAt,
+//!     uint256 chainId,uint256 relayerFeeAmount,bool isBetSponsored,
+//!     bool isFeeSponsored,bool isSponsoredBetReturnable)
+//! ```
+//!
+//! under the `Live Betting` 1.0.0 domain of the core contract.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use k256::ecdsa::SigningKey;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+use tracing::debug;
+
+use crate::config::Config;
+
+/// Decimals of the bet token (USDT on Polygon)
+const TOKEN_DECIMALS: i32 = 6;
+
+/// Decimals of Azuro's fixed-point odds
+const ODDS_DECIMALS: i32 = 12;
+
+/// How long the relayer may hold an order before it lapses
+const ORDER_TTL: Duration = Duration::from_secs(60);
+
+/// EIP-712 domain the relayer verifies orders against
+const DOMAIN_NAME: &str = "Live Betting";
+const DOMAIN_VERSION: &str = "1.0.0";
+
+/// Shown to the bettor's wallet and checked by the relayer
+const ATTENTION: &str = "By signing this transaction, I agree to place a bet via esport-signal";
+
+/// A single (ordinar) bet on one outcome of a condition
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetOrder {
+    /// Azuro condition ID, as the decimal string the subgraph returns
+    pub condition_id: String,
+    /// Outcome backed, e.g. 29 for team 1 of a match winner condition
+    pub outcome_id: u128,
+    /// Stake in bet token units (USD)
+    pub amount: f64,
+    /// Lowest decimal odds the bet may be accepted at
+    pub min_odds: f64,
+}
+
+/// Places signed bets through the Azuro relayer API
+pub struct AzuroBetClient {
+    client: Client,
+    relayer_url: String,
+    environment: String,
+    chain_id: u64,
+    core: [u8; 20],
+    affiliate: [u8; 20],
+    wallet: Wallet,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderResponse {
+    id: String,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+impl AzuroBetClient {
+    /// Client for the relayer at `relayer_url`, betting in `environment`
+    /// (e.g. `PolygonUSDT`) on the core contract at `core`
+    pub fn new(
+        relayer_url: &str,
+        environment: &str,
+        chain_id: u64,
+        core: &str,
+        affiliate: Option<&str>,
+        private_key: &str,
+    ) -> Result<Self> {
+        let wallet = Wallet::from_hex(private_key)?;
+        Ok(Self {
+            client: Client::new(),
+            relayer_url: relayer_url.trim_end_matches('/').to_string(),
+            environment: environment.to_string(),
+            chain_id,
+            core: parse_address(core).context("Invalid Azuro core address")?,
+            // Without an affiliate the bettor is its own
+            affiliate: match affiliate {
+                Some(affiliate) => parse_address(affiliate).context("Invalid Azuro affiliate")?,
+                None => wallet.address,
+            },
+            wallet,
+        })
+    }
+
+    /// Client from config; `None` when no bettor key is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(private_key) = &config.azuro_private_key else {
+            return Ok(None);
+        };
+        let core = config
+            .azuro_core_address
+            .as_deref()
+            .context("AZURO_CORE_ADDRESS is required with AZURO_PRIVATE_KEY")?;
+
+        Self::new(
+            &config.azuro_relayer_url,
+            &config.azuro_environment,
+            config.azuro_chain_id,
+            core,
+            config.azuro_affiliate.as_deref(),
+            private_key,
+        )
+        .map(Some)
+    }
+
+    /// Bettor address, `0x`-prefixed
+    pub fn bettor(&self) -> String {
+        hex_string(&self.wallet.address)
+    }
+
+    /// Sign and submit a bet, returning the relayer's order ID
+    pub async fn place_bet(&self, order: &BetOrder) -> Result<String> {
+        let nonce = Utc::now().timestamp_millis() as u128;
+        let expires_at = (Utc::now() + ORDER_TTL).timestamp() as u128;
+        let amount = to_fixed(order.amount, TOKEN_DECIMALS);
+        let min_odds = to_fixed(order.min_odds, ODDS_DECIMALS);
+
+        let client_data = TypedStruct::new("ClientData")
+            .field("attention", "string", Value::Str(ATTENTION.to_string()))
+            .field("affiliate", "address", Value::Address(self.affiliate))
+            .field("core", "address", Value::Address(self.core))
+            .field("expiresAt", "uint256", Value::uint(expires_at))
+            .field("chainId", "uint256", Value::uint(self.chain_id.into()))
+            .field("relayerFeeAmount", "uint256", Value::uint(0))
+            .field("isBetSponsored", "bool", Value::Bool(false))
+            .field("isFeeSponsored", "bool", Value::Bool(false))
+            .field("isSponsoredBetReturnable", "bool", Value::Bool(false));
+        let bet_data = TypedStruct::new("ClientBetData")
+            .field("clientData", "ClientData", Value::Struct(client_data))
+            .field(
+                "conditionId",
+                "uint256",
+                Value::Uint(parse_uint(&order.condition_id)?),
+            )
+            .field("outcomeId", "uint128", Value::uint(order.outcome_id))
+            .field("minOdds", "uint64", Value::uint(min_odds))
+            .field("amount", "uint128", Value::uint(amount))
+            .field("nonce", "uint256", Value::uint(nonce));
+
+        let domain = TypedStruct::new("EIP712Domain")
+            .field("name", "string", Value::Str(DOMAIN_NAME.to_string()))
+            .field("version", "string", Value::Str(DOMAIN_VERSION.to_string()))
+            .field("chainId", "uint256", Value::uint(self.chain_id.into()))
+            .field("verifyingContract", "address", Value::Address(self.core));
+        let signature = self.wallet.sign(&typed_data_hash(&domain, &bet_data))?;
+
+        let bettor = self.bettor();
+        let body = json!({
+            "environment": self.environment,
+            "bettor": bettor,
+            "betOwner": bettor,
+            "clientBetData": {
+                "clientData": {
+                    "attention": ATTENTION,
+                    "affiliate": hex_string(&self.affiliate),
+                    "core": hex_string(&self.core),
+                    "expiresAt": expires_at,
+                    "chainId": self.chain_id,
+                    "relayerFeeAmount": "0",
+                    "isBetSponsored": false,
+                    "isFeeSponsored": false,
+                    "isSponsoredBetReturnable": false,
+                },
+                "bet": {
+                    "conditionId": order.condition_id,
+                    "outcomeId": order.outcome_id,
+                    "minOdds": min_odds.to_string(),
+                    "amount": amount.to_string(),
+                    "nonce": nonce.to_string(),
+                },
+            },
+            "bettorSignature": hex_string(&signature),
+        });
+
+        let url = format!("{}/bet/orders/ordinar", self.relayer_url);
+        debug!("Submitting Azuro bet order to {}", url);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to submit Azuro bet order")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Azuro relayer error: {} - {}", status, text);
+        }
+
+        let order: OrderResponse = response
+            .json()
+            .await
+            .context("Failed to parse Azuro order response")?;
+        if let Some(state) = &order.state {
+            debug!("Azuro order {} is {}", order.id, state);
+        }
+        Ok(order.id)
+    }
+}
+
+/// Amount in fixed point with `decimals` decimals
+fn to_fixed(amount: f64, decimals: i32) -> u128 {
+    (amount * 10f64.powi(decimals)).round().max(0.0) as u128
+}
+
+/// Signing key and the address it controls
+struct Wallet {
+    key: SigningKey,
+    address: [u8; 20],
+}
+
+impl Wallet {
+    fn from_hex(private_key: &str) -> Result<Self> {
+        let bytes = parse_hex(private_key).context("Invalid Azuro private key")?;
+        let key = SigningKey::from_slice(&bytes).context("Invalid Azuro private key")?;
+
+        // Last 20 bytes of the hash of the uncompressed public key, untagged
+        let public = key.verifying_key().to_encoded_point(false);
+        let hash = keccak(&public.as_bytes()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+
+        Ok(Self { key, address })
+    }
+
+    /// `r || s || v` signature of a 32-byte digest, with `v` of 27 or 28
+    fn sign(&self, digest: &[u8; 32]) -> Result<[u8; 65]> {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(digest)
+            .context("Failed to sign Azuro bet order")?;
+
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&signature.to_bytes());
+        bytes[64] = 27 + recovery_id.to_byte();
+        Ok(bytes)
+    }
+}
+
+/// A value in EIP-712 typed data
+enum Value {
+    /// Any `uintN`, big-endian
+    Uint([u8; 32]),
+    Address([u8; 20]),
+    Bool(bool),
+    Str(String),
+    Struct(TypedStruct),
+}
+
+impl Value {
+    fn uint(value: u128) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[16..].copy_from_slice(&value.to_be_bytes());
+        Value::Uint(bytes)
+    }
+
+    /// The 32-byte word this value encodes to in a struct hash
+    fn encode(&self) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        match self {
+            Value::Uint(bytes) => word = *bytes,
+            Value::Address(address) => word[12..].copy_from_slice(address),
+            Value::Bool(value) => word[31] = u8::from(*value),
+            Value::Str(text) => word = keccak(text.as_bytes()),
+            Value::Struct(inner) => word = inner.hash(),
+        }
+        word
+    }
+}
+
+/// A struct in EIP-712 typed data, fields in declaration order
+struct TypedStruct {
+    name: &'static str,
+    fields: Vec<(&'static str, &'static str, Value)>,
+}
+
+impl TypedStruct {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            fields: Vec::new(),
+        }
+    }
+
+    fn field(mut self, name: &'static str, kind: &'static str, value: Value) -> Self {
+        self.fields.push((name, kind, value));
+        self
+    }
+
+    /// `Name(type field,...)` of this struct alone
+    fn signature(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, kind, _)| format!("{} {}", kind, name))
+            .collect();
+        format!("{}({})", self.name, fields.join(","))
+    }
+
+    /// Signatures of the structs this one references, at any depth
+    fn collect_referenced(&self, referenced: &mut BTreeMap<&'static str, String>) {
+        for (_, _, value) in &self.fields {
+            if let Value::Struct(inner) = value {
+                referenced.insert(inner.name, inner.signature());
+                inner.collect_referenced(referenced);
+            }
+        }
+    }
+
+    /// This struct's signature followed by the referenced ones by name
+    fn encode_type(&self) -> String {
+        let mut referenced = BTreeMap::new();
+        self.collect_referenced(&mut referenced);
+        referenced.remove(self.name);
+
+        let mut encoded = self.signature();
+        for signature in referenced.values() {
+            encoded.push_str(signature);
+        }
+        encoded
+    }
+
+    fn hash(&self) -> [u8; 32] {
+        let mut encoded = Vec::with_capacity(32 * (self.fields.len() + 1));
+        encoded.extend_from_slice(&keccak(self.encode_type().as_bytes()));
+        for (_, _, value) in &self.fields {
+            encoded.extend_from_slice(&value.encode());
+        }
+        keccak(&encoded)
+    }
+}
+
+/// Digest signed for `message` under `domain`
+fn typed_data_hash(domain: &TypedStruct, message: &TypedStruct) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(66);
+    encoded.extend_from_slice(&[0x19, 0x01]);
+    encoded.extend_from_slice(&domain.hash());
+    encoded.extend_from_slice(&message.hash());
+    keccak(&encoded)
+}
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Bytes of a hex string, with or without `0x`
+fn parse_hex(text: &str) -> Result<Vec<u8>> {
+    let digits = text.trim().trim_start_matches("0x");
+    if !digits.len().is_multiple_of(2) {
+        anyhow::bail!("odd number of hex digits");
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .with_context(|| format!("not hex: {}", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+fn parse_address(text: &str) -> Result<[u8; 20]> {
+    parse_hex(text)?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow::anyhow!("expected 20 bytes, got {}", bytes.len()))
+}
+
+/// Big-endian 256-bit value of a decimal string
+fn parse_uint(text: &str) -> Result<[u8; 32]> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        anyhow::bail!("Not a decimal integer: {:?}", text);
+    }
+
+    let mut bytes = [0u8; 32];
+    for digit in text.bytes().map(|b| u32::from(b - b'0')) {
+        // bytes = bytes * 10 + digit
+        let mut carry = digit;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            anyhow::bail!("Integer exceeds 256 bits: {}", text);
+        }
+    }
+    Ok(bytes)
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `Mail` example from the EIP-712 specification
+    #[test]
+    fn test_typed_data_hash_matches_the_eip712_example() {
+        let person = |name: &str, wallet: &str| {
+            TypedStruct::new("Person")
+                .field("name", "string", Value::Str(name.to_string()))
+                .field(
+                    "wallet",
+                    "address",
+                    Value::Address(parse_address(wallet).unwrap()),
+                )
+        };
+        let domain = TypedStruct::new("EIP712Domain")
+            .field("name", "string", Value::Str("Ether Mail".to_string()))
+            .field("version", "string", Value::Str("1".to_string()))
+            .field("chainId", "uint256", Value::uint(1))
+            .field(
+                "verifyingContract",
+                "address",
+                Value::Address(
+                    parse_address("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap(),
+                ),
+            );
+        let mail = TypedStruct::new("Mail")
+            .field(
+                "from",
+                "Person",
+                Value::Struct(person("Cow", "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826")),
+            )
+            .field(
+                "to",
+                "Person",
+                Value::Struct(person("Bob", "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB")),
+            )
+            .field("contents", "string", Value::Str("Hello, Bob!".to_string()));
+
+        assert_eq!(
+            mail.encode_type(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        let digest = typed_data_hash(&domain, &mail);
+        assert_eq!(
+            hex_string(&digest),
+            "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+
+        // Cow's key is keccak("cow")
+        let wallet = Wallet::from_hex(&hex_string(&keccak(b"cow"))).unwrap();
+        assert_eq!(
+            hex_string(&wallet.address),
+            "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826"
+        );
+        let signature = wallet.sign(&digest).unwrap();
+        assert_eq!(
+            hex_string(&signature),
+            "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+             07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c"
+        );
+    }
+
+    #[test]
+    fn test_parse_uint_reads_condition_ids_beyond_u128() {
+        let small = parse_uint("300").unwrap();
+        assert_eq!(&small[30..], &[0x01, 0x2c]);
+        assert!(small[..30].iter().all(|b| *b == 0));
+
+        // 2^128
+        let large = parse_uint("340282366920938463463374607431768211456").unwrap();
+        assert_eq!(large[15], 1);
+        assert!(large.iter().enumerate().all(|(i, b)| i == 15 || *b == 0));
+
+        assert!(parse_uint("12a").is_err());
+        assert!(parse_uint("").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Betfair event type ID for esports
+const ESPORTS_EVENT_TYPE_ID: &str = "27454571";
+
+/// Markets fetched per catalogue request
+const MAX_MARKETS: u32 = 200;
+
+/// Client for the Betfair Exchange betting API
+pub struct BetfairClient {
+    client: Client,
+    base_url: String,
+    app_key: String,
+    session_token: String,
+}
+
+/// A Dota 2 match-odds market with the best available back prices
+#[derive(Debug, Clone)]
+pub struct BetfairMatchOdds {
+    pub market_id: String,
+    pub event_name: String,
+    pub runners: Vec<BetfairRunner>,
+}
+
+/// One selection in a Betfair market
+#[derive(Debug, Clone)]
+pub struct BetfairRunner {
+    pub selection_id: i64,
+    pub name: String,
+
+    /// Best decimal odds available to back
+    pub best_back_odds: Option<f64>,
+
+    /// Stake available at the best back odds
+    pub best_back_size: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketCatalogueResponse {
+    market_id: String,
+    event: Option<EventResponse>,
+    #[serde(default)]
+    runners: Vec<RunnerCatalogueResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventResponse {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunnerCatalogueResponse {
+    selection_id: i64,
+    runner_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketBookResponse {
+    market_id: String,
+    status: String,
+    #[serde(default)]
+    runners: Vec<RunnerBookResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunnerBookResponse {
+    selection_id: i64,
+    ex: Option<ExchangePricesResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExchangePricesResponse {
+    #[serde(default)]
+    available_to_back: Vec<PriceSizeResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PriceSizeResponse {
+    price: f64,
+    size: f64,
+}
+
+impl BetfairClient {
+    /// Create a new Betfair client from an application key and session token
+    pub fn new(base_url: &str, app_key: &str, session_token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.to_string(),
+            app_key: app_key.to_string(),
+            session_token: session_token.to_string(),
+        }
+    }
+
+    /// Fetch open Dota 2 match-odds markets with best back prices
+    pub async fn fetch_dota2_match_odds(&self) -> Result<Vec<BetfairMatchOdds>> {
+        let catalogue: Vec<MarketCatalogueResponse> = self
+            .post(
+                "listMarketCatalogue/",
+                json!({
+                    "filter": {
+                        "eventTypeIds": [ESPORTS_EVENT_TYPE_ID],
+                        "textQuery": "Dota",
+                        "marketTypeCodes": ["MATCH_ODDS"],
+                    },
+                    "marketProjection": ["EVENT", "RUNNER_DESCRIPTION"],
+                    "maxResults": MAX_MARKETS,
+                }),
+            )
+            .await
+            .context("Failed to fetch Betfair market catalogue")?;
+
+        if catalogue.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let market_ids: Vec<&str> = catalogue.iter().map(|m| m.market_id.as_str()).collect();
+        debug!("Found {} Betfair Dota 2 markets", market_ids.len());
+
+        let books: Vec<MarketBookResponse> = self
+            .post(
+                "listMarketBook/",
+                json!({
+                    "marketIds": market_ids,
+                    "priceProjection": { "priceData": ["EX_BEST_OFFERS"] },
+                }),
+            )
+            .await
+            .context("Failed to fetch Betfair market books")?;
+
+        let markets: Vec<BetfairMatchOdds> = catalogue
+            .iter()
+            .filter_map(|market| {
+                let book = books
+                    .iter()
+                    .find(|b| b.market_id == market.market_id && b.status == "OPEN")?;
+
+                let runners = market
+                    .runners
+                    .iter()
+                    .map(|runner| {
+                        let best = book
+                            .runners
+                            .iter()
+                            .find(|r| r.selection_id == runner.selection_id)
+                            .and_then(|r| r.ex.as_ref())
+                            .and_then(|ex| ex.available_to_back.first());
+
+                        BetfairRunner {
+                            selection_id: runner.selection_id,
+                            name: runner.runner_name.clone(),
+                            best_back_odds: best.map(|p| p.price),
+                            best_back_size: best.map(|p| p.size).unwrap_or(0.0),
+                        }
+                    })
+                    .collect();
+
+                Some(BetfairMatchOdds {
+                    market_id: market.market_id.clone(),
+                    event_name: market
+                        .event
+                        .as_ref()
+                        .map(|e| e.name.clone())
+                        .unwrap_or_default(),
+                    runners,
+                })
+            })
+            .collect();
+
+        info!("Fetched {} open Betfair Dota 2 markets", markets.len());
+        Ok(markets)
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        operation: &str,
+        body: serde_json::Value,
+    ) -> Result<T> {
+        let url = format!("{}/{}", self.base_url, operation);
+        debug!("Calling Betfair: {}", url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Accept", "application/json")
+            .header("X-Application", &self.app_key)
+            .header("X-Authentication", &self.session_token)
+            .json(&body)
+            .send()
+            .await
+            .context("Betfair request failed")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Betfair API error: {} - {}", status, text);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse Betfair response")
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use crate::matching::MarketQuestion;
+use crate::models::{Market, Venue};
+
+/// Client for the Kalshi trade API (public market data)
+pub struct KalshiClient {
+    client: Client,
+    base_url: String,
+    series_tickers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketsResponse {
+    #[serde(default)]
+    markets: Vec<MarketResponse>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketResponse {
+    ticker: String,
+    event_ticker: String,
+    title: String,
+    /// Outcome the YES side pays on (the team name for game markets)
+    #[serde(default)]
+    yes_sub_title: String,
+    /// Prices in cents
+    #[serde(default)]
+    yes_bid: f64,
+    #[serde(default)]
+    yes_ask: f64,
+    #[serde(default)]
+    last_price: f64,
+    /// Liquidity in cents
+    #[serde(default)]
+    liquidity: f64,
+    /// Contracts traded in the last 24 hours, each paying $1
+    #[serde(default)]
+    volume_24h: Option<f64>,
+    close_time: Option<String>,
+    status: String,
+}
+
+impl MarketResponse {
+    /// Probability implied by the YES quote (mid if both sides quoted, else last trade)
+    fn yes_probability(&self) -> Option<f64> {
+        let cents = if self.yes_bid > 0.0 && self.yes_ask > 0.0 {
+            (self.yes_bid + self.yes_ask) / 2.0
+        } else if self.last_price > 0.0 {
+            self.last_price
+        } else {
+            return None;
+        };
+
+        Some(cents / 100.0)
+    }
+}
+
+impl KalshiClient {
+    /// Create a new Kalshi client for the given series
+    pub fn new(base_url: &str, series_tickers: Vec<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.to_string(),
+            series_tickers,
+        }
+    }
+
+    /// Fetch open Dota 2 match-winner contracts as two-team markets
+    ///
+    /// A series that fails is skipped, but if every series fails the scan
+    /// fails, so the scanner keeps the venue's last known markets.
+    pub async fn fetch_dota2_markets(&self) -> Result<Vec<Market>> {
+        let mut markets = Vec::new();
+        let mut last_error = None;
+        let mut fetched = 0;
+
+        for series_ticker in &self.series_tickers {
+            match self.fetch_series(series_ticker).await {
+                Ok(series_markets) => {
+                    fetched += 1;
+                    markets.extend(series_markets);
+                }
+                Err(e) => {
+                    warn!("Failed to fetch Kalshi series {}: {}", series_ticker, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        if let Some(e) = last_error.filter(|_| fetched == 0) {
+            return Err(e.context("Every Kalshi series failed"));
+        }
+
+        info!(
+            "Total active Kalshi Dota 2 markets found: {}",
+            markets.len()
+        );
+        Ok(markets)
+    }
+
+    /// Fetch all open markets of a series and pair them by event
+    async fn fetch_series(&self, series_ticker: &str) -> Result<Vec<Market>> {
+        let mut by_event: BTreeMap<String, Vec<MarketResponse>> = BTreeMap::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut url = format!(
+                "{}/markets?series_ticker={}&status=open&limit=200",
+                self.base_url,
+                urlencoding::encode(series_ticker)
+            );
+            if let Some(cursor) = &cursor {
+                url.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+            }
+            debug!("Fetching Kalshi markets: {}", url);
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Accept", "application/json")
+                .send()
+                .await
+                .context("Failed to fetch Kalshi markets")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Kalshi API error: {} - {}", status, text);
+            }
+
+            let page: MarketsResponse = response
+                .json()
+                .await
+                .context("Failed to parse Kalshi markets response")?;
+
+            for market in page.markets {
+                by_event
+                    .entry(market.event_ticker.clone())
+                    .or_default()
+                    .push(market);
+            }
+
+            match page.cursor.filter(|c| !c.is_empty()) {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(by_event
+            .into_values()
+            .filter_map(|event| self.convert_event(event))
+            .collect())
+    }
+
+    /// Convert an event's two per-team contracts into one two-team market
+    ///
+    /// Team A is the first contract; its ticker identifies the market.
+    fn convert_event(&self, mut event: Vec<MarketResponse>) -> Option<Market> {
+        // Need exactly 2 contracts (one per team) for a match winner event
+        if event.len() != 2 || event.iter().any(|m| m.status != "active") {
+            return None;
+        }
+        event.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+
+        let (a, b) = (&event[0], &event[1]);
+        let team_a_odds = a.yes_probability()?;
+        let team_b_odds = b.yes_probability().unwrap_or(1.0 - team_a_odds);
+
+        let end_date = a
+            .close_time
+            .as_ref()
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+
+        let details = MarketQuestion::parse(&a.title);
+
+        let market = Market {
+            condition_id: a.ticker.clone(),
+            venue: Venue::Kalshi,
+            question: a.title.clone(),
+            team_a: a.yes_sub_title.trim().to_string(),
+            team_b: b.yes_sub_title.trim().to_string(),
+            team_a_odds,
+            team_b_odds,
+            opening_team_a_odds: team_a_odds,
+            opened_at: None,
+            team_a_bid: (a.yes_bid > 0.0).then(|| a.yes_bid / 100.0),
+            team_a_ask: (a.yes_ask > 0.0).then(|| a.yes_ask / 100.0),
+            liquidity: (a.liquidity + b.liquidity) / 100.0,
+            // Contracts trade below $1, so this overstates USD volume slightly
+            volume_24h: a.volume_24h.zip(b.volume_24h).map(|(a, b)| a + b),
+            end_date,
+            active: true,
+            url: Some(format!(
+                "https://kalshi.com/markets/{}",
+                a.event_ticker.to_lowercase()
+            )),
+            series_format: details.series_format,
+            game_number: details.game_number,
+            qualifiers: details.qualifiers,
+            outcomes: Vec::new(),
+        };
+
+        info!(
+            "Found Kalshi market: {} vs {} (odds: {:.0}% / {:.0}%)",
+            market.team_a,
+            market.team_b,
+            market.team_a_odds * 100.0,
+            market.team_b_odds * 100.0
+        );
+
+        Some(market)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::extract::Query;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Serve `/markets` for the `KXDOTA2GAME` series only; others get a 500
+    async fn mock_kalshi() -> String {
+        let markets = |Query(params): Query<HashMap<String, String>>| async move {
+            if params.get("series_ticker").map(String::as_str) != Some("KXDOTA2GAME") {
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            let contract = |ticker: &str, team: &str, bid: f64, ask: f64| {
+                json!({
+                    "ticker": ticker,
+                    "event_ticker": "KXDOTA2GAME-26OCT16SPIOG",
+                    "title": "Will Team Spirit beat OG?",
+                    "yes_sub_title": team,
+                    "yes_bid": bid,
+                    "yes_ask": ask,
+                    "liquidity": 500000.0,
+                    "status": "active",
+                })
+            };
+            Ok(Json(json!({
+                "markets": [
+                    contract("KXDOTA2GAME-26OCT16SPIOG-SPI", "Team Spirit", 60.0, 62.0),
+                    contract("KXDOTA2GAME-26OCT16SPIOG-OG", "OG", 38.0, 40.0),
+                ],
+                "cursor": "",
+            })))
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/markets", get(markets));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_scan_fails_only_when_every_series_fails() {
+        let base_url = mock_kalshi().await;
+        let client = |series: &[&str]| {
+            KalshiClient::new(&base_url, series.iter().map(|s| s.to_string()).collect())
+        };
+
+        let markets = client(&["KXDOTA2MAP", "KXDOTA2GAME"])
+            .fetch_dota2_markets()
+            .await
+            .unwrap();
+        assert_eq!(markets.len(), 1);
+        // Sorted by ticker, so OG's contract is team A
+        assert_eq!(markets[0].condition_id, "KXDOTA2GAME-26OCT16SPIOG-OG");
+        assert_eq!(markets[0].team_b, "Team Spirit");
+        assert!((markets[0].team_a_odds - 0.39).abs() < 1e-9);
+
+        assert!(client(&["KXDOTA2MAP"]).fetch_dota2_markets().await.is_err());
+        assert!(client(&[]).fetch_dota2_markets().await.unwrap().is_empty());
+    }
+}
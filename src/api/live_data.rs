@@ -1,14 +1,129 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use anyhow::{Context, Result};
-use chrono::Utc;
-use reqwest::Client;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Response, StatusCode};
 use serde::Deserialize;
-use tracing::{debug, info};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use super::{RateQuota, StratzClient};
+use crate::matching::MatchReconciler;
+use crate::model::lineup;
+use crate::models::{LeagueTier, LiveMatchState, PlayerState, TeamState};
+
+/// How long the current patch lookup is reused before refreshing
+const PATCH_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// How long hero roles are reused before refreshing; they change only with patches
+const HERO_CACHE_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// How long a player's recent averages are reused before refreshing
+const PLAYER_CACHE_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// Most uncached players looked up per poll, to stay inside OpenDota's
+/// rate limit when many games start at once
+const MAX_PLAYER_LOOKUPS_PER_POLL: usize = 10;
+
+/// How long league names and tiers are reused before refreshing
+const LEAGUE_CACHE_TTL: Duration = Duration::from_secs(24 * 3600);
 
-use crate::models::{LiveMatchState, TeamState};
+/// How long a team roster is reused before refreshing and diffing
+const ROSTER_CACHE_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// Most uncached team rosters looked up per poll
+const MAX_ROSTER_LOOKUPS_PER_POLL: usize = 4;
+
+/// Map of hero_id -> OpenDota roles
+type HeroRoles = Arc<HashMap<i32, Vec<String>>>;
+
+/// Map of league_id -> (name, tier)
+type Leagues = Arc<HashMap<i64, (String, Option<LeagueTier>)>>;
+
+/// A player's average (gold, XP) per minute over recent matches
+type PlayerAverages = (Option<f64>, Option<f64>);
+
+/// Account IDs of a team's current members
+type Roster = Arc<HashSet<i64>>;
 
 /// Client for live match data (using OpenDota API)
 pub struct LiveDataClient {
     client: Client,
+    /// Cached (patch_id, fetched_at) from OpenDota constants
+    current_patch: RwLock<Option<(i32, Instant)>>,
+    /// Cached (hero roles, fetched_at) from OpenDota constants
+    hero_roles: RwLock<Option<(HeroRoles, Instant)>>,
+    /// Cached (leagues, fetched_at) from OpenDota leagues
+    leagues: RwLock<Option<(Leagues, Instant)>>,
+    /// Cached (averages, fetched_at) by account ID from OpenDota players
+    player_averages: RwLock<HashMap<i64, (PlayerAverages, Instant)>>,
+    /// Cached (roster, fetched_at) by team ID from OpenDota teams
+    rosters: RwLock<HashMap<i64, (Roster, Instant)>>,
+    /// Quota reported by the last OpenDota response
+    quota: Mutex<Option<RateQuota>>,
+    /// OpenDota requests sent so far
+    requests: AtomicU64,
+    /// Enriches live matches with drafts, farm and objectives when configured
+    stratz: Option<StratzClient>,
+    /// Pairs STRATZ games with OpenDota's, including under another match ID
+    reconciler: Mutex<MatchReconciler>,
+}
+
+/// Patch entry from OpenDota `/constants/patch`
+#[derive(Debug, Deserialize)]
+struct PatchConstant {
+    id: i32,
+    name: String,
+}
+
+/// Hero entry from OpenDota `/constants/heroes`
+#[derive(Debug, Deserialize)]
+struct HeroConstant {
+    id: i32,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// League from OpenDota `/leagues`
+#[derive(Debug, Deserialize)]
+struct League {
+    leagueid: i64,
+    name: Option<String>,
+    tier: Option<String>,
+}
+
+/// Match from OpenDota `/players/{account_id}/recentMatches`
+#[derive(Debug, Deserialize)]
+struct RecentMatch {
+    gold_per_min: Option<f64>,
+    xp_per_min: Option<f64>,
+}
+
+/// Player from OpenDota `/teams/{team_id}/players`
+#[derive(Debug, Deserialize)]
+struct TeamPlayer {
+    account_id: i64,
+    #[serde(default)]
+    is_current_team_member: Option<bool>,
+}
+
+/// Finished match from OpenDota `/matches/{match_id}`
+#[derive(Debug, Deserialize)]
+struct MatchResult {
+    radiant_win: Option<bool>,
+}
+
+/// Player in an OpenDota live match
+#[derive(Debug, Deserialize)]
+struct OpenDotaLivePlayer {
+    account_id: Option<i64>,
+    #[serde(default)]
+    hero_id: i32,
+    /// 0 for Radiant, 1 for Dire
+    team: Option<i32>,
 }
 
 /// Live match from OpenDota API
@@ -16,6 +131,8 @@ pub struct LiveDataClient {
 struct OpenDotaLiveMatch {
     match_id: String,
     league_id: i64,
+    #[serde(default)]
+    series_id: Option<i64>,
     team_name_radiant: Option<String>,
     team_name_dire: Option<String>,
     team_id_radiant: Option<i64>,
@@ -25,6 +142,10 @@ struct OpenDotaLiveMatch {
     radiant_lead: Option<i64>,
     game_time: Option<i32>,
     building_state: Option<i64>,
+    /// Unix time OpenDota last refreshed the match
+    last_update_time: Option<i64>,
+    #[serde(default)]
+    players: Vec<OpenDotaLivePlayer>,
 }
 
 impl LiveDataClient {
@@ -32,7 +153,50 @@ impl LiveDataClient {
     pub fn new() -> Self {
         Self {
             client: Client::new(),
+            current_patch: RwLock::new(None),
+            hero_roles: RwLock::new(None),
+            leagues: RwLock::new(None),
+            player_averages: RwLock::new(HashMap::new()),
+            rosters: RwLock::new(HashMap::new()),
+            quota: Mutex::new(None),
+            requests: AtomicU64::new(0),
+            stratz: None,
+            reconciler: Mutex::default(),
+        }
+    }
+
+    /// Enrich live matches with STRATZ's view of the same games, and add
+    /// league games only STRATZ reports
+    pub fn with_stratz(mut self, stratz: StratzClient) -> Self {
+        self.stratz = Some(stratz);
+        self
+    }
+
+    /// Quota OpenDota reported on its last response, if any
+    pub fn quota(&self) -> Option<RateQuota> {
+        *self.quota.lock().unwrap()
+    }
+
+    /// OpenDota requests sent since the client was created
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// GET an OpenDota URL, recording the quota left on the response
+    async fn get(&self, url: &str) -> reqwest::Result<Response> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let response = self
+            .client
+            .get(url)
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+
+        if let Some(quota) = RateQuota::from_headers(response.headers()) {
+            debug!("OpenDota quota: {}", quota);
+            *self.quota.lock().unwrap() = Some(quota);
         }
+        Ok(response)
     }
 
     /// Fetch all live professional matches using OpenDota API
@@ -42,10 +206,7 @@ impl LiveDataClient {
         info!("Fetching live matches from OpenDota");
 
         let response = self
-            .client
             .get(url)
-            .header("Accept", "application/json")
-            .send()
             .await
             .context("Failed to fetch live matches from OpenDota")?;
 
@@ -61,7 +222,7 @@ impl LiveDataClient {
             .context("Failed to parse OpenDota live matches")?;
 
         // Filter for pro matches (league_id > 0 or has team names)
-        let pro_matches: Vec<LiveMatchState> = matches
+        let mut pro_matches: Vec<LiveMatchState> = matches
             .into_iter()
             .filter(|m| {
                 m.league_id > 0
@@ -73,28 +234,473 @@ impl LiveDataClient {
             .map(|m| self.convert_match(m))
             .collect();
 
+        if let Some(stratz) = &self.stratz {
+            match stratz.fetch_live_matches().await {
+                Ok(enriching) => self.reconciler.lock().unwrap().reconcile(
+                    &mut pro_matches,
+                    enriching,
+                    Instant::now(),
+                ),
+                // OpenDota alone still covers the games
+                Err(e) => warn!("Failed to fetch STRATZ live matches: {}", e),
+            }
+        }
+
+        self.attach_details(&mut pro_matches).await;
+        self.attach_player_averages(&mut pro_matches).await;
+        self.attach_stand_ins(&mut pro_matches).await;
+
         info!("OpenDota returned {} live pro matches", pro_matches.len());
 
         Ok(pro_matches)
     }
 
-    /// Fetch a specific match by ID
+    /// Whether `fetch_match` queries the one match rather than every live one
+    pub fn has_match_query(&self) -> bool {
+        self.stratz.is_some()
+    }
+
+    /// Fetch a specific match by ID; `None` once it's no longer live
+    ///
+    /// With STRATZ configured this is one STRATZ query for the match (under
+    /// the ID STRATZ reports it as), otherwise the whole OpenDota live feed.
     pub async fn fetch_match(&self, match_id: i64) -> Result<Option<LiveMatchState>> {
-        let matches = self.fetch_live_matches().await?;
-        Ok(matches.into_iter().find(|m| m.match_id == match_id))
+        let Some(stratz) = &self.stratz else {
+            let matches = self.fetch_live_matches().await?;
+            return Ok(matches.into_iter().find(|m| m.match_id == match_id));
+        };
+
+        let source_id = self.reconciler.lock().unwrap().source_id(match_id);
+        let Some(mut state) = stratz.fetch_match(source_id).await? else {
+            return Ok(None);
+        };
+        state.match_id = match_id;
+
+        let matches = std::slice::from_mut(&mut state);
+        self.attach_details(matches).await;
+        self.attach_player_averages(matches).await;
+        self.attach_stand_ins(matches).await;
+        Ok(Some(state))
+    }
+
+    /// Current patch ID, refreshed from OpenDota at most once per `PATCH_CACHE_TTL`
+    async fn current_patch(&self) -> Option<i32> {
+        let cached = *self.current_patch.read().await;
+
+        if let Some((patch, fetched_at)) = cached {
+            if fetched_at.elapsed() < PATCH_CACHE_TTL {
+                return Some(patch);
+            }
+        }
+
+        match self.fetch_current_patch().await {
+            Ok(patch) => {
+                *self.current_patch.write().await = Some((patch, Instant::now()));
+                Some(patch)
+            }
+            Err(e) => {
+                // Fall back to the stale value rather than dropping the patch tag
+                warn!("Failed to fetch current patch: {}", e);
+                cached.map(|(patch, _)| patch)
+            }
+        }
+    }
+
+    /// Hero roles, refreshed from OpenDota at most once per `HERO_CACHE_TTL`
+    async fn hero_roles(&self) -> Option<HeroRoles> {
+        let cached = self.hero_roles.read().await.clone();
+
+        if let Some((roles, fetched_at)) = &cached {
+            if fetched_at.elapsed() < HERO_CACHE_TTL {
+                return Some(Arc::clone(roles));
+            }
+        }
+
+        match self.fetch_hero_roles().await {
+            Ok(roles) => {
+                let roles = Arc::new(roles);
+                *self.hero_roles.write().await = Some((Arc::clone(&roles), Instant::now()));
+                Some(roles)
+            }
+            Err(e) => {
+                // Stale roles beat no lineup scores
+                warn!("Failed to fetch hero roles: {}", e);
+                cached.map(|(roles, _)| roles)
+            }
+        }
+    }
+
+    /// League names and tiers, refreshed from OpenDota at most once per
+    /// `LEAGUE_CACHE_TTL`
+    async fn leagues(&self) -> Option<Leagues> {
+        let cached = self.leagues.read().await.clone();
+
+        if let Some((leagues, fetched_at)) = &cached {
+            if fetched_at.elapsed() < LEAGUE_CACHE_TTL {
+                return Some(Arc::clone(leagues));
+            }
+        }
+
+        match self.fetch_leagues().await {
+            Ok(leagues) => {
+                let leagues = Arc::new(leagues);
+                *self.leagues.write().await = Some((Arc::clone(&leagues), Instant::now()));
+                Some(leagues)
+            }
+            Err(e) => {
+                // Stale names beat none; new leagues show up on the next refresh
+                warn!("Failed to fetch leagues: {}", e);
+                cached.map(|(leagues, _)| leagues)
+            }
+        }
+    }
+
+    /// Tag matches with the current patch, their league's name and tier, and
+    /// each lineup's late-game scaling
+    async fn attach_details(&self, matches: &mut [LiveMatchState]) {
+        // Live data has no patch field; all live games are on the current patch
+        let patch = self.current_patch().await;
+        let hero_roles = self.hero_roles().await;
+        let leagues = self.leagues().await;
+
+        for state in matches.iter_mut() {
+            state.patch = patch;
+
+            // Live data only has the league ID
+            let league = state
+                .league_id
+                .and_then(|id| leagues.as_deref().and_then(|leagues| leagues.get(&id)));
+            if let Some((name, tier)) = league {
+                state.league_name = Some(name.clone());
+                state.league_tier = *tier;
+            }
+
+            for team in [&mut state.radiant, &mut state.dire] {
+                team.late_game_scaling = hero_roles.as_deref().and_then(|roles| {
+                    lineup::team_scaling(
+                        team.players
+                            .iter()
+                            .filter_map(|p| roles.get(&p.hero_id))
+                            .map(Vec::as_slice),
+                    )
+                });
+            }
+        }
+    }
+
+    /// Fetch every league's name and tier from OpenDota
+    async fn fetch_leagues(&self) -> Result<HashMap<i64, (String, Option<LeagueTier>)>> {
+        let url = "https://api.opendota.com/api/leagues";
+
+        let response = self
+            .get(url)
+            .await
+            .context("Failed to fetch leagues from OpenDota")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenDota API error: {} - {}", status, text);
+        }
+
+        let leagues: Vec<League> = response
+            .json()
+            .await
+            .context("Failed to parse OpenDota leagues")?;
+
+        debug!("Loaded {} leagues", leagues.len());
+
+        Ok(leagues
+            .into_iter()
+            .filter_map(|league| {
+                let tier = league.tier.and_then(|t| t.parse().ok());
+                Some((league.leagueid, (league.name?, tier)))
+            })
+            .collect())
+    }
+
+    /// Fill in recent averages for players whose live GPM or XPM is known
+    ///
+    /// Players without live farm figures are skipped, since a deviation
+    /// can't be computed without them.
+    async fn attach_player_averages(&self, matches: &mut [LiveMatchState]) {
+        let mut lookups = 0;
+
+        for state in matches.iter_mut() {
+            for player in state
+                .radiant
+                .players
+                .iter_mut()
+                .chain(state.dire.players.iter_mut())
+            {
+                let Some(account_id) = player.account_id else {
+                    continue;
+                };
+                if player.gold_per_min.is_none() && player.xp_per_min.is_none() {
+                    continue;
+                }
+
+                let cached = self.player_averages.read().await.get(&account_id).copied();
+                let averages = match cached {
+                    Some((averages, fetched_at)) if fetched_at.elapsed() < PLAYER_CACHE_TTL => {
+                        Some(averages)
+                    }
+                    _ if lookups >= MAX_PLAYER_LOOKUPS_PER_POLL => {
+                        cached.map(|(averages, _)| averages)
+                    }
+                    _ => {
+                        lookups += 1;
+                        match self.fetch_player_averages(account_id).await {
+                            Ok(averages) => {
+                                self.player_averages
+                                    .write()
+                                    .await
+                                    .insert(account_id, (averages, Instant::now()));
+                                Some(averages)
+                            }
+                            Err(e) => {
+                                warn!("Failed to fetch averages for player {}: {}", account_id, e);
+                                cached.map(|(averages, _)| averages)
+                            }
+                        }
+                    }
+                };
+
+                if let Some((gold_per_min, xp_per_min)) = averages {
+                    player.avg_gold_per_min = gold_per_min;
+                    player.avg_xp_per_min = xp_per_min;
+                }
+            }
+        }
+    }
+
+    /// Count players missing from each team's current roster
+    ///
+    /// Teams without an ID, and rosters not yet fetched once this poll's
+    /// lookups are used up, leave `stand_ins` unset.
+    async fn attach_stand_ins(&self, matches: &mut [LiveMatchState]) {
+        let mut lookups = 0;
+
+        for state in matches.iter_mut() {
+            for team in [&mut state.radiant, &mut state.dire] {
+                let Some(team_id) = team.team_id.filter(|id| *id > 0) else {
+                    continue;
+                };
+
+                let cached = self.rosters.read().await.get(&team_id).cloned();
+                let roster = match cached {
+                    Some((roster, fetched_at)) if fetched_at.elapsed() < ROSTER_CACHE_TTL => {
+                        Some(roster)
+                    }
+                    _ if lookups >= MAX_ROSTER_LOOKUPS_PER_POLL => cached.map(|(roster, _)| roster),
+                    _ => {
+                        lookups += 1;
+                        match self.fetch_roster(team_id).await {
+                            Ok(roster) => {
+                                let roster = Arc::new(roster);
+                                if let Some((previous, _)) = &cached {
+                                    log_roster_change(&team.name, previous, &roster);
+                                }
+                                self.rosters
+                                    .write()
+                                    .await
+                                    .insert(team_id, (Arc::clone(&roster), Instant::now()));
+                                Some(roster)
+                            }
+                            Err(e) => {
+                                warn!("Failed to fetch roster for team {}: {}", team_id, e);
+                                cached.map(|(roster, _)| roster)
+                            }
+                        }
+                    }
+                };
+
+                // An empty roster means OpenDota doesn't track the team
+                team.stand_ins = roster.filter(|r| !r.is_empty()).map(|roster| {
+                    team.players
+                        .iter()
+                        .filter_map(|p| p.account_id)
+                        .filter(|id| !roster.contains(id))
+                        .count() as i32
+                });
+            }
+        }
+    }
+
+    /// Fetch the account IDs of a team's current members
+    async fn fetch_roster(&self, team_id: i64) -> Result<HashSet<i64>> {
+        let url = format!("https://api.opendota.com/api/teams/{}/players", team_id);
+
+        let response = self
+            .get(&url)
+            .await
+            .context("Failed to fetch team players from OpenDota")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenDota API error: {} - {}", status, text);
+        }
+
+        let players: Vec<TeamPlayer> = response
+            .json()
+            .await
+            .context("Failed to parse OpenDota team players")?;
+
+        Ok(players
+            .into_iter()
+            .filter(|p| p.is_current_team_member == Some(true))
+            .map(|p| p.account_id)
+            .collect())
+    }
+
+    /// Whether Radiant won a finished match; `None` until OpenDota has
+    /// the result
+    pub async fn fetch_match_result(&self, match_id: i64) -> Result<Option<bool>> {
+        let url = format!("https://api.opendota.com/api/matches/{}", match_id);
+
+        let response = self
+            .get(&url)
+            .await
+            .context("Failed to fetch match from OpenDota")?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenDota API error: {} - {}", status, text);
+        }
+
+        let result: MatchResult = response
+            .json()
+            .await
+            .context("Failed to parse OpenDota match")?;
+        Ok(result.radiant_win)
+    }
+
+    /// Fetch a player's average GPM and XPM over their recent matches
+    async fn fetch_player_averages(&self, account_id: i64) -> Result<PlayerAverages> {
+        let url = format!(
+            "https://api.opendota.com/api/players/{}/recentMatches",
+            account_id
+        );
+
+        let response = self
+            .get(&url)
+            .await
+            .context("Failed to fetch recent matches from OpenDota")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenDota API error: {} - {}", status, text);
+        }
+
+        let recent: Vec<RecentMatch> = response
+            .json()
+            .await
+            .context("Failed to parse OpenDota recent matches")?;
+
+        let mean = |values: Vec<f64>| {
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+
+        Ok((
+            mean(recent.iter().filter_map(|m| m.gold_per_min).collect()),
+            mean(recent.iter().filter_map(|m| m.xp_per_min).collect()),
+        ))
+    }
+
+    /// Fetch hero roles from OpenDota constants
+    async fn fetch_hero_roles(&self) -> Result<HashMap<i32, Vec<String>>> {
+        let url = "https://api.opendota.com/api/constants/heroes";
+
+        let response = self
+            .get(url)
+            .await
+            .context("Failed to fetch hero constants from OpenDota")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenDota API error: {} - {}", status, text);
+        }
+
+        let heroes: HashMap<String, HeroConstant> = response
+            .json()
+            .await
+            .context("Failed to parse OpenDota hero constants")?;
+
+        debug!("Loaded roles for {} heroes", heroes.len());
+
+        Ok(heroes
+            .into_values()
+            .map(|hero| (hero.id, hero.roles))
+            .collect())
+    }
+
+    /// Fetch the latest patch from OpenDota constants
+    async fn fetch_current_patch(&self) -> Result<i32> {
+        let url = "https://api.opendota.com/api/constants/patch";
+
+        let response = self
+            .get(url)
+            .await
+            .context("Failed to fetch patch constants from OpenDota")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenDota API error: {} - {}", status, text);
+        }
+
+        let patches: Vec<PatchConstant> = response
+            .json()
+            .await
+            .context("Failed to parse OpenDota patch constants")?;
+
+        let latest = patches
+            .into_iter()
+            .max_by_key(|p| p.id)
+            .context("OpenDota returned no patches")?;
+
+        debug!("Current patch: {} (id {})", latest.name, latest.id);
+
+        Ok(latest.id)
     }
 
     /// Convert OpenDota match to our model
+    ///
+    /// Patch, league name and lineup scaling are added by `attach_details`.
     fn convert_match(&self, data: OpenDotaLiveMatch) -> LiveMatchState {
         let match_id: i64 = data.match_id.parse().unwrap_or(0);
 
+        // Live data carries no per-player farm, so GPM/XPM stay unset
+        let players = |team: i32| -> Vec<PlayerState> {
+            data.players
+                .iter()
+                .filter(|p| p.team == Some(team))
+                .map(|p| PlayerState {
+                    account_id: p.account_id.filter(|id| *id > 0),
+                    hero_id: p.hero_id,
+                    ..Default::default()
+                })
+                .collect()
+        };
+        let radiant_players = players(0);
+        let dire_players = players(1);
+
         // Calculate building kills from building_state bitmask
         let (radiant_towers_killed, dire_towers_killed, radiant_rax_killed, dire_rax_killed) =
-            self.parse_building_state(data.building_state);
+            parse_building_state(data.building_state);
 
         LiveMatchState {
             match_id,
-            league_name: None, // OpenDota doesn't include league name in live data
+            league_id: Some(data.league_id).filter(|id| *id > 0),
+            league_name: None,
+            league_tier: None,
             radiant: TeamState {
                 name: data
                     .team_name_radiant
@@ -103,6 +709,13 @@ impl LiveDataClient {
                 kills: data.radiant_score.unwrap_or(0),
                 towers_killed: dire_towers_killed,
                 barracks_killed: dire_rax_killed,
+                // OpenDota live data has no aegis or Roshan events; STRATZ
+                // fills both in when configured
+                has_aegis: false,
+                bans: Vec::new(),
+                players: radiant_players,
+                late_game_scaling: None,
+                stand_ins: None,
             },
             dire: TeamState {
                 name: data.team_name_dire.unwrap_or_else(|| "Dire".to_string()),
@@ -110,44 +723,76 @@ impl LiveDataClient {
                 kills: data.dire_score.unwrap_or(0),
                 towers_killed: radiant_towers_killed,
                 barracks_killed: radiant_rax_killed,
+                has_aegis: false,
+                bans: Vec::new(),
+                players: dire_players,
+                late_game_scaling: None,
+                stand_ins: None,
             },
             gold_lead: data.radiant_lead.unwrap_or(0),
             game_time: data.game_time.unwrap_or(0),
+            patch: None,
+            // 0 means the game isn't part of a series
+            series_id: data.series_id.filter(|id| *id > 0),
+            game_number: None,
+            roshan_killed_at: None,
+            radiant_win_rate: None,
             is_live: true,
-            updated_at: Utc::now(),
+            updated_at: data
+                .last_update_time
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .unwrap_or_else(Utc::now),
         }
     }
+}
 
-    /// Parse building state bitmask
-    /// Returns: (radiant_towers_killed, dire_towers_killed, radiant_rax_killed, dire_rax_killed)
-    fn parse_building_state(&self, state: Option<i64>) -> (i32, i32, i32, i32) {
-        let state = match state {
-            Some(s) => s as u32,
-            None => return (0, 0, 0, 0),
-        };
+/// Parse building state bitmask
+/// Returns: (radiant_towers_killed, dire_towers_killed, radiant_rax_killed, dire_rax_killed)
+pub(super) fn parse_building_state(state: Option<i64>) -> (i32, i32, i32, i32) {
+    let state = match state {
+        Some(s) => s as u32,
+        None => return (0, 0, 0, 0),
+    };
+
+    // Building state format (from OpenDota):
+    // Bits 0-10: Radiant towers (11 towers)
+    // Bits 11-16: Radiant barracks (6 barracks)
+    // Bits 17-27: Dire towers (11 towers)
+    // Bits 28-33: Dire barracks (6 barracks)
+
+    let radiant_towers = state & 0x7FF; // bits 0-10
+    let radiant_rax = (state >> 11) & 0x3F; // bits 11-16
+    let dire_towers = (state >> 18) & 0x7FF; // bits 18-28
+    let dire_rax = (state >> 29) & 0x3F; // bits 29-34
+
+    // Count destroyed (0 bits = destroyed)
+    let radiant_towers_destroyed = 11 - radiant_towers.count_ones() as i32;
+    let dire_towers_destroyed = 11 - dire_towers.count_ones() as i32;
+    let radiant_rax_destroyed = 6 - radiant_rax.count_ones() as i32;
+    let dire_rax_destroyed = 6 - dire_rax.count_ones() as i32;
+
+    (
+        radiant_towers_destroyed,
+        dire_towers_destroyed,
+        radiant_rax_destroyed,
+        dire_rax_destroyed,
+    )
+}
+
+/// Log members who joined or left a team since its last roster fetch
+fn log_roster_change(team: &str, previous: &HashSet<i64>, current: &HashSet<i64>) {
+    let joined: Vec<&i64> = current.difference(previous).collect();
+    let left: Vec<&i64> = previous.difference(current).collect();
+    if !joined.is_empty() || !left.is_empty() {
+        info!(
+            "Roster change for {}: joined {:?}, left {:?}",
+            team, joined, left
+        );
+    }
+}
 
-        // Building state format (from OpenDota):
-        // Bits 0-10: Radiant towers (11 towers)
-        // Bits 11-16: Radiant barracks (6 barracks)
-        // Bits 17-27: Dire towers (11 towers)
-        // Bits 28-33: Dire barracks (6 barracks)
-
-        let radiant_towers = state & 0x7FF; // bits 0-10
-        let radiant_rax = (state >> 11) & 0x3F; // bits 11-16
-        let dire_towers = (state >> 18) & 0x7FF; // bits 18-28
-        let dire_rax = (state >> 29) & 0x3F; // bits 29-34
-
-        // Count destroyed (0 bits = destroyed)
-        let radiant_towers_destroyed = 11 - radiant_towers.count_ones() as i32;
-        let dire_towers_destroyed = 11 - dire_towers.count_ones() as i32;
-        let radiant_rax_destroyed = 6 - radiant_rax.count_ones() as i32;
-        let dire_rax_destroyed = 6 - dire_rax.count_ones() as i32;
-
-        (
-            radiant_towers_destroyed,
-            dire_towers_destroyed,
-            radiant_rax_destroyed,
-            dire_rax_destroyed,
-        )
+impl Default for LiveDataClient {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::models::{Market, Venue};
+
+use super::{AzuroClient, KalshiClient, PolymarketClient};
+
+/// A venue the market scanner pulls Dota 2 match-winner markets from
+#[async_trait]
+pub trait MarketSource: Send + Sync {
+    /// Venue the fetched markets trade on
+    fn venue(&self) -> Venue;
+
+    /// Fetch currently active two-team markets
+    async fn fetch_markets(&self) -> Result<Vec<Market>>;
+}
+
+#[async_trait]
+impl MarketSource for PolymarketClient {
+    fn venue(&self) -> Venue {
+        Venue::Polymarket
+    }
+
+    async fn fetch_markets(&self) -> Result<Vec<Market>> {
+        self.fetch_dota2_markets().await
+    }
+}
+
+#[async_trait]
+impl MarketSource for KalshiClient {
+    fn venue(&self) -> Venue {
+        Venue::Kalshi
+    }
+
+    async fn fetch_markets(&self) -> Result<Vec<Market>> {
+        self.fetch_dota2_markets().await
+    }
+}
+
+#[async_trait]
+impl MarketSource for AzuroClient {
+    fn venue(&self) -> Venue {
+        Venue::Azuro
+    }
+
+    async fn fetch_markets(&self) -> Result<Vec<Market>> {
+        self.fetch_dota2_markets().await
+    }
+}
@@ -1,8 +1,28 @@
+pub mod azuro;
+#[cfg(feature = "azuro")]
+pub mod azuro_bets;
+pub mod betfair;
+pub mod kalshi;
 pub mod live_data;
+pub mod market_source;
 pub mod opendota;
 pub mod opendota_historical;
+pub mod pinnacle;
 pub mod polymarket;
+pub mod polymarket_clob;
+pub mod quota;
+pub mod stratz;
 
+pub use azuro::AzuroClient;
+#[cfg(feature = "azuro")]
+pub use azuro_bets::{AzuroBetClient, BetOrder};
+pub use betfair::BetfairClient;
+pub use kalshi::KalshiClient;
 pub use live_data::LiveDataClient;
+pub use market_source::MarketSource;
 pub use opendota_historical::OpenDotaHistoricalClient;
-pub use polymarket::PolymarketClient;
+pub use pinnacle::PinnacleClient;
+pub use polymarket::{PolymarketClient, ResolvedMarket};
+pub use polymarket_clob::ClobClient;
+pub use quota::RateQuota;
+pub use stratz::StratzClient;
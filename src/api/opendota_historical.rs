@@ -34,6 +34,8 @@ pub struct MatchDetails {
     pub radiant_team: Option<TeamInfo>,
     pub dire_team: Option<TeamInfo>,
     pub league: Option<LeagueInfo>,
+    /// OpenDota patch ID (see `/constants/patch`)
+    pub patch: Option<i32>,
     pub radiant_gold_adv: Option<Vec<i32>>,
     pub radiant_xp_adv: Option<Vec<i32>>,
 }
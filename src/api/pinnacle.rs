@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{debug, info};
+
+/// Pinnacle sport ID for esports
+const ESPORTS_SPORT_ID: u32 = 12;
+
+/// Prefix of Pinnacle league names for Dota 2 events
+const DOTA2_LEAGUE_PREFIX: &str = "Dota 2";
+
+/// Client for the Pinnacle odds API
+pub struct PinnacleClient {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+/// A Dota 2 series moneyline with the margin removed
+#[derive(Debug, Clone)]
+pub struct MoneyLine {
+    pub home: String,
+    pub away: String,
+    pub league: String,
+
+    /// Implied home win probability, normalized so home + away = 1
+    pub home_prob: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MatchupResponse {
+    id: i64,
+    league: LeagueResponse,
+    #[serde(default)]
+    participants: Vec<ParticipantResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LeagueResponse {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParticipantResponse {
+    alignment: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketResponse {
+    matchup_id: i64,
+    #[serde(rename = "type")]
+    market_type: String,
+    period: i32,
+    #[serde(default)]
+    prices: Vec<PriceResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceResponse {
+    designation: Option<String>,
+    /// American odds
+    price: f64,
+}
+
+impl PinnacleClient {
+    /// Create a new Pinnacle client
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Fetch full-series moneylines for Dota 2 matchups
+    pub async fn fetch_dota2_moneylines(&self) -> Result<Vec<MoneyLine>> {
+        let matchups: Vec<MatchupResponse> = self
+            .get(&format!("sports/{}/matchups", ESPORTS_SPORT_ID))
+            .await
+            .context("Failed to fetch Pinnacle matchups")?;
+
+        let matchups: HashMap<i64, MatchupResponse> = matchups
+            .into_iter()
+            .filter(|m| m.league.name.starts_with(DOTA2_LEAGUE_PREFIX))
+            .map(|m| (m.id, m))
+            .collect();
+
+        debug!("Found {} Pinnacle Dota 2 matchups", matchups.len());
+
+        let markets: Vec<MarketResponse> = self
+            .get(&format!(
+                "sports/{}/markets/straight?primaryOnly=true",
+                ESPORTS_SPORT_ID
+            ))
+            .await
+            .context("Failed to fetch Pinnacle markets")?;
+
+        let lines: Vec<MoneyLine> = markets
+            .iter()
+            // Period 0 is the full series
+            .filter(|m| m.market_type == "moneyline" && m.period == 0)
+            .filter_map(|m| {
+                let matchup = matchups.get(&m.matchup_id)?;
+                let participant = |alignment: &str| {
+                    matchup
+                        .participants
+                        .iter()
+                        .find(|p| p.alignment == alignment)
+                        .map(|p| p.name.clone())
+                };
+                let price = |designation: &str| {
+                    m.prices
+                        .iter()
+                        .find(|p| p.designation.as_deref() == Some(designation))
+                        .map(|p| p.price)
+                };
+
+                Some(MoneyLine {
+                    home: participant("home")?,
+                    away: participant("away")?,
+                    league: matchup.league.name.clone(),
+                    home_prob: no_vig_probability(price("home")?, price("away")?)?,
+                })
+            })
+            .collect();
+
+        info!("Fetched {} Pinnacle Dota 2 moneylines", lines.len());
+        Ok(lines)
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/{}", self.base_url, path);
+        debug!("Fetching Pinnacle: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Accept", "application/json")
+            .header("X-API-Key", &self.api_key)
+            .send()
+            .await
+            .context("Pinnacle request failed")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Pinnacle API error: {} - {}", status, text);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse Pinnacle response")
+    }
+}
+
+/// Convert American odds to decimal odds
+pub fn american_to_decimal(price: f64) -> Option<f64> {
+    if price >= 100.0 {
+        Some(1.0 + price / 100.0)
+    } else if price <= -100.0 {
+        Some(1.0 + 100.0 / -price)
+    } else {
+        None
+    }
+}
+
+/// Home win probability from a two-way American moneyline, margin removed
+pub fn no_vig_probability(home_price: f64, away_price: f64) -> Option<f64> {
+    let home = 1.0 / american_to_decimal(home_price)?;
+    let away = 1.0 / american_to_decimal(away_price)?;
+
+    Some(home / (home + away))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_vig_probability() {
+        assert_eq!(american_to_decimal(150.0), Some(2.5));
+        assert_eq!(american_to_decimal(-200.0), Some(1.5));
+        assert_eq!(american_to_decimal(50.0), None);
+
+        // -110 / -110 is an even match once the margin is removed
+        assert!((no_vig_probability(-110.0, -110.0).unwrap() - 0.5).abs() < 1e-9);
+
+        let p = no_vig_probability(-200.0, 170.0).unwrap();
+        assert!(p > 0.64 && p < 0.66);
+    }
+}
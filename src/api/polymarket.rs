@@ -1,16 +1,43 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use anyhow::{Context, Result};
-use reqwest::Client;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::stream::{self, StreamExt};
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use tracing::{debug, info, warn};
 
-use crate::models::PolymarketMarket;
+use crate::matching::MarketQuestion;
+use crate::models::{Market, MarketOutcome, Venue};
 
 const DOTA2_SERIES_ID: &str = "10309";
 
+/// Events fetched at once during a scan
+const EVENT_FETCH_CONCURRENCY: usize = 5;
+
+/// How long a response is reused without asking the API again
+const RESPONSE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// How long an unrequested response is kept for revalidation
+const RESPONSE_CACHE_RETENTION: Duration = Duration::from_secs(3600);
+
 /// Client for Polymarket Gamma API
 pub struct PolymarketClient {
     client: Client,
     base_url: String,
+    /// Recent response bodies by URL, revalidated by ETag once stale
+    responses: Mutex<HashMap<String, CachedResponse>>,
+}
+
+/// A response body kept for reuse
+struct CachedResponse {
+    body: Arc<str>,
+    etag: Option<String>,
+    /// When the body was last fetched or confirmed unchanged
+    checked_at: Instant,
 }
 
 /// Series response from Polymarket (events list only)
@@ -33,10 +60,18 @@ struct SeriesEvent {
 #[serde(rename_all = "camelCase")]
 struct EventResponse {
     id: String,
+    #[serde(default)]
     title: String,
     active: bool,
     closed: bool,
     #[serde(default)]
+    slug: Option<String>,
+    /// One Yes/No market per contender, exactly one of which resolves Yes
+    #[serde(default)]
+    neg_risk: bool,
+    #[serde(rename = "negRiskMarketID", default)]
+    neg_risk_market_id: Option<String>,
+    #[serde(default)]
     markets: Vec<MarketResponse>,
 }
 
@@ -55,6 +90,29 @@ struct MarketResponse {
     end_date_iso: Option<String>,
     #[serde(default)]
     sports_market_type: Option<String>,
+    /// Contender name within a negRisk event
+    #[serde(default)]
+    group_item_title: Option<String>,
+    #[serde(default)]
+    volume_24hr: Option<f64>,
+    /// Top of the first outcome's order book
+    #[serde(default)]
+    best_bid: Option<f64>,
+    #[serde(default)]
+    best_ask: Option<f64>,
+    /// JSON string array of CLOB token IDs, one per outcome
+    #[serde(default)]
+    clob_token_ids: Option<String>,
+}
+
+/// A settled match-winner market
+#[derive(Debug, Clone)]
+pub struct ResolvedMarket {
+    /// The market as last listed; team odds are the settlement prices
+    pub market: Market,
+    /// CLOB token of the team A outcome, for price history
+    pub team_a_token_id: String,
+    pub team_a_won: bool,
 }
 
 impl PolymarketClient {
@@ -63,38 +121,16 @@ impl PolymarketClient {
         Self {
             client: Client::new(),
             base_url: base_url.to_string(),
+            responses: Mutex::new(HashMap::new()),
         }
     }
 
     /// Fetch active Dota 2 markets from Polymarket sports series
-    pub async fn fetch_dota2_markets(&self) -> Result<Vec<PolymarketMarket>> {
-        // Step 1: Get list of events from series
-        let series_url = format!("{}/series/{}", self.base_url, DOTA2_SERIES_ID);
-        debug!("Fetching Dota 2 series from: {}", series_url);
-
-        let response = self
-            .client
-            .get(&series_url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .context("Failed to fetch Dota 2 series")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            warn!("Polymarket API error: {} - {}", status, text);
-            return Ok(Vec::new());
-        }
-
-        let series: SeriesResponse = response
-            .json()
-            .await
-            .context("Failed to parse Dota 2 series response")?;
-
-        // Step 2: Filter active events and fetch each one for markets
-        let active_event_ids: Vec<String> = series
-            .events
+    pub async fn fetch_dota2_markets(&self) -> Result<Vec<Market>> {
+        // Step 1: Filter active events and fetch each one for markets
+        let active_event_ids: Vec<String> = self
+            .fetch_series_events()
+            .await?
             .into_iter()
             .filter(|e| e.active && !e.closed)
             .map(|e| e.id)
@@ -102,11 +138,19 @@ impl PolymarketClient {
 
         debug!("Found {} active events", active_event_ids.len());
 
-        let mut markets = Vec::new();
+        // Step 2: Fetch each event to get its markets, a few at a time
+        let results: Vec<_> = stream::iter(active_event_ids)
+            .map(|event_id| async move {
+                let result = self.fetch_event_markets(&event_id).await;
+                (event_id, result)
+            })
+            .buffer_unordered(EVENT_FETCH_CONCURRENCY)
+            .collect()
+            .await;
 
-        // Fetch each event to get its markets
-        for event_id in active_event_ids {
-            match self.fetch_event_markets(&event_id).await {
+        let mut markets = Vec::new();
+        for (event_id, result) in results {
+            match result {
                 Ok(event_markets) => markets.extend(event_markets),
                 Err(e) => {
                     warn!("Failed to fetch event {}: {}", event_id, e);
@@ -118,34 +162,181 @@ impl PolymarketClient {
         Ok(markets)
     }
 
-    /// Fetch markets for a specific event
-    async fn fetch_event_markets(&self, event_id: &str) -> Result<Vec<PolymarketMarket>> {
+    /// Fetch settled Dota 2 match-winner markets, oldest event first
+    pub async fn fetch_resolved_dota2_markets(&self) -> Result<Vec<ResolvedMarket>> {
+        let mut closed_event_ids: Vec<String> = self
+            .fetch_series_events()
+            .await?
+            .into_iter()
+            .filter(|e| e.closed)
+            .map(|e| e.id)
+            .collect();
+        closed_event_ids.sort_by_key(|id| id.parse::<u64>().unwrap_or(u64::MAX));
+
+        debug!("Found {} closed events", closed_event_ids.len());
+
+        let mut resolved = Vec::new();
+
+        for event_id in closed_event_ids {
+            let event = match self.fetch_event(&event_id).await {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Failed to fetch event {}: {}", event_id, e);
+                    continue;
+                }
+            };
+            if event.neg_risk {
+                continue;
+            }
+
+            for market in event.markets {
+                let is_moneyline = market.sports_market_type.as_deref() == Some("moneyline");
+                if !is_moneyline || !market.closed {
+                    continue;
+                }
+
+                let token_ids: Option<Vec<String>> = market
+                    .clob_token_ids
+                    .as_deref()
+                    .and_then(|ids| serde_json::from_str(ids).ok());
+                let Some(team_a_token_id) = token_ids.and_then(|ids| ids.into_iter().next()) else {
+                    continue;
+                };
+
+                if let Some(market) = self.convert_market(market) {
+                    resolved.push(ResolvedMarket {
+                        team_a_won: market.team_a_odds > 0.5,
+                        market,
+                        team_a_token_id,
+                    });
+                }
+            }
+        }
+
+        info!("Total resolved Dota 2 markets found: {}", resolved.len());
+        Ok(resolved)
+    }
+
+    /// Events listed in the Dota 2 sports series
+    async fn fetch_series_events(&self) -> Result<Vec<SeriesEvent>> {
+        let series_url = format!("{}/series/{}", self.base_url, DOTA2_SERIES_ID);
+        debug!("Fetching Dota 2 series from: {}", series_url);
+
+        let body = match self
+            .get_cached(&series_url)
+            .await
+            .context("Failed to fetch Dota 2 series")?
+        {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Polymarket API error: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+
+        let series: SeriesResponse =
+            serde_json::from_str(&body).context("Failed to parse Dota 2 series response")?;
+
+        Ok(series.events)
+    }
+
+    /// Fetch one event with its markets
+    async fn fetch_event(&self, event_id: &str) -> Result<EventResponse> {
         let url = format!("{}/events/{}", self.base_url, event_id);
         debug!("Fetching event: {}", url);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+        let body = match self
+            .get_cached(&url)
             .await
-            .context("Failed to fetch event")?;
+            .context("Failed to fetch event")?
+        {
+            Ok(body) => body,
+            Err(e) => anyhow::bail!("Event API error: {}", e),
+        };
+
+        serde_json::from_str(&body).context("Failed to parse event response")
+    }
+
+    /// GET a URL's body, reusing a response younger than
+    /// `RESPONSE_CACHE_TTL` and otherwise revalidating it with its ETag;
+    /// the inner error is the status and text of a failed request
+    async fn get_cached(&self, url: &str) -> Result<std::result::Result<Arc<str>, String>> {
+        let (etag, cached) = {
+            let responses = self.responses.lock().unwrap();
+            match responses.get(url) {
+                Some(cached) if cached.checked_at.elapsed() < RESPONSE_CACHE_TTL => {
+                    debug!("Using cached response for {}", url);
+                    return Ok(Ok(Arc::clone(&cached.body)));
+                }
+                Some(cached) => (cached.etag.clone(), Some(Arc::clone(&cached.body))),
+                None => (None, None),
+            }
+        };
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Event API error: {} - {}", status, text);
+        let mut request = self.client.get(url).header("Accept", "application/json");
+        if let Some(etag) = &etag {
+            request = request.header(IF_NONE_MATCH, etag);
         }
+        let response = request.send().await?;
 
-        let event: EventResponse = response
-            .json()
-            .await
-            .context("Failed to parse event response")?;
+        let status = response.status();
+        let (body, etag) = match cached {
+            Some(body) if status == StatusCode::NOT_MODIFIED => {
+                debug!("{} unchanged", url);
+                (body, etag)
+            }
+            _ if !status.is_success() => {
+                let text = response.text().await.unwrap_or_default();
+                return Ok(Err(format!("{} - {}", status, text)));
+            }
+            _ => {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                (Arc::from(response.text().await?), etag)
+            }
+        };
+
+        let mut responses = self.responses.lock().unwrap();
+        responses.retain(|_, cached| cached.checked_at.elapsed() < RESPONSE_CACHE_RETENTION);
+        responses.insert(
+            url.to_string(),
+            CachedResponse {
+                body: Arc::clone(&body),
+                etag,
+                checked_at: Instant::now(),
+            },
+        );
+        Ok(Ok(body))
+    }
+
+    /// Fetch markets for a specific event
+    async fn fetch_event_markets(&self, event_id: &str) -> Result<Vec<Market>> {
+        let event = self.fetch_event(event_id).await?;
 
         if !event.active || event.closed {
             return Ok(Vec::new());
         }
 
+        let url = event
+            .slug
+            .as_ref()
+            .map(|slug| format!("https://polymarket.com/event/{}", slug));
+
+        if event.neg_risk {
+            let outright = self.convert_outright(event).map(|pm| Market { url, ..pm });
+            if let Some(pm) = &outright {
+                info!(
+                    "Found outright market: {} ({} contenders)",
+                    pm.question,
+                    pm.outcomes.len()
+                );
+            }
+            return Ok(outright.into_iter().collect());
+        }
+
         let mut markets = Vec::new();
 
         for market in event.markets {
@@ -160,7 +351,8 @@ impl PolymarketClient {
                 continue;
             }
 
-            if let Some(pm) = self.convert_market(market) {
+            if let Some(mut pm) = self.convert_market(market) {
+                pm.url = url.clone();
                 info!(
                     "Found market: {} vs {} (odds: {:.0}% / {:.0}%)",
                     pm.team_a,
@@ -176,7 +368,7 @@ impl PolymarketClient {
     }
 
     /// Convert API market response to our model
-    fn convert_market(&self, market: MarketResponse) -> Option<PolymarketMarket> {
+    fn convert_market(&self, market: MarketResponse) -> Option<Market> {
         // Parse JSON string arrays
         let outcomes: Vec<String> = serde_json::from_str(&market.outcomes).ok()?;
         let outcome_prices: Vec<String> = serde_json::from_str(&market.outcome_prices).ok()?;
@@ -186,41 +378,127 @@ impl PolymarketClient {
             return None;
         }
 
-        let team_a = outcomes.get(0)?.trim().to_string();
+        let team_a = outcomes.first()?.trim().to_string();
         let team_b = outcomes.get(1)?.trim().to_string();
 
-        let team_a_odds: f64 = outcome_prices.get(0)?.parse().ok()?;
+        let team_a_odds: f64 = outcome_prices.first()?.parse().ok()?;
         let team_b_odds: f64 = outcome_prices.get(1)?.parse().ok()?;
 
-        let liquidity: f64 = market
-            .liquidity_num
-            .or_else(|| market.liquidity.as_ref().and_then(|l| l.parse().ok()))
-            .unwrap_or(0.0);
+        let liquidity = market.liquidity_usd();
+        let end_date = parse_end_date(market.end_date_iso.as_deref());
 
-        let end_date = market
-            .end_date_iso
-            .as_ref()
-            .and_then(|d| {
-                chrono::DateTime::parse_from_rfc3339(d)
-                    .ok()
-                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                    .or_else(|| {
-                        chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
-                            .ok()
-                            .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
-                    })
-            });
+        let details = MarketQuestion::parse(&market.question);
 
-        Some(PolymarketMarket {
+        Some(Market {
             condition_id: market.condition_id,
+            venue: Venue::Polymarket,
             question: market.question,
             team_a,
             team_b,
             team_a_odds,
             team_b_odds,
+            opening_team_a_odds: team_a_odds,
+            opened_at: None,
+            // Gamma quotes the book of the first outcome, which is team A
+            team_a_bid: market.best_bid.filter(|p| *p > 0.0),
+            team_a_ask: market.best_ask.filter(|p| *p > 0.0),
             liquidity,
+            volume_24h: market.volume_24hr,
             end_date,
             active: market.active && !market.closed,
+            url: None,
+            series_format: details.series_format,
+            game_number: details.game_number,
+            qualifiers: details.qualifiers,
+            outcomes: Vec::new(),
         })
     }
+
+    /// Convert a negRisk event to one market with an outcome per contender
+    ///
+    /// Each contender's price is the Yes price of its own market; contenders
+    /// whose market has closed (eliminated) are left out.
+    fn convert_outright(&self, event: EventResponse) -> Option<Market> {
+        let mut outcomes = Vec::new();
+        let mut liquidity = 0.0;
+        let mut end_date = None;
+
+        for market in &event.markets {
+            if !market.active || market.closed {
+                continue;
+            }
+            let Some(name) = market.group_item_title.as_ref().filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            let price = serde_json::from_str::<Vec<String>>(&market.outcome_prices)
+                .ok()
+                .and_then(|prices| prices.first()?.parse::<f64>().ok());
+            let Some(price) = price else {
+                continue;
+            };
+
+            outcomes.push(MarketOutcome {
+                name: name.trim().to_string(),
+                price,
+                condition_id: market.condition_id.clone(),
+            });
+            liquidity += market.liquidity_usd();
+            end_date = end_date.or_else(|| parse_end_date(market.end_date_iso.as_deref()));
+        }
+
+        // Two contenders left is still an outright, but one is settled
+        if outcomes.len() < 2 {
+            return None;
+        }
+        outcomes.sort_by(|a, b| b.price.total_cmp(&a.price));
+
+        let details = MarketQuestion::parse(&event.title);
+
+        Some(Market {
+            condition_id: event
+                .neg_risk_market_id
+                .unwrap_or_else(|| format!("event-{}", event.id)),
+            venue: Venue::Polymarket,
+            question: event.title,
+            team_a: String::new(),
+            team_b: String::new(),
+            team_a_odds: 0.0,
+            team_b_odds: 0.0,
+            opening_team_a_odds: 0.0,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity,
+            volume_24h: None,
+            end_date,
+            active: true,
+            url: None,
+            series_format: details.series_format,
+            game_number: details.game_number,
+            qualifiers: details.qualifiers,
+            outcomes,
+        })
+    }
+}
+
+impl MarketResponse {
+    /// Liquidity in USD, from whichever field is set
+    fn liquidity_usd(&self) -> f64 {
+        self.liquidity_num
+            .or_else(|| self.liquidity.as_ref().and_then(|l| l.parse().ok()))
+            .unwrap_or(0.0)
+    }
+}
+
+/// Parse an end date given as RFC 3339 or a bare date
+fn parse_end_date(date: Option<&str>) -> Option<DateTime<Utc>> {
+    let date = date?;
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc())
+        })
 }
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::models::PricePoint;
+
+/// Client for the Polymarket CLOB API
+pub struct ClobClient {
+    client: Client,
+    base_url: String,
+}
+
+/// Response from `/prices-history`
+#[derive(Debug, Deserialize)]
+struct PriceHistoryResponse {
+    #[serde(default)]
+    history: Vec<HistoryPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryPoint {
+    /// Unix timestamp in seconds
+    t: i64,
+    p: f64,
+}
+
+impl ClobClient {
+    /// Create a new CLOB client
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.to_string(),
+        }
+    }
+
+    /// Full price history of an outcome token, oldest first, sampled every
+    /// `fidelity_minutes`
+    pub async fn fetch_price_history(
+        &self,
+        token_id: &str,
+        fidelity_minutes: u32,
+    ) -> Result<Vec<PricePoint>> {
+        let url = format!("{}/prices-history", self.base_url);
+        debug!("Fetching price history for token {}", token_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("market", token_id),
+                ("interval", "max"),
+                ("fidelity", &fidelity_minutes.to_string()),
+            ])
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .context("Failed to fetch price history")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("CLOB API error: {} - {}", status, text);
+        }
+
+        let body: PriceHistoryResponse = response
+            .json()
+            .await
+            .context("Failed to parse price history")?;
+
+        let mut points: Vec<PricePoint> = body
+            .history
+            .into_iter()
+            .filter_map(|point| {
+                Some(PricePoint {
+                    timestamp: DateTime::from_timestamp(point.t, 0)?,
+                    price: point.p,
+                })
+            })
+            .collect();
+        points.sort_by_key(|point| point.timestamp);
+
+        Ok(points)
+    }
+}
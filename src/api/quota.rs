@@ -0,0 +1,132 @@
+//! OpenDota rate-limit quota, read from response headers
+//!
+//! Every OpenDota response reports the calls left this minute and this
+//! (UTC) day. The live fetcher spreads the rest of the daily quota over the
+//! rest of the day and sits out the minute window once it runs dry, so a
+//! busy day slows polling down instead of hitting 429s mid-game.
+
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Days, Utc};
+use reqwest::header::HeaderMap;
+
+/// Calls left in the current minute
+const MINUTE_HEADER: &str = "x-rate-limit-remaining-minute";
+
+/// Calls left in the current day
+const DAY_HEADER: &str = "x-rate-limit-remaining-day";
+
+/// Length of the per-minute window
+const MINUTE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Calls left in OpenDota's rate-limit windows, as of the last response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateQuota {
+    pub remaining_minute: Option<u32>,
+    pub remaining_day: Option<u32>,
+}
+
+impl RateQuota {
+    /// Quota reported by a response; None when it carries neither header
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok())
+        };
+        let quota = Self {
+            remaining_minute: header(MINUTE_HEADER),
+            remaining_day: header(DAY_HEADER),
+        };
+        (quota != Self::default()).then_some(quota)
+    }
+
+    /// Poll interval that keeps `calls_per_poll` inside the quota: at least
+    /// `base`, a full minute once the minute window can't cover another
+    /// poll, and the rest of the day split evenly over the polls left in it
+    pub fn paced_interval(
+        &self,
+        base: Duration,
+        calls_per_poll: u32,
+        now: DateTime<Utc>,
+    ) -> Duration {
+        let calls_per_poll = calls_per_poll.max(1);
+        let mut interval = base;
+
+        if self
+            .remaining_minute
+            .is_some_and(|left| left < calls_per_poll)
+        {
+            interval = interval.max(MINUTE_WINDOW);
+        }
+
+        if let Some(left) = self.remaining_day {
+            let midnight = (now.date_naive() + Days::new(1))
+                .and_hms_opt(0, 0, 0)
+                .expect("midnight is a valid time")
+                .and_utc();
+            let until_reset = (midnight - now).to_std().unwrap_or_default();
+            let polls_left = left / calls_per_poll;
+            let paced = if polls_left == 0 {
+                until_reset
+            } else {
+                until_reset / polls_left
+            };
+            interval = interval.max(paced);
+        }
+
+        interval
+    }
+}
+
+impl fmt::Display for RateQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let left = |n: Option<u32>| n.map_or_else(|| "?".to_string(), |n| n.to_string());
+        write!(
+            f,
+            "{}/minute, {}/day left",
+            left(self.remaining_minute),
+            left(self.remaining_day)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_quota_paces_polling() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateQuota::from_headers(&headers), None);
+        headers.insert(MINUTE_HEADER, HeaderValue::from_static("3"));
+        headers.insert(DAY_HEADER, HeaderValue::from_static("1200"));
+        let quota = RateQuota::from_headers(&headers).unwrap();
+        assert_eq!(quota.to_string(), "3/minute, 1200/day left");
+
+        // 12 hours left: 1200 calls at 2 per poll is a poll every 72s
+        let noon = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let base = Duration::from_secs(10);
+        assert_eq!(quota.paced_interval(base, 2, noon), Duration::from_secs(72));
+
+        // Plenty left for the day, but not for another poll this minute
+        let late = Utc.with_ymd_and_hms(2026, 3, 1, 23, 0, 0).unwrap();
+        assert_eq!(quota.paced_interval(base, 2, late), base);
+        assert_eq!(quota.paced_interval(base, 5, late), MINUTE_WINDOW);
+
+        // Nothing left waits for the daily reset
+        let empty = RateQuota {
+            remaining_minute: None,
+            remaining_day: Some(0),
+        };
+        assert_eq!(
+            empty.paced_interval(base, 2, late),
+            Duration::from_secs(3600)
+        );
+    }
+}
@@ -0,0 +1,458 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+use super::live_data::parse_building_state;
+use crate::models::{LiveMatchState, PlayerState, TeamState};
+
+/// Live matches fetched per query
+const MAX_MATCHES: u32 = 100;
+
+/// Item ID of the Aegis of the Immortal
+const AEGIS_ITEM_ID: i32 = 117;
+
+/// STRATZ sits behind Cloudflare, which lets API tokens through only with
+/// this user agent
+const USER_AGENT: &str = "STRATZ_API";
+
+const LIVE_MATCHES_QUERY: &str = r#"
+query LiveMatches($take: Int!) {
+  live {
+    matches(request: { isCompleted: false, take: $take }) {
+      ...LiveMatch
+    }
+  }
+}
+"#;
+
+const LIVE_MATCH_QUERY: &str = r#"
+query LiveMatch($id: Long!) {
+  live {
+    match(id: $id) {
+      ...LiveMatch
+    }
+  }
+}
+"#;
+
+/// Everything the signal model reads from a live game, in one round trip
+const LIVE_MATCH_FRAGMENT: &str = r#"
+fragment LiveMatch on MatchLiveType {
+  matchId
+  leagueId
+  gameTime
+  radiantScore
+  direScore
+  buildingState
+  modifiedDateTime
+  radiantTeam { id name }
+  direTeam { id name }
+  winRateValues
+  players {
+    steamAccountId
+    heroId
+    isRadiant
+    networth
+    goldPerMinute
+    experiencePerMinute
+    itemId0 itemId1 itemId2 itemId3 itemId4 itemId5
+  }
+  playbackData {
+    pickBans { heroId isPick isRadiant order }
+    roshanEvents { time isAlive }
+  }
+}
+"#;
+
+/// Client for the STRATZ GraphQL API, used to enrich OpenDota's live feed
+/// with drafts, player farm, Roshan kills and STRATZ's win-rate estimate
+pub struct StratzClient {
+    client: Client,
+    api_url: String,
+    api_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveMatchesData {
+    live: Option<LiveMatches>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveMatches {
+    #[serde(default)]
+    matches: Vec<StratzLiveMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveMatchData {
+    live: Option<LiveMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveMatch {
+    #[serde(rename = "match")]
+    live_match: Option<StratzLiveMatch>,
+}
+
+/// Live match from STRATZ `live.matches`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StratzLiveMatch {
+    match_id: i64,
+    league_id: Option<i64>,
+    game_time: Option<i32>,
+    radiant_score: Option<i32>,
+    dire_score: Option<i32>,
+    building_state: Option<i64>,
+    /// Unix time STRATZ last refreshed the match
+    modified_date_time: Option<i64>,
+    radiant_team: Option<StratzTeam>,
+    dire_team: Option<StratzTeam>,
+    /// Radiant's win probability, one value per game minute
+    #[serde(default)]
+    win_rate_values: Vec<f64>,
+    #[serde(default)]
+    players: Vec<StratzLivePlayer>,
+    playback_data: Option<PlaybackData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StratzTeam {
+    id: Option<i64>,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StratzLivePlayer {
+    steam_account_id: Option<i64>,
+    #[serde(default)]
+    hero_id: i32,
+    is_radiant: Option<bool>,
+    networth: Option<i64>,
+    gold_per_minute: Option<f64>,
+    experience_per_minute: Option<f64>,
+    item_id0: Option<i32>,
+    item_id1: Option<i32>,
+    item_id2: Option<i32>,
+    item_id3: Option<i32>,
+    item_id4: Option<i32>,
+    item_id5: Option<i32>,
+}
+
+impl StratzLivePlayer {
+    /// Whether the aegis is in the player's inventory
+    fn has_aegis(&self) -> bool {
+        [
+            self.item_id0,
+            self.item_id1,
+            self.item_id2,
+            self.item_id3,
+            self.item_id4,
+            self.item_id5,
+        ]
+        .contains(&Some(AEGIS_ITEM_ID))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaybackData {
+    #[serde(default)]
+    pick_bans: Vec<PickBan>,
+    #[serde(default)]
+    roshan_events: Vec<RoshanEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PickBan {
+    hero_id: Option<i32>,
+    is_pick: bool,
+    is_radiant: Option<bool>,
+    #[serde(default)]
+    order: i32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoshanEvent {
+    /// Game time in seconds
+    time: i32,
+    is_alive: bool,
+}
+
+impl StratzClient {
+    /// Create a new STRATZ client
+    pub fn new(api_url: &str, api_token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: api_url.to_string(),
+            api_token: api_token.to_string(),
+        }
+    }
+
+    /// Fetch live league matches with their drafts, players and objectives
+    pub async fn fetch_live_matches(&self) -> Result<Vec<LiveMatchState>> {
+        let data: LiveMatchesData = self
+            .query(LIVE_MATCHES_QUERY, json!({ "take": MAX_MATCHES }))
+            .await?;
+
+        let matches: Vec<LiveMatchState> = data
+            .live
+            .map(|live| live.matches)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| m.league_id.is_some_and(|id| id > 0))
+            .map(convert_match)
+            .collect();
+
+        info!("STRATZ returned {} live league matches", matches.len());
+        Ok(matches)
+    }
+
+    /// Fetch one live match by ID; `None` once it's no longer live
+    pub async fn fetch_match(&self, match_id: i64) -> Result<Option<LiveMatchState>> {
+        let data: LiveMatchData = self
+            .query(LIVE_MATCH_QUERY, json!({ "id": match_id }))
+            .await?;
+
+        Ok(data
+            .live
+            .and_then(|live| live.live_match)
+            .map(convert_match))
+    }
+
+    /// Run a query with the live match fragment appended
+    async fn query<T: DeserializeOwned>(&self, query: &str, variables: Value) -> Result<T> {
+        debug!("Querying STRATZ: {}", self.api_url);
+
+        let response = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(&self.api_token)
+            .header("User-Agent", USER_AGENT)
+            .json(&json!({
+                "query": format!("{}{}", query, LIVE_MATCH_FRAGMENT),
+                "variables": variables,
+            }))
+            .send()
+            .await
+            .context("Failed to query STRATZ")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("STRATZ API error: {} - {}", status, text);
+        }
+
+        let body: GraphQlResponse<T> = response
+            .json()
+            .await
+            .context("Failed to parse STRATZ response")?;
+
+        if let Some(error) = body.errors.first() {
+            anyhow::bail!("STRATZ query error: {}", error.message);
+        }
+        body.data.context("STRATZ returned no data")
+    }
+}
+
+/// Convert a STRATZ live match to our model
+///
+/// League names, patch and lineup scaling are left to `LiveDataClient`,
+/// which has them cached from OpenDota.
+fn convert_match(data: StratzLiveMatch) -> LiveMatchState {
+    let playback = data.playback_data.unwrap_or_default();
+
+    let team = |radiant: bool, team: Option<StratzTeam>, score: Option<i32>| {
+        let (team_id, name) = team.map(|t| (t.id, t.name)).unwrap_or_default();
+        let mut picks: Vec<&PickBan> = playback
+            .pick_bans
+            .iter()
+            .filter(|p| p.is_radiant == Some(radiant) && p.hero_id.is_some_and(|id| id > 0))
+            .collect();
+        picks.sort_by_key(|p| p.order);
+
+        let mut players: Vec<PlayerState> = data
+            .players
+            .iter()
+            .filter(|p| p.is_radiant == Some(radiant))
+            .map(|p| PlayerState {
+                account_id: p.steam_account_id.filter(|id| *id > 0),
+                hero_id: p.hero_id,
+                gold_per_min: p.gold_per_minute,
+                xp_per_min: p.experience_per_minute,
+                ..Default::default()
+            })
+            .collect();
+        // Mid-draft, picks land before the player list shows the heroes
+        let shown: Vec<i32> = players.iter().map(|p| p.hero_id).collect();
+        let mut unshown = picks
+            .iter()
+            .filter(|p| p.is_pick)
+            .filter_map(|p| p.hero_id)
+            .filter(|hero| !shown.contains(hero));
+        for player in players.iter_mut().filter(|p| p.hero_id == 0) {
+            match unshown.next() {
+                Some(hero) => player.hero_id = hero,
+                None => break,
+            }
+        }
+
+        TeamState {
+            name: name.unwrap_or_else(|| if radiant { "Radiant" } else { "Dire" }.to_string()),
+            team_id: team_id.filter(|id| *id > 0),
+            kills: score.unwrap_or(0),
+            has_aegis: data
+                .players
+                .iter()
+                .any(|p| p.is_radiant == Some(radiant) && p.has_aegis()),
+            bans: picks
+                .iter()
+                .filter(|p| !p.is_pick)
+                .filter_map(|p| p.hero_id)
+                .collect(),
+            players,
+            ..Default::default()
+        }
+    };
+    let networth = |radiant: bool| -> i64 {
+        data.players
+            .iter()
+            .filter(|p| p.is_radiant == Some(radiant))
+            .filter_map(|p| p.networth)
+            .sum()
+    };
+
+    let (radiant_towers_killed, dire_towers_killed, radiant_rax_killed, dire_rax_killed) =
+        parse_building_state(data.building_state);
+    let mut radiant = team(true, data.radiant_team, data.radiant_score);
+    radiant.towers_killed = dire_towers_killed;
+    radiant.barracks_killed = dire_rax_killed;
+    let mut dire = team(false, data.dire_team, data.dire_score);
+    dire.towers_killed = radiant_towers_killed;
+    dire.barracks_killed = radiant_rax_killed;
+
+    LiveMatchState {
+        match_id: data.match_id,
+        league_id: data.league_id,
+        league_name: None,
+        league_tier: None,
+        radiant,
+        dire,
+        gold_lead: networth(true) - networth(false),
+        game_time: data.game_time.unwrap_or(0),
+        patch: None,
+        series_id: None,
+        game_number: None,
+        roshan_killed_at: playback
+            .roshan_events
+            .iter()
+            .filter(|e| !e.is_alive)
+            .map(|e| e.time)
+            .max(),
+        radiant_win_rate: data
+            .win_rate_values
+            .last()
+            .copied()
+            .filter(|p| (0.0..=1.0).contains(p)),
+        is_live: true,
+        updated_at: data
+            .modified_date_time
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+            .unwrap_or_else(Utc::now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_match_maps_draft_players_and_objectives() {
+        let body = json!({
+            "data": { "live": { "matches": [{
+                "matchId": 8123456789_i64,
+                "leagueId": 16935,
+                "gameTime": 1500,
+                "radiantScore": 21,
+                "direScore": 12,
+                "buildingState": null,
+                "modifiedDateTime": 1760600000,
+                "radiantTeam": { "id": 7119388, "name": "Team Spirit" },
+                "direTeam": { "id": 2586976, "name": "OG" },
+                "winRateValues": [0.5, 0.58, 0.71],
+                "players": [
+                    { "steamAccountId": 321580662, "heroId": 1, "isRadiant": true,
+                      "networth": 14000, "goldPerMinute": 610.0, "experiencePerMinute": 700.0 },
+                    { "steamAccountId": 106573901, "heroId": 0, "isRadiant": true,
+                      "networth": 6000, "goldPerMinute": null, "experiencePerMinute": null },
+                    { "steamAccountId": 94054712, "heroId": 44, "isRadiant": false,
+                      "networth": 15000, "goldPerMinute": 520.0, "experiencePerMinute": 600.0,
+                      "itemId0": 63, "itemId3": 117 }
+                ],
+                "playbackData": {
+                    "pickBans": [
+                        { "heroId": 1, "isPick": true, "isRadiant": true, "order": 8 },
+                        { "heroId": 23, "isPick": false, "isRadiant": true, "order": 0 },
+                        { "heroId": 5, "isPick": true, "isRadiant": true, "order": 9 },
+                        { "heroId": 44, "isPick": true, "isRadiant": false, "order": 7 },
+                        { "heroId": 74, "isPick": false, "isRadiant": false, "order": 1 }
+                    ],
+                    "roshanEvents": [
+                        { "time": 0, "isAlive": true },
+                        { "time": 1210, "isAlive": false },
+                        { "time": 1690, "isAlive": true }
+                    ]
+                }
+            }] } }
+        });
+        let response: GraphQlResponse<LiveMatchesData> = serde_json::from_value(body).unwrap();
+        let data = response.data.unwrap().live.unwrap();
+        let state = convert_match(data.matches.into_iter().next().unwrap());
+
+        assert_eq!(state.match_id, 8123456789);
+        assert_eq!(state.league_id, Some(16935));
+        assert_eq!(state.radiant.name, "Team Spirit");
+        assert_eq!(state.radiant.team_id, Some(7119388));
+        assert_eq!(state.radiant.kills, 21);
+        assert_eq!(state.gold_lead, -15000 + 20000);
+        assert_eq!(state.radiant.bans, vec![23]);
+        assert_eq!(state.dire.bans, vec![74]);
+        // The second Radiant pick fills the player still showing no hero
+        let heroes: Vec<i32> = state.radiant.players.iter().map(|p| p.hero_id).collect();
+        assert_eq!(heroes, vec![1, 5]);
+        assert_eq!(state.radiant.players[0].gold_per_min, Some(610.0));
+        assert_eq!(state.dire.players[0].xp_per_min, Some(600.0));
+        assert_eq!(state.roshan_killed_at, Some(1210));
+        assert!(!state.radiant.has_aegis);
+        assert!(state.dire.has_aegis);
+        assert_eq!(state.aegis_holder(), Some(false));
+        assert_eq!(state.radiant_win_rate, Some(0.71));
+        assert_eq!(state.updated_at.timestamp(), 1760600000);
+    }
+
+    #[test]
+    fn test_ended_match_is_none() {
+        let body = json!({ "data": { "live": { "match": null } } });
+        let response: GraphQlResponse<LiveMatchData> = serde_json::from_value(body).unwrap();
+        assert!(response.data.unwrap().live.unwrap().live_match.is_none());
+    }
+}
@@ -0,0 +1,217 @@
+use std::env;
+
+use anyhow::Result;
+use tracing::info;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use esport_signal::analytics::{
+    calibration, summarize_accuracy, AccuracyStats, CalibrationBucket, SettledSignal,
+    CALIBRATION_BUCKET,
+};
+use esport_signal::config::Config;
+use esport_signal::db::{HistoricalStore, MatchOutcome, SignalFilter, SignalStore};
+use esport_signal::models::Signal;
+
+const USAGE: &str = "Usage: analyze [--format markdown|csv]";
+
+/// Signals fetched per query
+const PAGE_SIZE: i64 = 1000;
+
+/// Output format for the report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Csv,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "analyze=info,warn".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let args: Vec<String> = env::args().collect();
+    let format = parse_format(&args)?;
+
+    let config = Config::from_env()?;
+    let store = SignalStore::new(&config.database_url).await?;
+    // Creates the results table on a database fetch_historical hasn't filled yet
+    HistoricalStore::new(&config.database_url).await?;
+
+    // Settle every signal whose match has a historical result
+    let mut settled = Vec::new();
+    let mut unsided = 0;
+    for (outcome, radiant_win) in [
+        (MatchOutcome::RadiantWin, true),
+        (MatchOutcome::DireWin, false),
+    ] {
+        for signal in fetch_all(&store, &SignalFilter::default().outcome(outcome)).await? {
+            match SettledSignal::new(signal, radiant_win) {
+                Some(s) => settled.push(s),
+                None => unsided += 1,
+            }
+        }
+    }
+    if unsided > 0 {
+        info!(
+            "Skipped {} settled signals stored before their side was recorded",
+            unsided
+        );
+    }
+
+    let signal_type = |s: &SettledSignal| s.signal.signal_type.to_string();
+    let sections: [(&str, Vec<AccuracyStats>, Vec<CalibrationBucket>); 3] = [
+        (
+            "Overall",
+            summarize_accuracy(&settled, |_| "all".to_string()),
+            calibration(&settled, |_| "all".to_string()),
+        ),
+        (
+            "Signal type",
+            summarize_accuracy(&settled, signal_type),
+            calibration(&settled, signal_type),
+        ),
+        (
+            "League",
+            summarize_accuracy(&settled, SettledSignal::league),
+            calibration(&settled, SettledSignal::league),
+        ),
+    ];
+
+    match format {
+        Format::Markdown => print_markdown(&sections, settled.len()),
+        Format::Csv => print_csv(&sections),
+    }
+
+    Ok(())
+}
+
+/// Every signal matching a filter, newest first
+async fn fetch_all(store: &SignalStore, filter: &SignalFilter) -> Result<Vec<Signal>> {
+    let mut signals = Vec::new();
+    let mut before = None;
+    loop {
+        let page = store.query_signals_page(filter, before, PAGE_SIZE).await?;
+        signals.extend(page.signals);
+        match page.next_cursor {
+            Some(cursor) => before = Some(cursor),
+            None => return Ok(signals),
+        }
+    }
+}
+
+/// Parse --format argument
+fn parse_format(args: &[String]) -> Result<Format> {
+    for (i, arg) in args.iter().enumerate() {
+        if arg == "--format" || arg == "-f" {
+            return match args.get(i + 1).map(|s| s.as_str()) {
+                Some("markdown") | Some("md") => Ok(Format::Markdown),
+                Some("csv") => Ok(Format::Csv),
+                _ => anyhow::bail!("{}", USAGE),
+            };
+        }
+    }
+    Ok(Format::Markdown)
+}
+
+fn print_markdown(
+    sections: &[(&str, Vec<AccuracyStats>, Vec<CalibrationBucket>)],
+    settled_count: usize,
+) {
+    println!("# Signal Accuracy\n");
+    println!("{} settled signals\n", settled_count);
+    println!("Precision: share of signals whose net edge favoured the winner. Edges are on the favoured side before fees; realized = payout (1 or 0) minus price.\n");
+
+    for (title, accuracy, buckets) in sections {
+        println!("## {}\n", title);
+        println!(
+            "| {} | Strength | Signals | Precision | Predicted edge | Realized edge |",
+            title
+        );
+        println!("|---|---|---:|---:|---:|---:|");
+        for s in accuracy {
+            println!(
+                "| {} | {} | {} | {:.0}% | {} | {:+.1}% |",
+                s.key,
+                s.strength,
+                s.signals,
+                s.precision * 100.0,
+                s.avg_predicted_edge
+                    .map(|e| format!("{:+.1}%", e * 100.0))
+                    .unwrap_or_else(|| "-".to_string()),
+                s.avg_realized_edge * 100.0
+            );
+        }
+        println!();
+
+        println!(
+            "| {} | Team A win prob | Signals | Avg predicted | Observed |",
+            title
+        );
+        println!("|---|---|---:|---:|---:|");
+        for b in buckets {
+            println!(
+                "| {} | {:.0}–{:.0}% | {} | {:.1}% | {:.1}% |",
+                b.key,
+                b.lower * 100.0,
+                (b.lower + CALIBRATION_BUCKET) * 100.0,
+                b.signals,
+                b.avg_predicted * 100.0,
+                b.observed * 100.0
+            );
+        }
+        println!();
+    }
+}
+
+fn print_csv(sections: &[(&str, Vec<AccuracyStats>, Vec<CalibrationBucket>)]) {
+    println!("group,key,strength,signals,precision,avg_predicted_edge,avg_realized_edge");
+    for (title, accuracy, _) in sections {
+        let group = title.to_lowercase().replace(' ', "_");
+        for s in accuracy {
+            println!(
+                "{},{},{},{},{:.6},{},{:.6}",
+                group,
+                csv_field(&s.key),
+                s.strength,
+                s.signals,
+                s.precision,
+                s.avg_predicted_edge
+                    .map(|e| format!("{:.6}", e))
+                    .unwrap_or_default(),
+                s.avg_realized_edge
+            );
+        }
+    }
+
+    println!();
+    println!("group,key,bucket_lower,signals,avg_predicted,observed");
+    for (title, _, buckets) in sections {
+        let group = title.to_lowercase().replace(' ', "_");
+        for b in buckets {
+            println!(
+                "{},{},{:.2},{},{:.6},{:.6}",
+                group,
+                csv_field(&b.key),
+                b.lower,
+                b.signals,
+                b.avg_predicted,
+                b.observed
+            );
+        }
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter or quote
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
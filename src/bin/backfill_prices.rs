@@ -0,0 +1,156 @@
+use std::env;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use tokio::time::sleep;
+use tracing::{info, warn};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use esport_signal::api::{ClobClient, PolymarketClient};
+use esport_signal::config::Config;
+use esport_signal::db::{PriceHistoryMarket, PriceHistoryStore};
+
+const USAGE: &str = "Usage: backfill_prices [--fidelity MINUTES] [--limit N] [--refresh]";
+
+/// Price sample spacing when `--fidelity` isn't given
+const DEFAULT_FIDELITY_MINUTES: u32 = 1;
+const RATE_LIMIT_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Debug)]
+struct Args {
+    fidelity_minutes: u32,
+    /// Most markets to backfill this run
+    limit: Option<usize>,
+    /// Re-fetch markets already in the store
+    refresh: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "backfill_prices=info,esport_signal=info,warn".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let args = parse_args(&env::args().collect::<Vec<_>>())?;
+
+    let config = Config::from_env()?;
+    let store = PriceHistoryStore::new(&config.database_url).await?;
+    let gamma = PolymarketClient::new(&config.polymarket_api_url);
+    let clob = ClobClient::new(&config.polymarket_clob_url);
+
+    let resolved = gamma.fetch_resolved_dota2_markets().await?;
+    let done = if args.refresh {
+        Default::default()
+    } else {
+        store.backfilled_ids().await?
+    };
+
+    let pending: Vec<_> = resolved
+        .into_iter()
+        .filter(|r| !done.contains(&r.market.condition_id))
+        .take(args.limit.unwrap_or(usize::MAX))
+        .collect();
+    info!(
+        "Backfilling {} resolved markets ({} already stored)",
+        pending.len(),
+        done.len()
+    );
+
+    let mut stored = 0;
+    let mut empty = 0;
+    let mut failed = 0;
+
+    for resolved in pending {
+        sleep(RATE_LIMIT_DELAY).await;
+
+        let market = &resolved.market;
+        let points = match clob
+            .fetch_price_history(&resolved.team_a_token_id, args.fidelity_minutes)
+            .await
+        {
+            Ok(points) => points,
+            Err(e) => {
+                warn!("Failed to fetch prices for {}: {}", market.condition_id, e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        // Markets that never traded have no history; try again next run
+        if points.is_empty() {
+            empty += 1;
+            continue;
+        }
+
+        let record = PriceHistoryMarket {
+            condition_id: market.condition_id.clone(),
+            question: market.question.clone(),
+            team_a: market.team_a.clone(),
+            team_b: market.team_b.clone(),
+            team_a_token_id: resolved.team_a_token_id.clone(),
+            team_a_won: resolved.team_a_won,
+            end_date: market.end_date,
+            backfilled_at: Utc::now(),
+        };
+        store.save(&record, &points).await?;
+        stored += 1;
+
+        info!(
+            "{} vs {}: {} prices ({} won)",
+            market.team_a,
+            market.team_b,
+            points.len(),
+            if resolved.team_a_won {
+                &market.team_a
+            } else {
+                &market.team_b
+            }
+        );
+    }
+
+    info!(
+        "Done: {} stored, {} without history, {} failed",
+        stored, empty, failed
+    );
+
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> Result<Args> {
+    let mut parsed = Args {
+        fidelity_minutes: DEFAULT_FIDELITY_MINUTES,
+        limit: None,
+        refresh: false,
+    };
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--fidelity" => {
+                parsed.fidelity_minutes = iter
+                    .next()
+                    .context(USAGE)?
+                    .parse()
+                    .context("Invalid --fidelity")?;
+            }
+            "--limit" => {
+                parsed.limit = Some(
+                    iter.next()
+                        .context(USAGE)?
+                        .parse()
+                        .context("Invalid --limit")?,
+                );
+            }
+            "--refresh" => parsed.refresh = true,
+            other => anyhow::bail!("Unknown argument: {}\n{}", other, USAGE),
+        }
+    }
+
+    Ok(parsed)
+}
@@ -53,8 +53,8 @@ async fn main() -> Result<()> {
 
     // Get starting point for pagination
     let mut less_than_match_id = store.get_min_match_id().await?;
-    if less_than_match_id.is_some() {
-        info!("Resuming from match_id < {}", less_than_match_id.unwrap());
+    if let Some(match_id) = less_than_match_id {
+        info!("Resuming from match_id < {}", match_id);
     }
 
     let mut fetched_count = 0;
@@ -216,6 +216,7 @@ async fn fetch_and_store_match(
         radiant_xp_adv,
         start_time: details.start_time,
         league_name,
+        patch: details.patch,
         fetched_at: Utc::now().to_rfc3339(),
     };
 
@@ -0,0 +1,197 @@
+use std::env;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use esport_signal::config::Config;
+use esport_signal::db::PositionStore;
+use esport_signal::models::{Position, PositionSide};
+use esport_signal::notifications::Notifier;
+use esport_signal::trading::{RiskEngine, RiskLimits};
+
+const DEFAULT_LIST_LIMIT: i64 = 50;
+
+const USAGE: &str = "Usage:
+  positions open --market <condition_id> --side <a|b> --price <p> --shares <n> [--signal <id>] [--match <id>] [--force]
+  positions close <id> --price <p> [--shares <n>]
+  positions resolve <id> --won|--lost
+  positions list [--open] [--market <condition_id>] [--limit <n>]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "positions=info,esport_signal=warn,warn".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let config = Config::from_env()?;
+    let store = Arc::new(PositionStore::new(&config.database_url).await?);
+
+    match command.as_str() {
+        "open" => {
+            let risk = RiskEngine::new(
+                RiskLimits::from_config(&config),
+                store.clone(),
+                Notifier::from_config(&config),
+            );
+            open(&risk, &args).await
+        }
+        "close" => close(&store, &args).await,
+        "resolve" => resolve(&store, &args).await,
+        "list" => list(&store, &args).await,
+        other => anyhow::bail!("Unknown command: {}\n{}", other, USAGE),
+    }
+}
+
+/// Record a manually opened position, subject to risk limits
+async fn open(risk: &RiskEngine, args: &[String]) -> Result<()> {
+    let market = flag_value(args, "--market").context("--market is required")?;
+    let side: PositionSide = flag_value(args, "--side")
+        .context("--side is required")?
+        .parse()?;
+    let price: f64 = parse_flag(args, "--price")?.context("--price is required")?;
+    let shares: f64 = parse_flag(args, "--shares")?.context("--shares is required")?;
+
+    if !(0.0..=1.0).contains(&price) || shares <= 0.0 {
+        anyhow::bail!("--price must be within 0-1 and --shares positive");
+    }
+
+    let mut position = Position::open(market, side, price, shares);
+    position.signal_id = parse_flag(args, "--signal")?;
+    position.match_id = parse_flag(args, "--match")?;
+
+    let id = risk
+        .open_position(&position, has_flag(args, "--force"))
+        .await?;
+    println!(
+        "Opened position {} | {} {} @ {:.3} x {} (${:.2})",
+        id,
+        position.market_condition_id,
+        position.side,
+        position.entry_price,
+        position.shares,
+        position.cost_basis()
+    );
+
+    Ok(())
+}
+
+/// Sell some or all remaining shares
+async fn close(store: &PositionStore, args: &[String]) -> Result<()> {
+    let id = position_id(args)?;
+    let price: f64 = parse_flag(args, "--price")?.context("--price is required")?;
+
+    let shares = match parse_flag(args, "--shares")? {
+        Some(shares) => shares,
+        None => {
+            store
+                .get_position(id)
+                .await?
+                .with_context(|| format!("Position {} not found", id))?
+                .remaining_shares
+        }
+    };
+
+    let position = store.close_position(id, shares, price).await?;
+    print_position(&position);
+
+    Ok(())
+}
+
+/// Settle a position at market resolution
+async fn resolve(store: &PositionStore, args: &[String]) -> Result<()> {
+    let id = position_id(args)?;
+    let won = match (has_flag(args, "--won"), has_flag(args, "--lost")) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => anyhow::bail!("Exactly one of --won or --lost is required"),
+    };
+
+    let position = store.resolve_position(id, won).await?;
+    print_position(&position);
+
+    Ok(())
+}
+
+/// List positions
+async fn list(store: &PositionStore, args: &[String]) -> Result<()> {
+    let positions = if has_flag(args, "--open") {
+        store.get_open_positions().await?
+    } else if let Some(market) = flag_value(args, "--market") {
+        store.get_positions_for_market(market).await?
+    } else {
+        let limit = parse_flag(args, "--limit")?.unwrap_or(DEFAULT_LIST_LIMIT);
+        store.get_recent_positions(limit).await?
+    };
+
+    for position in &positions {
+        print_position(position);
+    }
+
+    let realized: f64 = positions.iter().map(|p| p.realized_pnl).sum();
+    println!(
+        "{} positions | Realized PnL: ${:+.2}",
+        positions.len(),
+        realized
+    );
+
+    Ok(())
+}
+
+fn print_position(position: &Position) {
+    println!(
+        "#{} | {} | {} | {} @ {:.3} | {}/{} shares held | Signal: {} | PnL: ${:+.2}",
+        position.id.unwrap_or_default(),
+        position.status,
+        position.market_condition_id,
+        position.side,
+        position.entry_price,
+        position.remaining_shares,
+        position.shares,
+        position
+            .signal_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        position.realized_pnl
+    );
+}
+
+/// Positional position id following the command
+fn position_id(args: &[String]) -> Result<i64> {
+    args.get(1)
+        .context("Position id is required")?
+        .parse()
+        .context("Position id must be a number")
+}
+
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|arg| arg == flag)
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
+}
+
+fn parse_flag<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<Option<T>> {
+    flag_value(args, flag)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("{} has an invalid value: {}", flag, value))
+        })
+        .transpose()
+}
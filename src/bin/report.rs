@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::env;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use esport_signal::analytics::{
+    attribute, simulate_exit, simulate_hold, summarize, summarize_clv, unexited_entries,
+    Attribution, ClvSummary, GroupStats, SignalClv, Trade,
+};
+use esport_signal::config::Config;
+use esport_signal::db::{PositionStore, SignalStore};
+use esport_signal::models::Signal;
+
+const USAGE: &str = "Usage: report [--format markdown|csv] [--clv]";
+
+/// Output format for the report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Csv,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "report=info,warn".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let args: Vec<String> = env::args().collect();
+    let format = parse_format(&args)?;
+
+    let config = Config::from_env()?;
+    let signal_store = SignalStore::new(&config.database_url).await?;
+    let position_store = PositionStore::new(&config.database_url).await?;
+
+    // Closing line value of every actionable signal
+    let closing_lines: HashMap<String, f64> = signal_store
+        .get_closing_lines()
+        .await?
+        .into_iter()
+        .map(|line| (line.market_condition_id, line.team_a_odds))
+        .collect();
+    let actionable = signal_store.get_actionable_signals().await?;
+    let clv: Vec<SignalClv> = actionable
+        .iter()
+        .filter_map(|signal| {
+            let closing = *closing_lines.get(&signal.market_condition_id)?;
+            SignalClv::new(signal.clone(), closing)
+        })
+        .collect();
+
+    if args.iter().any(|arg| arg == "--clv") {
+        match format {
+            Format::Markdown => print_signal_clv_markdown(&clv),
+            Format::Csv => print_signal_clv_csv(&clv),
+        }
+        return Ok(());
+    }
+
+    let clv_sections: [(&str, Vec<ClvSummary>); 2] = [
+        (
+            "Strength",
+            summarize_clv(&clv, |c| c.signal.strength.to_string()),
+        ),
+        (
+            "Signal type",
+            summarize_clv(&clv, |c| c.signal.signal_type.to_string()),
+        ),
+    ];
+
+    // Join finished positions with the signals that prompted them
+    let mut trades = Vec::new();
+    for position in position_store.get_finished_positions().await? {
+        let signal = match position.signal_id {
+            Some(id) => signal_store.get_signal(id).await?,
+            None => None,
+        };
+        trades.push(Trade { position, signal });
+    }
+
+    // Paper-trade every entry signal at the reference stake, to its exit or,
+    // for entries nothing exited, to the result or the market's last price
+    let exits = signal_store.get_all_exit_signals().await?;
+    let mut simulated = Vec::new();
+    for exit in &exits {
+        if let Some(entry) = signal_store.get_signal(exit.entry_signal_id).await? {
+            simulated.extend(simulate_exit(entry, exit, config.reference_stake));
+        }
+    }
+    let last_signals: HashMap<String, Signal> = signal_store
+        .get_latest_signal_per_market(DateTime::<Utc>::UNIX_EPOCH)
+        .await?
+        .into_iter()
+        .map(|signal| (signal.market_condition_id.clone(), signal))
+        .collect();
+    for entry in unexited_entries(&actionable, &exits) {
+        if let Some(last) = last_signals.get(&entry.market_condition_id) {
+            simulated.extend(simulate_hold(entry, last, config.reference_stake));
+        }
+    }
+    let attribution = attribute(&trades, &simulated);
+
+    let sections: [(&str, Vec<GroupStats>); 4] = [
+        ("Overall", summarize(&trades, |_| "all".to_string())),
+        ("Week", summarize(&trades, Trade::week)),
+        ("League", summarize(&trades, Trade::league)),
+        ("Signal type", summarize(&trades, Trade::signal_type)),
+    ];
+
+    match format {
+        Format::Markdown => {
+            print_markdown(&sections, trades.len());
+            print_attribution_markdown(&attribution, config.reference_stake);
+            print_clv_markdown(&clv_sections);
+        }
+        Format::Csv => {
+            print_csv(&sections);
+            print_attribution_csv(&attribution);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse --format argument
+fn parse_format(args: &[String]) -> Result<Format> {
+    for (i, arg) in args.iter().enumerate() {
+        if arg == "--format" || arg == "-f" {
+            return match args.get(i + 1).map(|s| s.as_str()) {
+                Some("markdown") | Some("md") => Ok(Format::Markdown),
+                Some("csv") => Ok(Format::Csv),
+                _ => anyhow::bail!("{}", USAGE),
+            };
+        }
+    }
+    Ok(Format::Markdown)
+}
+
+fn print_markdown(sections: &[(&str, Vec<GroupStats>)], trade_count: usize) {
+    println!("# Trade Journal\n");
+    println!("{} finished positions\n", trade_count);
+
+    for (title, stats) in sections {
+        println!("## {}\n", title);
+        println!(
+            "| {} | Trades | Staked | PnL | ROI | Avg entry edge | Edge captured | Max drawdown |",
+            title
+        );
+        println!("|---|---:|---:|---:|---:|---:|---:|---:|");
+
+        for s in stats {
+            println!(
+                "| {} | {} | ${:.2} | ${:+.2} | {:+.1}% | {} | {:+.1}% | ${:.2} |",
+                s.key,
+                s.trades,
+                s.staked,
+                s.pnl,
+                s.roi() * 100.0,
+                s.avg_entry_edge
+                    .map(|e| format!("{:+.1}%", e * 100.0))
+                    .unwrap_or_else(|| "-".to_string()),
+                s.avg_edge_captured * 100.0,
+                s.max_drawdown
+            );
+        }
+        println!();
+    }
+}
+
+fn print_csv(sections: &[(&str, Vec<GroupStats>)]) {
+    println!("group,key,trades,staked,pnl,roi,avg_entry_edge,avg_edge_captured,max_drawdown");
+
+    for (title, stats) in sections {
+        print_csv_rows(&title.to_lowercase().replace(' ', "_"), stats);
+    }
+}
+
+fn print_csv_rows(group: &str, stats: &[GroupStats]) {
+    for s in stats {
+        println!(
+            "{},{},{},{:.4},{:.4},{:.6},{},{:.6},{:.4}",
+            group,
+            csv_field(&s.key),
+            s.trades,
+            s.staked,
+            s.pnl,
+            s.roi(),
+            s.avg_entry_edge
+                .map(|e| format!("{:.6}", e))
+                .unwrap_or_default(),
+            s.avg_edge_captured,
+            s.max_drawdown
+        );
+    }
+}
+
+fn print_attribution_markdown(attribution: &[Attribution], stake: f64) {
+    println!("## Signal type and strength\n");
+    println!(
+        "Realized = positions taken; simulated = ${:.0} on every exited Strong+ entry, sold at its exit signal.\n",
+        stake
+    );
+    println!("| Signal type | Strength | Trades | PnL | ROI | Sim trades | Sim PnL | Sim ROI |");
+    println!("|---|---|---:|---:|---:|---:|---:|---:|");
+
+    let cells = |stats: &Option<GroupStats>| match stats {
+        Some(s) => format!("{} | ${:+.2} | {:+.1}%", s.trades, s.pnl, s.roi() * 100.0),
+        None => "0 | - | -".to_string(),
+    };
+
+    for row in attribution {
+        println!(
+            "| {} | {} | {} | {} |",
+            row.signal_type,
+            row.strength,
+            cells(&row.realized),
+            cells(&row.simulated)
+        );
+    }
+    println!();
+}
+
+fn print_attribution_csv(attribution: &[Attribution]) {
+    let realized: Vec<GroupStats> = attribution
+        .iter()
+        .filter_map(|a| a.realized.clone())
+        .collect();
+    let simulated: Vec<GroupStats> = attribution
+        .iter()
+        .filter_map(|a| a.simulated.clone())
+        .collect();
+
+    print_csv_rows("attribution_realized", &realized);
+    print_csv_rows("attribution_simulated", &simulated);
+}
+
+fn print_clv_markdown(sections: &[(&str, Vec<ClvSummary>)]) {
+    println!("## Closing line value\n");
+    println!("Favoured-side price at the last live update minus price at the signal.\n");
+
+    for (title, stats) in sections {
+        println!("| {} | Signals | Avg CLV | Beat close |", title);
+        println!("|---|---:|---:|---:|");
+        for s in stats {
+            println!(
+                "| {} | {} | {:+.2}% | {:.0}% |",
+                s.key,
+                s.signals,
+                s.avg_clv * 100.0,
+                s.beat_close_rate * 100.0
+            );
+        }
+        println!();
+    }
+}
+
+fn print_signal_clv_markdown(rows: &[SignalClv]) {
+    println!("# Closing Line Value\n");
+    println!("| Signal | Created | Market | Type | Strength | Side | Entry | Close | CLV |");
+    println!("|---:|---|---|---|---|---|---:|---:|---:|");
+
+    for row in rows {
+        println!(
+            "| {} | {} | {} | {} | {} | {} | {:.3} | {:.3} | {:+.1}% |",
+            row.signal.id.unwrap_or_default(),
+            row.signal.created_at.format("%Y-%m-%d %H:%M"),
+            row.signal.market_condition_id,
+            row.signal.signal_type,
+            row.signal.strength,
+            row.side,
+            row.entry_price,
+            row.closing_price,
+            row.clv() * 100.0
+        );
+    }
+}
+
+fn print_signal_clv_csv(rows: &[SignalClv]) {
+    println!("signal_id,created_at,market_condition_id,signal_type,strength,side,entry_price,closing_price,clv");
+
+    for row in rows {
+        println!(
+            "{},{},{},{},{},{},{:.4},{:.4},{:.6}",
+            row.signal.id.unwrap_or_default(),
+            row.signal.created_at.to_rfc3339(),
+            csv_field(&row.signal.market_condition_id),
+            row.signal.signal_type,
+            row.signal.strength,
+            row.side,
+            row.entry_price,
+            row.closing_price,
+            row.clv()
+        );
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter or quote
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,140 @@
+use std::env;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use esport_signal::config::Config;
+use esport_signal::db::{SignalFilter, SignalStore};
+use esport_signal::models::Signal;
+
+const DEFAULT_LIMIT: i64 = 50;
+
+const USAGE: &str = "Usage:
+  signals [--team <name>] [--min-strength <strength>] [--type <type,...>] [--min-edge <x>]
+          [--since <rfc3339>] [--until <rfc3339>] [--match <id>] [--market <condition_id>]
+          [--outcome RadiantWin|DireWin|Pending] [--limit <n>] [--before <id>]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "signals=info,esport_signal=warn,warn".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if has_flag(&args, "--help") || has_flag(&args, "-h") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let filter = parse_filter(&args)?;
+    let limit = parse_flag(&args, "--limit")?.unwrap_or(DEFAULT_LIMIT);
+    let before = parse_flag(&args, "--before")?;
+
+    let config = Config::from_env()?;
+    let store = SignalStore::new(&config.database_url).await?;
+
+    let page = store.query_signals_page(&filter, before, limit).await?;
+
+    for signal in &page.signals {
+        print_signal(signal);
+    }
+
+    match page.next_cursor {
+        Some(cursor) => println!(
+            "{} signals | next page: --before {}",
+            page.signals.len(),
+            cursor
+        ),
+        None => println!("{} signals", page.signals.len()),
+    }
+
+    Ok(())
+}
+
+/// Build the query from command-line flags
+fn parse_filter(args: &[String]) -> Result<SignalFilter> {
+    let mut filter = SignalFilter::default();
+
+    if let Some(team) = flag_value(args, "--team") {
+        filter = filter.team(team);
+    }
+    if let Some(strength) = parse_flag(args, "--min-strength")? {
+        filter = filter.min_strength(strength);
+    }
+    if let Some(types) = flag_value(args, "--type") {
+        filter = filter.signal_types(
+            types
+                .split(',')
+                .map(|t| t.trim().parse())
+                .collect::<Result<Vec<_>>>()?,
+        );
+    }
+    if let Some(edge) = parse_flag(args, "--min-edge")? {
+        filter = filter.min_net_edge(edge);
+    }
+    if let Some(since) = parse_flag::<DateTime<Utc>>(args, "--since")? {
+        filter = filter.since(since);
+    }
+    if let Some(until) = parse_flag::<DateTime<Utc>>(args, "--until")? {
+        filter = filter.until(until);
+    }
+    if let Some(match_id) = parse_flag(args, "--match")? {
+        filter = filter.match_id(match_id);
+    }
+    if let Some(market) = flag_value(args, "--market") {
+        filter = filter.market(market);
+    }
+    if let Some(outcome) = parse_flag(args, "--outcome")? {
+        filter = filter.outcome(outcome);
+    }
+
+    Ok(filter)
+}
+
+fn print_signal(signal: &Signal) {
+    println!(
+        "#{} | {} | Match {} | {} | {} {} | Market: {:.1}% | Model: {} | Net edge: {}",
+        signal.id.unwrap_or_default(),
+        signal.created_at.format("%Y-%m-%d %H:%M:%S"),
+        signal.match_id,
+        signal.market_condition_id,
+        signal.strength,
+        signal.signal_type,
+        signal.market_team_a_odds * 100.0,
+        signal
+            .team_a_win_prob
+            .map(|p| format!("{:.1}%", p * 100.0))
+            .unwrap_or_else(|| "-".to_string()),
+        signal
+            .net_edge
+            .map(|e| format!("{:+.1}%", e * 100.0))
+            .unwrap_or_else(|| "-".to_string()),
+    );
+}
+
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|arg| arg == flag)
+}
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
+}
+
+fn parse_flag<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<Option<T>> {
+    flag_value(args, flag)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("{} has an invalid value: {}", flag, value))
+        })
+        .transpose()
+}
@@ -0,0 +1,251 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Utc;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+use esport_signal::config::Config;
+use esport_signal::db::SignalStore;
+use esport_signal::models::{LiveMatchState, Signal, SignalStrength};
+
+/// How often the dashboard re-reads the database
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Markets without a snapshot this recent are no longer shown as live
+const LIVE_WINDOW_MINUTES: i64 = 2;
+
+/// Signals kept in the feed
+const FEED_SIZE: i64 = 200;
+
+/// Latest data read from the signal store
+#[derive(Default)]
+struct Dashboard {
+    live: Vec<(Signal, Option<LiveMatchState>)>,
+    feed: Vec<Signal>,
+    /// Feed entries scrolled past from the newest
+    scroll: usize,
+    error: Option<String>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let config = Config::from_env()?;
+    let signal_store = SignalStore::new(&config.database_url).await?;
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &signal_store).await;
+    ratatui::restore();
+
+    result
+}
+
+/// Redraw until `q` or Esc is pressed
+async fn run(terminal: &mut DefaultTerminal, signal_store: &SignalStore) -> Result<()> {
+    let mut dashboard = Dashboard::default();
+    let mut last_refresh: Option<Instant> = None;
+
+    loop {
+        if last_refresh.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+            dashboard.refresh(signal_store).await;
+            last_refresh = Some(Instant::now());
+        }
+
+        terminal.draw(|frame| dashboard.draw(frame))?;
+
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        dashboard.scroll = (dashboard.scroll + 1).min(dashboard.feed.len());
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        dashboard.scroll = dashboard.scroll.saturating_sub(1);
+                    }
+                    KeyCode::Home => dashboard.scroll = 0,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl Dashboard {
+    /// Reload live markets and the signal feed, keeping old data on error
+    async fn refresh(&mut self, signal_store: &SignalStore) {
+        let since = Utc::now() - chrono::Duration::minutes(LIVE_WINDOW_MINUTES);
+
+        let live = signal_store.get_latest_signal_per_market(since).await;
+        let feed = signal_store.get_recent_signals(FEED_SIZE).await;
+
+        match (live, feed) {
+            (Ok(live), Ok(feed)) => {
+                self.live = live
+                    .into_iter()
+                    .map(|signal| {
+                        let state = serde_json::from_str(&signal.match_snapshot).ok();
+                        (signal, state)
+                    })
+                    .collect();
+                self.feed = feed
+                    .into_iter()
+                    .filter(|s| s.strength != SignalStrength::Neutral)
+                    .collect();
+                self.scroll = self.scroll.min(self.feed.len());
+                self.error = None;
+            }
+            (Err(e), _) | (_, Err(e)) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, matches, feed, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Percentage(45),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let title = match &self.error {
+            Some(e) => Line::styled(format!(" esport-signal | DB error: {}", e), Color::Red),
+            None => Line::styled(
+                format!(
+                    " esport-signal | {} live markets | {}",
+                    self.live.len(),
+                    Utc::now().format("%H:%M:%S UTC")
+                ),
+                Modifier::BOLD,
+            ),
+        };
+        frame.render_widget(title, header);
+
+        frame.render_widget(self.matches_table(), matches);
+        frame.render_widget(self.feed_list(), feed);
+        frame.render_widget(
+            Line::styled(" q quit | ↑/↓ scroll feed | Home newest", Color::DarkGray),
+            footer,
+        );
+    }
+
+    /// Live matches with game state, market price and model estimate
+    fn matches_table(&self) -> Table<'_> {
+        let rows = self.live.iter().map(|(signal, state)| {
+            let (teams, score, gold, towers, clock) = match state {
+                Some(s) => (
+                    format!("{} vs {}", s.radiant.name, s.dire.name),
+                    format!("{}-{}", s.radiant.kills, s.dire.kills),
+                    format!("{:+.1}k", s.gold_lead as f64 / 1000.0),
+                    format!("{}-{}", s.radiant.towers_killed, s.dire.towers_killed),
+                    format!("{}:{:02}", s.game_time / 60, s.game_time.rem_euclid(60)),
+                ),
+                None => (
+                    signal.match_id.to_string(),
+                    "-".into(),
+                    "-".into(),
+                    "-".into(),
+                    "-".into(),
+                ),
+            };
+
+            Row::new(vec![
+                teams,
+                clock,
+                score,
+                gold,
+                towers,
+                format!("{:.1}%", signal.market_team_a_odds * 100.0),
+                model_cell(signal),
+                signal
+                    .net_edge
+                    .map(|e| format!("{:+.1}%", e * 100.0))
+                    .unwrap_or_else(|| "-".into()),
+                signal.strength.to_string(),
+            ])
+            .style(strength_style(signal.strength))
+        });
+
+        Table::new(
+            rows,
+            [
+                Constraint::Fill(3),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Length(20),
+                Constraint::Length(7),
+                Constraint::Length(10),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Radiant vs Dire",
+                "Time",
+                "Kills",
+                "Gold",
+                "Towers",
+                "Mkt A",
+                "Model A [interval]",
+                "Net",
+                "Strength",
+            ])
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::bordered().title(" Live matches "))
+    }
+
+    /// Non-neutral signals, newest first
+    fn feed_list(&self) -> List<'_> {
+        let items = self.feed.iter().skip(self.scroll).map(|signal| {
+            ListItem::new(format!(
+                "{} | match {} | {} | {} | market {:.1}% | model {} | net {}",
+                signal.created_at.format("%H:%M:%S"),
+                signal.match_id,
+                signal.signal_type,
+                signal.strength,
+                signal.market_team_a_odds * 100.0,
+                model_cell(signal),
+                signal
+                    .net_edge
+                    .map(|e| format!("{:+.1}%", e * 100.0))
+                    .unwrap_or_else(|| "-".into()),
+            ))
+            .style(strength_style(signal.strength))
+        });
+
+        List::new(items).block(Block::bordered().title(" Signal feed "))
+    }
+}
+
+/// Team A model probability with its interval
+fn model_cell(signal: &Signal) -> String {
+    match (
+        signal.team_a_win_prob,
+        signal.team_a_prob_lower,
+        signal.team_a_prob_upper,
+    ) {
+        (Some(p), Some(lo), Some(hi)) => {
+            format!("{:.1}% [{:.0}-{:.0}]", p * 100.0, lo * 100.0, hi * 100.0)
+        }
+        (Some(p), _, _) => format!("{:.1}%", p * 100.0),
+        _ => "-".to_string(),
+    }
+}
+
+fn strength_style(strength: SignalStrength) -> Style {
+    match strength {
+        SignalStrength::Neutral => Style::new(),
+        SignalStrength::Weak => Style::new().fg(Color::Yellow),
+        _ => Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+    }
+}
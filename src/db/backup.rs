@@ -0,0 +1,219 @@
+//! Online backup and restore of the SQLite database
+//!
+//! Both run against the live file through SQLite itself, so the service can
+//! keep running: `VACUUM INTO` writes a consistent snapshot from one read
+//! transaction, and a restore replaces every table's rows from the backup
+//! inside one write transaction, which other connections see all at once.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{Connection, Row};
+use tracing::info;
+
+/// Rows copied back into one table by a restore
+#[derive(Debug, Clone)]
+pub struct RestoredTable {
+    pub name: String,
+    pub rows: u64,
+}
+
+/// Write a consistent copy of the database at `database_url` to `dest`,
+/// which must not exist yet
+pub async fn backup(database_url: &str, dest: &Path) -> Result<()> {
+    if dest.exists() {
+        anyhow::bail!("{} already exists", dest.display());
+    }
+
+    let mut conn = connect(database_url).await?;
+    sqlx::query("VACUUM INTO ?")
+        .bind(dest.to_string_lossy().as_ref())
+        .execute(&mut conn)
+        .await
+        .context("Failed to write backup")?;
+    conn.close().await?;
+
+    check_integrity(dest).await?;
+    info!("Backed up {} to {}", database_url, dest.display());
+    Ok(())
+}
+
+/// Replace the rows of every table in the backup at `src`
+///
+/// Tables the backup lacks are left alone and tables the database lacks are
+/// created; columns are matched by name, so a backup from an older schema
+/// restores into a migrated database.
+pub async fn restore(database_url: &str, src: &Path) -> Result<Vec<RestoredTable>> {
+    if !src.exists() {
+        anyhow::bail!("{} does not exist", src.display());
+    }
+    check_integrity(src).await?;
+
+    let mut conn = connect(database_url).await?;
+    sqlx::query("ATTACH DATABASE ? AS backup")
+        .bind(src.to_string_lossy().as_ref())
+        .execute(&mut conn)
+        .await
+        .context("Failed to attach backup")?;
+
+    let result = copy_tables(&mut conn).await;
+
+    sqlx::query("DETACH DATABASE backup")
+        .execute(&mut conn)
+        .await?;
+    conn.close().await?;
+
+    let restored = result?;
+    info!(
+        "Restored {} tables into {} from {}",
+        restored.len(),
+        database_url,
+        src.display()
+    );
+    Ok(restored)
+}
+
+/// Copy every backup table into main in one transaction
+async fn copy_tables(conn: &mut SqliteConnection) -> Result<Vec<RestoredTable>> {
+    let tables: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, sql FROM backup.sqlite_master \
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .context("Failed to list backup tables")?;
+
+    // IMMEDIATE takes the write lock up front, so the service's writes wait
+    // instead of failing halfway through
+    sqlx::query("BEGIN IMMEDIATE").execute(&mut *conn).await?;
+    // Tables are refilled one by one, so references are checked at commit
+    sqlx::query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+
+    let mut restored = Vec::new();
+    for (name, sql) in tables {
+        match copy_table(conn, &name, &sql).await {
+            Ok(rows) => restored.push(RestoredTable { name, rows }),
+            Err(e) => {
+                sqlx::query("ROLLBACK").execute(&mut *conn).await?;
+                return Err(e.context(format!("Failed to restore {} table", name)));
+            }
+        }
+    }
+
+    sqlx::query("COMMIT").execute(&mut *conn).await?;
+    Ok(restored)
+}
+
+/// Replace one table's rows; returns how many were copied
+async fn copy_table(conn: &mut SqliteConnection, name: &str, sql: &str) -> Result<u64> {
+    let exists: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM main.sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(name)
+            .fetch_one(&mut *conn)
+            .await?;
+    if exists.0 == 0 {
+        sqlx::query(sql).execute(&mut *conn).await?;
+    }
+
+    // table_info leaves out generated columns, which can't be written
+    let columns: Vec<String> = sqlx::query(
+        "SELECT b.name FROM pragma_table_info(?1, 'backup') b \
+         JOIN pragma_table_info(?1, 'main') m ON m.name = b.name",
+    )
+    .bind(name)
+    .fetch_all(&mut *conn)
+    .await?
+    .iter()
+    .map(|row| quote(row.get("name")))
+    .collect();
+    let columns = columns.join(", ");
+
+    sqlx::query(&format!("DELETE FROM main.{}", quote(name)))
+        .execute(&mut *conn)
+        .await?;
+    let copied = sqlx::query(&format!(
+        "INSERT INTO main.{table} ({columns}) SELECT {columns} FROM backup.{table}",
+        table = quote(name),
+        columns = columns
+    ))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(copied.rows_affected())
+}
+
+/// Fail unless SQLite finds the file intact
+async fn check_integrity(path: &Path) -> Result<()> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let (result,): (String,) = sqlx::query_as("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await?;
+    conn.close().await?;
+
+    if result != "ok" {
+        anyhow::bail!("{} failed its integrity check: {}", path.display(), result);
+    }
+    Ok(())
+}
+
+/// Single connection to an existing database
+async fn connect(database_url: &str) -> Result<SqliteConnection> {
+    let options = SqliteConnectOptions::from_str(database_url).context("Invalid database URL")?;
+    SqliteConnection::connect_with(&options)
+        .await
+        .context("Failed to connect to database")
+}
+
+/// Quote an identifier for SQL
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let dir = std::env::temp_dir().join(format!("esport-signal-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("live.db");
+        let copy = dir.join("copy.db");
+        let url = format!("sqlite:{}?mode=rwc", db.display());
+
+        let mut conn = connect(&url).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, x INTEGER, y INTEGER AS (x * 2) VIRTUAL);
+             INSERT INTO t (x) VALUES (1), (2);",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        backup(&url, &copy).await.unwrap();
+        assert!(backup(&url, &copy).await.is_err());
+
+        sqlx::query("DELETE FROM t; INSERT INTO t (x) VALUES (9)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let restored = restore(&url, &copy).await.unwrap();
+        assert_eq!(restored[0].rows, 2);
+
+        let (sum,): (i64,) = sqlx::query_as("SELECT SUM(y) FROM t")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(sum, 6);
+
+        conn.close().await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,10 +3,12 @@ use std::str::FromStr;
 use anyhow::{Context, Result};
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
-    Pool, Sqlite,
+    Pool, QueryBuilder, Sqlite,
 };
 use tracing::info;
 
+use super::add_column_if_missing;
+
 /// Historical match data for ML training
 #[derive(Debug, Clone)]
 pub struct HistoricalMatch {
@@ -20,9 +22,26 @@ pub struct HistoricalMatch {
     pub radiant_xp_adv: String,    // JSON array
     pub start_time: Option<i64>,
     pub league_name: Option<String>,
+    pub patch: Option<i32>,        // OpenDota patch ID
     pub fetched_at: String,
 }
 
+/// Filters for `HistoricalStore::query_matches`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct HistoricalMatchFilter {
+    /// Case-insensitive substring of either team name
+    pub team: Option<String>,
+
+    /// Case-insensitive substring of the league name
+    pub league: Option<String>,
+
+    /// Unix start time range, inclusive lower bound
+    pub start_after: Option<i64>,
+    pub start_before: Option<i64>,
+
+    pub radiant_win: Option<bool>,
+}
+
 /// SQLite store for historical match data
 pub struct HistoricalStore {
     pool: Pool<Sqlite>,
@@ -74,6 +93,7 @@ impl HistoricalStore {
                 radiant_xp_adv TEXT NOT NULL,
                 start_time INTEGER,
                 league_name TEXT,
+                patch INTEGER,
                 fetched_at TEXT NOT NULL
             )
             "#,
@@ -82,6 +102,9 @@ impl HistoricalStore {
         .await
         .context("Failed to create historical_matches table")?;
 
+        // Columns added after the initial schema
+        add_column_if_missing(&self.pool, "historical_matches", "patch", "INTEGER").await?;
+
         // Create index on match_id for quick lookups
         sqlx::query(
             r#"
@@ -119,8 +142,9 @@ impl HistoricalStore {
                 radiant_xp_adv,
                 start_time,
                 league_name,
+                patch,
                 fetched_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(match_data.match_id)
@@ -132,6 +156,7 @@ impl HistoricalStore {
         .bind(&match_data.radiant_xp_adv)
         .bind(match_data.start_time)
         .bind(&match_data.league_name)
+        .bind(match_data.patch)
         .bind(&match_data.fetched_at)
         .execute(&self.pool)
         .await
@@ -153,6 +178,60 @@ impl HistoricalStore {
         Ok(row.0 > 0)
     }
 
+    /// Get a match by its OpenDota match_id
+    pub async fn get_match(&self, match_id: i64) -> Result<Option<HistoricalMatch>> {
+        let row = sqlx::query_as::<_, HistoricalMatchRow>(
+            "SELECT * FROM historical_matches WHERE match_id = ?",
+        )
+        .bind(match_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch historical match")?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
+    /// Get the most recent matches matching a filter
+    pub async fn query_matches(
+        &self,
+        filter: &HistoricalMatchFilter,
+        limit: i64,
+    ) -> Result<Vec<HistoricalMatch>> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM historical_matches WHERE 1 = 1");
+
+        if let Some(team) = &filter.team {
+            let pattern = format!("%{}%", team);
+            query
+                .push(" AND (radiant_team LIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR dire_team LIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        if let Some(league) = &filter.league {
+            query.push(" AND league_name LIKE ").push_bind(format!("%{}%", league));
+        }
+        if let Some(after) = filter.start_after {
+            query.push(" AND start_time >= ").push_bind(after);
+        }
+        if let Some(before) = filter.start_before {
+            query.push(" AND start_time < ").push_bind(before);
+        }
+        if let Some(radiant_win) = filter.radiant_win {
+            query.push(" AND radiant_win = ").push_bind(radiant_win);
+        }
+
+        query.push(" ORDER BY start_time DESC LIMIT ").push_bind(limit);
+
+        let rows = query
+            .build_query_as::<HistoricalMatchRow>()
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to query historical matches")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
     /// Get the count of historical matches
     pub async fn get_count(&self) -> Result<i64> {
         let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM historical_matches")
@@ -201,6 +280,7 @@ struct HistoricalMatchRow {
     radiant_xp_adv: String,
     start_time: Option<i64>,
     league_name: Option<String>,
+    patch: Option<i32>,
     fetched_at: String,
 }
 
@@ -217,6 +297,7 @@ impl From<HistoricalMatchRow> for HistoricalMatch {
             radiant_xp_adv: row.radiant_xp_adv,
             start_time: row.start_time,
             league_name: row.league_name,
+            patch: row.patch,
             fetched_at: row.fetched_at,
         }
     }
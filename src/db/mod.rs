@@ -1,5 +1,45 @@
+pub mod backup;
 pub mod historical;
+pub mod positions;
+pub mod prices;
 pub mod signals;
 
-pub use historical::{HistoricalMatch, HistoricalStore};
-pub use signals::SignalStore;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Sqlite};
+use tracing::info;
+
+pub use historical::{HistoricalMatch, HistoricalMatchFilter, HistoricalStore};
+pub use positions::PositionStore;
+pub use prices::{PriceHistoryMarket, PriceHistoryStore};
+pub use signals::{MatchOutcome, SignalFilter, SignalPage, SignalStore};
+
+/// Add a column to an existing table if an older database lacks it
+///
+/// Checks `table_xinfo` so generated columns count as present.
+pub(crate) async fn add_column_if_missing(
+    pool: &Pool<Sqlite>,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pragma_table_xinfo(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await
+        .with_context(|| format!("Failed to inspect {} table", table))?;
+
+    if row.0 == 0 {
+        sqlx::query(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))
+        .execute(pool)
+        .await
+        .with_context(|| format!("Failed to add {}.{} column", table, column))?;
+
+        info!("Migrated {} table: added {} column", table, column);
+    }
+
+    Ok(())
+}
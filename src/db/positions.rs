@@ -0,0 +1,322 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, Sqlite,
+};
+use tracing::info;
+
+use crate::models::{Position, PositionSide, PositionStatus};
+
+/// SQLite store for trading positions
+pub struct PositionStore {
+    pool: Pool<Sqlite>,
+}
+
+impl PositionStore {
+    /// Create a new position store and initialize the database
+    pub async fn new(database_url: &str) -> Result<Self> {
+        // Create data directory if needed
+        if let Some(path) = database_url.strip_prefix("sqlite:") {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                if !parent.as_os_str().is_empty() {
+                    std::fs::create_dir_all(parent)
+                        .context("Failed to create database directory")?;
+                }
+            }
+        }
+
+        // Parse connection options and enable create_if_missing
+        let options = SqliteConnectOptions::from_str(database_url)
+            .context("Invalid database URL")?
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .context("Failed to connect to database")?;
+
+        let store = Self { pool };
+        store.init_schema().await?;
+
+        info!("Position store initialized");
+        Ok(store)
+    }
+
+    /// Wait for in-flight queries and close all connections
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    /// Initialize database schema
+    async fn init_schema(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS positions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                signal_id INTEGER,
+                market_condition_id TEXT NOT NULL,
+                match_id INTEGER,
+                side TEXT NOT NULL,
+                entry_price REAL NOT NULL,
+                shares REAL NOT NULL,
+                remaining_shares REAL NOT NULL,
+                realized_pnl REAL NOT NULL DEFAULT 0,
+                status TEXT NOT NULL,
+                opened_at TEXT NOT NULL,
+                closed_at TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create positions table")?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_positions_market
+            ON positions (market_condition_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_positions_status
+            ON positions (status)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a newly opened position
+    pub async fn insert_position(&self, position: &Position) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO positions (
+                signal_id,
+                market_condition_id,
+                match_id,
+                side,
+                entry_price,
+                shares,
+                remaining_shares,
+                realized_pnl,
+                status,
+                opened_at,
+                closed_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(position.signal_id)
+        .bind(&position.market_condition_id)
+        .bind(position.match_id)
+        .bind(position.side.as_str())
+        .bind(position.entry_price)
+        .bind(position.shares)
+        .bind(position.remaining_shares)
+        .bind(position.realized_pnl)
+        .bind(position.status.as_str())
+        .bind(position.opened_at.to_rfc3339())
+        .bind(position.closed_at.map(|dt| dt.to_rfc3339()))
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert position")?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Sell shares of an active position, returning the updated position
+    pub async fn close_position(&self, id: i64, shares: f64, price: f64) -> Result<Position> {
+        let mut position = self.require_position(id).await?;
+        position.close(shares, price)?;
+        self.update_position(&position).await?;
+
+        Ok(position)
+    }
+
+    /// Settle an active position at market resolution, returning the updated position
+    pub async fn resolve_position(&self, id: i64, won: bool) -> Result<Position> {
+        let mut position = self.require_position(id).await?;
+        position.resolve(won)?;
+        self.update_position(&position).await?;
+
+        Ok(position)
+    }
+
+    /// Persist lifecycle fields of an existing position
+    async fn update_position(&self, position: &Position) -> Result<()> {
+        let id = position.id.context("Position has no id")?;
+
+        sqlx::query(
+            r#"
+            UPDATE positions
+            SET remaining_shares = ?, realized_pnl = ?, status = ?, closed_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(position.remaining_shares)
+        .bind(position.realized_pnl)
+        .bind(position.status.as_str())
+        .bind(position.closed_at.map(|dt| dt.to_rfc3339()))
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update position")?;
+
+        Ok(())
+    }
+
+    /// Get a position by id
+    pub async fn get_position(&self, id: i64) -> Result<Option<Position>> {
+        let row = sqlx::query_as::<_, PositionRow>("SELECT * FROM positions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to fetch position")?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
+    /// Get a position by id, failing if it doesn't exist
+    async fn require_position(&self, id: i64) -> Result<Position> {
+        self.get_position(id)
+            .await?
+            .with_context(|| format!("Position {} not found", id))
+    }
+
+    /// Get positions that still hold shares
+    pub async fn get_open_positions(&self) -> Result<Vec<Position>> {
+        let rows = sqlx::query_as::<_, PositionRow>(
+            r#"
+            SELECT * FROM positions
+            WHERE status IN ('Open', 'PartiallyClosed')
+            ORDER BY opened_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch open positions")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get positions with no shares left (sold or settled), oldest close first
+    pub async fn get_finished_positions(&self) -> Result<Vec<Position>> {
+        let rows = sqlx::query_as::<_, PositionRow>(
+            r#"
+            SELECT * FROM positions
+            WHERE status IN ('Closed', 'Resolved')
+            ORDER BY closed_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch finished positions")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get positions opened at or after a time, whatever their state
+    pub async fn get_positions_opened_since(&self, since: DateTime<Utc>) -> Result<Vec<Position>> {
+        let rows = sqlx::query_as::<_, PositionRow>(
+            r#"
+            SELECT * FROM positions
+            WHERE opened_at >= ?
+            ORDER BY opened_at DESC
+            "#,
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch positions")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get all positions in a market
+    pub async fn get_positions_for_market(
+        &self,
+        market_condition_id: &str,
+    ) -> Result<Vec<Position>> {
+        let rows = sqlx::query_as::<_, PositionRow>(
+            r#"
+            SELECT * FROM positions
+            WHERE market_condition_id = ?
+            ORDER BY opened_at DESC
+            "#,
+        )
+        .bind(market_condition_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch positions")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get the most recent positions
+    pub async fn get_recent_positions(&self, limit: i64) -> Result<Vec<Position>> {
+        let rows = sqlx::query_as::<_, PositionRow>(
+            r#"
+            SELECT * FROM positions
+            ORDER BY opened_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch positions")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+}
+
+/// Database row representation
+#[derive(sqlx::FromRow)]
+struct PositionRow {
+    id: i64,
+    signal_id: Option<i64>,
+    market_condition_id: String,
+    match_id: Option<i64>,
+    side: String,
+    entry_price: f64,
+    shares: f64,
+    remaining_shares: f64,
+    realized_pnl: f64,
+    status: String,
+    opened_at: String,
+    closed_at: Option<String>,
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+}
+
+impl From<PositionRow> for Position {
+    fn from(row: PositionRow) -> Self {
+        Position {
+            id: Some(row.id),
+            signal_id: row.signal_id,
+            market_condition_id: row.market_condition_id,
+            match_id: row.match_id,
+            side: row.side.parse().unwrap_or(PositionSide::TeamA),
+            entry_price: row.entry_price,
+            shares: row.shares,
+            remaining_shares: row.remaining_shares,
+            realized_pnl: row.realized_pnl,
+            status: row.status.parse().unwrap_or(PositionStatus::Open),
+            opened_at: parse_timestamp(&row.opened_at).unwrap_or_else(Utc::now),
+            closed_at: row.closed_at.as_deref().and_then(parse_timestamp),
+        }
+    }
+}
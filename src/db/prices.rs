@@ -0,0 +1,229 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, Sqlite,
+};
+use tracing::info;
+
+use crate::models::PricePoint;
+
+/// A resolved market with backfilled prices
+#[derive(Debug, Clone)]
+pub struct PriceHistoryMarket {
+    pub condition_id: String,
+    pub question: String,
+    pub team_a: String,
+    pub team_b: String,
+    /// CLOB token the prices are for
+    pub team_a_token_id: String,
+    pub team_a_won: bool,
+    pub end_date: Option<DateTime<Utc>>,
+    pub backfilled_at: DateTime<Utc>,
+}
+
+/// SQLite store for historical market prices
+pub struct PriceHistoryStore {
+    pool: Pool<Sqlite>,
+}
+
+impl PriceHistoryStore {
+    /// Create a new price history store and initialize the database
+    pub async fn new(database_url: &str) -> Result<Self> {
+        // Create data directory if needed
+        if let Some(path) = database_url.strip_prefix("sqlite:") {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                if !parent.as_os_str().is_empty() {
+                    std::fs::create_dir_all(parent)
+                        .context("Failed to create database directory")?;
+                }
+            }
+        }
+
+        // Parse connection options and enable create_if_missing
+        let options = SqliteConnectOptions::from_str(database_url)
+            .context("Invalid database URL")?
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .context("Failed to connect to database")?;
+
+        let store = Self { pool };
+        store.init_schema().await?;
+
+        info!("Price history store initialized");
+        Ok(store)
+    }
+
+    /// Initialize database schema
+    async fn init_schema(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS price_history_markets (
+                condition_id TEXT PRIMARY KEY,
+                question TEXT NOT NULL,
+                team_a TEXT NOT NULL,
+                team_b TEXT NOT NULL,
+                team_a_token_id TEXT NOT NULL,
+                team_a_won INTEGER NOT NULL,
+                end_date TEXT,
+                backfilled_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create price_history_markets table")?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS price_history (
+                condition_id TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                team_a_price REAL NOT NULL,
+                PRIMARY KEY (condition_id, timestamp)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create price_history table")?;
+
+        Ok(())
+    }
+
+    /// Replace a market's stored price series
+    pub async fn save(&self, market: &PriceHistoryMarket, points: &[PricePoint]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO price_history_markets (
+                condition_id,
+                question,
+                team_a,
+                team_b,
+                team_a_token_id,
+                team_a_won,
+                end_date,
+                backfilled_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&market.condition_id)
+        .bind(&market.question)
+        .bind(&market.team_a)
+        .bind(&market.team_b)
+        .bind(&market.team_a_token_id)
+        .bind(market.team_a_won)
+        .bind(market.end_date.map(|d| d.to_rfc3339()))
+        .bind(market.backfilled_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to save price history market")?;
+
+        sqlx::query("DELETE FROM price_history WHERE condition_id = ?")
+            .bind(&market.condition_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear price history")?;
+
+        for point in points {
+            sqlx::query(
+                "INSERT OR REPLACE INTO price_history (condition_id, timestamp, team_a_price) VALUES (?, ?, ?)",
+            )
+            .bind(&market.condition_id)
+            .bind(point.timestamp.timestamp())
+            .bind(point.price)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to insert price point")?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Condition IDs of every backfilled market
+    pub async fn backfilled_ids(&self) -> Result<HashSet<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT condition_id FROM price_history_markets")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to fetch backfilled markets")?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Every backfilled market, oldest end date first
+    pub async fn get_markets(&self) -> Result<Vec<PriceHistoryMarket>> {
+        let rows = sqlx::query_as::<_, PriceHistoryMarketRow>(
+            "SELECT * FROM price_history_markets ORDER BY end_date ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch price history markets")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Team A price series of a market, oldest first
+    pub async fn get_prices(&self, condition_id: &str) -> Result<Vec<PricePoint>> {
+        let rows: Vec<(i64, f64)> = sqlx::query_as(
+            "SELECT timestamp, team_a_price FROM price_history WHERE condition_id = ? ORDER BY timestamp ASC",
+        )
+        .bind(condition_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch price history")?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(timestamp, price)| {
+                Some(PricePoint {
+                    timestamp: DateTime::from_timestamp(timestamp, 0)?,
+                    price,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Database row representation
+#[derive(sqlx::FromRow)]
+struct PriceHistoryMarketRow {
+    condition_id: String,
+    question: String,
+    team_a: String,
+    team_b: String,
+    team_a_token_id: String,
+    team_a_won: bool,
+    end_date: Option<String>,
+    backfilled_at: String,
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+}
+
+impl From<PriceHistoryMarketRow> for PriceHistoryMarket {
+    fn from(row: PriceHistoryMarketRow) -> Self {
+        PriceHistoryMarket {
+            condition_id: row.condition_id,
+            question: row.question,
+            team_a: row.team_a,
+            team_b: row.team_b,
+            team_a_token_id: row.team_a_token_id,
+            team_a_won: row.team_a_won,
+            end_date: row.end_date.as_deref().and_then(parse_timestamp),
+            backfilled_at: parse_timestamp(&row.backfilled_at).unwrap_or_else(Utc::now),
+        }
+    }
+}
@@ -1,13 +1,251 @@
 use std::str::FromStr;
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
-    Pool, Sqlite,
+    Pool, QueryBuilder, Sqlite,
 };
 use tracing::info;
 
-use crate::models::Signal;
+use super::add_column_if_missing;
+use crate::analytics::{SignalStats, StatsGroup};
+use crate::models::{
+    ArbitrageSignal, ClosingLine, ExitReason, ExitSignal, LiveMatchState, MutedMarket, Signal,
+    SignalStrength, SignalType,
+};
+
+/// Snapshot fields exposed as columns, computed from `match_snapshot`
+const SNAPSHOT_COLUMNS: [(&str, &str); 6] = [
+    ("game_time", "$.game_time"),
+    ("gold_lead", "$.gold_lead"),
+    ("radiant_kills", "$.radiant.kills"),
+    ("dire_kills", "$.dire.kills"),
+    ("radiant_towers", "$.radiant.towers_killed"),
+    ("dire_towers", "$.dire.towers_killed"),
+];
+
+/// Analytics views as (name, SELECT), in dependency order
+///
+/// `signal_metrics` holds the per-signal definitions `signal_stats` and the
+/// summaries aggregate; `signal_outcomes` needs `historical_matches`, which
+/// `HistoricalStore` creates.
+const VIEWS: [(&str, &str); 4] = [
+    (
+        "signal_metrics",
+        r#"
+        SELECT
+            s.*,
+            substr(s.created_at, 1, 10) AS day,
+            COALESCE(json_extract(s.match_snapshot, '$.league_name'), 'unknown') AS league,
+            CASE WHEN s.net_edge != 0 THEN 1 ELSE 0 END AS actionable,
+            CASE WHEN s.net_edge != 0 THEN ABS(s.net_edge) END AS actionable_edge
+        FROM signals s
+        "#,
+    ),
+    (
+        "signal_outcomes",
+        r#"
+        SELECT
+            m.*,
+            CASE
+                WHEN h.match_id IS NULL THEN 'Pending'
+                WHEN h.radiant_win THEN 'RadiantWin'
+                ELSE 'DireWin'
+            END AS outcome,
+            CASE
+                WHEN h.match_id IS NULL OR m.team_a_is_radiant IS NULL THEN NULL
+                ELSE m.team_a_is_radiant = h.radiant_win
+            END AS team_a_won
+        FROM signal_metrics m
+        LEFT JOIN historical_matches h ON h.match_id = m.match_id
+        "#,
+    ),
+    (
+        "daily_strength_summary",
+        r#"
+        SELECT
+            day,
+            strength,
+            COUNT(*) AS signals,
+            SUM(actionable) AS actionable,
+            AVG(actionable_edge) AS avg_edge,
+            AVG(confidence) AS avg_confidence,
+            COUNT(correct) AS graded,
+            SUM(correct) AS correct
+        FROM signal_metrics
+        GROUP BY day, strength
+        "#,
+    ),
+    (
+        "team_edge",
+        r#"
+        SELECT
+            team,
+            COUNT(*) AS signals,
+            COUNT(CASE WHEN team_edge > 0 THEN 1 END) AS backed,
+            AVG(team_edge) AS avg_net_edge
+        FROM (
+            SELECT
+                json_extract(match_snapshot, '$.radiant.name') AS team,
+                CASE WHEN team_a_is_radiant THEN net_edge ELSE -net_edge END AS team_edge
+            FROM signals
+            WHERE net_edge IS NOT NULL AND team_a_is_radiant IS NOT NULL
+            UNION ALL
+            SELECT
+                json_extract(match_snapshot, '$.dire.name'),
+                CASE WHEN team_a_is_radiant THEN -net_edge ELSE net_edge END
+            FROM signals
+            WHERE net_edge IS NOT NULL AND team_a_is_radiant IS NOT NULL
+        )
+        GROUP BY team
+        "#,
+    ),
+];
+
+/// Result of the match a signal was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOutcome {
+    RadiantWin,
+    DireWin,
+    /// No historical record of the match yet
+    Pending,
+}
+
+impl FromStr for MatchOutcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "RadiantWin" => Ok(MatchOutcome::RadiantWin),
+            "DireWin" => Ok(MatchOutcome::DireWin),
+            "Pending" => Ok(MatchOutcome::Pending),
+            other => anyhow::bail!("Unknown match outcome: {}", other),
+        }
+    }
+}
+
+/// Composable signal query; unset fields match everything
+///
+/// Build with the chained setters, e.g.
+/// `SignalFilter::default().team("Spirit").min_strength(SignalStrength::Strong)`.
+#[derive(Debug, Clone, Default)]
+pub struct SignalFilter {
+    pub match_id: Option<i64>,
+    pub market_condition_id: Option<String>,
+
+    /// Case-insensitive substring of either team name in the snapshot
+    pub team: Option<String>,
+
+    pub min_strength: Option<SignalStrength>,
+
+    /// Signal types to include; empty matches every type
+    pub signal_types: Vec<SignalType>,
+
+    /// Minimum net edge on either side (compared to `|net_edge|`)
+    pub min_net_edge: Option<f64>,
+
+    /// Created at or after
+    pub since: Option<DateTime<Utc>>,
+
+    /// Created before
+    pub until: Option<DateTime<Utc>>,
+
+    /// Match result from `historical_matches`
+    pub outcome: Option<MatchOutcome>,
+}
+
+impl SignalFilter {
+    pub fn match_id(mut self, match_id: i64) -> Self {
+        self.match_id = Some(match_id);
+        self
+    }
+
+    pub fn market(mut self, market_condition_id: impl Into<String>) -> Self {
+        self.market_condition_id = Some(market_condition_id.into());
+        self
+    }
+
+    pub fn team(mut self, team: impl Into<String>) -> Self {
+        self.team = Some(team.into());
+        self
+    }
+
+    pub fn min_strength(mut self, strength: SignalStrength) -> Self {
+        self.min_strength = Some(strength);
+        self
+    }
+
+    pub fn signal_types(mut self, types: impl IntoIterator<Item = SignalType>) -> Self {
+        self.signal_types = types.into_iter().collect();
+        self
+    }
+
+    pub fn min_net_edge(mut self, edge: f64) -> Self {
+        self.min_net_edge = Some(edge);
+        self
+    }
+
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn outcome(mut self, outcome: MatchOutcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+
+    /// Check a signal in memory, e.g. one just stored
+    ///
+    /// `outcome` needs the historical table and is ignored here.
+    pub fn matches(&self, signal: &Signal) -> bool {
+        if self.match_id.is_some_and(|id| id != signal.match_id)
+            || self
+                .market_condition_id
+                .as_ref()
+                .is_some_and(|market| *market != signal.market_condition_id)
+            || self.min_strength.is_some_and(|min| signal.strength < min)
+            || (!self.signal_types.is_empty() && !self.signal_types.contains(&signal.signal_type))
+            || self.since.is_some_and(|since| signal.created_at < since)
+            || self.until.is_some_and(|until| signal.created_at >= until)
+        {
+            return false;
+        }
+
+        if let Some(min) = self.min_net_edge {
+            if signal.net_edge.is_none_or(|edge| edge.abs() < min) {
+                return false;
+            }
+        }
+
+        if let Some(team) = &self.team {
+            let team = team.to_lowercase();
+            let Ok(state) = serde_json::from_str::<LiveMatchState>(&signal.match_snapshot) else {
+                return false;
+            };
+            return state.radiant.name.to_lowercase().contains(&team)
+                || state.dire.name.to_lowercase().contains(&team);
+        }
+
+        true
+    }
+}
+
+/// One page of a keyset-paginated signal query, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalPage {
+    pub signals: Vec<Signal>,
+
+    /// Pass as `before_id` to fetch the next page; None on the last page
+    pub next_cursor: Option<i64>,
+}
 
 /// SQLite store for match snapshots
 pub struct SignalStore {
@@ -45,6 +283,17 @@ impl SignalStore {
         Ok(store)
     }
 
+    /// Check that the database answers queries
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Wait for in-flight queries and close all connections
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
     /// Initialize database schema
     async fn init_schema(&self) -> Result<()> {
         sqlx::query(
@@ -52,8 +301,28 @@ impl SignalStore {
             CREATE TABLE IF NOT EXISTS signals (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 market_condition_id TEXT NOT NULL,
+                venue TEXT NOT NULL DEFAULT 'Polymarket',
                 match_id INTEGER NOT NULL,
                 market_team_a_odds REAL NOT NULL,
+                team_a_prior_prob REAL,
+                sharp_team_a_prob REAL,
+                team_a_win_prob REAL,
+                team_a_prob_lower REAL,
+                team_a_prob_upper REAL,
+                confidence REAL,
+                edge REAL,
+                net_edge REAL,
+                strength TEXT NOT NULL DEFAULT 'Neutral',
+                signal_type TEXT NOT NULL DEFAULT 'Snapshot',
+                escalated INTEGER NOT NULL DEFAULT 0,
+                fight_winner TEXT,
+                expected_remaining_secs INTEGER,
+                model_version TEXT,
+                features TEXT,
+                reason TEXT,
+                team_a_is_radiant INTEGER,
+                correct INTEGER,
+                graded_at TEXT,
                 match_snapshot TEXT NOT NULL,
                 created_at TEXT NOT NULL
             )
@@ -63,6 +332,61 @@ impl SignalStore {
         .await
         .context("Failed to create signals table")?;
 
+        // Columns added after the initial schema
+        add_column_if_missing(&self.pool, "signals", "team_a_win_prob", "REAL").await?;
+        add_column_if_missing(&self.pool, "signals", "model_version", "TEXT").await?;
+        add_column_if_missing(&self.pool, "signals", "team_a_prior_prob", "REAL").await?;
+        add_column_if_missing(&self.pool, "signals", "team_a_prob_lower", "REAL").await?;
+        add_column_if_missing(&self.pool, "signals", "team_a_prob_upper", "REAL").await?;
+        add_column_if_missing(&self.pool, "signals", "sharp_team_a_prob", "REAL").await?;
+        add_column_if_missing(&self.pool, "signals", "edge", "REAL").await?;
+        add_column_if_missing(&self.pool, "signals", "net_edge", "REAL").await?;
+        add_column_if_missing(
+            &self.pool,
+            "signals",
+            "strength",
+            "TEXT NOT NULL DEFAULT 'Neutral'",
+        )
+        .await?;
+        add_column_if_missing(
+            &self.pool,
+            "signals",
+            "signal_type",
+            "TEXT NOT NULL DEFAULT 'Snapshot'",
+        )
+        .await?;
+        add_column_if_missing(
+            &self.pool,
+            "signals",
+            "escalated",
+            "INTEGER NOT NULL DEFAULT 0",
+        )
+        .await?;
+        add_column_if_missing(&self.pool, "signals", "fight_winner", "TEXT").await?;
+        add_column_if_missing(&self.pool, "signals", "expected_remaining_secs", "INTEGER").await?;
+        add_column_if_missing(&self.pool, "signals", "features", "TEXT").await?;
+        add_column_if_missing(&self.pool, "signals", "reason", "TEXT").await?;
+        add_column_if_missing(&self.pool, "signals", "team_a_is_radiant", "INTEGER").await?;
+        add_column_if_missing(&self.pool, "signals", "correct", "INTEGER").await?;
+        add_column_if_missing(&self.pool, "signals", "graded_at", "TEXT").await?;
+        add_column_if_missing(&self.pool, "signals", "confidence", "REAL").await?;
+        add_column_if_missing(
+            &self.pool,
+            "signals",
+            "venue",
+            "TEXT NOT NULL DEFAULT 'Polymarket'",
+        )
+        .await?;
+
+        // Virtual, so rows stored before they existed have them too
+        for (column, path) in SNAPSHOT_COLUMNS {
+            let definition = format!(
+                "INTEGER GENERATED ALWAYS AS (json_extract(match_snapshot, '{}')) VIRTUAL",
+                path
+            );
+            add_column_if_missing(&self.pool, "signals", column, &definition).await?;
+        }
+
         // Create indexes for common queries
         sqlx::query(
             r#"
@@ -91,6 +415,102 @@ impl SignalStore {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS exit_signals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entry_signal_id INTEGER NOT NULL REFERENCES signals (id),
+                trigger_signal_id INTEGER NOT NULL REFERENCES signals (id),
+                market_condition_id TEXT NOT NULL,
+                match_id INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                entry_net_edge REAL NOT NULL,
+                current_net_edge REAL NOT NULL,
+                market_team_a_odds REAL NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create exit_signals table")?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_exit_signals_entry
+            ON exit_signals (entry_signal_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS arbitrage_signals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                market_condition_id TEXT NOT NULL,
+                betfair_market_id TEXT NOT NULL,
+                polymarket_team TEXT NOT NULL,
+                polymarket_cost REAL NOT NULL,
+                betfair_team TEXT NOT NULL,
+                betfair_back_odds REAL NOT NULL,
+                betfair_available REAL NOT NULL,
+                betfair_stake REAL NOT NULL,
+                profit REAL NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create arbitrage_signals table")?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_arbitrage_signals_market
+            ON arbitrage_signals (market_condition_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS closing_lines (
+                market_condition_id TEXT PRIMARY KEY,
+                team_a_odds REAL NOT NULL,
+                recorded_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create closing_lines table")?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS muted_markets (
+                market_condition_id TEXT PRIMARY KEY,
+                reason TEXT,
+                muted_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to create muted_markets table")?;
+
+        // Recreated on every start so older databases get current definitions
+        for (name, select) in VIEWS {
+            sqlx::query(&format!("DROP VIEW IF EXISTS {}", name))
+                .execute(&self.pool)
+                .await?;
+            sqlx::query(&format!("CREATE VIEW {} AS {}", name, select))
+                .execute(&self.pool)
+                .await
+                .with_context(|| format!("Failed to create {} view", name))?;
+        }
+
         Ok(())
     }
 
@@ -100,16 +520,52 @@ impl SignalStore {
             r#"
             INSERT INTO signals (
                 market_condition_id,
+                venue,
                 match_id,
                 market_team_a_odds,
+                team_a_prior_prob,
+                sharp_team_a_prob,
+                team_a_win_prob,
+                team_a_prob_lower,
+                team_a_prob_upper,
+                confidence,
+                edge,
+                net_edge,
+                strength,
+                signal_type,
+                escalated,
+                fight_winner,
+                expected_remaining_secs,
+                model_version,
+                features,
+                reason,
+                team_a_is_radiant,
                 match_snapshot,
                 created_at
-            ) VALUES (?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&signal.market_condition_id)
+        .bind(signal.venue.as_str())
         .bind(signal.match_id)
         .bind(signal.market_team_a_odds)
+        .bind(signal.team_a_prior_prob)
+        .bind(signal.sharp_team_a_prob)
+        .bind(signal.team_a_win_prob)
+        .bind(signal.team_a_prob_lower)
+        .bind(signal.team_a_prob_upper)
+        .bind(signal.confidence)
+        .bind(signal.edge)
+        .bind(signal.net_edge)
+        .bind(signal.strength.as_str())
+        .bind(signal.signal_type.as_str())
+        .bind(signal.escalated)
+        .bind(&signal.fight_winner)
+        .bind(signal.expected_remaining_secs)
+        .bind(&signal.model_version)
+        .bind(&signal.features)
+        .bind(&signal.reason)
+        .bind(signal.team_a_is_radiant)
         .bind(&signal.match_snapshot)
         .bind(signal.created_at.to_rfc3339())
         .execute(&self.pool)
@@ -119,6 +575,230 @@ impl SignalStore {
         Ok(result.last_insert_rowid())
     }
 
+    /// Insert a new exit signal
+    pub async fn insert_exit_signal(&self, exit: &ExitSignal) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO exit_signals (
+                entry_signal_id,
+                trigger_signal_id,
+                market_condition_id,
+                match_id,
+                reason,
+                entry_net_edge,
+                current_net_edge,
+                market_team_a_odds,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(exit.entry_signal_id)
+        .bind(exit.trigger_signal_id)
+        .bind(&exit.market_condition_id)
+        .bind(exit.match_id)
+        .bind(exit.reason.as_str())
+        .bind(exit.entry_net_edge)
+        .bind(exit.current_net_edge)
+        .bind(exit.market_team_a_odds)
+        .bind(exit.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert exit signal")?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Record the latest live price of a market, replacing the previous one
+    pub async fn upsert_closing_line(&self, line: &ClosingLine) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO closing_lines (market_condition_id, team_a_odds, recorded_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (market_condition_id) DO UPDATE SET
+                team_a_odds = excluded.team_a_odds,
+                recorded_at = excluded.recorded_at
+            "#,
+        )
+        .bind(&line.market_condition_id)
+        .bind(line.team_a_odds)
+        .bind(line.recorded_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .context("Failed to record closing line")?;
+
+        Ok(())
+    }
+
+    /// Get closing lines for all markets
+    pub async fn get_closing_lines(&self) -> Result<Vec<ClosingLine>> {
+        let rows = sqlx::query_as::<_, ClosingLineRow>("SELECT * FROM closing_lines")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to fetch closing lines")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Mute a market, replacing the reason if it's already muted
+    pub async fn mute_market(&self, mute: &MutedMarket) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO muted_markets (market_condition_id, reason, muted_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (market_condition_id) DO UPDATE SET reason = excluded.reason
+            "#,
+        )
+        .bind(&mute.market_condition_id)
+        .bind(&mute.reason)
+        .bind(mute.muted_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .context("Failed to mute market")?;
+
+        Ok(())
+    }
+
+    /// Unmute a market; false if it wasn't muted
+    pub async fn unmute_market(&self, market_condition_id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM muted_markets WHERE market_condition_id = ?")
+            .bind(market_condition_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to unmute market")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether signal generation is muted for a market
+    pub async fn is_market_muted(&self, market_condition_id: &str) -> Result<bool> {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT 1 FROM muted_markets WHERE market_condition_id = ?")
+                .bind(market_condition_id)
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to check muted market")?;
+
+        Ok(row.is_some())
+    }
+
+    /// Get all muted markets, most recently muted first
+    pub async fn get_muted_markets(&self) -> Result<Vec<MutedMarket>> {
+        let rows = sqlx::query_as::<_, MutedMarketRow>(
+            "SELECT * FROM muted_markets ORDER BY muted_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch muted markets")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get signals whose interval cleared the price on either side, oldest first
+    pub async fn get_actionable_signals(&self) -> Result<Vec<Signal>> {
+        let rows = sqlx::query_as::<_, SignalRow>(
+            r#"
+            SELECT * FROM signals
+            WHERE strength != 'Neutral'
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Insert a new arbitrage signal
+    pub async fn insert_arbitrage_signal(&self, arb: &ArbitrageSignal) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO arbitrage_signals (
+                market_condition_id,
+                betfair_market_id,
+                polymarket_team,
+                polymarket_cost,
+                betfair_team,
+                betfair_back_odds,
+                betfair_available,
+                betfair_stake,
+                profit,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&arb.market_condition_id)
+        .bind(&arb.betfair_market_id)
+        .bind(&arb.polymarket_team)
+        .bind(arb.polymarket_cost)
+        .bind(&arb.betfair_team)
+        .bind(arb.betfair_back_odds)
+        .bind(arb.betfair_available)
+        .bind(arb.betfair_stake)
+        .bind(arb.profit)
+        .bind(arb.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert arbitrage signal")?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Get arbitrage signals for a market
+    pub async fn get_arbitrage_signals_for_market(
+        &self,
+        market_condition_id: &str,
+    ) -> Result<Vec<ArbitrageSignal>> {
+        let rows = sqlx::query_as::<_, ArbitrageSignalRow>(
+            r#"
+            SELECT * FROM arbitrage_signals
+            WHERE market_condition_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(market_condition_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch arbitrage signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get all exit signals, oldest first
+    pub async fn get_all_exit_signals(&self) -> Result<Vec<ExitSignal>> {
+        let rows = sqlx::query_as::<_, ExitSignalRow>(
+            r#"
+            SELECT * FROM exit_signals
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch exit signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get exit signals for a market
+    pub async fn get_exit_signals_for_market(
+        &self,
+        market_condition_id: &str,
+    ) -> Result<Vec<ExitSignal>> {
+        let rows = sqlx::query_as::<_, ExitSignalRow>(
+            r#"
+            SELECT * FROM exit_signals
+            WHERE market_condition_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(market_condition_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch exit signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
     /// Get recent signals for a market
     pub async fn get_signals_for_market(
         &self,
@@ -142,6 +822,17 @@ impl SignalStore {
         Ok(rows.into_iter().map(|r| r.into()).collect())
     }
 
+    /// Get a signal by id
+    pub async fn get_signal(&self, id: i64) -> Result<Option<Signal>> {
+        let row = sqlx::query_as::<_, SignalRow>("SELECT * FROM signals WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to fetch signal")?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
     /// Get recent signals for a match
     pub async fn get_signals_for_match(&self, match_id: i64, limit: i64) -> Result<Vec<Signal>> {
         let rows = sqlx::query_as::<_, SignalRow>(
@@ -161,6 +852,172 @@ impl SignalStore {
         Ok(rows.into_iter().map(|r| r.into()).collect())
     }
 
+    /// Get the most recent signals across all markets
+    pub async fn get_recent_signals(&self, limit: i64) -> Result<Vec<Signal>> {
+        let rows = sqlx::query_as::<_, SignalRow>(
+            r#"
+            SELECT * FROM signals
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch recent signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get the latest signal of every market updated since a time
+    pub async fn get_latest_signal_per_market(&self, since: DateTime<Utc>) -> Result<Vec<Signal>> {
+        let rows = sqlx::query_as::<_, SignalRow>(
+            r#"
+            SELECT s.* FROM signals s
+            JOIN (
+                SELECT MAX(id) AS id FROM signals
+                WHERE created_at >= ?
+                GROUP BY market_condition_id
+            ) latest ON s.id = latest.id
+            ORDER BY s.created_at DESC
+            "#,
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch latest signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get the newest signals matching a filter
+    pub async fn query_signals(&self, filter: &SignalFilter, limit: i64) -> Result<Vec<Signal>> {
+        Ok(self.query_signals_page(filter, None, limit).await?.signals)
+    }
+
+    /// Get one page of signals matching a filter, newest first
+    ///
+    /// Pages are keyed on id rather than offset, so signals stored between
+    /// calls don't shift or repeat rows.
+    pub async fn query_signals_page(
+        &self,
+        filter: &SignalFilter,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<SignalPage> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT s.* FROM signals s WHERE 1 = 1");
+
+        if let Some(before_id) = before_id {
+            query.push(" AND s.id < ").push_bind(before_id);
+        }
+        push_conditions(&mut query, filter);
+
+        // One extra row tells whether another page follows
+        query
+            .push(" ORDER BY s.id DESC LIMIT ")
+            .push_bind(limit.max(0) + 1);
+
+        let mut rows = query
+            .build_query_as::<SignalRow>()
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to query signals")?;
+
+        let next_cursor = if rows.len() as i64 > limit.max(0) {
+            rows.pop();
+            rows.last().map(|row| row.id)
+        } else {
+            None
+        };
+
+        Ok(SignalPage {
+            signals: rows.into_iter().map(|r| r.into()).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Aggregate signals matching a filter, grouped and sorted by key
+    pub async fn signal_stats(
+        &self,
+        filter: &SignalFilter,
+        group: StatsGroup,
+    ) -> Result<Vec<SignalStats>> {
+        let key = match group {
+            StatsGroup::SignalType => "s.signal_type",
+            StatsGroup::Strength => "s.strength",
+            StatsGroup::League => "s.league",
+            StatsGroup::Day => "s.day",
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new(format!(
+            r#"
+            SELECT
+                {key} AS key,
+                COUNT(*) AS signals,
+                SUM(s.actionable) AS actionable,
+                AVG(s.actionable_edge) AS avg_edge,
+                AVG(s.confidence) AS avg_confidence,
+                COUNT(s.correct) AS graded,
+                AVG(s.correct) AS hit_rate
+            FROM signal_metrics s
+            WHERE 1 = 1
+            "#
+        ));
+        push_conditions(&mut query, filter);
+        query.push(" GROUP BY key ORDER BY key");
+
+        let rows = query
+            .build_query_as::<SignalStatsRow>()
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to aggregate signals")?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Ungraded signals whose match has a result, oldest first, each with
+    /// the match's `radiant_win`
+    pub async fn get_ungraded_signals(&self, limit: i64) -> Result<Vec<(Signal, bool)>> {
+        let rows = sqlx::query_as::<_, UngradedRow>(
+            r#"
+            SELECT s.*, h.radiant_win
+            FROM signals s
+            JOIN historical_matches h ON h.match_id = s.match_id
+            WHERE s.graded_at IS NULL
+            ORDER BY s.id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch ungraded signals")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.signal.into(), r.radiant_win))
+            .collect())
+    }
+
+    /// Record a signal's grade; `correct` is None for signals that can't be
+    /// graded, so they aren't fetched again
+    pub async fn set_grade(
+        &self,
+        id: i64,
+        correct: Option<bool>,
+        graded_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE signals SET correct = ?, graded_at = ? WHERE id = ?")
+            .bind(correct)
+            .bind(graded_at.to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to grade signal")?;
+
+        Ok(())
+    }
+
     /// Get count of signals
     pub async fn get_signal_count(&self) -> Result<i64> {
         let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM signals")
@@ -172,13 +1029,112 @@ impl SignalStore {
     }
 }
 
+/// Append `AND ...` conditions for every set filter field to a query over `signals s`
+fn push_conditions(query: &mut QueryBuilder<'_, Sqlite>, filter: &SignalFilter) {
+    if let Some(match_id) = filter.match_id {
+        query.push(" AND s.match_id = ").push_bind(match_id);
+    }
+    if let Some(market) = &filter.market_condition_id {
+        query
+            .push(" AND s.market_condition_id = ")
+            .push_bind(market.clone());
+    }
+    if let Some(team) = &filter.team {
+        let pattern = format!("%{}%", escape_like(team));
+        query
+            .push(" AND (json_extract(s.match_snapshot, '$.radiant.name') LIKE ")
+            .push_bind(pattern.clone())
+            .push(" ESCAPE '\\' OR json_extract(s.match_snapshot, '$.dire.name') LIKE ")
+            .push_bind(pattern)
+            .push(" ESCAPE '\\')");
+    }
+    if let Some(min) = filter.min_strength {
+        query.push(" AND s.strength IN (");
+        let mut names = query.separated(", ");
+        for strength in SignalStrength::ALL.iter().filter(|s| **s >= min) {
+            names.push_bind(strength.as_str());
+        }
+        query.push(")");
+    }
+    if !filter.signal_types.is_empty() {
+        query.push(" AND s.signal_type IN (");
+        let mut names = query.separated(", ");
+        for signal_type in &filter.signal_types {
+            names.push_bind(signal_type.as_str());
+        }
+        query.push(")");
+    }
+    if let Some(min) = filter.min_net_edge {
+        query.push(" AND ABS(s.net_edge) >= ").push_bind(min);
+    }
+    if let Some(since) = filter.since {
+        query
+            .push(" AND s.created_at >= ")
+            .push_bind(since.to_rfc3339());
+    }
+    if let Some(until) = filter.until {
+        query
+            .push(" AND s.created_at < ")
+            .push_bind(until.to_rfc3339());
+    }
+    // Only outcome filters need the historical table
+    match filter.outcome {
+        Some(MatchOutcome::RadiantWin) => {
+            query.push(
+                " AND s.match_id IN (SELECT match_id FROM historical_matches WHERE radiant_win = 1)",
+            );
+        }
+        Some(MatchOutcome::DireWin) => {
+            query.push(
+                " AND s.match_id IN (SELECT match_id FROM historical_matches WHERE radiant_win = 0)",
+            );
+        }
+        Some(MatchOutcome::Pending) => {
+            query.push(" AND s.match_id NOT IN (SELECT match_id FROM historical_matches)");
+        }
+        None => {}
+    }
+}
+
+/// Escape `LIKE` wildcards so user input matches literally (with `ESCAPE '\'`)
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// Database row representation
 #[derive(sqlx::FromRow)]
 struct SignalRow {
     id: i64,
     market_condition_id: String,
+    venue: String,
     match_id: i64,
     market_team_a_odds: f64,
+    team_a_prior_prob: Option<f64>,
+    sharp_team_a_prob: Option<f64>,
+    team_a_win_prob: Option<f64>,
+    team_a_prob_lower: Option<f64>,
+    team_a_prob_upper: Option<f64>,
+    confidence: Option<f64>,
+    edge: Option<f64>,
+    net_edge: Option<f64>,
+    strength: String,
+    signal_type: String,
+    escalated: bool,
+    fight_winner: Option<String>,
+    expected_remaining_secs: Option<i32>,
+    model_version: Option<String>,
+    features: Option<String>,
+    reason: Option<String>,
+    team_a_is_radiant: Option<bool>,
+    correct: Option<bool>,
+    graded_at: Option<String>,
     match_snapshot: String,
     created_at: String,
 }
@@ -188,8 +1144,31 @@ impl From<SignalRow> for Signal {
         Signal {
             id: Some(row.id),
             market_condition_id: row.market_condition_id,
+            venue: row.venue.parse().unwrap_or_default(),
             match_id: row.match_id,
             market_team_a_odds: row.market_team_a_odds,
+            team_a_prior_prob: row.team_a_prior_prob,
+            sharp_team_a_prob: row.sharp_team_a_prob,
+            team_a_win_prob: row.team_a_win_prob,
+            team_a_prob_lower: row.team_a_prob_lower,
+            team_a_prob_upper: row.team_a_prob_upper,
+            confidence: row.confidence,
+            edge: row.edge,
+            net_edge: row.net_edge,
+            strength: row.strength.parse().unwrap_or(SignalStrength::Neutral),
+            signal_type: row.signal_type.parse().unwrap_or(SignalType::Snapshot),
+            escalated: row.escalated,
+            fight_winner: row.fight_winner,
+            expected_remaining_secs: row.expected_remaining_secs,
+            model_version: row.model_version,
+            features: row.features,
+            reason: row.reason,
+            team_a_is_radiant: row.team_a_is_radiant,
+            correct: row.correct,
+            graded_at: row
+                .graded_at
+                .and_then(|at| chrono::DateTime::parse_from_rfc3339(&at).ok())
+                .map(|dt| dt.with_timezone(&chrono::Utc)),
             match_snapshot: row.match_snapshot,
             created_at: chrono::DateTime::parse_from_rfc3339(&row.created_at)
                 .map(|dt| dt.with_timezone(&chrono::Utc))
@@ -197,3 +1176,256 @@ impl From<SignalRow> for Signal {
         }
     }
 }
+
+/// Signal joined with its match result
+#[derive(sqlx::FromRow)]
+struct UngradedRow {
+    #[sqlx(flatten)]
+    signal: SignalRow,
+    radiant_win: bool,
+}
+
+/// Aggregate row of `signal_stats`
+#[derive(sqlx::FromRow)]
+struct SignalStatsRow {
+    key: String,
+    signals: i64,
+    actionable: i64,
+    avg_edge: Option<f64>,
+    avg_confidence: Option<f64>,
+    graded: i64,
+    hit_rate: Option<f64>,
+}
+
+impl From<SignalStatsRow> for SignalStats {
+    fn from(row: SignalStatsRow) -> Self {
+        SignalStats {
+            key: row.key,
+            signals: row.signals,
+            actionable: row.actionable,
+            avg_edge: row.avg_edge,
+            avg_confidence: row.avg_confidence,
+            graded: row.graded,
+            hit_rate: row.hit_rate,
+        }
+    }
+}
+
+/// Database row representation
+#[derive(sqlx::FromRow)]
+struct ExitSignalRow {
+    id: i64,
+    entry_signal_id: i64,
+    trigger_signal_id: i64,
+    market_condition_id: String,
+    match_id: i64,
+    reason: String,
+    entry_net_edge: f64,
+    current_net_edge: f64,
+    market_team_a_odds: f64,
+    created_at: String,
+}
+
+impl From<ExitSignalRow> for ExitSignal {
+    fn from(row: ExitSignalRow) -> Self {
+        ExitSignal {
+            id: Some(row.id),
+            entry_signal_id: row.entry_signal_id,
+            trigger_signal_id: row.trigger_signal_id,
+            market_condition_id: row.market_condition_id,
+            match_id: row.match_id,
+            reason: row.reason.parse().unwrap_or(ExitReason::Collapsed),
+            entry_net_edge: row.entry_net_edge,
+            current_net_edge: row.current_net_edge,
+            market_team_a_odds: row.market_team_a_odds,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        }
+    }
+}
+
+/// Database row representation
+#[derive(sqlx::FromRow)]
+struct ArbitrageSignalRow {
+    id: i64,
+    market_condition_id: String,
+    betfair_market_id: String,
+    polymarket_team: String,
+    polymarket_cost: f64,
+    betfair_team: String,
+    betfair_back_odds: f64,
+    betfair_available: f64,
+    betfair_stake: f64,
+    profit: f64,
+    created_at: String,
+}
+
+impl From<ArbitrageSignalRow> for ArbitrageSignal {
+    fn from(row: ArbitrageSignalRow) -> Self {
+        ArbitrageSignal {
+            id: Some(row.id),
+            market_condition_id: row.market_condition_id,
+            betfair_market_id: row.betfair_market_id,
+            polymarket_team: row.polymarket_team,
+            polymarket_cost: row.polymarket_cost,
+            betfair_team: row.betfair_team,
+            betfair_back_odds: row.betfair_back_odds,
+            betfair_available: row.betfair_available,
+            betfair_stake: row.betfair_stake,
+            profit: row.profit,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        }
+    }
+}
+
+/// Database row representation
+#[derive(sqlx::FromRow)]
+struct ClosingLineRow {
+    market_condition_id: String,
+    team_a_odds: f64,
+    recorded_at: String,
+}
+
+impl From<ClosingLineRow> for ClosingLine {
+    fn from(row: ClosingLineRow) -> Self {
+        ClosingLine {
+            market_condition_id: row.market_condition_id,
+            team_a_odds: row.team_a_odds,
+            recorded_at: chrono::DateTime::parse_from_rfc3339(&row.recorded_at)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        }
+    }
+}
+
+/// Database row representation
+#[derive(sqlx::FromRow)]
+struct MutedMarketRow {
+    market_condition_id: String,
+    reason: Option<String>,
+    muted_at: String,
+}
+
+impl From<MutedMarketRow> for MutedMarket {
+    fn from(row: MutedMarketRow) -> Self {
+        MutedMarket {
+            market_condition_id: row.market_condition_id,
+            reason: row.reason,
+            muted_at: chrono::DateTime::parse_from_rfc3339(&row.muted_at)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::SignalClv;
+    use crate::models::{TeamState, Venue};
+
+    #[tokio::test]
+    async fn test_closing_line_follows_the_last_live_price() {
+        let store = SignalStore::new("sqlite::memory:").await.unwrap();
+        let line = |team_a_odds: f64| ClosingLine {
+            market_condition_id: "0xa".to_string(),
+            team_a_odds,
+            recorded_at: Utc::now(),
+        };
+
+        // Backed team A at 0.50; the market moved to 0.62 before the close
+        let entry = Signal {
+            market_condition_id: "0xa".to_string(),
+            market_team_a_odds: 0.50,
+            net_edge: Some(0.06),
+            strength: SignalStrength::Strong,
+            ..Default::default()
+        };
+        store.upsert_closing_line(&line(0.50)).await.unwrap();
+        store.upsert_closing_line(&line(0.62)).await.unwrap();
+
+        let lines = store.get_closing_lines().await.unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].team_a_odds, 0.62);
+
+        let clv = SignalClv::new(entry, lines[0].team_a_odds).unwrap();
+        assert!((clv.clv() - 0.12).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_signal_keeps_its_venue() {
+        let store = SignalStore::new("sqlite::memory:").await.unwrap();
+        let signal = Signal {
+            market_condition_id: "KXDOTA2GAME-26OCT16SPIOG-SPI".to_string(),
+            venue: Venue::Kalshi,
+            match_snapshot: serde_json::to_string(&LiveMatchState::default()).unwrap(),
+            ..Default::default()
+        };
+        let id = store.insert_signal(&signal).await.unwrap();
+
+        let stored = store.get_signal(id).await.unwrap().unwrap();
+        assert_eq!(stored.venue, Venue::Kalshi);
+    }
+
+    #[tokio::test]
+    async fn test_team_filter_matches_wildcards_literally() {
+        let store = SignalStore::new("sqlite::memory:").await.unwrap();
+        for (radiant, dire) in [("Team_Spirit", "OG"), ("TeamXSpirit", "100% Gaming")] {
+            let state = LiveMatchState {
+                radiant: TeamState {
+                    name: radiant.to_string(),
+                    ..Default::default()
+                },
+                dire: TeamState {
+                    name: dire.to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let signal = Signal {
+                match_snapshot: serde_json::to_string(&state).unwrap(),
+                ..Default::default()
+            };
+            store.insert_signal(&signal).await.unwrap();
+        }
+
+        let teams = |team: &str| {
+            let filter = SignalFilter::default().team(team);
+            let store = &store;
+            async move { store.query_signals(&filter, 10).await.unwrap().len() }
+        };
+        assert_eq!(teams("Team_").await, 1);
+        assert_eq!(teams("0%").await, 1);
+        assert_eq!(teams("%").await, 1);
+        assert_eq!(teams("\\").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stats_report_hit_rate_over_graded_signals() {
+        let store = SignalStore::new("sqlite::memory:").await.unwrap();
+        let signal = Signal {
+            net_edge: Some(0.06),
+            strength: SignalStrength::Strong,
+            match_snapshot: serde_json::to_string(&LiveMatchState::default()).unwrap(),
+            ..Default::default()
+        };
+        for correct in [Some(true), Some(false), None] {
+            let id = store.insert_signal(&signal).await.unwrap();
+            if correct.is_some() {
+                store.set_grade(id, correct, Utc::now()).await.unwrap();
+            }
+        }
+
+        let stats = store
+            .signal_stats(&SignalFilter::default(), StatsGroup::Strength)
+            .await
+            .unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].signals, 3);
+        assert_eq!(stats[0].graded, 2);
+        assert_eq!(stats[0].hit_rate, Some(0.5));
+    }
+}
@@ -0,0 +1,325 @@
+//! Worker liveness and upstream API status for the `/healthz` and
+//! `/metrics` endpoints
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Missed intervals after which a worker counts as stalled
+const STALE_INTERVALS: u32 = 3;
+
+/// Upper bounds, in seconds, of the upstream call duration buckets
+pub const LATENCY_BUCKETS: [f64; 9] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Shared registry that workers report ticks and upstream calls to
+///
+/// Cheap to clone; a default registry is detached and simply unread.
+#[derive(Debug, Clone, Default)]
+pub struct Health {
+    inner: Arc<Mutex<HealthState>>,
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    workers: BTreeMap<String, WorkerState>,
+    upstreams: BTreeMap<String, UpstreamStatus>,
+    queues: BTreeMap<String, QueueStatus>,
+    signals: BTreeMap<SignalKey, u64>,
+    matched_markets: usize,
+    live_matches: Option<usize>,
+}
+
+/// Where in the processor a signal was counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SignalStage {
+    /// Computed from a match update
+    Generated,
+    /// Dropped as a disabled type, or downgraded to Neutral on a muted market
+    Suppressed,
+    /// Written to the database
+    Stored,
+}
+
+impl SignalStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalStage::Generated => "generated",
+            SignalStage::Suppressed => "suppressed",
+            SignalStage::Stored => "stored",
+        }
+    }
+}
+
+/// Signal counter key: stage, signal type and strength
+pub type SignalKey = (SignalStage, String, String);
+
+#[derive(Debug)]
+struct WorkerState {
+    started_at: DateTime<Utc>,
+    last_tick: Option<DateTime<Utc>>,
+    interval: Option<Duration>,
+}
+
+/// Liveness of one worker
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub started_at: DateTime<Utc>,
+    pub last_tick: Option<DateTime<Utc>>,
+    /// Expected seconds between ticks; event-driven workers have none
+    pub interval_secs: Option<u64>,
+    pub healthy: bool,
+}
+
+/// Last outcome and running totals of calls to one upstream API
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpstreamStatus {
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<DateTime<Utc>>,
+    pub last_error_message: Option<String>,
+    /// Calls since startup, failed ones included
+    pub calls: u64,
+    pub errors: u64,
+    #[serde(skip)]
+    pub latency: LatencyHistogram,
+}
+
+/// Durations of calls to one upstream, in `LATENCY_BUCKETS`
+#[derive(Debug, Clone, Default)]
+pub struct LatencyHistogram {
+    /// Calls that took at most each bucket's bound (cumulative)
+    pub buckets: [u64; LATENCY_BUCKETS.len()],
+    pub sum_secs: f64,
+    pub count: u64,
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        for (count, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= bound {
+                *count += 1;
+            }
+        }
+        self.sum_secs += secs;
+        self.count += 1;
+    }
+}
+
+/// Depth and losses of one consumer's match update queue
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueueStatus {
+    pub depth: usize,
+    pub capacity: usize,
+    /// Deepest the queue has been since startup
+    pub peak_depth: usize,
+    /// Periodic updates dropped to make room
+    pub dropped: u64,
+    /// Updates not delivered because the consumer stopped
+    pub send_failures: u64,
+}
+
+/// Snapshot served by `/healthz`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// All workers ticking and the database reachable
+    pub healthy: bool,
+    pub database: bool,
+    pub workers: BTreeMap<String, WorkerStatus>,
+    pub upstreams: BTreeMap<String, UpstreamStatus>,
+    pub queues: BTreeMap<String, QueueStatus>,
+    /// Markets matched to a live match at the last successful poll
+    pub matched_markets: usize,
+    /// Matches the last successful live poll returned; None before the first
+    pub live_matches: Option<usize>,
+    /// Signals since startup, for `/metrics`
+    #[serde(skip)]
+    pub signals: BTreeMap<SignalKey, u64>,
+}
+
+impl Health {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a worker started, with its expected tick interval
+    pub fn register_worker(&self, name: &str, interval: Option<Duration>) {
+        let mut state = self.lock();
+        state.workers.insert(
+            name.to_string(),
+            WorkerState {
+                started_at: Utc::now(),
+                last_tick: None,
+                interval,
+            },
+        );
+    }
+
+    /// Record a completed worker tick and its current interval
+    pub fn worker_tick(&self, name: &str, interval: Option<Duration>) {
+        let now = Utc::now();
+        let mut state = self.lock();
+        let worker = state
+            .workers
+            .entry(name.to_string())
+            .or_insert(WorkerState {
+                started_at: now,
+                last_tick: None,
+                interval,
+            });
+        worker.last_tick = Some(now);
+        worker.interval = interval;
+    }
+
+    /// Record a successful call to an upstream API and how long it took
+    pub fn upstream_ok(&self, name: &str, duration: Duration) {
+        let mut state = self.lock();
+        let upstream = state.upstreams.entry(name.to_string()).or_default();
+        upstream.last_success = Some(Utc::now());
+        upstream.calls += 1;
+        upstream.latency.observe(duration);
+    }
+
+    /// Record a failed call to an upstream API and how long it took
+    pub fn upstream_error(&self, name: &str, duration: Duration, error: impl Display) {
+        let mut state = self.lock();
+        let upstream = state.upstreams.entry(name.to_string()).or_default();
+        upstream.last_error = Some(Utc::now());
+        upstream.last_error_message = Some(error.to_string());
+        upstream.calls += 1;
+        upstream.errors += 1;
+        upstream.latency.observe(duration);
+    }
+
+    /// Record the current depth of a consumer's update queue
+    pub fn queue_depth(&self, name: &str, depth: usize, capacity: usize) {
+        let mut state = self.lock();
+        let queue = state.queues.entry(name.to_string()).or_default();
+        queue.depth = depth;
+        queue.capacity = capacity;
+        queue.peak_depth = queue.peak_depth.max(depth);
+    }
+
+    /// Record a periodic update dropped from a full queue
+    pub fn queue_dropped(&self, name: &str) {
+        self.lock()
+            .queues
+            .entry(name.to_string())
+            .or_default()
+            .dropped += 1;
+    }
+
+    /// Record an update that couldn't be delivered to a queue
+    pub fn queue_send_failed(&self, name: &str) {
+        self.lock()
+            .queues
+            .entry(name.to_string())
+            .or_default()
+            .send_failures += 1;
+    }
+
+    /// Count a signal at a stage of the processor
+    pub fn signal_counted(
+        &self,
+        stage: SignalStage,
+        signal_type: impl Display,
+        strength: impl Display,
+    ) {
+        *self
+            .lock()
+            .signals
+            .entry((stage, signal_type.to_string(), strength.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Record how many matches a live poll returned
+    pub fn live_matches(&self, count: usize) {
+        self.lock().live_matches = Some(count);
+    }
+
+    /// Record how many markets the live fetcher matched to a live match
+    pub fn matched_markets(&self, count: usize) {
+        self.lock().matched_markets = count;
+    }
+
+    /// Current status; `database` is the result of a connectivity check
+    pub fn report(&self, database: bool) -> HealthReport {
+        let now = Utc::now();
+        let state = self.lock();
+
+        let workers: BTreeMap<String, WorkerStatus> = state
+            .workers
+            .iter()
+            .map(|(name, worker)| (name.clone(), worker.status(now)))
+            .collect();
+
+        HealthReport {
+            healthy: database && workers.values().all(|w| w.healthy),
+            database,
+            workers,
+            upstreams: state.upstreams.clone(),
+            queues: state.queues.clone(),
+            matched_markets: state.matched_markets,
+            live_matches: state.live_matches,
+            signals: state.signals.clone(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        // A panic while holding the lock can't leave the maps inconsistent
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl WorkerState {
+    /// Stalled once `STALE_INTERVALS` intervals pass without a tick
+    fn status(&self, now: DateTime<Utc>) -> WorkerStatus {
+        let healthy = match self.interval {
+            Some(interval) => {
+                let since = self.last_tick.unwrap_or(self.started_at);
+                let max_age = chrono::Duration::from_std(interval * STALE_INTERVALS)
+                    .unwrap_or(chrono::Duration::MAX);
+                now - since <= max_age
+            }
+            None => true,
+        };
+
+        WorkerStatus {
+            started_at: self.started_at,
+            last_tick: self.last_tick,
+            interval_secs: self.interval.map(|i| i.as_secs()),
+            healthy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_stalls_after_missed_intervals() {
+        let worker = WorkerState {
+            started_at: Utc::now() - chrono::Duration::seconds(100),
+            last_tick: Some(Utc::now() - chrono::Duration::seconds(20)),
+            interval: Some(Duration::from_secs(5)),
+        };
+        assert!(!worker.status(Utc::now()).healthy);
+
+        let worker = WorkerState {
+            last_tick: Some(Utc::now() - chrono::Duration::seconds(10)),
+            ..worker
+        };
+        assert!(worker.status(Utc::now()).healthy);
+
+        let event_driven = WorkerState {
+            interval: None,
+            ..worker
+        };
+        assert!(event_driven.status(Utc::now()).healthy);
+    }
+}
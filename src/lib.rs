@@ -1,6 +1,16 @@
+pub mod analytics;
 pub mod api;
 pub mod config;
 pub mod db;
+pub mod health;
+pub mod logging;
 pub mod matching;
+pub mod model;
 pub mod models;
+pub mod notifications;
+pub mod ratings;
+pub mod rules;
+pub mod server;
+pub mod sinks;
+pub mod trading;
 pub mod workers;
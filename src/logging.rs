@@ -0,0 +1,226 @@
+//! Stdout logging plus an optional rotating log file
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::config::Config;
+
+/// Log file name prefix
+const FILE_PREFIX: &str = "esport-signal";
+
+/// When the log file is rolled over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    /// Roll when the file reaches `LOG_MAX_SIZE_MB`
+    Size,
+    Never,
+}
+
+impl LogRotation {
+    /// Name used in config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogRotation::Hourly => "hourly",
+            LogRotation::Daily => "daily",
+            LogRotation::Size => "size",
+            LogRotation::Never => "never",
+        }
+    }
+}
+
+impl fmt::Display for LogRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogRotation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hourly" => Ok(LogRotation::Hourly),
+            "daily" => Ok(LogRotation::Daily),
+            "size" => Ok(LogRotation::Size),
+            "never" => Ok(LogRotation::Never),
+            other => anyhow::bail!("Unknown log rotation: {}", other),
+        }
+    }
+}
+
+/// Initialize stdout logging and, if `LOG_DIR` is set, a rotating file log
+///
+/// With `to_stderr` console logs go to stderr instead, leaving stdout to
+/// `--output jsonl`. The returned guard flushes the file writer when
+/// dropped; keep it alive in `main`.
+pub fn init(config: &Config, default_filter: &str, to_stderr: bool) -> Result<Option<WorkerGuard>> {
+    let (file_layer, guard) = match &config.log_dir {
+        Some(dir) => {
+            let (writer, guard) = tracing_appender::non_blocking(file_writer(config, dir)?);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+    let console = if to_stderr {
+        BoxMakeWriter::new(io::stderr)
+    } else {
+        BoxMakeWriter::new(io::stdout)
+    };
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| default_filter.into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(console))
+        .with(file_layer)
+        .init();
+
+    Ok(guard)
+}
+
+/// Writer for the configured rotation policy
+fn file_writer(config: &Config, dir: &str) -> Result<Box<dyn Write + Send>> {
+    fs::create_dir_all(dir).context("Failed to create log directory")?;
+
+    let rotation = match config.log_rotation {
+        LogRotation::Size => {
+            return Ok(Box::new(SizeRollingWriter::new(
+                Path::new(dir),
+                config.log_max_size_mb * 1024 * 1024,
+                config.log_max_files,
+            )?));
+        }
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+
+    let appender = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(config.log_max_files.max(1))
+        .build(dir)
+        .context("Failed to create log file")?;
+
+    Ok(Box::new(appender))
+}
+
+/// Log file that rolls over to `.1`, `.2`, ... once it reaches a size limit
+struct SizeRollingWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl SizeRollingWriter {
+    fn new(dir: &Path, max_bytes: u64, max_files: usize) -> Result<Self> {
+        let path = dir.join(format!("{}.log", FILE_PREFIX));
+        let file = open_append(&path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            written,
+        })
+    }
+
+    /// Path of the `n`th rolled file
+    fn rolled(&self, n: usize) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.path.display(), n))
+    }
+
+    /// Shift rolled files up by one, dropping the oldest, and start a new file
+    fn roll(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let keep = self.max_files.saturating_sub(1);
+        if keep == 0 {
+            fs::remove_file(&self.path).ok();
+        } else {
+            fs::remove_file(self.rolled(keep)).ok();
+            for n in (1..keep).rev() {
+                fs::rename(self.rolled(n), self.rolled(n + 1)).ok();
+            }
+            fs::rename(&self.path, self.rolled(1))?;
+        }
+
+        self.file = open_append(&self.path).map_err(io::Error::other)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.roll()?;
+        }
+
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_rolling_keeps_max_files() {
+        let dir = std::env::temp_dir().join(format!("esport-signal-logs-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut writer = SizeRollingWriter::new(&dir, 10, 3).unwrap();
+        for line in [
+            "first line\n",
+            "second line\n",
+            "third line\n",
+            "fourth line\n",
+        ] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("esport-signal.log"), "fourth line\n");
+        assert_eq!(read("esport-signal.log.1"), "third line\n");
+        assert_eq!(read("esport-signal.log.2"), "second line\n");
+        assert!(!dir.join("esport-signal.log.3").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,130 +1,676 @@
-mod api;
-mod config;
-mod db;
-mod matching;
-mod models;
-mod workers;
-
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
-use tokio::sync::{mpsc, RwLock};
-use tracing::{error, info};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tokio::sync::{broadcast, watch, RwLock};
+use tracing::{error, info, warn};
 
-use crate::api::{LiveDataClient, PolymarketClient};
-use crate::config::Config;
-use crate::db::SignalStore;
-use crate::matching::TeamResolver;
-use crate::models::{ActiveMarkets, LiveMatchCache};
-use crate::workers::{LiveFetcherWorker, MarketScannerWorker, SignalProcessorWorker};
+use esport_signal::api::{
+    AzuroClient, BetfairClient, KalshiClient, LiveDataClient, PinnacleClient, PolymarketClient,
+    StratzClient,
+};
+use esport_signal::config::{Config, Tuning};
+use esport_signal::db::{self, HistoricalStore, PositionStore, SignalStore};
+use esport_signal::health::Health;
+use esport_signal::logging;
+use esport_signal::matching::TeamResolver;
+use esport_signal::model::{self, DurationModel, LogisticModel, ProbabilityModel, SharedModel};
+use esport_signal::models::{ActiveMarkets, SharpOddsCache};
+use esport_signal::notifications::Notifier;
+use esport_signal::ratings::{EloRatings, GlickoRatings, RecentForm};
+use esport_signal::rules::SignalScript;
+use esport_signal::server::grpc::{self, SignalService};
+use esport_signal::server::rate_limit::RateLimiter;
+use esport_signal::server::{self, AppState};
+use esport_signal::sinks::{FeedSink, JsonlSink, SignalSinks};
+use esport_signal::workers::{
+    ArbitrageWorker, ConfigReloaderWorker, DigestWorker, LiveFetcherWorker, MarketScannerWorker,
+    ModelReloaderWorker, NoDataWatchdogWorker, OutrightWorker, SettlementWatchWorker,
+    SharpOddsWorker, SignalGraderWorker, SignalProcessorWorker, StopLossWorker, UpdateBus,
+    DEFAULT_UPDATE_CAPACITY,
+};
+
+/// Longest wait for queued match updates to be processed on shutdown
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest wait for the sinks to deliver queued signals on shutdown
+const SINK_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Command line of the service itself
+const USAGE: &str = "Usage: esport-signal [config check] [--config <path>] [--output jsonl]";
+
+/// Command line of the `db` subcommand
+const DB_USAGE: &str = "Usage: esport-signal db backup|restore <path> [--config <path>]";
+
+/// Signals a slow feed subscriber (gRPC stream or NATS producer) can fall
+/// behind before skipping ahead
+const SIGNAL_FEED_CAPACITY: usize = 256;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "esport_signal=info,warn".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Load configuration (env vars override `--config <path>`)
+    let config_path = config_path_arg()?;
+    let config = Config::load(config_path.as_deref())?;
+
+    // `config check`: print the effective config and exit
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("config") {
+        return check_config(&config, &args);
+    }
+
+    // `db backup|restore <path>`: snapshot with `VACUUM INTO` or restore in one
+    // transaction, both safe while the service runs
+    if args.get(1).map(String::as_str) == Some("db") {
+        return run_db_command(&config, &args).await;
+    }
+
+    // `--output jsonl`: also stream stored signals to stdout, logging to stderr
+    let jsonl_output = jsonl_output_arg()?;
+
+    // Initialize logging (stdout, plus a rotating file when LOG_DIR is set)
+    let _log_guard = logging::init(&config, "esport_signal=info,warn", jsonl_output)?;
 
     info!("Starting esport-signal");
 
-    // Load configuration
-    let config = Config::from_env()?;
-    info!("Configuration loaded");
+    let problems = config.validate();
+    if !problems.is_empty() {
+        for problem in &problems {
+            error!("Invalid config: {}", problem);
+        }
+        anyhow::bail!(
+            "{} config problem(s); run `esport-signal config check` for details",
+            problems.len()
+        );
+    }
+    info!(
+        "Configuration loaded{}",
+        config_path
+            .as_ref()
+            .map(|p| format!(" from {}", p.display()))
+            .unwrap_or_default()
+    );
 
     // Initialize database
     let signal_store = Arc::new(SignalStore::new(&config.database_url).await?);
+    let position_store = Arc::new(PositionStore::new(&config.database_url).await?);
     info!("Database initialized");
 
+    let notifier = Notifier::from_config(&config);
+
     // Load team aliases
     let team_resolver = load_team_resolver()?;
     let team_resolver = Arc::new(team_resolver);
     info!("Team resolver initialized");
 
+    // Compute team ratings and recent form from historical matches
+    let historical_store = Arc::new(HistoricalStore::new(&config.database_url).await?);
+    let historical_matches = historical_store.get_all().await?;
+    let ratings = Arc::new(EloRatings::from_matches(
+        &historical_matches,
+        &team_resolver,
+    ));
+    let durations = Arc::new(DurationModel::from_matches(&historical_matches));
+    let glicko = Arc::new(GlickoRatings::from_matches(
+        &historical_matches,
+        &team_resolver,
+    ));
+    let form = Arc::new(RecentForm::from_matches(
+        &historical_matches,
+        &team_resolver,
+        config.form_matches,
+    ));
+
+    // Load probability model
+    let model: Arc<dyn ProbabilityModel> = match &config.model_path {
+        Some(path) => Arc::from(model::load_model(Path::new(path))?),
+        None => Arc::new(LogisticModel::default()),
+    };
+    info!("Probability model loaded: {}", model.version());
+    let model: SharedModel = Arc::new(RwLock::new(model));
+
     // Initialize API clients
     let polymarket_client = PolymarketClient::new(&config.polymarket_api_url);
-    let live_data_client = LiveDataClient::new();
+    let mut live_data_client = LiveDataClient::new();
+    if let Some(token) = &config.stratz_api_token {
+        live_data_client =
+            live_data_client.with_stratz(StratzClient::new(&config.stratz_api_url, token));
+    }
     info!("API clients initialized");
 
     // Shared state
-    let active_markets: Arc<RwLock<ActiveMarkets>> = Arc::new(RwLock::new(Default::default()));
-    let match_cache: Arc<RwLock<LiveMatchCache>> = Arc::new(RwLock::new(Default::default()));
+    let active_markets: Arc<ActiveMarkets> = Arc::default();
+    let sharp_odds: Arc<RwLock<SharpOddsCache>> = Arc::new(RwLock::new(Default::default()));
+
+    // Tuning values the config reloader can swap while running
+    let tuning = Tuning::shared(&config);
+
+    // Worker ticks and upstream calls for /healthz
+    let health = Health::new();
+
+    // Match updates, fanned out to each consumer's queue
+    let mut updates = UpdateBus::new().with_health(health.clone());
+    let update_rx = updates.subscribe("signal_processor", DEFAULT_UPDATE_CAPACITY);
+    let watchdog_rx = (config.no_data_alert_minutes > 0)
+        .then(|| updates.subscribe("watchdog", DEFAULT_UPDATE_CAPACITY));
+    let settlement_rx = (config.settlement_lag_max_price > 0.0)
+        .then(|| updates.subscribe("settlement_watch", DEFAULT_UPDATE_CAPACITY));
+    #[cfg(feature = "nats")]
+    let nats_rx = config
+        .nats_url
+        .is_some()
+        .then(|| updates.subscribe("nats_producer", DEFAULT_UPDATE_CAPACITY));
 
-    // Channel for match updates
-    let (update_tx, update_rx) = mpsc::channel(100);
+    // Stored signals for live gRPC subscribers and the NATS producer
+    let (signal_feed, _) = broadcast::channel(SIGNAL_FEED_CAPACITY);
+
+    // Outputs for stored signals: the feed, configured exporters and,
+    // with `--output jsonl`, stdout
+    let mut sinks = SignalSinks::from_config(&config, health.clone())
+        .with_sink(FeedSink::new(signal_feed.clone()));
+    if jsonl_output {
+        sinks = sinks.with_sink(JsonlSink::new());
+    }
+    // Kept to close the sinks on shutdown, after the processor drains
+    let sink_registry = sinks.clone();
 
     // Create workers
-    let market_scanner = MarketScannerWorker::new(
-        polymarket_client,
+    let mut market_scanner = MarketScannerWorker::new(
+        Box::new(polymarket_client),
         Arc::clone(&active_markets),
-        config.polymarket_scan_interval,
-    );
+        Arc::clone(&tuning),
+    )
+    .with_team_resolver(Arc::clone(&team_resolver))
+    .with_ratings(Arc::clone(&ratings), Arc::clone(&form))
+    .with_notifier(notifier.clone())
+    .with_health(health.clone());
+    if config.kalshi_enabled {
+        market_scanner = market_scanner.with_source(Box::new(KalshiClient::new(
+            &config.kalshi_api_url,
+            config.kalshi_series_tickers.clone(),
+        )));
+    }
+    if config.azuro_enabled {
+        market_scanner =
+            market_scanner.with_source(Box::new(AzuroClient::new(&config.azuro_subgraph_url)));
+    }
 
-    let live_fetcher = LiveFetcherWorker::new(
-        live_data_client,
-        Arc::clone(&active_markets),
-        Arc::clone(&match_cache),
-        Arc::clone(&team_resolver),
-        update_tx,
-        config.live_match_poll_interval,
+    let live_fetcher = Arc::new(
+        LiveFetcherWorker::new(
+            live_data_client,
+            Arc::clone(&active_markets),
+            Arc::clone(&team_resolver),
+            updates,
+            Arc::clone(&tuning),
+        )
+        .with_health(health.clone())
+        .with_notifier(notifier.clone()),
     );
 
     let signal_processor = SignalProcessorWorker::new(
         Arc::clone(&active_markets),
         Arc::clone(&signal_store),
+        Arc::clone(&model),
+        Arc::clone(&ratings),
+        Arc::clone(&team_resolver),
+        Arc::clone(&tuning),
         update_rx,
-    );
+    )
+    .with_health(health.clone())
+    .with_recent_form(form)
+    .with_glicko_ratings(glicko)
+    .with_duration_model(Arc::clone(&durations))
+    .with_sinks(sinks.clone())
+    .with_notifier(notifier.clone());
+    let signal_processor = match &config.signal_script {
+        Some(path) => {
+            let script = SignalScript::load(Path::new(path))?;
+            info!("Signal script loaded: {}", path);
+            signal_processor.with_script(Arc::new(script))
+        }
+        None => signal_processor,
+    };
+
+    // Compare against a sharp sportsbook when an API key is configured
+    let sharp_odds_fetcher = config.pinnacle_api_key.as_ref().map(|api_key| {
+        SharpOddsWorker::new(
+            PinnacleClient::new(&config.pinnacle_api_url, api_key),
+            Arc::clone(&active_markets),
+            Arc::clone(&team_resolver),
+            Arc::clone(&sharp_odds),
+            Arc::clone(&tuning),
+        )
+        .with_health(health.clone())
+    });
+    let signal_processor = if sharp_odds_fetcher.is_some() {
+        signal_processor.with_sharp_odds(Arc::clone(&sharp_odds))
+    } else {
+        signal_processor
+    };
+
+    let stop_loss = StopLossWorker::new(
+        Arc::clone(&position_store),
+        Arc::clone(&signal_store),
+        Arc::clone(&active_markets),
+        notifier.clone(),
+        config.stop_loss_auto_exit,
+        Arc::clone(&tuning),
+    )
+    .with_health(health.clone());
+
+    // Detect Polymarket/Betfair arbitrage when Betfair credentials are configured
+    let arbitrage = match (&config.betfair_app_key, &config.betfair_session_token) {
+        (Some(app_key), Some(session_token)) => Some(
+            ArbitrageWorker::new(
+                BetfairClient::new(&config.betfair_api_url, app_key, session_token),
+                Arc::clone(&active_markets),
+                Arc::clone(&team_resolver),
+                Arc::clone(&signal_store),
+                notifier.clone(),
+                config.betfair_commission,
+                Arc::clone(&tuning),
+            )
+            .with_health(health.clone()),
+        ),
+        _ => None,
+    };
+
+    // Price tournament and group winner markets; idle while there are none
+    let outright = OutrightWorker::new(
+        Arc::clone(&active_markets),
+        Arc::clone(&signal_store),
+        Arc::clone(&ratings),
+        Arc::clone(&team_resolver),
+        Arc::clone(&tuning),
+    )
+    .with_health(health.clone());
+
+    // Hot-reload the model file when one is configured
+    let model_reloader = config.model_path.as_ref().map(|path| {
+        ModelReloaderWorker::new(
+            path.into(),
+            Arc::clone(&model),
+            config.model_reload_interval,
+        )
+        .with_health(health.clone())
+    });
+
+    // Apply tuning changes from the config file when one is given
+    let config_reloader = config_path.as_ref().map(|path| {
+        ConfigReloaderWorker::new(
+            path.clone(),
+            Arc::clone(&tuning),
+            config.config_reload_interval,
+        )
+        .with_health(health.clone())
+    });
+
+    // Alert when matched markets stop getting live data mid-game
+    let watchdog = watchdog_rx.map(|update_rx| {
+        NoDataWatchdogWorker::new(
+            Duration::from_secs(config.no_data_alert_minutes * 60),
+            Arc::clone(&active_markets),
+            durations,
+            notifier.clone(),
+            update_rx,
+        )
+        .with_health(health.clone())
+    });
+
+    // Signal finished games whose markets still trade short of settled
+    let settlement_watch = settlement_rx.map(|update_rx| {
+        SettlementWatchWorker::new(
+            LiveDataClient::new(),
+            Arc::clone(&active_markets),
+            Arc::clone(&signal_store),
+            Arc::clone(&tuning),
+            config.settlement_lag_max_price,
+            update_rx,
+        )
+        .with_sinks(sinks)
+        .with_notifier(notifier.clone())
+        .with_health(health.clone())
+    });
+
+    // Grade signals as match results arrive in historical_matches
+    let grader = SignalGraderWorker::new(Arc::clone(&signal_store), config.grade_interval)
+        .with_health(health.clone());
+
+    // Daily digest for sinks that skip real-time signal alerts
+    let digest = notifier.has_digest_sinks().then(|| {
+        DigestWorker::new(
+            Arc::clone(&signal_store),
+            notifier.clone(),
+            config.notify_digest_time,
+        )
+        .with_health(health.clone())
+    });
 
     info!("Workers created, starting...");
 
+    // Workers that stop cleanly watch this; the rest are aborted
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
     // Spawn workers
-    let scanner_handle = tokio::spawn(async move {
-        market_scanner.run().await;
+    let scanner_shutdown = shutdown_rx.clone();
+    let mut scanner_handle = tokio::spawn(async move {
+        market_scanner.run(scanner_shutdown).await;
     });
 
-    let fetcher_handle = tokio::spawn(async move {
-        live_fetcher.run().await;
+    let fetcher_shutdown = shutdown_rx.clone();
+    let mut fetcher_handle = tokio::spawn(async move {
+        live_fetcher.run(fetcher_shutdown).await;
     });
 
-    let processor_handle = tokio::spawn(async move {
+    let mut processor_handle = tokio::spawn(async move {
         signal_processor.run().await;
     });
 
+    let stop_loss_shutdown = shutdown_rx.clone();
+    let mut stop_loss_handle = tokio::spawn(async move {
+        stop_loss.run(stop_loss_shutdown).await;
+    });
+
+    // Signal service for trading bots when a gRPC address is configured
+    let grpc_handle = config.grpc_addr.clone().map(|addr| {
+        let service = SignalService::new(
+            Arc::clone(&signal_store),
+            signal_feed.clone(),
+            shutdown_rx.clone(),
+        );
+        let tokens = Arc::new(config.api_tokens.clone());
+        let grpc_shutdown = shutdown_rx.clone();
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve(&addr, service, tokens, grpc_shutdown).await {
+                error!("{:#}", e);
+            }
+        })
+    });
+
+    // Health and GraphQL endpoints when an HTTP address is configured
+    let server_handle = config.http_addr.clone().map(|addr| {
+        let state = AppState {
+            health: health.clone(),
+            signal_store: Arc::clone(&signal_store),
+            graphql: server::graphql::schema(Arc::clone(&signal_store), historical_store),
+            team_resolver: Arc::clone(&team_resolver),
+            api_tokens: Arc::new(config.api_tokens.clone()),
+            rate_limiter: Arc::new(RateLimiter::new(config.api_rate_limit)),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = server::serve(&addr, state, shutdown_rx).await {
+                error!("{:#}", e);
+            }
+        })
+    });
+
+    let mut background = vec![tokio::spawn(async move {
+        outright.run().await;
+    })];
+
+    background.push(tokio::spawn(async move {
+        grader.run().await;
+    }));
+
+    if let Some(digest) = digest {
+        background.push(tokio::spawn(async move {
+            digest.run().await;
+        }));
+    }
+
+    if let Some(watchdog) = watchdog {
+        background.push(tokio::spawn(async move {
+            watchdog.run().await;
+        }));
+    }
+
+    if let Some(settlement_watch) = settlement_watch {
+        background.push(tokio::spawn(async move {
+            settlement_watch.run().await;
+        }));
+    }
+
+    if let Some(sharp_odds_fetcher) = sharp_odds_fetcher {
+        background.push(tokio::spawn(async move {
+            sharp_odds_fetcher.run().await;
+        }));
+    }
+
+    if let Some(arbitrage) = arbitrage {
+        background.push(tokio::spawn(async move {
+            arbitrage.run().await;
+        }));
+    }
+
+    if let Some(model_reloader) = model_reloader {
+        background.push(tokio::spawn(async move {
+            model_reloader.run().await;
+        }));
+    }
+
+    if let Some(config_reloader) = config_reloader {
+        background.push(tokio::spawn(async move {
+            config_reloader.run().await;
+        }));
+    }
+
+    // Signals and match updates on NATS for separate consumer services
+    #[cfg(feature = "nats")]
+    if let (Some(nats_url), Some(nats_rx)) = (config.nats_url.clone(), nats_rx) {
+        let producer = esport_signal::workers::NatsProducerWorker::new(
+            nats_url,
+            config.nats_subject_prefix.clone(),
+            signal_feed.subscribe(),
+            nats_rx,
+        )
+        .with_health(health.clone());
+        background.push(tokio::spawn(async move {
+            producer.run().await;
+        }));
+    }
+
+    // Bets on strong Azuro signals when a bettor key is configured
+    #[cfg(feature = "azuro")]
+    match esport_signal::api::AzuroBetClient::from_config(&config) {
+        Ok(Some(client)) => {
+            let bettor = esport_signal::workers::AzuroBetWorker::new(
+                client,
+                Arc::clone(&active_markets),
+                Arc::clone(&position_store),
+                esport_signal::trading::RiskLimits::from_config(&config),
+                esport_signal::workers::BetSettings {
+                    min_strength: config.azuro_bet_min_strength,
+                    max_bet: config.azuro_max_bet,
+                    max_slippage: config.azuro_max_slippage,
+                },
+                signal_feed.subscribe(),
+                notifier.clone(),
+            )
+            .with_health(health.clone());
+            background.push(tokio::spawn(async move {
+                bettor.run().await;
+            }));
+        }
+        Ok(None) => {}
+        Err(e) => error!("Azuro betting disabled: {:#}", e),
+    }
+
     info!("All workers started");
 
     // Wait for shutdown signal
     tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
+        _ = shutdown_signal() => {
             info!("Shutdown signal received");
         }
-        result = scanner_handle => {
+        result = &mut scanner_handle => {
             error!("Market scanner exited unexpectedly: {:?}", result);
         }
-        result = fetcher_handle => {
+        result = &mut fetcher_handle => {
             error!("Live fetcher exited unexpectedly: {:?}", result);
         }
-        result = processor_handle => {
+        result = &mut processor_handle => {
             error!("Signal processor exited unexpectedly: {:?}", result);
         }
+        result = &mut stop_loss_handle => {
+            error!("Stop-loss monitor exited unexpectedly: {:?}", result);
+        }
     }
 
     info!("Shutting down esport-signal");
+
+    // Stop producers first; the fetcher drops its sender when it exits
+    shutdown_tx.send(true).ok();
+    for (name, handle) in [
+        ("Market scanner", scanner_handle),
+        ("Live fetcher", fetcher_handle),
+        ("Stop-loss monitor", stop_loss_handle),
+    ] {
+        // Finished handles were already awaited by the select above
+        if handle.is_finished() {
+            continue;
+        }
+        if let Err(e) = handle.await {
+            if !e.is_cancelled() {
+                error!("{} failed during shutdown: {}", name, e);
+            }
+        }
+    }
+
+    for handle in [server_handle, grpc_handle].into_iter().flatten() {
+        handle.await.ok();
+    }
+
+    // Let the processor finish the queued updates, then deliver what it
+    // stored to the sinks, then stop the rest and close the pools
+    if !processor_handle.is_finished() {
+        match tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, processor_handle).await {
+            Ok(_) => info!("Match updates drained"),
+            Err(_) => warn!(
+                "Signal processor did not drain within {:?}, dropping remaining updates",
+                SHUTDOWN_DRAIN_TIMEOUT
+            ),
+        }
+    }
+
+    match tokio::time::timeout(SINK_FLUSH_TIMEOUT, sink_registry.close()).await {
+        Ok(()) => info!("Signal sinks flushed"),
+        Err(_) => warn!(
+            "Signal sinks did not flush within {:?}, dropping queued signals",
+            SINK_FLUSH_TIMEOUT
+        ),
+    }
+
+    for handle in &background {
+        handle.abort();
+    }
+
+    signal_store.close().await;
+    position_store.close().await;
+    info!("Database closed");
+
     Ok(())
 }
 
-/// Load team resolver from JSON file or create default
+/// Resolve on SIGINT (Ctrl-C) or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                tokio::signal::ctrl_c().await.ok();
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
+
+/// Handle `config check`: print resolved settings and report problems
+fn check_config(config: &Config, args: &[String]) -> Result<()> {
+    if args.get(2).map(String::as_str) != Some("check") {
+        anyhow::bail!("Usage: esport-signal config check [--config <path>]");
+    }
+
+    for (key, value) in config.effective() {
+        println!("{}={}", key, value);
+    }
+
+    let problems = config.validate();
+    if problems.is_empty() {
+        println!("\nConfig OK");
+        return Ok(());
+    }
+
+    println!();
+    for problem in &problems {
+        println!("ERROR: {}", problem);
+    }
+    anyhow::bail!("{} config problem(s)", problems.len())
+}
+
+/// Handle `db backup <path>` and `db restore <path>`
+async fn run_db_command(config: &Config, args: &[String]) -> Result<()> {
+    let (Some(command), Some(path)) = (args.get(2), args.get(3)) else {
+        anyhow::bail!(DB_USAGE);
+    };
+    let path = Path::new(path);
+
+    match command.as_str() {
+        "backup" => {
+            db::backup::backup(&config.database_url, path).await?;
+            println!("Backed up {} to {}", config.database_url, path.display());
+        }
+        "restore" => {
+            for table in db::backup::restore(&config.database_url, path).await? {
+                println!("{}: {} rows", table.name, table.rows);
+            }
+            println!("Restored {} from {}", config.database_url, path.display());
+        }
+        _ => anyhow::bail!(DB_USAGE),
+    }
+    Ok(())
+}
+
+/// Path given by `--config <path>`, if any
+fn config_path_arg() -> Result<Option<PathBuf>> {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|arg| arg == "--config") {
+        Some(i) => match args.get(i + 1) {
+            Some(path) => Ok(Some(PathBuf::from(path))),
+            None => anyhow::bail!("{}", USAGE),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Whether `--output jsonl` was passed
+fn jsonl_output_arg() -> Result<bool> {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|arg| arg == "--output") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("jsonl") => Ok(true),
+            _ => anyhow::bail!("{}", USAGE),
+        },
+        None => Ok(false),
+    }
+}
+
+/// Load team aliases; admin API edits are saved to the same file
 fn load_team_resolver() -> Result<TeamResolver> {
     let aliases_path = Path::new("data/team_aliases.json");
 
-    if aliases_path.exists() {
-        TeamResolver::load_from_file(aliases_path)
-    } else {
-        info!("No team aliases file found, using default resolver");
-        Ok(TeamResolver::new())
+    if !aliases_path.exists() {
+        info!("No team aliases file found, starting without aliases");
     }
+    TeamResolver::open(aliases_path)
 }
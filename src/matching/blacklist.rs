@@ -0,0 +1,84 @@
+//! Teams, leagues and markets to ignore entirely
+//!
+//! The scanner drops blacklisted markets, by condition ID or either team,
+//! before they become active; the fetcher drops live matches with a
+//! blacklisted team or league before matching them to markets, since a
+//! market doesn't know its league.
+
+use super::watchlist::involves;
+use super::TeamResolver;
+use crate::models::{LiveMatchState, Market};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Blacklist {
+    /// Team names, matched through the team aliases
+    pub teams: Vec<String>,
+
+    /// Case-insensitive substrings of the league name
+    pub leagues: Vec<String>,
+
+    /// Market condition IDs
+    pub markets: Vec<String>,
+}
+
+impl Blacklist {
+    /// Whether a market is blacklisted by condition ID or either team
+    pub fn blocks_market(&self, market: &Market, resolver: &TeamResolver) -> bool {
+        self.markets.contains(&market.condition_id)
+            || (!market.is_outright()
+                && self.teams.iter().any(|team| {
+                    resolver.names_match(team, &market.team_a)
+                        || resolver.names_match(team, &market.team_b)
+                }))
+    }
+
+    /// Whether a live match has a blacklisted team or league
+    pub fn blocks_match(&self, state: &LiveMatchState, resolver: &TeamResolver) -> bool {
+        involves(&self.teams, &self.leagues, state, resolver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Venue;
+
+    fn market(condition_id: &str, team_a: &str, team_b: &str) -> Market {
+        Market {
+            condition_id: condition_id.to_string(),
+            venue: Venue::Polymarket,
+            question: String::new(),
+            team_a: team_a.to_string(),
+            team_b: team_b.to_string(),
+            team_a_odds: 0.5,
+            team_b_odds: 0.5,
+            opening_team_a_odds: 0.5,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 0.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_blocks_market_by_id_or_team() {
+        let resolver = TeamResolver::new();
+        let blacklist = Blacklist {
+            teams: vec!["Showmatch All-Stars".to_string()],
+            leagues: Vec::new(),
+            markets: vec!["0xambiguous".to_string()],
+        };
+
+        assert!(blacklist.blocks_market(&market("0xambiguous", "OG", "Liquid"), &resolver));
+        assert!(blacklist.blocks_market(&market("0xa", "OG", "showmatch all-stars"), &resolver));
+        assert!(!blacklist.blocks_market(&market("0xa", "OG", "Liquid"), &resolver));
+    }
+}
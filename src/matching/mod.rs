@@ -1,3 +1,15 @@
+pub mod blacklist;
+pub mod pins;
+pub mod question;
+pub mod reconcile;
+pub mod series;
 pub mod team_resolver;
+pub mod watchlist;
 
-pub use team_resolver::TeamResolver;
+pub use blacklist::Blacklist;
+pub use pins::{MarketPin, MarketPins};
+pub use question::MarketQuestion;
+pub use reconcile::MatchReconciler;
+pub use series::SeriesTracker;
+pub use team_resolver::{TeamAliases, TeamResolver};
+pub use watchlist::Watchlist;
@@ -0,0 +1,105 @@
+//! Manual market-to-match assignments
+//!
+//! When team names can't be resolved (a stand-in roster, a sponsor rename
+//! without an alias yet), `MATCH_PINS` ties a market to a live match ID and
+//! the fetcher uses it instead of `TeamResolver`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+
+/// A market's pinned live match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketPin {
+    pub match_id: i64,
+
+    /// Side the market's team A plays; inferred from team names when unset
+    pub team_a_is_radiant: Option<bool>,
+}
+
+/// Pins by condition ID, as `condition_id=match_id[:radiant|dire]` pairs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarketPins(pub BTreeMap<String, MarketPin>);
+
+impl MarketPins {
+    pub fn get(&self, condition_id: &str) -> Option<&MarketPin> {
+        self.0.get(condition_id)
+    }
+}
+
+impl FromStr for MarketPins {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (condition_id, target) = pair
+                    .split_once('=')
+                    .with_context(|| format!("Expected condition_id=match_id: {}", pair))?;
+                let (match_id, side) = match target.split_once(':') {
+                    Some((match_id, side)) => (match_id, Some(side.trim())),
+                    None => (target, None),
+                };
+                let match_id = match_id
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Invalid match ID: {}", pair))?;
+                let team_a_is_radiant = match side {
+                    None => None,
+                    Some(side) if side.eq_ignore_ascii_case("radiant") => Some(true),
+                    Some(side) if side.eq_ignore_ascii_case("dire") => Some(false),
+                    Some(side) => anyhow::bail!("Side must be radiant or dire: {}", side),
+                };
+                Ok((
+                    condition_id.trim().to_string(),
+                    MarketPin {
+                        match_id,
+                        team_a_is_radiant,
+                    },
+                ))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for MarketPins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self
+            .0
+            .iter()
+            .map(|(condition_id, pin)| match pin.team_a_is_radiant {
+                Some(true) => format!("{}={}:radiant", condition_id, pin.match_id),
+                Some(false) => format!("{}={}:dire", condition_id, pin.match_id),
+                None => format!("{}={}", condition_id, pin.match_id),
+            })
+            .collect();
+        f.write_str(&pairs.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pins() {
+        let pins: MarketPins = "0xa=8100000001, 0xb=8100000002:dire".parse().unwrap();
+        assert_eq!(
+            pins.get("0xa"),
+            Some(&MarketPin {
+                match_id: 8100000001,
+                team_a_is_radiant: None
+            })
+        );
+        assert_eq!(pins.get("0xb").unwrap().team_a_is_radiant, Some(false));
+        assert_eq!(pins.to_string(), "0xa=8100000001,0xb=8100000002:dire");
+
+        assert!("0xa".parse::<MarketPins>().is_err());
+        assert!("0xa=81:left".parse::<MarketPins>().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Structured details from market question strings
+//!
+//! Questions look like "Dota 2: Team Spirit vs OG (BO3) - PGL Wallachia
+//! Playoffs" or "Dota 2: Team Spirit vs OG - Game 2 Winner". The series
+//! format and game number are pulled out; any other parenthesized or
+//! dash-separated segment is kept as a qualifier.
+
+use crate::models::SeriesFormat;
+
+/// What a market question says beyond the two teams
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarketQuestion {
+    pub series_format: Option<SeriesFormat>,
+    /// Game within the series; `None` for series-winner markets
+    pub game_number: Option<u32>,
+    /// Remaining segments, e.g. the stage or event name
+    pub qualifiers: Vec<String>,
+}
+
+impl MarketQuestion {
+    pub fn parse(question: &str) -> Self {
+        let mut parsed = Self::default();
+
+        let question = question
+            .trim()
+            .strip_prefix("Dota 2:")
+            .unwrap_or(question)
+            .trim();
+
+        for (i, segment) in segments(question).into_iter().enumerate() {
+            let words: Vec<String> = segment
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .map(str::to_lowercase)
+                .collect();
+
+            let format = series_format(&words);
+            let game = game_number(&words);
+            parsed.series_format = parsed.series_format.or(format);
+            parsed.game_number = parsed.game_number.or(game);
+
+            // The first segment holds the team names
+            if i == 0 || format.is_some() || game.is_some() {
+                continue;
+            }
+            parsed.qualifiers.push(segment.to_string());
+        }
+
+        parsed
+    }
+}
+
+/// Split on ` - ` and pull out parenthesized groups; the text before any
+/// separator comes first
+fn segments(question: &str) -> Vec<&str> {
+    let mut head = Vec::new();
+    let mut groups = Vec::new();
+
+    for part in question.split(" - ") {
+        let mut rest = part;
+        while let Some(open) = rest.find('(') {
+            let Some(close) = rest[open..].find(')') else {
+                break;
+            };
+            head.push(&rest[..open]);
+            groups.push(&rest[open + 1..open + close]);
+            rest = &rest[open + close + 1..];
+        }
+        head.push(rest);
+    }
+
+    let mut segments: Vec<&str> = head.into_iter().chain(groups).map(str::trim).collect();
+    // Keep the leading team segment even if it's empty so indexes line up
+    let first = segments.remove(0);
+    segments.retain(|s| !s.is_empty());
+    segments.insert(0, first);
+    segments
+}
+
+/// `BO3`, `Bo5` or `best of 3`
+fn series_format(words: &[String]) -> Option<SeriesFormat> {
+    words.iter().enumerate().find_map(|(i, word)| {
+        if let Some(format) = word.strip_prefix("bo").and_then(|n| n.parse().ok()) {
+            return SeriesFormat::from_games(format);
+        }
+        if word == "best" && words.get(i + 1).map(String::as_str) == Some("of") {
+            return words
+                .get(i + 2)
+                .and_then(|n| n.parse().ok())
+                .and_then(SeriesFormat::from_games);
+        }
+        None
+    })
+}
+
+/// `Game 2` or `Map 2`
+fn game_number(words: &[String]) -> Option<u32> {
+    words
+        .windows(2)
+        .find(|pair| pair[0] == "game" || pair[0] == "map")
+        .and_then(|pair| pair[1].parse().ok())
+        .filter(|n| *n > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_question() {
+        let series =
+            MarketQuestion::parse("Dota 2: Team Spirit vs OG (BO3) - PGL Wallachia Playoffs");
+        assert_eq!(series.series_format, Some(SeriesFormat::Bo3));
+        assert_eq!(series.game_number, None);
+        assert_eq!(series.qualifiers, vec!["PGL Wallachia Playoffs"]);
+
+        let game = MarketQuestion::parse("Dota 2: Team Spirit vs OG - Game 2 Winner");
+        assert_eq!(game.series_format, None);
+        assert_eq!(game.game_number, Some(2));
+        assert!(game.qualifiers.is_empty());
+
+        let kalshi = MarketQuestion::parse("Tundra vs Falcons Map 3 (Best of 5) (Grand Final)");
+        assert_eq!(kalshi.series_format, Some(SeriesFormat::Bo5));
+        assert_eq!(kalshi.game_number, Some(3));
+        assert_eq!(kalshi.qualifiers, vec!["Grand Final"]);
+
+        assert_eq!(
+            MarketQuestion::parse("Liquid vs Gaimin Gladiators"),
+            MarketQuestion::default()
+        );
+    }
+}
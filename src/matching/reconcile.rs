@@ -0,0 +1,224 @@
+//! One live game reported by two sources
+//!
+//! OpenDota and STRATZ usually agree on a game's match ID, but not always:
+//! a lobby that restarts or a feed that lags can leave them reporting the
+//! same game under different IDs. Games are therefore also matched on the
+//! teams on each side, the league and the start time, and the second
+//! source's ID is remembered as an alias of the first's so the game keeps
+//! one cache entry when only the second source still reports it.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tracing::debug;
+
+use crate::models::LiveMatchState;
+
+/// Largest gap between two sources' start times for the same game; the
+/// next game of a series starts well after this
+const START_TOLERANCE_SECS: i64 = 120;
+
+/// Aliases not seen for this long are forgotten
+const ALIAS_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// What identifies a live game across sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GameIdentity {
+    radiant_team_id: i64,
+    dire_team_id: i64,
+    league_id: i64,
+    started_at: DateTime<Utc>,
+}
+
+impl GameIdentity {
+    /// `None` unless both teams and the league are known
+    fn of(state: &LiveMatchState) -> Option<Self> {
+        Some(Self {
+            radiant_team_id: state.radiant.team_id.filter(|id| *id > 0)?,
+            dire_team_id: state.dire.team_id.filter(|id| *id > 0)?,
+            league_id: state.league_id.filter(|id| *id > 0)?,
+            started_at: state.updated_at - chrono::Duration::seconds(state.game_time.into()),
+        })
+    }
+
+    fn same_game(&self, other: &GameIdentity) -> bool {
+        self.radiant_team_id == other.radiant_team_id
+            && self.dire_team_id == other.dire_team_id
+            && self.league_id == other.league_id
+            && (self.started_at - other.started_at).num_seconds().abs() <= START_TOLERANCE_SECS
+    }
+}
+
+#[derive(Debug)]
+struct Alias {
+    match_id: i64,
+    last_seen: Instant,
+}
+
+/// Merges a second source's live games into the primary source's, by match
+/// ID or game identity
+#[derive(Debug, Default)]
+pub struct MatchReconciler {
+    /// Primary match ID of each second-source ID seen under another one
+    aliases: HashMap<i64, Alias>,
+}
+
+impl MatchReconciler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ID the second source reports a primary game under
+    pub fn source_id(&self, match_id: i64) -> i64 {
+        self.aliases
+            .iter()
+            .find(|(_, alias)| alias.match_id == match_id)
+            .map(|(id, _)| *id)
+            .unwrap_or(match_id)
+    }
+
+    /// Enrich each primary game with the second source's view of it, and
+    /// add games only the second source reports
+    ///
+    /// A game keeps the primary match ID. Whichever source refreshed it last
+    /// sets the scoreboard (kills, buildings, gold lead, game time); the
+    /// other fills in only what the game lacks.
+    pub fn reconcile(
+        &mut self,
+        matches: &mut Vec<LiveMatchState>,
+        others: Vec<LiveMatchState>,
+        now: Instant,
+    ) {
+        self.aliases
+            .retain(|_, alias| now.duration_since(alias.last_seen) < ALIAS_TTL);
+
+        for mut other in others {
+            let identity = GameIdentity::of(&other);
+            let same = matches
+                .iter()
+                .position(|m| m.match_id == other.match_id)
+                .or_else(|| {
+                    let identity = identity?;
+                    matches
+                        .iter()
+                        .position(|m| GameIdentity::of(m).is_some_and(|id| id.same_game(&identity)))
+                });
+
+            match same {
+                Some(i) => {
+                    let state = &mut matches[i];
+                    if state.match_id != other.match_id {
+                        debug!(
+                            "Match {} is match {} under another ID",
+                            other.match_id, state.match_id
+                        );
+                        self.aliases.insert(
+                            other.match_id,
+                            Alias {
+                                match_id: state.match_id,
+                                last_seen: now,
+                            },
+                        );
+                    }
+                    merge(state, &other);
+                }
+                None => {
+                    // A game the primary source dropped keeps its cache entry
+                    if let Some(alias) = self.aliases.get_mut(&other.match_id) {
+                        alias.last_seen = now;
+                        other.match_id = alias.match_id;
+                    }
+                    matches.push(other);
+                }
+            }
+        }
+    }
+}
+
+/// Take the fresher scoreboard of the two, then enrich from the other
+fn merge(state: &mut LiveMatchState, other: &LiveMatchState) {
+    if other.updated_at > state.updated_at {
+        for (team, other) in [
+            (&mut state.radiant, &other.radiant),
+            (&mut state.dire, &other.dire),
+        ] {
+            team.kills = other.kills;
+            team.towers_killed = other.towers_killed;
+            team.barracks_killed = other.barracks_killed;
+        }
+        state.gold_lead = other.gold_lead;
+        state.game_time = other.game_time;
+        state.updated_at = other.updated_at;
+    }
+    state.enrich_from(other);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TeamState;
+
+    fn game(match_id: i64, started_at: DateTime<Utc>, game_time: i32) -> LiveMatchState {
+        let team = |team_id| TeamState {
+            team_id: Some(team_id),
+            ..Default::default()
+        };
+        LiveMatchState {
+            match_id,
+            league_id: Some(16935),
+            radiant: team(7119388),
+            dire: team(2586976),
+            game_time,
+            updated_at: started_at + chrono::Duration::seconds(game_time.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_same_game_under_another_id_is_merged() {
+        let start = Utc::now() - chrono::Duration::minutes(30);
+        let now = Instant::now();
+        let mut reconciler = MatchReconciler::new();
+
+        let mut matches = vec![game(100, start, 1500)];
+        // STRATZ is 20s fresher and reports Roshan; the start times differ
+        // by its polling lag
+        let mut stratz = game(101, start + chrono::Duration::seconds(30), 1490);
+        stratz.radiant.kills = 20;
+        stratz.roshan_killed_at = Some(1300);
+        // The next game of the series, same teams and league
+        let next = game(102, start + chrono::Duration::minutes(45), 60);
+
+        reconciler.reconcile(&mut matches, vec![stratz, next], now);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].match_id, 100);
+        assert_eq!(matches[0].radiant.kills, 20);
+        assert_eq!(matches[0].game_time, 1490);
+        assert_eq!(matches[0].roshan_killed_at, Some(1300));
+        assert_eq!(matches[1].match_id, 102);
+
+        // OpenDota drops the game; STRATZ's copy stays under OpenDota's ID
+        let mut matches = Vec::new();
+        reconciler.reconcile(&mut matches, vec![game(101, start, 1520)], now);
+        assert_eq!(matches[0].match_id, 100);
+        assert_eq!(reconciler.source_id(100), 101);
+        assert_eq!(reconciler.source_id(102), 102);
+    }
+
+    #[test]
+    fn test_stale_source_only_fills_gaps() {
+        let start = Utc::now() - chrono::Duration::minutes(30);
+        let mut matches = vec![game(100, start, 1500)];
+        matches[0].radiant.kills = 18;
+
+        let mut stale = game(100, start, 1440);
+        stale.radiant.kills = 15;
+        stale.radiant_win_rate = Some(0.6);
+
+        MatchReconciler::new().reconcile(&mut matches, vec![stale], Instant::now());
+        assert_eq!(matches[0].radiant.kills, 18);
+        assert_eq!(matches[0].game_time, 1500);
+        assert_eq!(matches[0].radiant_win_rate, Some(0.6));
+    }
+}
@@ -0,0 +1,137 @@
+//! Game numbers within a series
+//!
+//! Live data only describes the game in progress. Games of one series share
+//! an OpenDota series ID (or, without one, the same pair of teams), and match
+//! IDs increase over time, so a game's number is its position among the
+//! series' match IDs seen so far.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::TeamResolver;
+use crate::models::LiveMatchState;
+
+/// Series not seen for this long are forgotten, so a rematch starts over
+const SERIES_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// What identifies the games of one series
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SeriesKey {
+    Id(i64),
+    /// Normalized team names, sorted
+    Teams(String, String),
+}
+
+#[derive(Debug)]
+struct Series {
+    /// Match IDs of the series' games, ascending
+    match_ids: Vec<i64>,
+    last_seen: Instant,
+}
+
+/// Game numbers of the live series seen since startup
+#[derive(Debug, Default)]
+pub struct SeriesTracker {
+    series: HashMap<SeriesKey, Series>,
+}
+
+impl SeriesTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the live games and set each one's `game_number`
+    ///
+    /// A series already in progress at startup counts its first game seen
+    /// as game 1.
+    pub fn assign(
+        &mut self,
+        live_matches: &mut [LiveMatchState],
+        resolver: &TeamResolver,
+        now: Instant,
+    ) {
+        self.series
+            .retain(|_, series| now.duration_since(series.last_seen) < SERIES_TTL);
+
+        for live_match in live_matches.iter() {
+            let series = self
+                .series
+                .entry(Self::key(live_match, resolver))
+                .or_insert_with(|| Series {
+                    match_ids: Vec::new(),
+                    last_seen: now,
+                });
+            series.last_seen = now;
+            if let Err(i) = series.match_ids.binary_search(&live_match.match_id) {
+                series.match_ids.insert(i, live_match.match_id);
+            }
+        }
+
+        for live_match in live_matches.iter_mut() {
+            let series = &self.series[&Self::key(live_match, resolver)];
+            live_match.game_number = series
+                .match_ids
+                .iter()
+                .position(|id| *id == live_match.match_id)
+                .map(|i| i as u32 + 1);
+        }
+    }
+
+    fn key(live_match: &LiveMatchState, resolver: &TeamResolver) -> SeriesKey {
+        if let Some(id) = live_match.series_id {
+            return SeriesKey::Id(id);
+        }
+        let radiant = resolver.normalize(&live_match.radiant.name);
+        let dire = resolver.normalize(&live_match.dire.name);
+        if radiant <= dire {
+            SeriesKey::Teams(radiant, dire)
+        } else {
+            SeriesKey::Teams(dire, radiant)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TeamState;
+
+    fn live(match_id: i64, radiant: &str, dire: &str) -> LiveMatchState {
+        let team = |name: &str| TeamState {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        LiveMatchState {
+            match_id,
+            radiant: team(radiant),
+            dire: team(dire),
+            game_time: 600,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_games_numbered_in_match_order() {
+        let resolver = TeamResolver::new();
+        let mut tracker = SeriesTracker::new();
+        let start = Instant::now();
+
+        let mut games = vec![live(100, "Team Spirit", "OG"), live(90, "Liquid", "Tundra")];
+        tracker.assign(&mut games, &resolver, start);
+        assert_eq!(games[0].game_number, Some(1));
+        assert_eq!(games[1].game_number, Some(1));
+
+        // Sides swap between games
+        let mut games = vec![live(105, "OG", "Team Spirit")];
+        tracker.assign(&mut games, &resolver, start);
+        assert_eq!(games[0].game_number, Some(2));
+
+        let mut games = vec![live(100, "Team Spirit", "OG")];
+        tracker.assign(&mut games, &resolver, start);
+        assert_eq!(games[0].game_number, Some(1));
+
+        let mut games = vec![live(200, "Team Spirit", "OG")];
+        tracker.assign(&mut games, &resolver, start + SERIES_TTL);
+        assert_eq!(games[0].game_number, Some(1));
+    }
+}
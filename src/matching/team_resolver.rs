@@ -1,20 +1,31 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
-use crate::models::{LiveMatchState, PolymarketMarket};
+use super::MarketPin;
+use crate::models::{LiveMatchState, Market};
 
 /// Resolves team names between Polymarket and live match data
+///
+/// Aliases can be edited and reloaded while workers hold the resolver.
 pub struct TeamResolver {
     /// Map of alias -> canonical name
-    aliases: HashMap<String, String>,
+    aliases: ArcSwap<HashMap<String, String>>,
+
+    /// Alias entries as configured; edits are saved back to `path`
+    entries: Mutex<TeamAliases>,
+
+    /// Aliases file; None keeps edits in memory
+    path: Option<PathBuf>,
 }
 
 /// Team alias configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TeamAliases {
     pub teams: Vec<TeamAliasEntry>,
 }
@@ -30,8 +41,8 @@ pub struct TeamAliasEntry {
 /// Result of matching a market to a live match
 #[derive(Debug, Clone)]
 pub struct MatchResult {
-    pub market: PolymarketMarket,
-    pub match_state: LiveMatchState,
+    pub market: Market,
+    pub match_state: Arc<LiveMatchState>,
     /// Which team in the market corresponds to radiant
     pub market_team_a_is_radiant: bool,
 }
@@ -40,45 +51,139 @@ impl TeamResolver {
     /// Create a new resolver with no aliases
     pub fn new() -> Self {
         Self {
-            aliases: HashMap::new(),
+            aliases: ArcSwap::from_pointee(HashMap::new()),
+            entries: Mutex::new(TeamAliases::default()),
+            path: None,
         }
     }
 
-    /// Load aliases from a JSON file
-    pub fn load_from_file(path: &Path) -> Result<Self> {
-        let content =
-            std::fs::read_to_string(path).context("Failed to read team aliases file")?;
+    /// Load aliases from a JSON file, starting empty if it doesn't exist yet
+    pub fn open(path: &Path) -> Result<Self> {
+        let entries = read_aliases(path)?;
+        let aliases = alias_map(&entries);
+        info!("Loaded {} team alias mappings", aliases.len());
 
-        let aliases_config: TeamAliases =
-            serde_json::from_str(&content).context("Failed to parse team aliases JSON")?;
+        Ok(Self {
+            aliases: ArcSwap::from_pointee(aliases),
+            entries: Mutex::new(entries),
+            path: Some(path.to_path_buf()),
+        })
+    }
 
-        let mut aliases = HashMap::new();
+    /// Re-read the aliases file, e.g. after editing it by hand; returns the
+    /// number of mappings
+    pub fn reload(&self) -> Result<usize> {
+        let path = self
+            .path
+            .as_deref()
+            .context("Team resolver has no aliases file")?;
+        let mut entries = self.lock_entries();
+        *entries = read_aliases(path)?;
+        Ok(self.apply(&entries))
+    }
 
-        for entry in aliases_config.teams {
-            let canonical = entry.canonical.to_lowercase();
+    /// Map `alias` to the team `canonical`, moving it off any other team,
+    /// and save the aliases file
+    pub fn add_alias(&self, alias: &str, canonical: &str) -> Result<()> {
+        let alias = alias.trim();
+        let canonical = canonical.trim();
+        anyhow::ensure!(
+            !alias.is_empty() && !canonical.is_empty(),
+            "Alias and canonical name must not be empty"
+        );
 
-            // Map canonical name to itself
-            aliases.insert(canonical.clone(), canonical.clone());
+        let mut entries = self.lock_entries();
+        anyhow::ensure!(
+            !entries.teams.iter().any(|entry| {
+                entry.canonical.eq_ignore_ascii_case(alias)
+                    && !entry.canonical.eq_ignore_ascii_case(canonical)
+            }),
+            "{} is the canonical name of another team",
+            alias
+        );
 
-            // Map all aliases to canonical
-            for alias in entry.aliases {
-                aliases.insert(alias.to_lowercase(), canonical.clone());
-            }
+        for entry in &mut entries.teams {
+            entry.aliases.retain(|a| !a.eq_ignore_ascii_case(alias));
+        }
+        // `canonical` may itself be an alias of the team
+        match entries.teams.iter_mut().find(|entry| {
+            entry.canonical.eq_ignore_ascii_case(canonical)
+                || entry
+                    .aliases
+                    .iter()
+                    .any(|a| a.eq_ignore_ascii_case(canonical))
+        }) {
+            Some(entry) if entry.canonical.eq_ignore_ascii_case(alias) => {}
+            Some(entry) => entry.aliases.push(alias.to_string()),
+            None => entries.teams.push(TeamAliasEntry {
+                canonical: canonical.to_string(),
+                aliases: vec![alias.to_string()],
+            }),
         }
 
-        info!("Loaded {} team alias mappings", aliases.len());
+        self.save(&entries)?;
+        self.apply(&entries);
+        info!("Added team alias {} -> {}", alias, canonical);
+        Ok(())
+    }
+
+    /// Drop an alias from every team and save the aliases file; false if it
+    /// wasn't an alias
+    pub fn remove_alias(&self, alias: &str) -> Result<bool> {
+        let alias = alias.trim();
+        let mut entries = self.lock_entries();
+
+        let mut removed = false;
+        for entry in &mut entries.teams {
+            let before = entry.aliases.len();
+            entry.aliases.retain(|a| !a.eq_ignore_ascii_case(alias));
+            removed |= entry.aliases.len() != before;
+        }
+        if !removed {
+            return Ok(false);
+        }
+
+        self.save(&entries)?;
+        self.apply(&entries);
+        info!("Removed team alias {}", alias);
+        Ok(true)
+    }
 
-        Ok(Self { aliases })
+    /// Alias entries as currently configured
+    pub fn entries(&self) -> TeamAliases {
+        self.lock_entries().clone()
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, TeamAliases> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Swap in the mappings of `entries`; returns their number
+    fn apply(&self, entries: &TeamAliases) -> usize {
+        let aliases = alias_map(entries);
+        let count = aliases.len();
+        self.aliases.store(Arc::new(aliases));
+        count
+    }
+
+    /// Write `entries` to the aliases file, if there is one
+    fn save(&self, entries: &TeamAliases) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let content = serde_json::to_string_pretty(entries)?;
+
+        // Replace the file in one step so a crash can't leave it half-written
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content + "\n").context("Failed to write team aliases file")?;
+        std::fs::rename(&tmp, path).context("Failed to replace team aliases file")
     }
 
     /// Normalize a team name to its canonical form
     pub fn normalize(&self, name: &str) -> String {
         let lower = name.to_lowercase().trim().to_string();
 
-        self.aliases
-            .get(&lower)
-            .cloned()
-            .unwrap_or_else(|| lower)
+        self.aliases.load().get(&lower).cloned().unwrap_or(lower)
     }
 
     /// Check if two team names match (accounting for aliases)
@@ -87,10 +192,12 @@ impl TeamResolver {
     }
 
     /// Find matching live matches for a market
+    ///
+    /// A game-level market only matches the live game with its game number.
     pub fn match_market_to_live(
         &self,
-        market: &PolymarketMarket,
-        live_matches: &[LiveMatchState],
+        market: &Market,
+        live_matches: &[Arc<LiveMatchState>],
     ) -> Option<MatchResult> {
         let market_team_a = self.normalize(&market.team_a);
         let market_team_b = self.normalize(&market.team_b);
@@ -114,6 +221,14 @@ impl TeamResolver {
             let team_a_is_radiant = market_team_a == radiant_name && market_team_b == dire_name;
             let team_a_is_dire = market_team_a == dire_name && market_team_b == radiant_name;
 
+            if market.game_number.is_some() && market.game_number != live_match.game_number {
+                debug!(
+                    "  Live match {} is game {:?}, market is game {:?}",
+                    live_match.match_id, live_match.game_number, market.game_number
+                );
+                continue;
+            }
+
             if team_a_is_radiant || team_a_is_dire {
                 info!(
                     "Matched market {} to live match {}",
@@ -122,7 +237,7 @@ impl TeamResolver {
 
                 return Some(MatchResult {
                     market: market.clone(),
-                    match_state: live_match.clone(),
+                    match_state: Arc::clone(live_match),
                     market_team_a_is_radiant: team_a_is_radiant,
                 });
             }
@@ -132,13 +247,64 @@ impl TeamResolver {
         None
     }
 
-    /// Add a new alias mapping
-    pub fn add_alias(&mut self, alias: &str, canonical: &str) {
-        self.aliases
-            .insert(alias.to_lowercase(), canonical.to_lowercase());
+    /// Match a market to the live match it's pinned to, skipping name matching
+    ///
+    /// Without a pinned side, team A is Dire if either market team matches
+    /// the opposite side by name, else Radiant.
+    pub fn match_pinned(
+        &self,
+        market: &Market,
+        pin: &MarketPin,
+        live_matches: &[Arc<LiveMatchState>],
+    ) -> Option<MatchResult> {
+        let live_match = live_matches.iter().find(|m| m.match_id == pin.match_id)?;
+
+        let team_a_is_radiant = pin.team_a_is_radiant.unwrap_or_else(|| {
+            !(self.names_match(&market.team_a, &live_match.dire.name)
+                || self.names_match(&market.team_b, &live_match.radiant.name))
+        });
+
+        debug!(
+            "Market {} pinned to live match {}",
+            market.condition_id, live_match.match_id
+        );
+
+        Some(MatchResult {
+            market: market.clone(),
+            match_state: Arc::clone(live_match),
+            market_team_a_is_radiant: team_a_is_radiant,
+        })
     }
 }
 
+/// Alias entries in a JSON file; none if the file doesn't exist
+fn read_aliases(path: &Path) -> Result<TeamAliases> {
+    if !path.exists() {
+        return Ok(TeamAliases::default());
+    }
+    let content = std::fs::read_to_string(path).context("Failed to read team aliases file")?;
+    serde_json::from_str(&content).context("Failed to parse team aliases JSON")
+}
+
+/// Lower-cased alias -> canonical name lookup
+fn alias_map(entries: &TeamAliases) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+
+    for entry in &entries.teams {
+        let canonical = entry.canonical.to_lowercase();
+
+        // Map canonical name to itself
+        aliases.insert(canonical.clone(), canonical.clone());
+
+        // Map all aliases to canonical
+        for alias in &entry.aliases {
+            aliases.insert(alias.to_lowercase(), canonical.clone());
+        }
+    }
+
+    aliases
+}
+
 impl Default for TeamResolver {
     fn default() -> Self {
         Self::new()
@@ -148,12 +314,13 @@ impl Default for TeamResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{TeamState, Venue};
 
     #[test]
     fn test_normalize() {
-        let mut resolver = TeamResolver::new();
-        resolver.add_alias("ts", "team spirit");
-        resolver.add_alias("spirit", "team spirit");
+        let resolver = TeamResolver::new();
+        resolver.add_alias("ts", "team spirit").unwrap();
+        resolver.add_alias("spirit", "team spirit").unwrap();
 
         assert_eq!(resolver.normalize("Team Spirit"), "team spirit");
         assert_eq!(resolver.normalize("TS"), "team spirit");
@@ -163,12 +330,107 @@ mod tests {
 
     #[test]
     fn test_names_match() {
-        let mut resolver = TeamResolver::new();
-        resolver.add_alias("ts", "team spirit");
-        resolver.add_alias("spirit", "team spirit");
+        let resolver = TeamResolver::new();
+        resolver.add_alias("ts", "team spirit").unwrap();
+        resolver.add_alias("spirit", "team spirit").unwrap();
 
         assert!(resolver.names_match("Team Spirit", "TS"));
         assert!(resolver.names_match("Spirit", "Team Spirit"));
         assert!(!resolver.names_match("Team Spirit", "OG"));
     }
+
+    #[test]
+    fn test_alias_edits_are_saved() {
+        let path = std::env::temp_dir().join(format!("team_aliases_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"teams": [{"canonical": "Team Spirit", "aliases": ["Spirit", "TS"]}]}"#,
+        )
+        .unwrap();
+        let resolver = TeamResolver::open(&path).unwrap();
+
+        // Adding through an alias joins that team, and moves the alias
+        resolver.add_alias("Spirit Academy", "spirit").unwrap();
+        assert!(resolver.names_match("spirit academy", "Team Spirit"));
+        resolver.add_alias("TS", "Tundra Esports").unwrap();
+        assert!(resolver.names_match("TS", "Tundra Esports"));
+        assert!(resolver.add_alias("Team Spirit", "Tundra Esports").is_err());
+
+        assert!(resolver.remove_alias("ts").unwrap());
+        assert!(!resolver.remove_alias("ts").unwrap());
+        assert!(!resolver.names_match("TS", "Tundra Esports"));
+
+        let reopened = TeamResolver::open(&path).unwrap();
+        assert_eq!(
+            reopened.entries().teams[0].aliases,
+            ["Spirit", "Spirit Academy"]
+        );
+        assert!(reopened.entries().teams[1].aliases.is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_match_pinned() {
+        let resolver = TeamResolver::new();
+        let market = Market {
+            condition_id: "0xa".to_string(),
+            venue: Venue::Polymarket,
+            question: String::new(),
+            team_a: "Spirit Academy".to_string(),
+            team_b: "Liquid".to_string(),
+            team_a_odds: 0.5,
+            team_b_odds: 0.5,
+            opening_team_a_odds: 0.5,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 0.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        };
+        // A stand-in roster plays under another name
+        let live = Arc::new(LiveMatchState {
+            match_id: 42,
+            radiant: TeamState {
+                name: "Liquid".to_string(),
+                ..Default::default()
+            },
+            dire: TeamState {
+                name: "Yellow Submarine".to_string(),
+                ..Default::default()
+            },
+            game_time: 600,
+            ..Default::default()
+        });
+        let pin = |team_a_is_radiant| MarketPin {
+            match_id: 42,
+            team_a_is_radiant,
+        };
+
+        assert!(resolver
+            .match_market_to_live(&market, std::slice::from_ref(&live))
+            .is_none());
+        let result = resolver
+            .match_pinned(&market, &pin(None), std::slice::from_ref(&live))
+            .unwrap();
+        assert!(!result.market_team_a_is_radiant); // Liquid is Radiant
+        let result = resolver
+            .match_pinned(&market, &pin(Some(true)), std::slice::from_ref(&live))
+            .unwrap();
+        assert!(result.market_team_a_is_radiant);
+
+        let elsewhere = MarketPin {
+            match_id: 7,
+            team_a_is_radiant: None,
+        };
+        assert!(resolver
+            .match_pinned(&market, &elsewhere, &[live])
+            .is_none());
+    }
 }
@@ -0,0 +1,151 @@
+//! Teams and leagues to follow more closely
+//!
+//! While a watched match is live the fetcher polls at
+//! `WATCH_POLL_INTERVAL`, and its signal alerts carry `watched = true` so
+//! notification rules can give them a lower threshold. New markets on a
+//! watched fixture are alerted as soon as the scanner finds them.
+
+use super::TeamResolver;
+use crate::models::{LiveMatchState, Market};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Watchlist {
+    /// Team names, matched through the team aliases
+    pub teams: Vec<String>,
+
+    /// Case-insensitive substrings of the league name, e.g. "The International"
+    pub leagues: Vec<String>,
+}
+
+impl Watchlist {
+    pub fn is_empty(&self) -> bool {
+        self.teams.is_empty() && self.leagues.is_empty()
+    }
+
+    /// Whether either team or the league of a live match is watched
+    pub fn includes(&self, state: &LiveMatchState, resolver: &TeamResolver) -> bool {
+        involves(&self.teams, &self.leagues, state, resolver)
+    }
+
+    /// Whether either team of a match market, or a league named in its
+    /// question, is watched
+    pub fn includes_market(&self, market: &Market, resolver: &TeamResolver) -> bool {
+        if market.is_outright() {
+            return false;
+        }
+
+        let has_team = self.teams.iter().any(|team| {
+            resolver.names_match(team, &market.team_a) || resolver.names_match(team, &market.team_b)
+        });
+
+        let question = market.question.to_lowercase();
+        let in_league = self
+            .leagues
+            .iter()
+            .any(|league| question.contains(&league.to_lowercase()));
+
+        has_team || in_league
+    }
+}
+
+/// Whether a live match has one of `teams` (through the aliases) or a league
+/// name containing one of `leagues`, ignoring case
+pub(super) fn involves(
+    teams: &[String],
+    leagues: &[String],
+    state: &LiveMatchState,
+    resolver: &TeamResolver,
+) -> bool {
+    let has_team = teams.iter().any(|team| {
+        resolver.names_match(team, &state.radiant.name)
+            || resolver.names_match(team, &state.dire.name)
+    });
+
+    let in_league = state.league_name.as_ref().is_some_and(|name| {
+        let name = name.to_lowercase();
+        leagues
+            .iter()
+            .any(|league| name.contains(&league.to_lowercase()))
+    });
+
+    has_team || in_league
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TeamState, Venue};
+
+    fn state(radiant: &str, dire: &str, league: Option<&str>) -> LiveMatchState {
+        LiveMatchState {
+            match_id: 1,
+            league_name: league.map(str::to_string),
+            radiant: TeamState {
+                name: radiant.to_string(),
+                ..Default::default()
+            },
+            dire: TeamState {
+                name: dire.to_string(),
+                ..Default::default()
+            },
+            game_time: 600,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_watchlist_matches_team_or_league() {
+        let resolver = TeamResolver::new();
+        let watchlist = Watchlist {
+            teams: vec!["Team Spirit".to_string()],
+            leagues: vec!["the international".to_string()],
+        };
+
+        assert!(watchlist.includes(&state("Liquid", "team spirit", None), &resolver));
+        assert!(watchlist.includes(
+            &state("Liquid", "Falcons", Some("The International 2026")),
+            &resolver
+        ));
+        assert!(!watchlist.includes(&state("Liquid", "Falcons", Some("DreamLeague")), &resolver));
+        assert!(!Watchlist::default().includes(&state("Liquid", "Team Spirit", None), &resolver));
+
+        let market = |question: &str, team_a: &str, team_b: &str| Market {
+            condition_id: "0xa".to_string(),
+            venue: Venue::Polymarket,
+            question: question.to_string(),
+            team_a: team_a.to_string(),
+            team_b: team_b.to_string(),
+            team_a_odds: 0.5,
+            team_b_odds: 0.5,
+            opening_team_a_odds: 0.5,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 0.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        };
+        assert!(watchlist.includes_market(
+            &market("Dota 2: Liquid vs Team Spirit", "Liquid", "Team Spirit"),
+            &resolver
+        ));
+        assert!(watchlist.includes_market(
+            &market(
+                "Dota 2: Liquid vs Falcons - The International Playoffs",
+                "Liquid",
+                "Falcons"
+            ),
+            &resolver
+        ));
+        assert!(!watchlist.includes_market(
+            &market("Dota 2: Liquid vs Falcons", "Liquid", "Falcons"),
+            &resolver
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! Expected time left in a game, from historical durations
+//!
+//! Looks up finished games that lasted past the current game time and had
+//! a similar gold lead (either side) at that minute; the remaining
+//! durations of those games give the estimate. Historical data has no
+//! building state, so gold lead is the only state conditioned on.
+
+use tracing::info;
+
+use crate::db::HistoricalMatch;
+
+/// Fewest similar games an estimate is based on; below it the gold lead
+/// condition is dropped
+const MIN_SAMPLES: usize = 20;
+
+/// Gold lead difference within which a historical game counts as similar
+const LEAD_TOLERANCE: i64 = 3000;
+
+/// Remaining time of a game, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationEstimate {
+    /// Median remaining time of similar games
+    pub median_secs: i32,
+
+    /// 90th percentile remaining time; the game has most likely ended by then
+    pub late_secs: i32,
+
+    /// Similar games the estimate is based on
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone)]
+struct GameCurve {
+    duration: i32,
+    /// Radiant gold lead at each minute
+    gold_adv: Vec<i64>,
+}
+
+/// Historical game lengths with their gold curves
+#[derive(Debug, Clone, Default)]
+pub struct DurationModel {
+    games: Vec<GameCurve>,
+}
+
+impl DurationModel {
+    /// Collect durations and gold curves of historical matches
+    pub fn from_matches(matches: &[HistoricalMatch]) -> Self {
+        let games: Vec<GameCurve> = matches
+            .iter()
+            .filter(|m| m.duration > 0)
+            .map(|m| GameCurve {
+                duration: m.duration,
+                gold_adv: serde_json::from_str(&m.radiant_gold_adv).unwrap_or_default(),
+            })
+            .collect();
+
+        info!("Duration model built from {} matches", games.len());
+
+        Self { games }
+    }
+
+    /// Expected time left in a game at `game_time` with a Radiant `gold_lead`;
+    /// `None` with fewer than `MIN_SAMPLES` games that lasted this long
+    pub fn estimate(&self, game_time: i32, gold_lead: i64) -> Option<DurationEstimate> {
+        let minute = (game_time.max(0) / 60) as usize;
+        let lasted: Vec<&GameCurve> = self
+            .games
+            .iter()
+            .filter(|g| g.duration > game_time)
+            .collect();
+
+        let similar: Vec<i32> = lasted
+            .iter()
+            .filter(|g| {
+                g.gold_adv
+                    .get(minute)
+                    .is_some_and(|lead| (lead.abs() - gold_lead.abs()).abs() <= LEAD_TOLERANCE)
+            })
+            .map(|g| g.duration - game_time)
+            .collect();
+
+        let mut remaining = if similar.len() >= MIN_SAMPLES {
+            similar
+        } else {
+            lasted.iter().map(|g| g.duration - game_time).collect()
+        };
+        if remaining.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        remaining.sort_unstable();
+        let percentile = |p: f64| remaining[((remaining.len() - 1) as f64 * p).round() as usize];
+
+        Some(DurationEstimate {
+            median_secs: percentile(0.5),
+            late_secs: percentile(0.9),
+            samples: remaining.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(duration_min: i32, lead_per_min: i64) -> GameCurve {
+        GameCurve {
+            duration: duration_min * 60,
+            gold_adv: (0..=duration_min as i64)
+                .map(|m| m * lead_per_min)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_big_lead_ends_sooner() {
+        // Stomps: 1k per minute, over at 30 min; close games go to 50
+        let games = (0..30)
+            .map(|_| game(30, 1000))
+            .chain((0..30).map(|_| game(50, 50)))
+            .collect();
+        let model = DurationModel { games };
+
+        let stomp = model.estimate(20 * 60, 20_000).unwrap();
+        let close = model.estimate(20 * 60, -1000).unwrap();
+        assert_eq!(stomp.median_secs, 10 * 60);
+        assert_eq!(close.median_secs, 30 * 60);
+
+        // Past every stomp's length only the long games are left
+        assert_eq!(
+            model.estimate(35 * 60, 20_000).unwrap().median_secs,
+            15 * 60
+        );
+        assert_eq!(model.estimate(60 * 60, 0), None);
+    }
+}
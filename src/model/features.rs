@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+use super::{lineup, performance};
+use crate::models::{Deaths, LiveMatchState, MatchUpdate, Momentum};
+
+/// Towers a team must have taken (every tier 1 and 2) before its pushes
+/// count as high ground
+const HIGH_GROUND_TOWERS: i32 = 6;
+
+/// Feature names in the order expected by exported models
+///
+/// Momentum features are left out so existing ONNX exports keep their
+/// input shape; logistic models can weight them by name.
+pub const FEATURE_NAMES: [&str; 5] = [
+    "gold_lead_k",
+    "kill_diff",
+    "tower_diff",
+    "barracks_diff",
+    "game_time_min",
+];
+
+/// Model input features extracted from a live match (Radiant perspective)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchFeatures {
+    /// Radiant gold lead in thousands
+    pub gold_lead_k: f64,
+
+    /// Radiant kills minus Dire kills
+    pub kill_diff: f64,
+
+    /// Towers destroyed by Radiant minus towers destroyed by Dire
+    pub tower_diff: f64,
+
+    /// Barracks destroyed by Radiant minus barracks destroyed by Dire
+    pub barracks_diff: f64,
+
+    /// Game time in minutes
+    pub game_time_min: f64,
+
+    /// Radiant minus Dire kills per minute over the last few game minutes
+    #[serde(default)]
+    pub kill_rate_diff: f64,
+
+    /// Radiant gold lead change in thousands per minute over the last few
+    /// game minutes
+    #[serde(default)]
+    pub gold_velocity_k: f64,
+
+    /// Extra Radiant heroes alive while the team ahead on towers can push
+    /// high ground (negative favours Dire); 0 otherwise
+    #[serde(default)]
+    pub high_ground_numbers: f64,
+
+    /// 1 while Radiant holds an unexpired aegis, -1 for Dire, 0 otherwise
+    #[serde(default)]
+    pub aegis: f64,
+
+    /// Radiant minus Dire lineup scaling, weighted from 0 at 20 minutes to
+    /// full at 40; 0 without both drafts
+    #[serde(default)]
+    pub late_game_scaling: f64,
+
+    /// Radiant minus Dire mean deviation of live GPM/XPM from players'
+    /// recent averages; 0 without figures for both sides
+    #[serde(default)]
+    pub performance_diff: f64,
+
+    /// OpenDota patch ID, used to select patch-specific parameters (not a model input)
+    #[serde(default)]
+    pub patch: Option<i32>,
+}
+
+impl MatchFeatures {
+    /// Extract features from a live match state
+    pub fn from_state(state: &LiveMatchState) -> Self {
+        Self {
+            gold_lead_k: state.gold_lead as f64 / 1000.0,
+            kill_diff: (state.radiant.kills - state.dire.kills) as f64,
+            tower_diff: (state.radiant.towers_killed - state.dire.towers_killed) as f64,
+            barracks_diff: (state.radiant.barracks_killed - state.dire.barracks_killed) as f64,
+            game_time_min: state.game_time as f64 / 60.0,
+            kill_rate_diff: 0.0,
+            gold_velocity_k: 0.0,
+            high_ground_numbers: 0.0,
+            aegis: match state.aegis_holder() {
+                Some(true) => 1.0,
+                Some(false) => -1.0,
+                None => 0.0,
+            },
+            late_game_scaling: scaling_diff(state)
+                * lineup::late_game_weight(state.game_time as f64 / 60.0),
+            performance_diff: match (
+                performance::team_deviation(&state.radiant.players),
+                performance::team_deviation(&state.dire.players),
+            ) {
+                (Some(radiant), Some(dire)) => radiant - dire,
+                _ => 0.0,
+            },
+            patch: state.patch,
+        }
+    }
+
+    /// Features of a live update, including its momentum and deaths
+    pub fn from_update(update: &MatchUpdate) -> Self {
+        Self::from_state(&update.state)
+            .with_momentum(update.momentum)
+            .with_deaths(update.deaths, &update.state)
+    }
+
+    /// Count a numbers advantage when the team holding it can take high ground
+    pub fn with_deaths(self, deaths: Deaths, state: &LiveMatchState) -> Self {
+        let advantage = deaths.dire - deaths.radiant;
+        let pushing = if advantage > 0 {
+            state.radiant.towers_killed >= HIGH_GROUND_TOWERS
+        } else {
+            state.dire.towers_killed >= HIGH_GROUND_TOWERS
+        };
+
+        Self {
+            high_ground_numbers: if pushing { advantage as f64 } else { 0.0 },
+            ..self
+        }
+    }
+
+    /// Weight lineup scaling by the minute the game is expected to end
+    /// rather than the current one, so a game heading long counts its
+    /// drafts before the late game arrives
+    pub fn with_expected_end(self, state: &LiveMatchState, end_min: f64) -> Self {
+        Self {
+            late_game_scaling: scaling_diff(state) * lineup::late_game_weight(end_min),
+            ..self
+        }
+    }
+
+    /// Add the match's recent momentum
+    pub fn with_momentum(self, momentum: Momentum) -> Self {
+        Self {
+            kill_rate_diff: momentum.kill_rate_diff,
+            gold_velocity_k: momentum.gold_velocity_k,
+            ..self
+        }
+    }
+
+    /// Look up a feature by name
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "gold_lead_k" => Some(self.gold_lead_k),
+            "kill_diff" => Some(self.kill_diff),
+            "tower_diff" => Some(self.tower_diff),
+            "barracks_diff" => Some(self.barracks_diff),
+            "game_time_min" => Some(self.game_time_min),
+            "kill_rate_diff" => Some(self.kill_rate_diff),
+            "gold_velocity_k" => Some(self.gold_velocity_k),
+            "high_ground_numbers" => Some(self.high_ground_numbers),
+            "aegis" => Some(self.aegis),
+            "late_game_scaling" => Some(self.late_game_scaling),
+            "performance_diff" => Some(self.performance_diff),
+            _ => None,
+        }
+    }
+
+    /// Features as a dense vector in `FEATURE_NAMES` order
+    pub fn to_vec(&self) -> Vec<f32> {
+        FEATURE_NAMES
+            .iter()
+            .map(|name| self.get(name).unwrap_or(0.0) as f32)
+            .collect()
+    }
+}
+
+/// Radiant minus Dire lineup scaling; 0 without both drafts
+fn scaling_diff(state: &LiveMatchState) -> f64 {
+    match (
+        state.radiant.late_game_scaling,
+        state.dire.late_game_scaling,
+    ) {
+        (Some(radiant), Some(dire)) => radiant - dire,
+        _ => 0.0,
+    }
+}
@@ -0,0 +1,65 @@
+//! How much a draft scales into the late game
+//!
+//! A lineup of hard carries gets stronger with every minute of farm, while a
+//! tempo lineup has to end the game before they come online. Scores come
+//! from OpenDota hero roles: carries count for, pushers against.
+
+/// Game minute from which lineup scaling starts to count
+const LATE_GAME_START_MIN: f64 = 20.0;
+
+/// Game minute from which lineup scaling counts fully
+const LATE_GAME_FULL_MIN: f64 = 40.0;
+
+/// Scaling of one hero from its roles, from -0.5 (pure pusher) to 1 (carry)
+pub fn hero_scaling(roles: &[String]) -> f64 {
+    let has = |role: &str| roles.iter().any(|r| r == role);
+
+    let mut score = 0.0;
+    if has("Carry") {
+        score += 1.0;
+    }
+    if has("Pusher") {
+        score -= 0.5;
+    }
+    score
+}
+
+/// Mean scaling of a team's heroes; `None` until any hero is known
+pub fn team_scaling<'a>(heroes: impl IntoIterator<Item = &'a [String]>) -> Option<f64> {
+    let scores: Vec<f64> = heroes.into_iter().map(hero_scaling).collect();
+    if scores.is_empty() {
+        return None;
+    }
+    Some(scores.iter().sum::<f64>() / scores.len() as f64)
+}
+
+/// How much lineup scaling counts at a game time, from 0 early to 1 late
+pub fn late_game_weight(game_time_min: f64) -> f64 {
+    ((game_time_min - LATE_GAME_START_MIN) / (LATE_GAME_FULL_MIN - LATE_GAME_START_MIN))
+        .clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roles(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_carry_lineup_outscales_tempo_lineup() {
+        let carry = roles(&["Carry", "Escape"]);
+        let support = roles(&["Support", "Disabler"]);
+        let pusher = roles(&["Pusher", "Nuker"]);
+
+        let hard_carry = team_scaling([&carry[..], &carry[..], &support[..]]).unwrap();
+        let tempo = team_scaling([&pusher[..], &pusher[..], &support[..]]).unwrap();
+        assert!(hard_carry > tempo);
+        assert_eq!(team_scaling(std::iter::empty()), None);
+
+        assert_eq!(late_game_weight(15.0), 0.0);
+        assert_eq!(late_game_weight(30.0), 0.5);
+        assert_eq!(late_game_weight(55.0), 1.0);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::prior::sigmoid;
+use super::{Contribution, MatchFeatures, Prediction, ProbabilityModel, DEFAULT_LOGIT_STD_ERROR};
+
+/// Intercept and per-feature weights of a logistic regression
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Coefficients {
+    /// Bias term
+    #[serde(default)]
+    pub intercept: f64,
+
+    /// Map of feature name -> coefficient
+    pub weights: HashMap<String, f64>,
+
+    /// Coefficient covariance matrix (feature names plus `intercept`),
+    /// e.g. statsmodels `cov_params()`, used for delta-method intervals
+    #[serde(default)]
+    pub covariance: HashMap<String, HashMap<String, f64>>,
+}
+
+impl Coefficients {
+    /// Linear predictor (log-odds) for the given features
+    fn logit(&self, features: &MatchFeatures) -> f64 {
+        self.weights
+            .iter()
+            .map(|(name, weight)| weight * features.get(name).unwrap_or(0.0))
+            .sum::<f64>()
+            + self.intercept
+    }
+
+    /// Standard error of the log-odds via the delta method (xᵀ Σ x)
+    fn logit_std_error(&self, features: &MatchFeatures) -> f64 {
+        if self.covariance.is_empty() {
+            return DEFAULT_LOGIT_STD_ERROR;
+        }
+
+        let value = |name: &str| {
+            if name == "intercept" {
+                1.0
+            } else {
+                features.get(name).unwrap_or(0.0)
+            }
+        };
+
+        let variance: f64 = self
+            .covariance
+            .iter()
+            .flat_map(|(row, cols)| {
+                cols.iter()
+                    .map(move |(col, cov)| value(row) * value(col) * cov)
+            })
+            .sum();
+
+        variance.max(0.0).sqrt()
+    }
+}
+
+/// Logistic regression over named match features
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogisticModel {
+    /// Version tag recorded with each signal
+    pub version: String,
+
+    /// Coefficients used when no patch-specific set applies
+    #[serde(flatten)]
+    pub base: Coefficients,
+
+    /// Map of OpenDota patch ID -> coefficients fitted on that patch
+    #[serde(default)]
+    pub patches: HashMap<i32, Coefficients>,
+}
+
+impl LogisticModel {
+    /// Load coefficients from a JSON file
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).context("Failed to read model file")?;
+
+        let model: LogisticModel =
+            serde_json::from_str(&content).context("Failed to parse model JSON")?;
+
+        Ok(model)
+    }
+
+    /// Select coefficients for a patch
+    ///
+    /// Uses the exact patch if fitted, otherwise the most recent earlier patch
+    /// (a new patch plays closest to the one before it), otherwise the base set.
+    pub fn coefficients_for(&self, patch: Option<i32>) -> &Coefficients {
+        let Some(patch) = patch else {
+            return &self.base;
+        };
+
+        self.patches
+            .iter()
+            .filter(|(id, _)| **id <= patch)
+            .max_by_key(|(id, _)| **id)
+            .map(|(_, coefficients)| coefficients)
+            .unwrap_or(&self.base)
+    }
+}
+
+impl Default for LogisticModel {
+    /// Hand-tuned baseline used when no model file is configured
+    fn default() -> Self {
+        let weights = [
+            ("gold_lead_k", 0.15),
+            ("kill_diff", 0.03),
+            ("tower_diff", 0.15),
+            ("barracks_diff", 0.35),
+            ("kill_rate_diff", 0.05),
+            ("gold_velocity_k", 0.1),
+            ("high_ground_numbers", 0.25),
+            ("aegis", 0.3),
+            ("late_game_scaling", 0.8),
+            ("performance_diff", 1.0),
+        ]
+        .into_iter()
+        .map(|(name, weight)| (name.to_string(), weight))
+        .collect();
+
+        Self {
+            version: "baseline-v6".to_string(),
+            base: Coefficients {
+                intercept: 0.0,
+                weights,
+                covariance: HashMap::new(),
+            },
+            patches: HashMap::new(),
+        }
+    }
+}
+
+impl ProbabilityModel for LogisticModel {
+    fn predict(&self, features: &MatchFeatures) -> Result<Prediction> {
+        let coefficients = self.coefficients_for(features.patch);
+
+        Ok(Prediction::from_logit(
+            coefficients.logit(features),
+            coefficients.logit_std_error(features),
+        ))
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Probability with every term minus probability with that feature's
+    /// term left out
+    fn contributions(&self, features: &MatchFeatures) -> Vec<Contribution> {
+        let coefficients = self.coefficients_for(features.patch);
+        let logit = coefficients.logit(features);
+        let probability = sigmoid(logit);
+
+        coefficients
+            .weights
+            .iter()
+            .filter_map(|(name, weight)| {
+                let term = weight * features.get(name)?;
+                (term != 0.0).then(|| Contribution {
+                    feature: name.clone(),
+                    probability: probability - sigmoid(logit - term),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::prior::logit;
+
+    #[test]
+    fn test_even_game_is_coin_flip() {
+        let model = LogisticModel::default();
+        let p = model.predict(&MatchFeatures::default()).unwrap();
+        assert!((p.probability - 0.5).abs() < 1e-9);
+        assert!(p.lower < 0.5 && p.upper > 0.5);
+    }
+
+    #[test]
+    fn test_gold_lead_favours_radiant() {
+        let model = LogisticModel::default();
+        let features = MatchFeatures {
+            gold_lead_k: 10.0,
+            ..Default::default()
+        };
+        assert!(model.predict(&features).unwrap().probability > 0.75);
+
+        let features = MatchFeatures {
+            gold_lead_k: -10.0,
+            ..Default::default()
+        };
+        assert!(model.predict(&features).unwrap().probability < 0.25);
+    }
+
+    #[test]
+    fn test_contributions_follow_feature_sign() {
+        let model = LogisticModel::default();
+        let features = MatchFeatures {
+            gold_lead_k: 8.0,
+            tower_diff: -2.0,
+            ..Default::default()
+        };
+        let contributions = model.contributions(&features);
+        let of = |name: &str| {
+            contributions
+                .iter()
+                .find(|c| c.feature == name)
+                .map(|c| c.probability)
+        };
+        assert!(of("gold_lead_k").unwrap() > 0.0);
+        assert!(of("tower_diff").unwrap() < 0.0);
+        assert_eq!(of("aegis"), None);
+    }
+
+    #[test]
+    fn test_patch_coefficient_selection() {
+        let json = r#"{
+            "version": "test",
+            "intercept": 0.0,
+            "weights": {"gold_lead_k": 0.1},
+            "patches": {
+                "54": {"intercept": 0.5, "weights": {}},
+                "56": {"intercept": 1.0, "weights": {}}
+            }
+        }"#;
+        let model: LogisticModel = serde_json::from_str(json).unwrap();
+
+        assert_eq!(model.coefficients_for(None).intercept, 0.0);
+        assert_eq!(model.coefficients_for(Some(53)).intercept, 0.0);
+        assert_eq!(model.coefficients_for(Some(54)).intercept, 0.5);
+        assert_eq!(model.coefficients_for(Some(55)).intercept, 0.5);
+        assert_eq!(model.coefficients_for(Some(57)).intercept, 1.0);
+    }
+
+    #[test]
+    fn test_covariance_interval_widens_with_feature_magnitude() {
+        let json = r#"{
+            "version": "test",
+            "weights": {"gold_lead_k": 0.1},
+            "covariance": {
+                "intercept": {"intercept": 0.01},
+                "gold_lead_k": {"gold_lead_k": 0.0004}
+            }
+        }"#;
+        let model: LogisticModel = serde_json::from_str(json).unwrap();
+
+        let narrow = model.predict(&MatchFeatures::default()).unwrap();
+        let wide = model
+            .predict(&MatchFeatures {
+                gold_lead_k: 20.0,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let logit_width = |p: Prediction| logit(p.upper) - logit(p.lower);
+        assert!(logit_width(wide) > logit_width(narrow));
+    }
+}
@@ -0,0 +1,143 @@
+pub mod duration;
+pub mod features;
+pub mod lineup;
+pub mod logistic;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+pub mod performance;
+pub mod prior;
+pub mod reason;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+pub use duration::{DurationEstimate, DurationModel};
+pub use features::MatchFeatures;
+pub use logistic::LogisticModel;
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxModel;
+pub use prior::PreGamePrior;
+pub use reason::{generate_reason, Contribution};
+
+/// Standard normal quantile for the two-sided 90% intervals stored with signals
+pub const INTERVAL_Z: f64 = 1.645;
+
+/// Log-odds standard error assumed when a model can't estimate its own
+pub const DEFAULT_LOGIT_STD_ERROR: f64 = 0.35;
+
+/// Least the side with mega creeps is given, whatever the features say
+///
+/// Comebacks against mega creeps are rare enough that markets lagging at
+/// 85–90% are underpricing a ~98% position.
+pub const MEGA_CREEPS_PREDICTION: Prediction = Prediction {
+    probability: 0.98,
+    lower: 0.95,
+    upper: 0.995,
+};
+
+/// Least the side in a terminal state is given: its enemy's throne is
+/// exposed with no barracks or heroes left to defend it
+pub const TERMINAL_STATE_PREDICTION: Prediction = Prediction {
+    probability: 0.995,
+    lower: 0.98,
+    upper: 0.999,
+};
+
+/// Win probability with a confidence interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Prediction {
+    /// Point estimate (0.0 - 1.0)
+    pub probability: f64,
+
+    /// Lower bound of the interval
+    pub lower: f64,
+
+    /// Upper bound of the interval
+    pub upper: f64,
+}
+
+impl Prediction {
+    /// Interval of `INTERVAL_Z` standard errors around a log-odds estimate
+    pub fn from_logit(logit: f64, std_error: f64) -> Self {
+        let margin = INTERVAL_Z * std_error.abs();
+
+        Self {
+            probability: prior::sigmoid(logit),
+            lower: prior::sigmoid(logit - margin),
+            upper: prior::sigmoid(logit + margin),
+        }
+    }
+
+    /// Same prediction from the opposing team's point of view
+    pub fn flip(self) -> Self {
+        Self {
+            probability: 1.0 - self.probability,
+            lower: 1.0 - self.upper,
+            upper: 1.0 - self.lower,
+        }
+    }
+
+    /// Raise the estimate and each bound to at least `floor`'s
+    pub fn at_least(self, floor: Prediction) -> Self {
+        Self {
+            probability: self.probability.max(floor.probability),
+            lower: self.lower.max(floor.lower),
+            upper: self.upper.max(floor.upper),
+        }
+    }
+
+    /// Apply a monotonically increasing transform to the estimate and bounds
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Self {
+            probability: f(self.probability),
+            lower: f(self.lower),
+            upper: f(self.upper),
+        }
+    }
+}
+
+/// Estimates win probability from live match features
+pub trait ProbabilityModel: Send + Sync {
+    /// Probability that Radiant wins, with a confidence interval
+    fn predict(&self, features: &MatchFeatures) -> Result<Prediction>;
+
+    /// Version tag recorded with each signal
+    fn version(&self) -> &str;
+
+    /// Change in Radiant win probability from each feature; empty when
+    /// the model can't attribute its output
+    fn contributions(&self, _features: &MatchFeatures) -> Vec<Contribution> {
+        Vec::new()
+    }
+}
+
+/// Currently active model, swapped in place when the model file changes
+pub type SharedModel = Arc<RwLock<Arc<dyn ProbabilityModel>>>;
+
+/// Load a model from disk, choosing the implementation by file extension
+///
+/// `.onnx` files require the `onnx` feature; anything else is parsed as
+/// logistic regression coefficients in JSON.
+pub fn load_model(path: &Path) -> Result<Box<dyn ProbabilityModel>> {
+    let is_onnx = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("onnx"))
+        .unwrap_or(false);
+
+    if is_onnx {
+        #[cfg(feature = "onnx")]
+        return Ok(Box::new(OnnxModel::load_from_file(path)?));
+
+        #[cfg(not(feature = "onnx"))]
+        anyhow::bail!(
+            "{} is an ONNX model but esport-signal was built without the `onnx` feature",
+            path.display()
+        );
+    }
+
+    Ok(Box::new(LogisticModel::load_from_file(path)?))
+}
@@ -0,0 +1,131 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use ort::session::Session;
+use ort::value::Tensor;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use super::features::FEATURE_NAMES;
+use super::{prior, MatchFeatures, Prediction, ProbabilityModel, DEFAULT_LOGIT_STD_ERROR};
+
+/// Preferred output name for scikit-learn/LightGBM exports (`zipmap=False`)
+const PROBABILITIES_OUTPUT: &str = "probabilities";
+
+/// Hex digits of the file's SHA-256 kept in the model version
+const VERSION_HASH_LEN: usize = 12;
+
+/// ONNX Runtime-backed model for classifiers trained in Python
+///
+/// Expects a single float input of shape `[1, FEATURE_NAMES.len()]` and either
+/// a `probabilities` output of shape `[1, 2]` (class 1 = Radiant win) or a
+/// single sigmoid output of shape `[1, 1]`.
+pub struct OnnxModel {
+    session: Mutex<Session>,
+    version: String,
+}
+
+impl OnnxModel {
+    /// Load an ONNX model from disk
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let session = Session::builder()
+            .and_then(|builder| builder.commit_from_file(path))
+            .map_err(|e| anyhow!("{}", e))
+            .context("Failed to load ONNX model")?;
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read ONNX model {}", path.display()))?;
+
+        let version = model_version(path, &bytes);
+
+        info!(
+            "Loaded ONNX model {} (inputs: {:?}, outputs: {:?})",
+            version,
+            session.inputs.iter().map(|i| &i.name).collect::<Vec<_>>(),
+            session.outputs.iter().map(|o| &o.name).collect::<Vec<_>>()
+        );
+
+        Ok(Self {
+            session: Mutex::new(session),
+            version,
+        })
+    }
+}
+
+impl ProbabilityModel for OnnxModel {
+    fn predict(&self, features: &MatchFeatures) -> Result<Prediction> {
+        let input = Tensor::from_array(([1usize, FEATURE_NAMES.len()], features.to_vec()))
+            .map_err(|e| anyhow!("Failed to build input tensor: {}", e))?;
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| anyhow!("ONNX session lock poisoned"))?;
+
+        let outputs = session
+            .run(ort::inputs![input])
+            .map_err(|e| anyhow!("ONNX inference failed: {}", e))?;
+
+        let value = outputs
+            .get(PROBABILITIES_OUTPUT)
+            .or_else(|| {
+                outputs
+                    .keys()
+                    .filter_map(|name| outputs.get(name))
+                    .find(|v| v.try_extract_tensor::<f32>().is_ok())
+            })
+            .ok_or_else(|| anyhow!("ONNX model has no float output"))?;
+
+        let (_, data) = value
+            .try_extract_tensor::<f32>()
+            .map_err(|e| anyhow!("Failed to read ONNX output: {}", e))?;
+
+        // [p_dire, p_radiant] for two-class outputs, otherwise a single sigmoid
+        let probability = match data {
+            [_, radiant, ..] => *radiant,
+            [single] => *single,
+            [] => anyhow::bail!("ONNX model returned an empty output"),
+        };
+
+        // Exported graphs carry no uncertainty estimate, so use a fixed log-odds error
+        Ok(Prediction::from_logit(
+            prior::logit(probability as f64),
+            DEFAULT_LOGIT_STD_ERROR,
+        ))
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+/// `onnx:<file stem>@<content hash>`, so retraining into the same file
+/// still changes the version stored with signals
+fn model_version(path: &Path, bytes: &[u8]) -> String {
+    let digest: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!(
+        "onnx:{}@{}",
+        path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        &digest[..VERSION_HASH_LEN]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_tracks_file_contents() {
+        let path = Path::new("models/win_prob.onnx");
+        let version = model_version(path, b"first export");
+        assert!(version.starts_with("onnx:win_prob@"));
+        assert_eq!(version.len(), "onnx:win_prob@".len() + VERSION_HASH_LEN);
+        assert_eq!(version, model_version(path, b"first export"));
+        assert_ne!(version, model_version(path, b"retrained export"));
+    }
+}
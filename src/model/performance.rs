@@ -0,0 +1,65 @@
+//! How far players are playing above or below their usual level
+//!
+//! A carry farming at 130% of their recent average GPM is worth more than
+//! the gold lead alone shows, since it hints at a draft or matchup working
+//! in their favour. Averages come from OpenDota recent matches.
+
+use crate::models::PlayerState;
+
+/// Relative deviation of one player's live GPM and XPM from their recent
+/// averages; 0.25 means 25% above their usual farm
+pub fn player_deviation(player: &PlayerState) -> Option<f64> {
+    let ratio = |live: Option<f64>, avg: Option<f64>| match (live, avg) {
+        (Some(live), Some(avg)) if avg > 0.0 => Some(live / avg - 1.0),
+        _ => None,
+    };
+
+    let deviations: Vec<f64> = [
+        ratio(player.gold_per_min, player.avg_gold_per_min),
+        ratio(player.xp_per_min, player.avg_xp_per_min),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    (!deviations.is_empty()).then(|| deviations.iter().sum::<f64>() / deviations.len() as f64)
+}
+
+/// Mean deviation of a team's players; `None` until any player has both
+/// live and historical figures
+pub fn team_deviation(players: &[PlayerState]) -> Option<f64> {
+    let deviations: Vec<f64> = players.iter().filter_map(player_deviation).collect();
+    if deviations.is_empty() {
+        return None;
+    }
+    Some(deviations.iter().sum::<f64>() / deviations.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(gpm: f64, avg_gpm: f64, xpm: Option<f64>, avg_xpm: f64) -> PlayerState {
+        PlayerState {
+            gold_per_min: Some(gpm),
+            avg_gold_per_min: Some(avg_gpm),
+            xp_per_min: xpm,
+            avg_xp_per_min: Some(avg_xpm),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_team_deviation() {
+        let hot = player(650.0, 500.0, Some(720.0), 600.0);
+        assert!((player_deviation(&hot).unwrap() - 0.25).abs() < 1e-9);
+
+        // Only GPM known
+        let cold = player(400.0, 500.0, None, 600.0);
+        assert!((player_deviation(&cold).unwrap() + 0.2).abs() < 1e-9);
+
+        let team = team_deviation(&[hot, cold, PlayerState::default()]).unwrap();
+        assert!((team - 0.025).abs() < 1e-9);
+        assert_eq!(team_deviation(&[PlayerState::default()]), None);
+    }
+}
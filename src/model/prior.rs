@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ratings::rating_confidence;
+
+/// Weight of the market opening price in the prior blend
+const MARKET_PRIOR_WEIGHT: f64 = 0.7;
+
+/// Weight of Elo in the prior blend
+const ELO_PRIOR_WEIGHT: f64 = 0.3;
+
+/// Weight of recent form in the prior blend
+const FORM_PRIOR_WEIGHT: f64 = 0.15;
+
+/// Share of the Elo and form log-odds kept per stand-in playing
+///
+/// Both are built from the regular roster's results, so they say less
+/// about a lineup that isn't it.
+const STAND_IN_HISTORY_WEIGHT: f64 = 0.7;
+
+/// Probabilities are clamped away from 0/1 before taking log-odds
+const PROBABILITY_EPSILON: f64 = 1e-6;
+
+/// Pre-game beliefs about team A winning, before any live evidence
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PreGamePrior {
+    /// Elo expected score for team A
+    pub elo: Option<f64>,
+
+    /// Market price for team A when the market was first seen
+    pub market_open: Option<f64>,
+
+    /// Team A win probability from both teams' recent form
+    #[serde(default)]
+    pub form: Option<f64>,
+
+    /// Combined Glicko-2 rating deviation of both teams, high for new or
+    /// long-idle teams
+    #[serde(default)]
+    pub rating_deviation: Option<f64>,
+}
+
+impl PreGamePrior {
+    /// Combined prior probability for team A (0.5 when nothing is known)
+    ///
+    /// The opening price already reflects public information and is weighted
+    /// above Elo, with recent form below both; the available estimates are
+    /// blended in log-odds space with their weights renormalized.
+    pub fn probability(&self) -> f64 {
+        let estimates = [
+            (self.market_open, MARKET_PRIOR_WEIGHT),
+            (self.elo, ELO_PRIOR_WEIGHT),
+            (self.form, FORM_PRIOR_WEIGHT),
+        ];
+
+        let (sum, total_weight) = estimates
+            .iter()
+            .filter_map(|(p, weight)| p.map(|p| (logit(p) * weight, weight)))
+            .fold((0.0, 0.0), |(sum, total), (x, weight)| {
+                (sum + x, total + weight)
+            });
+
+        if total_weight > 0.0 {
+            sigmoid(sum / total_weight)
+        } else {
+            0.5
+        }
+    }
+
+    /// How far history-based estimates can be trusted, from 1 for settled
+    /// teams down for new or idle ones; 1 without a deviation
+    pub fn confidence(&self) -> f64 {
+        self.rating_deviation.map(rating_confidence).unwrap_or(1.0)
+    }
+
+    /// Shrink Elo and form toward a coin flip for stand-ins in the match
+    pub fn with_stand_ins(self, stand_ins: i32) -> Self {
+        let weight = STAND_IN_HISTORY_WEIGHT.powi(stand_ins.max(0));
+        let shrink = |p: Option<f64>| p.map(|p| sigmoid(weight * logit(p)));
+
+        Self {
+            elo: shrink(self.elo),
+            form: shrink(self.form),
+            ..self
+        }
+    }
+}
+
+/// Update a prior with live-state evidence
+///
+/// The live model is trained without team identity, so its log-odds are
+/// treated as pure evidence and added to the prior's log-odds. The prior is
+/// discounted with a half-life in game minutes: a stronger team's edge shows
+/// up in the live state as the game goes on, and counting it in both terms
+/// would overstate the favourite.
+pub fn combine(prior: f64, live: f64, game_time_min: f64, half_life_min: f64) -> f64 {
+    let prior_weight = if half_life_min > 0.0 {
+        0.5f64.powf(game_time_min.max(0.0) / half_life_min)
+    } else {
+        0.0
+    };
+
+    sigmoid(prior_weight * logit(prior) + logit(live))
+}
+
+/// Log-odds of a probability
+pub fn logit(p: f64) -> f64 {
+    let p = p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+    (p / (1.0 - p)).ln()
+}
+
+/// Inverse of `logit`
+pub fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prior_defaults_to_coin_flip() {
+        assert_eq!(PreGamePrior::default().probability(), 0.5);
+    }
+
+    #[test]
+    fn test_form_moves_prior_less_than_elo() {
+        let market = PreGamePrior {
+            market_open: Some(0.6),
+            ..Default::default()
+        };
+        assert!((market.probability() - 0.6).abs() < 1e-9);
+
+        let with_form = PreGamePrior {
+            form: Some(0.8),
+            ..market
+        };
+        let with_elo = PreGamePrior {
+            elo: Some(0.8),
+            ..market
+        };
+        assert!(with_form.probability() > 0.6);
+        assert!(with_form.probability() < with_elo.probability());
+
+        // A stand-in weakens Elo but not the market price
+        let stand_in = with_elo.with_stand_ins(1);
+        assert!(stand_in.elo.unwrap() < 0.8);
+        assert_eq!(stand_in.market_open, Some(0.6));
+        assert_eq!(with_elo.with_stand_ins(0).elo, Some(0.8));
+    }
+
+    #[test]
+    fn test_prior_dominates_at_start() {
+        let p = combine(0.7, 0.5, 0.0, 15.0);
+        assert!((p - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_prior_fades_with_game_time() {
+        let early = combine(0.7, 0.5, 5.0, 15.0);
+        let late = combine(0.7, 0.5, 45.0, 15.0);
+        assert!(early > late);
+        assert!((late - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_prior_and_evidence_compound() {
+        let p = combine(0.6, 0.6, 0.0, 15.0);
+        assert!(p > 0.6);
+    }
+}
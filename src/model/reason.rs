@@ -0,0 +1,101 @@
+//! Human-readable reasons built from per-feature contributions
+
+use serde::{Deserialize, Serialize};
+
+/// Most factors listed in a reason
+const MAX_REASON_FACTORS: usize = 3;
+
+/// Smallest contribution listed, in probability
+const MIN_REASON_CONTRIBUTION: f64 = 0.005;
+
+/// Change in win probability attributed to one feature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contribution {
+    /// Feature name, as in `MatchFeatures::get`
+    pub feature: String,
+
+    /// Win probability with the feature minus without it
+    pub probability: f64,
+}
+
+impl Contribution {
+    /// Same contribution from the opposing team's point of view
+    pub fn flip(self) -> Self {
+        Self {
+            probability: -self.probability,
+            ..self
+        }
+    }
+}
+
+/// Short label for a feature in reasons
+pub fn feature_label(feature: &str) -> &str {
+    match feature {
+        "gold_lead_k" => "gold lead",
+        "kill_diff" => "kills",
+        "tower_diff" => "towers",
+        "barracks_diff" => "barracks",
+        "game_time_min" => "game time",
+        "kill_rate_diff" => "kill momentum",
+        "gold_velocity_k" => "gold momentum",
+        "high_ground_numbers" => "numbers on high ground",
+        "aegis" => "aegis",
+        "late_game_scaling" => "draft",
+        "performance_diff" => "player form",
+        other => other,
+    }
+}
+
+/// Largest contributions as e.g. "+6% gold lead, +3% towers, −2% draft";
+/// `None` when nothing moves the estimate noticeably
+pub fn generate_reason(contributions: &[Contribution]) -> Option<String> {
+    let mut factors: Vec<&Contribution> = contributions
+        .iter()
+        .filter(|c| c.probability.abs() >= MIN_REASON_CONTRIBUTION)
+        .collect();
+    factors.sort_by(|a, b| b.probability.abs().total_cmp(&a.probability.abs()));
+
+    let parts: Vec<String> = factors
+        .into_iter()
+        .take(MAX_REASON_FACTORS)
+        .map(|c| {
+            let sign = if c.probability >= 0.0 { '+' } else { '−' };
+            format!(
+                "{}{:.0}% {}",
+                sign,
+                c.probability.abs() * 100.0,
+                feature_label(&c.feature)
+            )
+        })
+        .collect();
+
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contribution(feature: &str, probability: f64) -> Contribution {
+        Contribution {
+            feature: feature.to_string(),
+            probability,
+        }
+    }
+
+    #[test]
+    fn test_reason_lists_largest_factors() {
+        let contributions = [
+            contribution("tower_diff", 0.03),
+            contribution("aegis", 0.001),
+            contribution("late_game_scaling", -0.02),
+            contribution("gold_lead_k", 0.06),
+            contribution("kill_diff", 0.01),
+        ];
+        assert_eq!(
+            generate_reason(&contributions).as_deref(),
+            Some("+6% gold lead, +3% towers, −2% draft")
+        );
+        assert_eq!(generate_reason(&[contribution("aegis", 0.001)]), None);
+    }
+}
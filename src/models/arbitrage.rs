@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Cross-venue arbitrage: buy one team on Polymarket, back the other on Betfair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbitrageSignal {
+    /// Unique arbitrage identifier
+    pub id: Option<i64>,
+
+    /// Polymarket condition_id
+    pub market_condition_id: String,
+
+    /// Betfair market ID
+    pub betfair_market_id: String,
+
+    /// Team bought on Polymarket
+    pub polymarket_team: String,
+
+    /// All-in cost per Polymarket share (quote + fee + slippage)
+    pub polymarket_cost: f64,
+
+    /// Team backed on Betfair
+    pub betfair_team: String,
+
+    /// Decimal odds backed on Betfair
+    pub betfair_back_odds: f64,
+
+    /// Stake available at those odds
+    pub betfair_available: f64,
+
+    /// Betfair stake per $1 of guaranteed payout
+    pub betfair_stake: f64,
+
+    /// Guaranteed profit per $1 of payout after fees and commission
+    pub profit: f64,
+
+    /// When the arbitrage was detected
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,302 @@
+//! In-game event detection between two polls of a match
+//!
+//! Each `SignalDetector` looks at one poll's state change and either names
+//! the event it recognises or passes. A `DetectorChain` asks its detectors
+//! in priority order and takes the first answer, falling back to a plain
+//! `Snapshot`, so a new event is a detector added to the chain rather than
+//! another branch in a shared match.
+
+use std::sync::Arc;
+
+use super::{Deaths, LiveMatchState, SignalType, Teamfight};
+
+/// Gold lead change within one update that counts as a swing
+pub const GOLD_SWING_THRESHOLD: i64 = 2000;
+
+/// Combined kills within one update that count as a teamfight
+pub const TEAMFIGHT_KILLS: i32 = 3;
+
+/// A match's state at this poll and the previous one
+#[derive(Debug, Clone, Copy)]
+pub struct StateDiff<'a> {
+    pub previous: &'a LiveMatchState,
+    pub current: &'a LiveMatchState,
+    /// Fight still taking kills, counted over the last few polls
+    pub teamfight: Option<&'a Teamfight>,
+    /// Heroes estimated to be waiting to respawn
+    pub deaths: Deaths,
+}
+
+impl StateDiff<'_> {
+    /// Growth of a per-state count since the previous poll
+    pub fn delta(&self, count: impl Fn(&LiveMatchState) -> i64) -> i64 {
+        count(self.current) - count(self.previous)
+    }
+}
+
+/// Recognises one kind of in-game event
+pub trait SignalDetector: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// The event this poll shows, if it's this detector's kind
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType>;
+}
+
+/// One side is a push from winning while its market may still lag;
+/// reported on every poll in that state, not only the first
+pub struct TerminalStateDetector;
+
+impl SignalDetector for TerminalStateDetector {
+    fn name(&self) -> &str {
+        "terminal_state"
+    }
+
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType> {
+        diff.current
+            .terminal_state(diff.deaths)
+            .map(|_| SignalType::TerminalState)
+    }
+}
+
+/// One side lost its last barracks in this poll
+pub struct MegaCreepsDetector;
+
+impl SignalDetector for MegaCreepsDetector {
+    fn name(&self) -> &str {
+        "mega_creeps"
+    }
+
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType> {
+        (diff.current.mega_creeps().is_some() && diff.previous.mega_creeps().is_none())
+            .then_some(SignalType::MegaCreeps)
+    }
+}
+
+/// Any barracks fell
+pub struct BarracksDetector;
+
+impl SignalDetector for BarracksDetector {
+    fn name(&self) -> &str {
+        "barracks"
+    }
+
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType> {
+        let barracks =
+            |s: &LiveMatchState| (s.radiant.barracks_killed + s.dire.barracks_killed).into();
+        (diff.delta(barracks) > 0).then_some(SignalType::BarracksKill)
+    }
+}
+
+/// Any tower fell
+pub struct TowerDetector;
+
+impl SignalDetector for TowerDetector {
+    fn name(&self) -> &str {
+        "tower"
+    }
+
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType> {
+        let towers = |s: &LiveMatchState| (s.radiant.towers_killed + s.dire.towers_killed).into();
+        (diff.delta(towers) > 0).then_some(SignalType::TowerKill)
+    }
+}
+
+/// `TEAMFIGHT_KILLS` kills in one poll, or a fight spread over several
+pub struct TeamfightDetector;
+
+impl SignalDetector for TeamfightDetector {
+    fn name(&self) -> &str {
+        "teamfight"
+    }
+
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType> {
+        let kills = |s: &LiveMatchState| (s.radiant.kills + s.dire.kills).into();
+        (diff.delta(kills) >= TEAMFIGHT_KILLS.into() || diff.teamfight.is_some())
+            .then_some(SignalType::Teamfight)
+    }
+}
+
+/// The gold lead moved by `GOLD_SWING_THRESHOLD` either way
+pub struct GoldSwingDetector;
+
+impl SignalDetector for GoldSwingDetector {
+    fn name(&self) -> &str {
+        "gold_swing"
+    }
+
+    fn detect(&self, diff: &StateDiff<'_>) -> Option<SignalType> {
+        (diff.delta(|s| s.gold_lead).abs() >= GOLD_SWING_THRESHOLD).then_some(SignalType::GoldSwing)
+    }
+}
+
+/// Detectors asked in priority order; the first event found wins
+#[derive(Clone)]
+pub struct DetectorChain {
+    detectors: Vec<Arc<dyn SignalDetector>>,
+}
+
+impl DetectorChain {
+    /// A chain with no detectors, which only ever reports `Snapshot`
+    pub fn empty() -> Self {
+        Self {
+            detectors: Vec::new(),
+        }
+    }
+
+    /// Add a detector below every detector already in the chain
+    pub fn with_detector(mut self, detector: impl SignalDetector + 'static) -> Self {
+        self.detectors.push(Arc::new(detector));
+        self
+    }
+
+    /// Names of the detectors, highest priority first
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.detectors.iter().map(|detector| detector.name())
+    }
+
+    /// Most significant event between two states of a match; a first
+    /// sighting has nothing to diff and is a `Snapshot`
+    pub fn detect(
+        &self,
+        previous: Option<&LiveMatchState>,
+        current: &LiveMatchState,
+        teamfight: Option<&Teamfight>,
+        deaths: Deaths,
+    ) -> SignalType {
+        let Some(previous) = previous else {
+            return SignalType::Snapshot;
+        };
+
+        let diff = StateDiff {
+            previous,
+            current,
+            teamfight,
+            deaths,
+        };
+        self.detectors
+            .iter()
+            .find_map(|detector| detector.detect(&diff))
+            .unwrap_or(SignalType::Snapshot)
+    }
+}
+
+/// The end of the game first, then objectives, fights and gold
+impl Default for DetectorChain {
+    fn default() -> Self {
+        Self::empty()
+            .with_detector(TerminalStateDetector)
+            .with_detector(MegaCreepsDetector)
+            .with_detector(BarracksDetector)
+            .with_detector(TowerDetector)
+            .with_detector(TeamfightDetector)
+            .with_detector(GoldSwingDetector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_type_detection() {
+        let chain = DetectorChain::default();
+        let alive = Deaths::default();
+        let previous = LiveMatchState {
+            match_id: 1,
+            game_time: 600,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            chain.detect(None, &previous, None, alive),
+            SignalType::Snapshot
+        );
+        assert_eq!(
+            chain.detect(Some(&previous), &previous, None, alive),
+            SignalType::Snapshot
+        );
+
+        let mut current = previous.clone();
+        current.gold_lead = -2500;
+        assert_eq!(
+            chain.detect(Some(&previous), &current, None, alive),
+            SignalType::GoldSwing
+        );
+
+        // A fight spread over several polls outranks the swing
+        let fight = Teamfight {
+            radiant_kills: 2,
+            dire_kills: 1,
+        };
+        assert_eq!(
+            chain.detect(Some(&previous), &current, Some(&fight), alive),
+            SignalType::Teamfight
+        );
+
+        current.radiant.kills = 2;
+        current.dire.kills = 1;
+        assert_eq!(
+            chain.detect(Some(&previous), &current, None, alive),
+            SignalType::Teamfight
+        );
+
+        // Objectives outrank kills and gold in the same update
+        current.dire.barracks_killed = 1;
+        assert_eq!(
+            chain.detect(Some(&previous), &current, None, alive),
+            SignalType::BarracksKill
+        );
+
+        let mut previous = current.clone();
+        previous.dire.barracks_killed = 5;
+        current.dire.barracks_killed = 6;
+        assert_eq!(
+            chain.detect(Some(&previous), &current, None, alive),
+            SignalType::MegaCreeps
+        );
+        // Only the update that completes the set
+        assert_eq!(
+            chain.detect(Some(&current), &current, None, alive),
+            SignalType::Snapshot
+        );
+
+        // With every tower down too, mega creeps end the game; it's
+        // reported for as long as the state holds
+        current.dire.towers_killed = LiveMatchState::TOWERS_PER_TEAM;
+        assert_eq!(
+            chain.detect(Some(&current), &current, None, alive),
+            SignalType::TerminalState
+        );
+
+        // An exposed throne with barracks standing needs its defenders dead
+        current.dire.barracks_killed = 2;
+        assert_eq!(
+            chain.detect(Some(&current), &current, None, alive),
+            SignalType::Snapshot
+        );
+        let wiped = Deaths {
+            radiant: 5,
+            dire: 0,
+        };
+        assert_eq!(
+            chain.detect(Some(&current), &current, None, wiped),
+            SignalType::TerminalState
+        );
+        // Dire's own wipe doesn't help it finish Radiant's throne
+        let wiped = Deaths {
+            radiant: 0,
+            dire: 5,
+        };
+        assert_eq!(
+            chain.detect(Some(&current), &current, None, wiped),
+            SignalType::Snapshot
+        );
+
+        // Without detectors every poll is a snapshot
+        assert_eq!(
+            DetectorChain::empty().detect(Some(&previous), &current, None, alive),
+            SignalType::Snapshot
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why a previously signaled edge should be closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    /// Net edge on the entry side fell to (or below) the exit threshold
+    Collapsed,
+    /// Model now favours the opposite side of the market
+    Reversed,
+}
+
+impl ExitReason {
+    /// Name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::Collapsed => "Collapsed",
+            ExitReason::Reversed => "Reversed",
+        }
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExitReason {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Collapsed" => Ok(ExitReason::Collapsed),
+            "Reversed" => Ok(ExitReason::Reversed),
+            other => anyhow::bail!("Unknown exit reason: {}", other),
+        }
+    }
+}
+
+/// Signal to close a position opened on an earlier signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitSignal {
+    /// Unique exit signal identifier
+    pub id: Option<i64>,
+
+    /// ID of the signal that opened the edge
+    pub entry_signal_id: i64,
+
+    /// ID of the signal that triggered the exit
+    pub trigger_signal_id: i64,
+
+    /// Polymarket condition_id
+    pub market_condition_id: String,
+
+    /// Match ID from OpenDota
+    pub match_id: i64,
+
+    /// Why the edge is considered gone
+    pub reason: ExitReason,
+
+    /// Net edge at entry (positive = team A side)
+    pub entry_net_edge: f64,
+
+    /// Net edge now (same sign convention)
+    pub current_net_edge: f64,
+
+    /// Market odds for team A at exit time
+    pub market_team_a_odds: f64,
+
+    /// When the exit was generated
+    pub created_at: DateTime<Utc>,
+}
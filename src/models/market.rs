@@ -1,12 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-/// Represents a Polymarket betting market for a Dota 2 match
+/// Prediction market a contract trades on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Venue {
+    #[default]
+    Polymarket,
+    Kalshi,
+    Azuro,
+}
+
+impl Venue {
+    /// Name stored in `signals.venue`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Venue::Polymarket => "Polymarket",
+            Venue::Kalshi => "Kalshi",
+            Venue::Azuro => "Azuro",
+        }
+    }
+}
+
+impl fmt::Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Venue {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Polymarket" => Ok(Venue::Polymarket),
+            "Kalshi" => Ok(Venue::Kalshi),
+            "Azuro" => Ok(Venue::Azuro),
+            other => anyhow::bail!("Unknown venue: {}", other),
+        }
+    }
+}
+
+/// Number of games a series is played over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SeriesFormat {
+    Bo1,
+    Bo2,
+    Bo3,
+    Bo5,
+}
+
+impl SeriesFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeriesFormat::Bo1 => "BO1",
+            SeriesFormat::Bo2 => "BO2",
+            SeriesFormat::Bo3 => "BO3",
+            SeriesFormat::Bo5 => "BO5",
+        }
+    }
+
+    /// Format for a series of `games` games, if it's one played in Dota 2
+    pub fn from_games(games: u32) -> Option<Self> {
+        match games {
+            1 => Some(SeriesFormat::Bo1),
+            2 => Some(SeriesFormat::Bo2),
+            3 => Some(SeriesFormat::Bo3),
+            5 => Some(SeriesFormat::Bo5),
+            _ => None,
+        }
+    }
+
+    /// Most games the series can last
+    pub fn max_games(&self) -> u32 {
+        match self {
+            SeriesFormat::Bo1 => 1,
+            SeriesFormat::Bo2 => 2,
+            SeriesFormat::Bo3 => 3,
+            SeriesFormat::Bo5 => 5,
+        }
+    }
+}
+
+impl fmt::Display for SeriesFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SeriesFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.to_ascii_lowercase()
+            .strip_prefix("bo")
+            .and_then(|n| n.parse().ok())
+            .and_then(Self::from_games)
+            .ok_or_else(|| anyhow::anyhow!("Unknown series format: {}", s))
+    }
+}
+
+/// One contender of a multi-outcome market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketOutcome {
+    /// Team name as listed by the venue
+    pub name: String,
+
+    /// Current price of the contender winning (0.0 - 1.0)
+    pub price: f64,
+
+    /// Venue ID of the contender's own Yes/No contract
+    pub condition_id: String,
+}
+
+/// Represents a prediction market for a Dota 2 match
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PolymarketMarket {
-    /// Unique market identifier (condition_id)
+pub struct Market {
+    /// Unique market identifier (Polymarket condition_id, Kalshi ticker or Azuro condition ID)
     pub condition_id: String,
 
+    /// Venue the market trades on
+    #[serde(default)]
+    pub venue: Venue,
+
     /// Market question/title (e.g., "Dota 2: Team Spirit vs OG (BO3)")
     pub question: String,
 
@@ -22,15 +140,138 @@ pub struct PolymarketMarket {
     /// Current odds for Team B (0.0 - 1.0)
     pub team_b_odds: f64,
 
+    /// Team A odds when the market was first seen (pre-game prior)
+    pub opening_team_a_odds: f64,
+
+    /// When the scanner first saw the market and took its opening odds
+    #[serde(default)]
+    pub opened_at: Option<DateTime<Utc>>,
+
+    /// Best bid for team A, when the venue quotes one
+    #[serde(default)]
+    pub team_a_bid: Option<f64>,
+
+    /// Best ask for team A, when the venue quotes one
+    #[serde(default)]
+    pub team_a_ask: Option<f64>,
+
     /// Total liquidity in USD
     pub liquidity: f64,
 
+    /// Traded volume over the last 24 hours in USD, when the venue reports it
+    #[serde(default)]
+    pub volume_24h: Option<f64>,
+
     /// Market end time
     pub end_date: Option<DateTime<Utc>>,
 
     /// Whether the market is currently active
     pub active: bool,
+
+    /// Web page for the market, when the venue has one
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Series format stated in the question
+    #[serde(default)]
+    pub series_format: Option<SeriesFormat>,
+
+    /// Game within the series the market settles on; `None` for the series winner
+    #[serde(default)]
+    pub game_number: Option<u32>,
+
+    /// Other qualifiers in the question (e.g., "Playoffs")
+    #[serde(default)]
+    pub qualifiers: Vec<String>,
+
+    /// Contenders of a multi-outcome event (tournament or group winner);
+    /// empty for two-team markets, whose teams are `team_a` and `team_b`
+    #[serde(default)]
+    pub outcomes: Vec<MarketOutcome>,
+}
+
+impl Market {
+    /// Whether this is a multi-outcome market rather than a single match
+    pub fn is_outright(&self) -> bool {
+        !self.outcomes.is_empty()
+    }
+
+    /// Event named by the question's last qualifier, e.g. "PGL Wallachia
+    /// Playoffs" in "Dota 2: Team Spirit vs OG (BO3) - PGL Wallachia Playoffs"
+    pub fn event_name(&self) -> Option<&str> {
+        self.qualifiers.last().map(String::as_str)
+    }
+
+    /// Price to buy team A: the ask, else the quoted odds
+    pub fn team_a_buy_price(&self) -> f64 {
+        self.team_a_ask.unwrap_or(self.team_a_odds)
+    }
+
+    /// Price to buy team B: one minus team A's bid, else the quoted odds
+    pub fn team_b_buy_price(&self) -> f64 {
+        self.team_a_bid
+            .map(|bid| 1.0 - bid)
+            .unwrap_or(self.team_b_odds)
+    }
+
+    /// Team A ask minus bid, when both are quoted
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.team_a_ask? - self.team_a_bid?)
+    }
 }
 
-/// Collection of active markets indexed by condition_id
-pub type ActiveMarkets = std::collections::HashMap<String, PolymarketMarket>;
+/// Price of a market's team A outcome at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    pub timestamp: DateTime<Utc>,
+    pub price: f64,
+}
+
+/// Collection of active markets on all venues indexed by condition_id
+///
+/// Shared by every worker; a sharded map, so readers only wait on the
+/// shard a write is in. Guards are sync locks and must not be held across
+/// an await
+pub type ActiveMarkets = dashmap::DashMap<String, Market>;
+
+/// Team A win probability quoted by an external sportsbook (vig removed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharpQuote {
+    /// Sportsbook the price came from (e.g., "pinnacle")
+    pub source: String,
+
+    /// Implied team A probability with the bookmaker margin removed
+    pub team_a_prob: f64,
+
+    /// When the price was fetched
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Latest sharp-book quotes indexed by Polymarket condition_id
+pub type SharpOddsCache = std::collections::HashMap<String, SharpQuote>;
+
+/// Last Polymarket price observed for a market while its match was live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosingLine {
+    /// Polymarket condition_id
+    pub market_condition_id: String,
+
+    /// Team A odds at the last live update
+    pub team_a_odds: f64,
+
+    /// When the price was observed
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Market whose updates are stored as Neutral snapshots instead of signals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MutedMarket {
+    /// Market condition_id
+    pub market_condition_id: String,
+
+    /// Why it was muted, e.g. "position filled"
+    pub reason: Option<String>,
+
+    /// When it was muted
+    pub muted_at: DateTime<Utc>,
+}
@@ -1,15 +1,30 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::{Deaths, MatchHistory, Momentum, SignalType, Teamfight, TEAM_SIZE};
+
 /// Live match state from OpenDota API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LiveMatchState {
     /// Match ID
     pub match_id: i64,
 
+    /// League ID, when the game is played in a league
+    #[serde(default)]
+    pub league_id: Option<i64>,
+
     /// League/tournament name
     pub league_name: Option<String>,
 
+    /// OpenDota tier of the league
+    #[serde(default)]
+    pub league_tier: Option<LeagueTier>,
+
     /// Radiant team info
     pub radiant: TeamState,
 
@@ -22,15 +37,189 @@ pub struct LiveMatchState {
     /// Current game time in seconds
     pub game_time: i32,
 
+    /// OpenDota patch ID the match is played on
+    #[serde(default)]
+    pub patch: Option<i32>,
+
+    /// OpenDota series the game belongs to, if reported
+    #[serde(default)]
+    pub series_id: Option<i64>,
+
+    /// Game within the series, as tracked by the live fetcher
+    #[serde(default)]
+    pub game_number: Option<u32>,
+
+    /// Game time in seconds of the last Roshan kill, when the source reports it
+    #[serde(default)]
+    pub roshan_killed_at: Option<i32>,
+
+    /// STRATZ's live estimate of Radiant's win probability, when enriched
+    /// from STRATZ
+    #[serde(default)]
+    pub radiant_win_rate: Option<f64>,
+
     /// Whether the game is currently in progress
     pub is_live: bool,
 
-    /// Last update timestamp
+    /// When the source last refreshed the match, or when it was fetched
+    /// if the source doesn't say
     pub updated_at: DateTime<Utc>,
 }
 
+/// OpenDota league tier, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeagueTier {
+    Excluded,
+    Amateur,
+    Professional,
+    Premium,
+}
+
+impl LeagueTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeagueTier::Excluded => "excluded",
+            LeagueTier::Amateur => "amateur",
+            LeagueTier::Professional => "professional",
+            LeagueTier::Premium => "premium",
+        }
+    }
+}
+
+impl fmt::Display for LeagueTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LeagueTier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "excluded" => Ok(LeagueTier::Excluded),
+            "amateur" => Ok(LeagueTier::Amateur),
+            "professional" => Ok(LeagueTier::Professional),
+            "premium" => Ok(LeagueTier::Premium),
+            other => anyhow::bail!("Unknown league tier: {}", other),
+        }
+    }
+}
+
+/// A player in a live match
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    /// Steam account ID, when public
+    pub account_id: Option<i64>,
+
+    /// OpenDota hero ID; 0 until picked
+    pub hero_id: i32,
+
+    /// Live gold per minute, when the source reports it
+    #[serde(default)]
+    pub gold_per_min: Option<f64>,
+
+    /// Live XP per minute, when the source reports it
+    #[serde(default)]
+    pub xp_per_min: Option<f64>,
+
+    /// Player's average gold per minute over recent matches
+    #[serde(default)]
+    pub avg_gold_per_min: Option<f64>,
+
+    /// Player's average XP per minute over recent matches
+    #[serde(default)]
+    pub avg_xp_per_min: Option<f64>,
+}
+
+impl LiveMatchState {
+    /// Game seconds after a Roshan kill that the aegis expires unused
+    pub const AEGIS_DURATION_SECS: i32 = 300;
+
+    /// Game seconds after a Roshan kill that it may respawn, earliest to latest
+    pub const ROSHAN_RESPAWN_SECS: (i32, i32) = (480, 660);
+
+    /// Barracks each side defends
+    pub const BARRACKS_PER_TEAM: i32 = 6;
+
+    /// Towers each side defends, tier 4s included
+    pub const TOWERS_PER_TEAM: i32 = 11;
+
+    /// Side with mega creeps (every enemy barracks down): `Some(true)` for Radiant
+    pub fn mega_creeps(&self) -> Option<bool> {
+        if self.radiant.barracks_killed >= Self::BARRACKS_PER_TEAM {
+            Some(true)
+        } else if self.dire.barracks_killed >= Self::BARRACKS_PER_TEAM {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Side one push from winning: `Some(true)` for Radiant
+    ///
+    /// The enemy throne is exposed, which the feed's tower counts can only
+    /// confirm once every tower is down, and either the enemy has no
+    /// barracks left or every enemy hero is dead (`deaths` estimates who is
+    /// waiting to respawn).
+    pub fn terminal_state(&self, deaths: Deaths) -> Option<bool> {
+        let finishing = |team: &TeamState, enemies_dead: i32| {
+            team.towers_killed >= Self::TOWERS_PER_TEAM
+                && (team.barracks_killed >= Self::BARRACKS_PER_TEAM || enemies_dead >= TEAM_SIZE)
+        };
+        if finishing(&self.radiant, deaths.dire) {
+            Some(true)
+        } else if finishing(&self.dire, deaths.radiant) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Game time window in which Roshan may respawn, after a known kill
+    pub fn roshan_respawn_window(&self) -> Option<(i32, i32)> {
+        let killed_at = self.roshan_killed_at?;
+        Some((
+            killed_at + Self::ROSHAN_RESPAWN_SECS.0,
+            killed_at + Self::ROSHAN_RESPAWN_SECS.1,
+        ))
+    }
+
+    /// Stand-ins playing on either team, counting only known rosters
+    pub fn stand_ins(&self) -> i32 {
+        self.radiant.stand_ins.unwrap_or(0) + self.dire.stand_ins.unwrap_or(0)
+    }
+
+    /// Whether Radiant (`Some(true)`) or Dire holds an unexpired aegis
+    pub fn aegis_holder(&self) -> Option<bool> {
+        let expired = self
+            .roshan_killed_at
+            .is_some_and(|killed_at| self.game_time - killed_at >= Self::AEGIS_DURATION_SECS);
+        if expired {
+            return None;
+        }
+
+        match (self.radiant.has_aegis, self.dire.has_aegis) {
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Fill in what another source adds to its view of the same game: the
+    /// aegis and last Roshan kill, its win-rate estimate, bans, and players'
+    /// heroes and live farm
+    pub fn enrich_from(&mut self, other: &LiveMatchState) {
+        self.roshan_killed_at = self.roshan_killed_at.or(other.roshan_killed_at);
+        self.radiant_win_rate = self.radiant_win_rate.or(other.radiant_win_rate);
+        self.radiant.enrich_from(&other.radiant);
+        self.dire.enrich_from(&other.dire);
+    }
+}
+
 /// State of a team in a live match
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TeamState {
     /// Team name
     pub name: String,
@@ -46,32 +235,292 @@ pub struct TeamState {
 
     /// Barracks destroyed (enemy barracks)
     pub barracks_killed: i32,
+
+    /// A hero on the team carries the aegis, when the source reports it
+    #[serde(default)]
+    pub has_aegis: bool,
+
+    /// Heroes the team banned in the draft, when the source reports it
+    #[serde(default)]
+    pub bans: Vec<i32>,
+
+    /// Players with their heroes and, where known, live and usual farm rates
+    #[serde(default)]
+    pub players: Vec<PlayerState>,
+
+    /// How much the lineup scales into the late game, from -0.5 (tempo)
+    /// to 1 (all carries); `None` until heroes and their roles are known
+    #[serde(default)]
+    pub late_game_scaling: Option<f64>,
+
+    /// Players not on the team's current roster; `None` until the roster
+    /// is known
+    #[serde(default)]
+    pub stand_ins: Option<i32>,
 }
 
-impl Default for TeamState {
-    fn default() -> Self {
-        Self {
-            name: String::new(),
-            team_id: None,
-            kills: 0,
-            towers_killed: 0,
-            barracks_killed: 0,
+impl TeamState {
+    /// Fill in the aegis, bans, and heroes and live farm of players matched
+    /// by account, from another source's view of the team
+    fn enrich_from(&mut self, other: &TeamState) {
+        // Sources without Roshan events never report the aegis
+        self.has_aegis |= other.has_aegis;
+        if self.bans.is_empty() {
+            self.bans = other.bans.clone();
+        }
+        if self.players.is_empty() {
+            self.players = other.players.clone();
+            return;
+        }
+
+        for player in &mut self.players {
+            let Some(source) = other
+                .players
+                .iter()
+                .find(|p| p.account_id.is_some() && p.account_id == player.account_id)
+            else {
+                continue;
+            };
+            if player.hero_id == 0 {
+                player.hero_id = source.hero_id;
+            }
+            player.gold_per_min = player.gold_per_min.or(source.gold_per_min);
+            player.xp_per_min = player.xp_per_min.or(source.xp_per_min);
         }
     }
 }
 
 /// Update sent from Live Fetcher to Signal Processor
+///
+/// States are shared with the match cache and the other markets following
+/// the same game rather than copied per market.
 #[derive(Debug, Clone)]
 pub struct MatchUpdate {
     /// Associated Polymarket condition_id
     pub market_condition_id: String,
 
     /// Current match state
-    pub state: LiveMatchState,
+    pub state: Arc<LiveMatchState>,
+
+    /// Whether the market's team A is playing on Radiant
+    pub market_team_a_is_radiant: bool,
 
     /// Previous state for diff calculation
-    pub previous_state: Option<LiveMatchState>,
+    pub previous_state: Option<Arc<LiveMatchState>>,
+
+    /// Kill and gold rates over the last few game minutes
+    pub momentum: Momentum,
+
+    /// Fight still taking kills, counted over the last few polls
+    pub teamfight: Option<Teamfight>,
+
+    /// Heroes estimated to be waiting to respawn
+    pub deaths: Deaths,
+
+    /// Most significant event since the previous poll, from the live
+    /// fetcher's `DetectorChain`
+    pub signal_type: SignalType,
+}
+
+impl MatchUpdate {
+    /// Whether the update carries a tower, barracks or teamfight since the
+    /// previous poll
+    pub fn is_event(&self) -> bool {
+        self.signal_type.is_event()
+    }
+}
+
+/// Latest state and recent history of each matched live game, by match_id
+///
+/// Only the live fetcher refreshes entries, so a game that ended or dropped
+/// out of the live feed stops being updated and is evicted once its
+/// `updated_at` is older than the TTL.
+#[derive(Debug, Default)]
+pub struct LiveMatchCache {
+    matches: HashMap<i64, CachedMatch>,
+}
+
+#[derive(Debug)]
+struct CachedMatch {
+    state: Arc<LiveMatchState>,
+    history: MatchHistory,
 }
 
-/// Map of match_id -> LiveMatchState for caching
-pub type LiveMatchCache = std::collections::HashMap<i64, LiveMatchState>;
+impl LiveMatchCache {
+    /// Cached state of a match
+    pub fn get(&self, match_id: &i64) -> Option<&Arc<LiveMatchState>> {
+        self.matches.get(match_id).map(|cached| &cached.state)
+    }
+
+    /// Store the latest state of a match and add it to its history
+    pub fn insert(&mut self, match_id: i64, state: Arc<LiveMatchState>) {
+        match self.matches.get_mut(&match_id) {
+            Some(cached) => {
+                cached.history.record(&state);
+                cached.state = state;
+            }
+            None => {
+                let mut history = MatchHistory::default();
+                history.record(&state);
+                self.matches
+                    .insert(match_id, CachedMatch { state, history });
+            }
+        }
+    }
+
+    /// Fight in progress in a match, from its recent history
+    pub fn teamfight(&self, match_id: &i64) -> Option<Teamfight> {
+        self.matches
+            .get(match_id)
+            .and_then(|cached| cached.history.recent_fight())
+    }
+
+    /// Heroes waiting to respawn in a match, from its recent history
+    pub fn deaths(&self, match_id: &i64) -> Deaths {
+        self.matches
+            .get(match_id)
+            .map(|cached| cached.history.recent_deaths())
+            .unwrap_or_default()
+    }
+
+    /// Kill and gold rates of a match over its recent history
+    pub fn momentum(&self, match_id: &i64) -> Momentum {
+        self.matches
+            .get(match_id)
+            .map(|cached| cached.history.momentum())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    /// Drop matches not updated within `ttl`, then the least recently
+    /// updated until at most `max_entries` remain; returns how many went
+    pub fn evict(&mut self, now: DateTime<Utc>, ttl: Duration, max_entries: usize) -> usize {
+        let before = self.matches.len();
+        self.matches
+            .retain(|_, cached| now.signed_duration_since(cached.state.updated_at) <= ttl);
+
+        if self.matches.len() > max_entries {
+            let mut by_age: Vec<(DateTime<Utc>, i64)> = self
+                .matches
+                .iter()
+                .map(|(id, cached)| (cached.state.updated_at, *id))
+                .collect();
+            by_age.sort();
+            let excess = self.matches.len() - max_entries;
+            for (_, id) in by_age.into_iter().take(excess) {
+                self.matches.remove(&id);
+            }
+        }
+
+        before - self.matches.len()
+    }
+}
+
+/// A live game at the horn with unnamed teams, for tests to fill in
+#[cfg(test)]
+impl Default for LiveMatchState {
+    fn default() -> Self {
+        Self {
+            match_id: 0,
+            league_id: None,
+            league_name: None,
+            league_tier: None,
+            radiant: TeamState::default(),
+            dire: TeamState::default(),
+            gold_lead: 0,
+            game_time: 0,
+            patch: None,
+            series_id: None,
+            game_number: None,
+            roshan_killed_at: None,
+            radiant_win_rate: None,
+            is_live: true,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(match_id: i64, updated_at: DateTime<Utc>) -> LiveMatchState {
+        LiveMatchState {
+            match_id,
+            updated_at,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aegis_expires() {
+        let mut live = state(1, Utc::now());
+        live.radiant.has_aegis = true;
+        live.game_time = 1500;
+        assert_eq!(live.aegis_holder(), Some(true));
+
+        live.roshan_killed_at = Some(1300);
+        assert_eq!(live.aegis_holder(), Some(true));
+        assert_eq!(live.roshan_respawn_window(), Some((1780, 1960)));
+
+        live.game_time = 1600;
+        assert_eq!(live.aegis_holder(), None);
+    }
+
+    #[test]
+    fn test_evict_silent_and_oldest() {
+        let now = Utc::now();
+        let mut cache = LiveMatchCache::default();
+        cache.insert(1, Arc::new(state(1, now - Duration::minutes(30))));
+        cache.insert(2, Arc::new(state(2, now - Duration::minutes(5))));
+        cache.insert(3, Arc::new(state(3, now - Duration::minutes(2))));
+        cache.insert(4, Arc::new(state(4, now)));
+
+        // Match 1 went silent; match 2 is the oldest over the cap
+        assert_eq!(cache.evict(now, Duration::minutes(10), 2), 2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&1).is_none());
+        assert!(cache.get(&2).is_none());
+        assert!(cache.get(&3).is_some());
+    }
+
+    #[test]
+    fn test_enrich_fills_only_what_is_missing() {
+        let player = |account_id, hero_id, gold_per_min| PlayerState {
+            account_id: Some(account_id),
+            hero_id,
+            gold_per_min,
+            ..Default::default()
+        };
+
+        let mut live = state(1, Utc::now());
+        live.radiant.players = vec![player(10, 0, None), player(11, 5, Some(400.0))];
+        live.roshan_killed_at = Some(900);
+
+        let mut stratz = state(1, Utc::now());
+        stratz.radiant.players = vec![player(11, 6, Some(500.0)), player(10, 1, Some(620.0))];
+        stratz.radiant.bans = vec![23];
+        stratz.radiant.has_aegis = true;
+        stratz.dire.players = vec![player(20, 44, Some(480.0))];
+        stratz.roshan_killed_at = Some(1200);
+        stratz.radiant_win_rate = Some(0.64);
+
+        live.enrich_from(&stratz);
+        assert_eq!(
+            live.radiant.players,
+            vec![player(10, 1, Some(620.0)), player(11, 5, Some(400.0))]
+        );
+        assert_eq!(live.radiant.bans, vec![23]);
+        assert_eq!(live.dire.players, stratz.dire.players);
+        assert_eq!(live.roshan_killed_at, Some(900));
+        assert_eq!(live.aegis_holder(), Some(true));
+        assert_eq!(live.radiant_win_rate, Some(0.64));
+    }
+}
@@ -1,7 +1,17 @@
+pub mod arbitrage;
+pub mod detector;
+pub mod exit_signal;
 pub mod market;
 pub mod match_state;
+pub mod momentum;
+pub mod position;
 pub mod signal;
 
+pub use arbitrage::*;
+pub use detector::*;
+pub use exit_signal::*;
 pub use market::*;
 pub use match_state::*;
+pub use momentum::*;
+pub use position::*;
 pub use signal::*;
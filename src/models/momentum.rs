@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::{LiveMatchState, TEAMFIGHT_KILLS};
+
+/// Game seconds of history momentum is measured over
+pub const MOMENTUM_WINDOW_SECS: i32 = 180;
+
+/// Shortest span that yields a rate; earlier rates are mostly poll noise
+const MIN_MOMENTUM_SPAN_SECS: i32 = 30;
+
+/// Game seconds within which `TEAMFIGHT_KILLS` kills count as one fight
+pub const TEAMFIGHT_WINDOW_SECS: i32 = 30;
+
+/// Players per team
+pub const TEAM_SIZE: i32 = 5;
+
+/// How fast a match is swinging, per game minute (Radiant perspective)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Momentum {
+    /// Radiant kills per minute minus Dire kills per minute
+    pub kill_rate_diff: f64,
+
+    /// Change in Radiant gold lead, in thousands per minute
+    pub gold_velocity_k: f64,
+}
+
+/// Kills clustered within `TEAMFIGHT_WINDOW_SECS` of game time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Teamfight {
+    pub radiant_kills: i32,
+    pub dire_kills: i32,
+}
+
+impl Teamfight {
+    /// Whether Radiant took more kills; `None` for an even trade
+    pub fn radiant_won(&self) -> Option<bool> {
+        (self.radiant_kills != self.dire_kills).then_some(self.radiant_kills > self.dire_kills)
+    }
+}
+
+/// Players estimated dead on each side, from kills within a respawn time
+///
+/// Live data has no death timers or buyback status, so a hero killed within
+/// `respawn_secs` counts as still dead and buybacks are not seen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deaths {
+    pub radiant: i32,
+    pub dire: i32,
+}
+
+/// Approximate respawn time at a game time
+///
+/// Respawn grows with hero level; levels track game time closely enough to
+/// go from about 10 sec at the horn to a 90 sec cap around 50 minutes.
+pub fn respawn_secs(game_time: i32) -> i32 {
+    (10 + game_time * 16 / 600).clamp(10, 90)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    game_time: i32,
+    radiant_kills: i32,
+    dire_kills: i32,
+    gold_lead: i64,
+}
+
+impl Sample {
+    fn kills(&self) -> i32 {
+        self.radiant_kills + self.dire_kills
+    }
+
+    fn kill_diff(&self) -> i32 {
+        self.radiant_kills - self.dire_kills
+    }
+}
+
+/// Recent states of one match, covering the last `MOMENTUM_WINDOW_SECS`
+/// of game time
+#[derive(Debug, Clone, Default)]
+pub struct MatchHistory {
+    samples: VecDeque<Sample>,
+}
+
+impl MatchHistory {
+    /// Add a polled state; repeats of the same game time are ignored
+    pub fn record(&mut self, state: &LiveMatchState) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| state.game_time <= last.game_time)
+        {
+            return;
+        }
+
+        self.samples.push_back(Sample {
+            game_time: state.game_time,
+            radiant_kills: state.radiant.kills,
+            dire_kills: state.dire.kills,
+            gold_lead: state.gold_lead,
+        });
+
+        while self
+            .samples
+            .front()
+            .is_some_and(|first| state.game_time - first.game_time > MOMENTUM_WINDOW_SECS)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Rates over the window; zero until it spans `MIN_MOMENTUM_SPAN_SECS`
+    pub fn momentum(&self) -> Momentum {
+        let (Some(first), Some(last)) = (self.samples.front(), self.samples.back()) else {
+            return Momentum::default();
+        };
+
+        let span = last.game_time - first.game_time;
+        if span < MIN_MOMENTUM_SPAN_SECS {
+            return Momentum::default();
+        }
+
+        let minutes = span as f64 / 60.0;
+        Momentum {
+            kill_rate_diff: (last.kill_diff() - first.kill_diff()) as f64 / minutes,
+            gold_velocity_k: (last.gold_lead - first.gold_lead) as f64 / 1000.0 / minutes,
+        }
+    }
+
+    /// Heroes still waiting to respawn on each side
+    pub fn recent_deaths(&self) -> Deaths {
+        let Some(last) = self.samples.back() else {
+            return Deaths::default();
+        };
+
+        // Kills counted from the last poll before a respawn time ago
+        let window = respawn_secs(last.game_time);
+        let Some(start) = self
+            .samples
+            .iter()
+            .rev()
+            .find(|s| last.game_time - s.game_time >= window)
+            .or(self.samples.front())
+        else {
+            return Deaths::default();
+        };
+
+        Deaths {
+            radiant: (last.dire_kills - start.dire_kills).clamp(0, TEAM_SIZE),
+            dire: (last.radiant_kills - start.radiant_kills).clamp(0, TEAM_SIZE),
+        }
+    }
+
+    /// Fight in progress: at least `TEAMFIGHT_KILLS` kills within
+    /// `TEAMFIGHT_WINDOW_SECS`, the latest of them since the previous poll
+    ///
+    /// Kills spread over a few polls count together, so a fight isn't
+    /// missed when the feed splits it.
+    pub fn recent_fight(&self) -> Option<Teamfight> {
+        let last = self.samples.back()?;
+        let previous = self.samples.get(self.samples.len().checked_sub(2)?)?;
+        if last.kills() <= previous.kills() {
+            return None;
+        }
+
+        // Kills counted from the last poll before the window
+        let start = self
+            .samples
+            .iter()
+            .rev()
+            .find(|s| last.game_time - s.game_time >= TEAMFIGHT_WINDOW_SECS)
+            .or(self.samples.front())?;
+
+        let fight = Teamfight {
+            radiant_kills: last.radiant_kills - start.radiant_kills,
+            dire_kills: last.dire_kills - start.dire_kills,
+        };
+        (fight.radiant_kills + fight.dire_kills >= TEAMFIGHT_KILLS).then_some(fight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TeamState;
+
+    fn state(game_time: i32, radiant_kills: i32, gold_lead: i64) -> LiveMatchState {
+        LiveMatchState {
+            match_id: 1,
+            radiant: TeamState {
+                kills: radiant_kills,
+                ..Default::default()
+            },
+            gold_lead,
+            game_time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fast_swing_outweighs_slow_swing() {
+        // 8k over 2 minutes
+        let mut fast = MatchHistory::default();
+        fast.record(&state(1200, 10, 0));
+        fast.record(&state(1320, 14, 8000));
+        let fast = fast.momentum();
+        assert!((fast.gold_velocity_k - 4.0).abs() < 1e-9);
+        assert!((fast.kill_rate_diff - 2.0).abs() < 1e-9);
+
+        // 8k over 15 minutes, only the last 3 of which are in the window
+        let mut slow = MatchHistory::default();
+        for minute in 0..=15 {
+            slow.record(&state(600 + minute * 60, 0, minute as i64 * 8000 / 15));
+        }
+        let slow = slow.momentum();
+        assert!(slow.gold_velocity_k < 0.6);
+
+        // One poll isn't a rate
+        let mut single = MatchHistory::default();
+        single.record(&state(600, 0, 5000));
+        assert_eq!(single.momentum(), Momentum::default());
+    }
+
+    #[test]
+    fn test_deaths_expire_after_respawn() {
+        let mut history = MatchHistory::default();
+        history.record(&state(1800, 10, 0));
+        history.record(&state(1820, 13, 0));
+        assert_eq!(
+            history.recent_deaths(),
+            Deaths {
+                radiant: 0,
+                dire: 3
+            }
+        );
+
+        // Respawn is about a minute at 30 minutes
+        history.record(&state(1900, 13, 0));
+        assert_eq!(history.recent_deaths(), Deaths::default());
+    }
+
+    #[test]
+    fn test_fight_spread_over_polls() {
+        let mut history = MatchHistory::default();
+        history.record(&state(600, 5, 0));
+        history.record(&state(610, 6, 0));
+        assert_eq!(history.recent_fight(), None);
+
+        // Two more kills on the next poll, still within the window
+        history.record(&state(620, 8, 0));
+        let fight = history.recent_fight().unwrap();
+        assert_eq!(fight.radiant_kills, 3);
+        assert_eq!(fight.radiant_won(), Some(true));
+
+        // Quiet poll ends it
+        history.record(&state(630, 8, 0));
+        assert_eq!(history.recent_fight(), None);
+    }
+}
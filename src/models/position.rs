@@ -0,0 +1,251 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Which market outcome a position holds shares of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionSide {
+    TeamA,
+    TeamB,
+}
+
+impl PositionSide {
+    /// Name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionSide::TeamA => "TeamA",
+            PositionSide::TeamB => "TeamB",
+        }
+    }
+
+    /// Side implied by a signed edge (positive = team A)
+    pub fn from_edge(edge: f64) -> Self {
+        if edge >= 0.0 {
+            PositionSide::TeamA
+        } else {
+            PositionSide::TeamB
+        }
+    }
+}
+
+impl fmt::Display for PositionSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PositionSide {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TeamA" | "a" | "A" => Ok(PositionSide::TeamA),
+            "TeamB" | "b" | "B" => Ok(PositionSide::TeamB),
+            other => anyhow::bail!("Unknown position side: {}", other),
+        }
+    }
+}
+
+/// Lifecycle state of a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionStatus {
+    /// Full size still held
+    Open,
+    /// Some shares sold before settlement
+    PartiallyClosed,
+    /// All shares sold before settlement
+    Closed,
+    /// Market settled; remaining shares paid out at 1 or 0
+    Resolved,
+}
+
+impl PositionStatus {
+    /// Name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionStatus::Open => "Open",
+            PositionStatus::PartiallyClosed => "PartiallyClosed",
+            PositionStatus::Closed => "Closed",
+            PositionStatus::Resolved => "Resolved",
+        }
+    }
+
+    /// Whether shares are still held
+    pub fn is_active(&self) -> bool {
+        matches!(self, PositionStatus::Open | PositionStatus::PartiallyClosed)
+    }
+}
+
+impl fmt::Display for PositionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PositionStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Open" => Ok(PositionStatus::Open),
+            "PartiallyClosed" => Ok(PositionStatus::PartiallyClosed),
+            "Closed" => Ok(PositionStatus::Closed),
+            "Resolved" => Ok(PositionStatus::Resolved),
+            other => anyhow::bail!("Unknown position status: {}", other),
+        }
+    }
+}
+
+/// A position held in a Polymarket market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    /// Unique position identifier
+    pub id: Option<i64>,
+
+    /// Signal that prompted the trade, if any
+    pub signal_id: Option<i64>,
+
+    /// Polymarket condition_id
+    pub market_condition_id: String,
+
+    /// Match ID from OpenDota, if known
+    pub match_id: Option<i64>,
+
+    /// Outcome held
+    pub side: PositionSide,
+
+    /// Average entry price per share (0.0 - 1.0)
+    pub entry_price: f64,
+
+    /// Shares bought
+    pub shares: f64,
+
+    /// Shares still held
+    pub remaining_shares: f64,
+
+    /// Realized profit/loss in USD so far
+    pub realized_pnl: f64,
+
+    /// Lifecycle state
+    pub status: PositionStatus,
+
+    /// When the position was opened
+    pub opened_at: DateTime<Utc>,
+
+    /// When the position was fully closed or resolved
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl Position {
+    /// New open position
+    pub fn open(
+        market_condition_id: &str,
+        side: PositionSide,
+        entry_price: f64,
+        shares: f64,
+    ) -> Self {
+        Self {
+            id: None,
+            signal_id: None,
+            market_condition_id: market_condition_id.to_string(),
+            match_id: None,
+            side,
+            entry_price,
+            shares,
+            remaining_shares: shares,
+            realized_pnl: 0.0,
+            status: PositionStatus::Open,
+            opened_at: Utc::now(),
+            closed_at: None,
+        }
+    }
+
+    /// USD cost of the shares bought
+    pub fn cost_basis(&self) -> f64 {
+        self.entry_price * self.shares
+    }
+
+    /// USD at risk in the shares still held
+    pub fn open_exposure(&self) -> f64 {
+        self.entry_price * self.remaining_shares
+    }
+
+    /// Sell shares before settlement, returning the PnL realized by this sale
+    pub fn close(&mut self, shares: f64, price: f64) -> anyhow::Result<f64> {
+        if !self.status.is_active() {
+            anyhow::bail!("Position is already {}", self.status);
+        }
+        if shares <= 0.0 || shares > self.remaining_shares + f64::EPSILON {
+            anyhow::bail!(
+                "Cannot close {} shares, {} remaining",
+                shares,
+                self.remaining_shares
+            );
+        }
+
+        let pnl = shares * (price - self.entry_price);
+        self.realized_pnl += pnl;
+        self.remaining_shares = (self.remaining_shares - shares).max(0.0);
+
+        if self.remaining_shares <= f64::EPSILON {
+            self.remaining_shares = 0.0;
+            self.status = PositionStatus::Closed;
+            self.closed_at = Some(Utc::now());
+        } else {
+            self.status = PositionStatus::PartiallyClosed;
+        }
+
+        Ok(pnl)
+    }
+
+    /// Settle remaining shares at 1.0 (won) or 0.0 (lost), returning their PnL
+    pub fn resolve(&mut self, won: bool) -> anyhow::Result<f64> {
+        if !self.status.is_active() {
+            anyhow::bail!("Position is already {}", self.status);
+        }
+
+        let payout = if won { 1.0 } else { 0.0 };
+        let pnl = self.remaining_shares * (payout - self.entry_price);
+
+        self.realized_pnl += pnl;
+        self.remaining_shares = 0.0;
+        self.status = PositionStatus::Resolved;
+        self.closed_at = Some(Utc::now());
+
+        Ok(pnl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_close_then_resolve() {
+        let mut position = Position::open("0xabc", PositionSide::TeamA, 0.40, 100.0);
+
+        let pnl = position.close(25.0, 0.60).unwrap();
+        assert!((pnl - 5.0).abs() < 1e-9);
+        assert_eq!(position.status, PositionStatus::PartiallyClosed);
+
+        let pnl = position.resolve(true).unwrap();
+        assert!((pnl - 45.0).abs() < 1e-9);
+        assert!((position.realized_pnl - 50.0).abs() < 1e-9);
+        assert_eq!(position.status, PositionStatus::Resolved);
+
+        assert!(position.resolve(false).is_err());
+    }
+
+    #[test]
+    fn test_full_close_and_oversell() {
+        let mut position = Position::open("0xabc", PositionSide::TeamB, 0.50, 10.0);
+
+        assert!(position.close(11.0, 0.40).is_err());
+
+        let pnl = position.close(10.0, 0.40).unwrap();
+        assert!((pnl + 1.0).abs() < 1e-9);
+        assert_eq!(position.status, PositionStatus::Closed);
+    }
+}
@@ -1,24 +1,380 @@
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::Venue;
+
+/// How far the model's interval clears the market price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SignalStrength {
+    /// Interval contains the market price
+    Neutral,
+    Weak,
+    Moderate,
+    Strong,
+    VeryStrong,
+}
+
+impl SignalStrength {
+    /// Every strength, weakest first
+    pub const ALL: [SignalStrength; 5] = [
+        SignalStrength::Neutral,
+        SignalStrength::Weak,
+        SignalStrength::Moderate,
+        SignalStrength::Strong,
+        SignalStrength::VeryStrong,
+    ];
+
+    /// Derive strength from the probability interval for team A
+    ///
+    /// `team_a_cost`/`team_b_cost` are the all-in prices of one share on each
+    /// side (quote plus fees and slippage). Only the bound nearest the market
+    /// counts, so a wide interval that doesn't clear the cost of either side
+    /// is `Neutral` however far the point estimate is.
+    pub fn from_interval(lower: f64, upper: f64, team_a_cost: f64, team_b_cost: f64) -> Self {
+        Self::from_interval_with_confidence(lower, upper, team_a_cost, team_b_cost, 1.0)
+    }
+
+    /// `from_interval` with the edge scaled by how far the quote can be
+    /// trusted (0-1), so thinly traded markets grade weaker
+    pub fn from_interval_with_confidence(
+        lower: f64,
+        upper: f64,
+        team_a_cost: f64,
+        team_b_cost: f64,
+        confidence: f64,
+    ) -> Self {
+        let team_a_edge = (lower - team_a_cost) * confidence;
+        let team_b_edge = ((1.0 - upper) - team_b_cost) * confidence;
+
+        if team_a_edge > 0.0 {
+            Self::from_edge(team_a_edge)
+        } else if team_b_edge > 0.0 {
+            Self::from_edge(team_b_edge)
+        } else {
+            SignalStrength::Neutral
+        }
+    }
+
+    /// Hold a strength priced off the quote below `Strong` unless the
+    /// interval is as strong against the side of the spread it would trade
+    ///
+    /// `at_spread` is `from_interval` with each side's cost taken at its ask.
+    pub fn capped_by_spread(self, at_spread: Self) -> Self {
+        if self >= SignalStrength::Strong && at_spread < self {
+            at_spread.max(SignalStrength::Moderate)
+        } else {
+            self
+        }
+    }
+
+    /// Bucket an absolute edge into a strength
+    pub fn from_edge(edge: f64) -> Self {
+        match edge.abs() {
+            e if e >= 0.15 => SignalStrength::VeryStrong,
+            e if e >= 0.10 => SignalStrength::Strong,
+            e if e >= 0.05 => SignalStrength::Moderate,
+            e if e > 0.0 => SignalStrength::Weak,
+            _ => SignalStrength::Neutral,
+        }
+    }
+
+    /// Name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalStrength::Neutral => "Neutral",
+            SignalStrength::Weak => "Weak",
+            SignalStrength::Moderate => "Moderate",
+            SignalStrength::Strong => "Strong",
+            SignalStrength::VeryStrong => "VeryStrong",
+        }
+    }
+}
+
+impl fmt::Display for SignalStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SignalStrength {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Neutral" => Ok(SignalStrength::Neutral),
+            "Weak" => Ok(SignalStrength::Weak),
+            "Moderate" => Ok(SignalStrength::Moderate),
+            "Strong" => Ok(SignalStrength::Strong),
+            "VeryStrong" => Ok(SignalStrength::VeryStrong),
+            other => anyhow::bail!("Unknown signal strength: {}", other),
+        }
+    }
+}
+
+/// In-game event that triggered a signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignalType {
+    /// Periodic snapshot with no notable event
+    Snapshot,
+    GoldSwing,
+    Teamfight,
+    TowerKill,
+    BarracksKill,
+    /// One side lost its last barracks; the other has mega creeps
+    MegaCreeps,
+    /// Emitted by the `SIGNAL_SCRIPT` hook
+    Script,
+    /// Emitted by a `SIGNAL_RULES` rule
+    Rule,
+    /// The game is over but its market still trades short of settled
+    SettlementLag,
+    /// One side is a push from winning: the enemy throne is exposed and
+    /// its barracks are gone or its heroes all dead
+    TerminalState,
+}
+
+impl SignalType {
+    /// Discrete in-game events, as opposed to drift between polls
+    pub fn is_event(&self) -> bool {
+        matches!(
+            self,
+            SignalType::TowerKill
+                | SignalType::BarracksKill
+                | SignalType::MegaCreeps
+                | SignalType::TerminalState
+                | SignalType::Teamfight
+        )
+    }
+
+    /// Name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalType::Snapshot => "Snapshot",
+            SignalType::GoldSwing => "GoldSwing",
+            SignalType::Teamfight => "Teamfight",
+            SignalType::TowerKill => "TowerKill",
+            SignalType::BarracksKill => "BarracksKill",
+            SignalType::MegaCreeps => "MegaCreeps",
+            SignalType::Script => "Script",
+            SignalType::Rule => "Rule",
+            SignalType::SettlementLag => "SettlementLag",
+            SignalType::TerminalState => "TerminalState",
+        }
+    }
+}
+
+impl fmt::Display for SignalType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SignalType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Snapshot" => Ok(SignalType::Snapshot),
+            "GoldSwing" => Ok(SignalType::GoldSwing),
+            "Teamfight" => Ok(SignalType::Teamfight),
+            "TowerKill" => Ok(SignalType::TowerKill),
+            "BarracksKill" => Ok(SignalType::BarracksKill),
+            "MegaCreeps" => Ok(SignalType::MegaCreeps),
+            "Script" => Ok(SignalType::Script),
+            "Rule" => Ok(SignalType::Rule),
+            "SettlementLag" => Ok(SignalType::SettlementLag),
+            "TerminalState" => Ok(SignalType::TerminalState),
+            other => anyhow::bail!("Unknown signal type: {}", other),
+        }
+    }
+}
+
 /// A match snapshot captured during live monitoring
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
     /// Unique signal identifier
     pub id: Option<i64>,
 
-    /// Polymarket condition_id this signal relates to
+    /// Venue's contract ID this signal relates to (Polymarket condition_id,
+    /// Kalshi ticker or Azuro condition)
     pub market_condition_id: String,
 
+    /// Venue the market trades on
+    #[serde(default)]
+    pub venue: Venue,
+
     /// Match ID from OpenDota
     pub match_id: i64,
 
     /// Current market odds for team A (from Polymarket)
     pub market_team_a_odds: f64,
 
+    /// Pre-game prior for team A (Elo and opening price)
+    pub team_a_prior_prob: Option<f64>,
+
+    /// Team A probability quoted by a sharp sportsbook, margin removed
+    pub sharp_team_a_prob: Option<f64>,
+
+    /// Model win probability for team A (None if prediction failed)
+    pub team_a_win_prob: Option<f64>,
+
+    /// Lower bound of the 90% interval on `team_a_win_prob`
+    pub team_a_prob_lower: Option<f64>,
+
+    /// Upper bound of the 90% interval on `team_a_win_prob`
+    pub team_a_prob_upper: Option<f64>,
+
+    /// Trust in the quote and data (0-1) that scaled the edge before
+    /// grading: volume, freshness, stand-ins and rating certainty
+    #[serde(default)]
+    pub confidence: Option<f64>,
+
+    /// Model probability minus market odds for team A (positive = back team A)
+    pub edge: Option<f64>,
+
+    /// Edge after fees and slippage on the better side (positive = back team A,
+    /// negative = back team B, 0.0 = neither side profitable)
+    pub net_edge: Option<f64>,
+
+    /// Strength derived from how far the interval clears the all-in price
+    pub strength: SignalStrength,
+
+    /// In-game event since the previous update
+    pub signal_type: SignalType,
+
+    /// Part of a run of Strong+ signals on the same side of the market
+    #[serde(default)]
+    pub escalated: bool,
+
+    /// Team that took more kills in a `Teamfight`; `None` for an even trade
+    #[serde(default)]
+    pub fight_winner: Option<String>,
+
+    /// Median game seconds left in similar historical games
+    #[serde(default)]
+    pub expected_remaining_secs: Option<i32>,
+
+    /// Version of the model that produced `team_a_win_prob`
+    pub model_version: Option<String>,
+
+    /// Model input features at signal time, Radiant perspective (JSON)
+    #[serde(default)]
+    pub features: Option<String>,
+
+    /// Features that moved the live model most for team A, e.g.
+    /// "+6% gold lead, +3% towers, −2% draft"
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// Whether the market's team A played Radiant; settles the signal
+    /// against `historical_matches.radiant_win`
+    #[serde(default)]
+    pub team_a_is_radiant: Option<bool>,
+
+    /// Whether the side the net edge favoured won; None until graded, and
+    /// for graded signals that favoured neither side
+    #[serde(default)]
+    pub correct: Option<bool>,
+
+    /// When the signal was graded against the match result
+    #[serde(default)]
+    pub graded_at: Option<DateTime<Utc>>,
+
     /// Raw match data at signal time (JSON)
     pub match_snapshot: String,
 
     /// When the signal was generated
     pub created_at: DateTime<Utc>,
 }
+
+/// A live, Neutral `Snapshot` with no model output, for tests to fill in
+#[cfg(test)]
+impl Default for Signal {
+    fn default() -> Self {
+        Self {
+            id: None,
+            market_condition_id: String::new(),
+            venue: Venue::Polymarket,
+            match_id: 0,
+            market_team_a_odds: 0.5,
+            team_a_prior_prob: None,
+            sharp_team_a_prob: None,
+            team_a_win_prob: None,
+            team_a_prob_lower: None,
+            team_a_prob_upper: None,
+            confidence: None,
+            edge: None,
+            net_edge: None,
+            strength: SignalStrength::Neutral,
+            signal_type: SignalType::Snapshot,
+            escalated: false,
+            fight_winner: None,
+            expected_remaining_secs: None,
+            model_version: None,
+            features: None,
+            reason: None,
+            team_a_is_radiant: None,
+            correct: None,
+            graded_at: None,
+            match_snapshot: String::new(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_straddling_interval_is_neutral() {
+        assert_eq!(
+            SignalStrength::from_interval(0.40, 0.80, 0.55, 0.45),
+            SignalStrength::Neutral
+        );
+    }
+
+    #[test]
+    fn test_strength_uses_nearest_bound() {
+        // Point estimate ~0.80 but the lower bound only clears by 0.06
+        assert_eq!(
+            SignalStrength::from_interval(0.61, 0.90, 0.55, 0.45),
+            SignalStrength::Moderate
+        );
+        // Team B side: upper bound 0.12 below market
+        assert_eq!(
+            SignalStrength::from_interval(0.20, 0.43, 0.55, 0.45),
+            SignalStrength::Strong
+        );
+    }
+
+    #[test]
+    fn test_costs_reduce_strength() {
+        // Same interval, but 4% of fees and slippage on top of the quote
+        assert_eq!(
+            SignalStrength::from_interval(0.61, 0.90, 0.59, 0.49),
+            SignalStrength::Weak
+        );
+    }
+
+    #[test]
+    fn test_spread_caps_strong_signals() {
+        // Strong off the 0.50 quote, but the ask is 0.55
+        let quoted = SignalStrength::from_interval(0.62, 0.70, 0.50, 0.50);
+        let at_spread = SignalStrength::from_interval(0.62, 0.70, 0.55, 0.55);
+        assert_eq!(quoted, SignalStrength::Strong);
+        assert_eq!(quoted.capped_by_spread(at_spread), SignalStrength::Moderate);
+
+        assert_eq!(
+            SignalStrength::VeryStrong.capped_by_spread(SignalStrength::Strong),
+            SignalStrength::Strong
+        );
+        assert_eq!(
+            SignalStrength::Moderate.capped_by_spread(SignalStrength::Neutral),
+            SignalStrength::Moderate
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Once-a-day summary for sinks that don't want real-time signal alerts
+//!
+//! Sinks in `NOTIFY_DIGEST_SINKS` skip signal alerts and instead get one
+//! message at `NOTIFY_DIGEST_TIME` listing each market that had Strong+
+//! signals in the past day and how the latest one was graded.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+use super::{Notification, Priority};
+use crate::models::{LiveMatchState, Signal, SignalStrength};
+
+/// Weakest signal included in a digest
+pub const DIGEST_MIN_STRENGTH: SignalStrength = SignalStrength::Strong;
+
+/// Markets listed before the rest are summarized as a count
+const MAX_DIGEST_LINES: usize = 20;
+
+/// First `at` (UTC) strictly after `now`
+pub fn next_digest_at(now: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+    let today = now.date_naive().and_time(at).and_utc();
+    if today > now {
+        today
+    } else {
+        today + Duration::days(1)
+    }
+}
+
+/// Digest of the given Strong+ signals, one line per market; None when
+/// there are none
+pub fn build_digest(signals: &[Signal], until: DateTime<Utc>) -> Option<Notification> {
+    // Signals by market, each market's newest first
+    let mut markets: BTreeMap<&str, Vec<&Signal>> = BTreeMap::new();
+    for signal in signals {
+        markets
+            .entry(&signal.market_condition_id)
+            .or_default()
+            .push(signal);
+    }
+    let mut markets: Vec<Vec<&Signal>> = markets
+        .into_values()
+        .map(|mut group| {
+            group.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+            group
+        })
+        .collect();
+    if markets.is_empty() {
+        return None;
+    }
+    markets.sort_by_key(|group| std::cmp::Reverse(group[0].created_at));
+
+    let grade = |group: &[&Signal]| group[0].correct;
+    let correct = markets.iter().filter(|g| grade(g) == Some(true)).count();
+    let wrong = markets.iter().filter(|g| grade(g) == Some(false)).count();
+
+    let mut lines = vec![format!(
+        "{} markets: {} correct, {} wrong, {} pending",
+        markets.len(),
+        correct,
+        wrong,
+        markets.len() - correct - wrong
+    )];
+    lines.extend(
+        markets
+            .iter()
+            .take(MAX_DIGEST_LINES)
+            .map(|g| digest_line(g)),
+    );
+    if markets.len() > MAX_DIGEST_LINES {
+        lines.push(format!("… and {} more", markets.len() - MAX_DIGEST_LINES));
+    }
+
+    Some(Notification::new(
+        Priority::Normal,
+        format!(
+            "Daily digest {}: {} Strong+ signals",
+            until.format("%Y-%m-%d"),
+            signals.len()
+        ),
+        lines.join("\n"),
+    ))
+}
+
+/// One market's line, described by its newest signal
+fn digest_line(group: &[&Signal]) -> String {
+    let latest = group[0];
+    let strongest = group
+        .iter()
+        .map(|s| s.strength)
+        .max()
+        .unwrap_or(latest.strength);
+
+    let state = serde_json::from_str::<LiveMatchState>(&latest.match_snapshot).ok();
+    let teams = state
+        .as_ref()
+        .map(|s| format!("{} vs {}", s.radiant.name, s.dire.name))
+        .unwrap_or_else(|| format!("Match {}", latest.match_id));
+
+    // Name the backed team when the signal knows which side team A played
+    let backed = match (latest.net_edge, latest.team_a_is_radiant, &state) {
+        (Some(edge), Some(team_a_is_radiant), Some(state)) if edge != 0.0 => {
+            let radiant = (edge > 0.0) == team_a_is_radiant;
+            let name = if radiant {
+                &state.radiant.name
+            } else {
+                &state.dire.name
+            };
+            format!(" | backed {}", name)
+        }
+        _ => String::new(),
+    };
+
+    let outcome = match latest.correct {
+        Some(true) => "correct",
+        Some(false) => "wrong",
+        None => "pending",
+    };
+
+    format!(
+        "{} | {} × up to {}{} | net edge {} | {}",
+        teams,
+        group.len(),
+        strongest,
+        backed,
+        latest
+            .net_edge
+            .map(|e| format!("{:+.1}%", e * 100.0))
+            .unwrap_or_else(|| "n/a".to_string()),
+        outcome
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::{SignalType, TeamState};
+
+    fn signal(market: &str, minutes: i64, net_edge: f64, correct: Option<bool>) -> Signal {
+        let state = LiveMatchState {
+            match_id: 1,
+            radiant: TeamState {
+                name: "Spirit".to_string(),
+                ..Default::default()
+            },
+            dire: TeamState {
+                name: "Liquid".to_string(),
+                ..Default::default()
+            },
+            game_time: 600,
+            ..Default::default()
+        };
+        Signal {
+            market_condition_id: market.to_string(),
+            match_id: 1,
+            edge: Some(net_edge),
+            net_edge: Some(net_edge),
+            strength: SignalStrength::Strong,
+            signal_type: SignalType::TowerKill,
+            team_a_is_radiant: Some(false),
+            correct,
+            match_snapshot: serde_json::to_string(&state).unwrap(),
+            created_at: Utc
+                .with_ymd_and_hms(2026, 3, 1, 12, minutes as u32, 0)
+                .unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_digest_groups_by_market() {
+        let until = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        assert!(build_digest(&[], until).is_none());
+
+        let signals = [
+            signal("a", 0, 0.05, None),
+            signal("a", 5, 0.08, Some(true)),
+            signal("b", 1, -0.06, None),
+        ];
+        let digest = build_digest(&signals, until).unwrap();
+        assert_eq!(digest.title, "Daily digest 2026-03-02: 3 Strong+ signals");
+
+        let lines: Vec<&str> = digest.body.lines().collect();
+        assert_eq!(lines[0], "2 markets: 1 correct, 0 wrong, 1 pending");
+        // Team A is Dire, so a positive edge backs Liquid
+        assert_eq!(
+            lines[1],
+            "Spirit vs Liquid | 2 × up to Strong | backed Liquid | net edge +8.0% | correct"
+        );
+        assert!(lines[2].contains("backed Spirit"));
+    }
+
+    #[test]
+    fn test_next_digest_at() {
+        let at = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let morning = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        assert_eq!(
+            next_digest_at(morning, at),
+            Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap()
+        );
+        let evening = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        assert_eq!(
+            next_digest_at(evening, at),
+            Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap()
+        );
+    }
+}
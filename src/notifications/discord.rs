@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{truncate, Notification, NotificationSink, Priority};
+
+/// Discord caps an embed's title and description at these many characters
+const MAX_TITLE_CHARS: usize = 256;
+const MAX_DESCRIPTION_CHARS: usize = 4096;
+
+/// Embed colors: red for urgent, blue otherwise
+const URGENT_COLOR: u32 = 0xE0_3E_3E;
+const NORMAL_COLOR: u32 = 0x58_65_F2;
+
+/// Posts notifications to a Discord channel webhook as embeds
+pub struct DiscordSink {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl DiscordSink {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url: webhook_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for DiscordSink {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // The webhook URL carries its token, so errors are logged without it
+        self.client
+            .post(&self.webhook_url)
+            .json(&discord_message(notification))
+            .send()
+            .await
+            .map_err(|e| e.without_url())
+            .context("Discord webhook request failed")?
+            .error_for_status()
+            .map_err(|e| e.without_url())
+            .context("Discord webhook rejected the message")?;
+
+        Ok(())
+    }
+}
+
+/// One embed; signal alerts add strength/type/price/model/edge/liquidity fields
+fn discord_message(notification: &Notification) -> Value {
+    let (title, color) = match notification.priority {
+        Priority::Urgent => (format!("🚨 {}", notification.title), URGENT_COLOR),
+        Priority::Normal => (notification.title.clone(), NORMAL_COLOR),
+    };
+
+    let mut embed = json!({
+        "title": truncate(&title, MAX_TITLE_CHARS),
+        "description": truncate(&notification.body, MAX_DESCRIPTION_CHARS),
+        "color": color,
+    });
+
+    if let Some(alert) = &notification.signal {
+        let (signal, market) = (&alert.signal, &alert.market);
+        let percent = |p: Option<f64>| {
+            p.map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_else(|| "n/a".to_string())
+        };
+        let field = |name: &str, value: String| {
+            json!({
+                "name": name,
+                "value": value,
+                "inline": true,
+            })
+        };
+
+        embed["fields"] = json!([
+            field("Strength", signal.strength.to_string()),
+            field("Type", signal.signal_type.to_string()),
+            field(
+                &format!("{} price", market.team_a),
+                format!("{:.1}%", market.team_a_odds * 100.0),
+            ),
+            field(
+                &format!("Model {}", market.team_a),
+                format!(
+                    "{} [{} - {}]",
+                    percent(signal.team_a_win_prob),
+                    percent(signal.team_a_prob_lower),
+                    percent(signal.team_a_prob_upper)
+                ),
+            ),
+            field(
+                "Net edge",
+                signal
+                    .net_edge
+                    .map(|e| format!("{:+.1}%", e * 100.0))
+                    .unwrap_or_else(|| "n/a".to_string()),
+            ),
+            field(
+                "Liquidity",
+                format!("${:.0} on {}", market.liquidity, market.venue),
+            ),
+        ]);
+        embed["footer"] = json!({
+            "text": format!(
+                "Signal #{} | Match {} | {}",
+                signal.id.unwrap_or_default(),
+                signal.match_id,
+                market.condition_id
+            ),
+        });
+    }
+
+    json!({ "embeds": [embed] })
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, Mailboxes};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{Notification, NotificationSink, Priority};
+use crate::config::Config;
+
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS (usually port 587)
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    Tls,
+    /// Unencrypted, for local relays only
+    None,
+}
+
+impl SmtpTls {
+    /// Name used in config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmtpTls::StartTls => "starttls",
+            SmtpTls::Tls => "tls",
+            SmtpTls::None => "none",
+        }
+    }
+}
+
+impl fmt::Display for SmtpTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SmtpTls {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "starttls" => Ok(SmtpTls::StartTls),
+            "tls" => Ok(SmtpTls::Tls),
+            "none" => Ok(SmtpTls::None),
+            other => anyhow::bail!("Unknown SMTP TLS mode: {}", other),
+        }
+    }
+}
+
+/// Emails notifications through an SMTP relay
+pub struct EmailSink {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Mailboxes,
+}
+
+impl EmailSink {
+    /// Create a sink from the `SMTP_*` and `EMAIL_*` settings; `None` when
+    /// `SMTP_HOST` is unset
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(host) = &config.smtp_host else {
+            return Ok(None);
+        };
+
+        let builder = match config.smtp_tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        let builder = match (&config.smtp_username, &config.smtp_password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        let from = config
+            .email_from
+            .as_deref()
+            .context("EMAIL_FROM is not set")?
+            .parse()
+            .context("EMAIL_FROM is not an email address")?;
+        let to: Mailboxes = config
+            .email_to
+            .join(",")
+            .parse()
+            .context("EMAIL_TO is not a list of email addresses")?;
+        if to.iter().next().is_none() {
+            anyhow::bail!("EMAIL_TO is not set");
+        }
+
+        Ok(Some(Self {
+            transport: builder.port(config.smtp_port).build(),
+            from,
+            to,
+        }))
+    }
+}
+
+#[async_trait]
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let subject = match notification.priority {
+            Priority::Urgent => format!("[esport-signal] URGENT: {}", notification.title),
+            Priority::Normal => format!("[esport-signal] {}", notification.title),
+        };
+
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for to in self.to.iter() {
+            builder = builder.to(to.clone());
+        }
+        let message = builder
+            .body(notification.body.clone())
+            .context("Failed to build email")?;
+
+        self.transport
+            .send(message)
+            .await
+            .context("SMTP delivery failed")?;
+
+        Ok(())
+    }
+}
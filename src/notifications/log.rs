@@ -0,0 +1,24 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+use super::{Notification, NotificationSink, Priority};
+
+/// Writes notifications to the application log
+pub struct LogSink;
+
+#[async_trait]
+impl NotificationSink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        match notification.priority {
+            Priority::Urgent => warn!("NOTIFY | {} | {}", notification.title, notification.body),
+            Priority::Normal => info!("NOTIFY | {} | {}", notification.title, notification.body),
+        }
+
+        Ok(())
+    }
+}
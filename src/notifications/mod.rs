@@ -0,0 +1,375 @@
+pub mod digest;
+pub mod discord;
+pub mod email;
+pub mod log;
+pub mod push;
+pub mod routing;
+pub mod slack;
+pub mod telegram;
+pub mod template;
+pub mod throttle;
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, error, warn};
+
+use crate::config::Config;
+use crate::models::{Market, Signal, SignalStrength};
+use crate::trading::RiskLimits;
+
+pub use self::digest::{build_digest, next_digest_at, DIGEST_MIN_STRENGTH};
+pub use self::discord::DiscordSink;
+pub use self::email::{EmailSink, SmtpTls};
+pub use self::log::LogSink;
+pub use self::push::{NtfySink, PushoverSink};
+pub use self::routing::{RoutingRule, RoutingRules, Targets};
+pub use self::slack::SlackSink;
+pub use self::telegram::TelegramSink;
+pub use self::template::Templates;
+pub use self::throttle::{SinkRateLimits, Suppressed, Throttle, ThrottleSettings};
+
+/// How urgently a notification needs attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Normal,
+    Urgent,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Normal => "Normal",
+            Priority::Urgent => "Urgent",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A stored signal and the market it was priced against
+#[derive(Debug, Clone)]
+pub struct SignalAlert {
+    pub signal: Signal,
+    pub market: Market,
+    /// The match involves a team or league on the watchlist
+    pub watched: bool,
+}
+
+/// A message to deliver to the operator
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub priority: Priority,
+    pub title: String,
+    pub body: String,
+    /// Set for signal alerts, which are routed by the configured rules
+    pub signal: Option<Arc<SignalAlert>>,
+}
+
+impl Notification {
+    pub fn new(priority: Priority, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            priority,
+            title: title.into(),
+            body: body.into(),
+            signal: None,
+        }
+    }
+
+    /// Alert for a stored signal; Very Strong and escalated signals are urgent
+    pub fn for_signal(signal: Signal, market: Market) -> Self {
+        let priority = if signal.escalated || signal.strength >= SignalStrength::VeryStrong {
+            Priority::Urgent
+        } else {
+            Priority::Normal
+        };
+        let title = format!(
+            "{}{} {} | {} vs {}",
+            if signal.escalated { "ESCALATED " } else { "" },
+            signal.strength,
+            signal.signal_type,
+            market.team_a,
+            market.team_b
+        );
+        let body = format!(
+            "Match {} | {} | Market: {:.1}% | Model: {} | Net edge: {}{}{}",
+            signal.match_id,
+            market.question,
+            signal.market_team_a_odds * 100.0,
+            signal
+                .team_a_win_prob
+                .map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_else(|| "n/a".to_string()),
+            signal
+                .net_edge
+                .map(|e| format!("{:+.1}%", e * 100.0))
+                .unwrap_or_else(|| "n/a".to_string()),
+            signal
+                .fight_winner
+                .as_ref()
+                .map(|team| format!(" | Fight won by {}", team))
+                .unwrap_or_default(),
+            signal
+                .reason
+                .as_ref()
+                .map(|reason| format!(" | {}", reason))
+                .unwrap_or_default(),
+        );
+
+        Self {
+            priority,
+            title,
+            body,
+            signal: Some(Arc::new(SignalAlert {
+                signal,
+                market,
+                watched: false,
+            })),
+        }
+    }
+
+    /// Mark a signal alert as on the watchlist, for `watched` routing rules
+    pub fn watched(mut self, watched: bool) -> Self {
+        if let Some(alert) = &mut self.signal {
+            Arc::make_mut(alert).watched = watched;
+        }
+        self
+    }
+
+    /// Hash identifying repeats of this notification
+    ///
+    /// Signal alerts repeat when the market, strength, type and side match,
+    /// however the prices moved; other notifications when the text matches.
+    pub fn dedup_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        match &self.signal {
+            Some(alert) => {
+                let signal = &alert.signal;
+                signal.market_condition_id.hash(&mut hasher);
+                signal.strength.hash(&mut hasher);
+                signal.signal_type.hash(&mut hasher);
+                signal.escalated.hash(&mut hasher);
+                signal
+                    .net_edge
+                    .map(|e| e.is_sign_positive())
+                    .hash(&mut hasher);
+            }
+            None => {
+                self.priority.hash(&mut hasher);
+                self.title.hash(&mut hasher);
+                self.body.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+/// A delivery channel for notifications
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Deliver a notification
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Fans notifications out to the configured sinks
+#[derive(Clone, Default)]
+pub struct Notifier {
+    sinks: Vec<Arc<dyn NotificationSink>>,
+    /// Which sinks signal alerts go to
+    rules: RoutingRules,
+    /// Rate limits and dedup shared by clones; off when unset
+    throttle: Option<Arc<Mutex<Throttle>>>,
+    /// Per-sink text for signal alerts; their own title and body when unset
+    templates: Option<Arc<Templates>>,
+    /// Sinks that get a daily digest instead of signal alerts
+    digest_sinks: Vec<String>,
+}
+
+impl Notifier {
+    /// Create a notifier with the given sinks
+    pub fn new(sinks: Vec<Arc<dyn NotificationSink>>) -> Self {
+        Self {
+            sinks,
+            rules: RoutingRules::default(),
+            throttle: None,
+            templates: None,
+            digest_sinks: Vec::new(),
+        }
+    }
+
+    /// Create a notifier with the sinks and routing rules in config
+    pub fn from_config(config: &Config) -> Self {
+        let mut sinks: Vec<Arc<dyn NotificationSink>> = vec![Arc::new(LogSink)];
+
+        match EmailSink::from_config(config) {
+            Ok(Some(email)) => sinks.push(Arc::new(email)),
+            Ok(None) => {}
+            Err(e) => warn!("Email notifications disabled: {:#}", e),
+        }
+        if let Some(url) = &config.slack_webhook_url {
+            sinks.push(Arc::new(SlackSink::new(url)));
+        }
+        if let Some(topic) = &config.ntfy_topic {
+            sinks.push(Arc::new(NtfySink::new(
+                &config.ntfy_server,
+                topic,
+                config.ntfy_token.clone(),
+            )));
+        }
+        if let (Some(token), Some(user)) = (&config.pushover_token, &config.pushover_user) {
+            sinks.push(Arc::new(PushoverSink::new(token, user)));
+        }
+        if let (Some(token), Some(chat)) = (&config.telegram_bot_token, &config.telegram_chat_id) {
+            sinks.push(Arc::new(TelegramSink::new(token, chat)));
+        }
+        if let Some(url) = &config.discord_webhook_url {
+            sinks.push(Arc::new(DiscordSink::new(url)));
+        }
+
+        let limits = RiskLimits::from_config(config);
+        let template_dir = config.notify_template_dir.as_deref().map(Path::new);
+        let templates = Templates::load(template_dir, limits).unwrap_or_else(|e| {
+            warn!("Using default notification templates: {:#}", e);
+            Templates::load(None, limits).expect("built-in templates are valid")
+        });
+
+        Self::new(sinks)
+            .with_rules(config.notify_rules.clone())
+            .with_throttle(ThrottleSettings {
+                rate_limit: config.notify_rate_limit,
+                sink_rate_limits: config.notify_sink_rate_limits.clone(),
+                dedup_window: Duration::from_secs(config.notify_dedup_window),
+            })
+            .with_templates(templates)
+            .with_digest_sinks(config.notify_digest_sinks.clone())
+    }
+
+    /// Route signal alerts by these rules
+    pub fn with_rules(mut self, rules: RoutingRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Rate limit and deduplicate per sink
+    pub fn with_throttle(mut self, settings: ThrottleSettings) -> Self {
+        self.throttle = Some(Arc::new(Mutex::new(Throttle::new(settings))));
+        self
+    }
+
+    /// Render signal alerts with these templates
+    pub fn with_templates(mut self, templates: Templates) -> Self {
+        self.templates = Some(Arc::new(templates));
+        self
+    }
+
+    /// Send these sinks a daily digest instead of signal alerts
+    pub fn with_digest_sinks(mut self, sinks: Vec<String>) -> Self {
+        self.digest_sinks = sinks;
+        self
+    }
+
+    /// Whether any sink is in digest mode
+    pub fn has_digest_sinks(&self) -> bool {
+        !self.digest_sinks.is_empty()
+    }
+
+    /// Names of the configured sinks
+    pub fn sink_names(&self) -> impl Iterator<Item = &str> {
+        self.sinks.iter().map(|sink| sink.name())
+    }
+
+    /// Send to the sinks the notification is routed to; delivery failures
+    /// are logged, not returned
+    ///
+    /// Signal alerts go where the first matching rule says, or nowhere,
+    /// skipping digest sinks; everything else goes to every sink.
+    pub async fn notify(&self, notification: Notification) {
+        let targets = match &notification.signal {
+            Some(alert) => self.rules.route(alert),
+            None => &Targets::All,
+        };
+        let is_alert = notification.signal.is_some();
+
+        for sink in self.sinks.iter().filter(|sink| {
+            targets.includes(sink.name()) && !(is_alert && self.is_digest_sink(sink.name()))
+        }) {
+            if let Some(throttle) = &self.throttle {
+                let checked = throttle.lock().expect("throttle lock poisoned").check(
+                    sink.name(),
+                    &notification,
+                    Instant::now(),
+                );
+                if let Err(reason) = checked {
+                    debug!(
+                        "Skipped notification via {} ({}): {}",
+                        sink.name(),
+                        reason,
+                        notification.title
+                    );
+                    continue;
+                }
+            }
+
+            let rendered = match (&notification.signal, &self.templates) {
+                (Some(alert), Some(templates)) => match templates.render(sink.name(), alert) {
+                    Ok((title, body)) => Some(Notification {
+                        title,
+                        body,
+                        ..notification.clone()
+                    }),
+                    Err(e) => {
+                        warn!("{:#}", e);
+                        None
+                    }
+                },
+                _ => None,
+            };
+
+            if let Err(e) = sink.send(rendered.as_ref().unwrap_or(&notification)).await {
+                error!("Failed to send notification via {}: {}", sink.name(), e);
+            }
+        }
+    }
+
+    /// Send a digest to the digest sinks only, bypassing rate limits
+    pub async fn send_digest(&self, notification: &Notification) {
+        for sink in self
+            .sinks
+            .iter()
+            .filter(|sink| self.is_digest_sink(sink.name()))
+        {
+            if let Err(e) = sink.send(notification).await {
+                error!("Failed to send digest via {}: {}", sink.name(), e);
+            }
+        }
+    }
+
+    fn is_digest_sink(&self, name: &str) -> bool {
+        self.digest_sinks.iter().any(|sink| sink == name)
+    }
+}
+
+/// `text` cut to `max` characters, ending in an ellipsis when cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let end = text
+        .char_indices()
+        .nth(max - 1)
+        .map_or(text.len(), |(i, _)| i);
+    format!("{}…", &text[..end])
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{Notification, NotificationSink, Priority};
+
+const PUSHOVER_API_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// Publishes notifications to an ntfy topic
+pub struct NtfySink {
+    client: reqwest::Client,
+    server: String,
+    topic: String,
+    /// Access token for protected topics
+    token: Option<String>,
+}
+
+impl NtfySink {
+    pub fn new(server: impl Into<String>, topic: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            server: server.into(),
+            topic: topic.into(),
+            token,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for NtfySink {
+    fn name(&self) -> &str {
+        "ntfy"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // ntfy priorities run 1-5; urgent (5) bypasses do-not-disturb
+        let priority = match notification.priority {
+            Priority::Urgent => 5,
+            Priority::Normal => 3,
+        };
+
+        // JSON publishing keeps non-ASCII team names out of HTTP headers
+        let mut request = self.client.post(&self.server).json(&json!({
+            "topic": self.topic,
+            "title": notification.title,
+            "message": notification.body,
+            "priority": priority,
+        }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        request
+            .send()
+            .await
+            .context("ntfy request failed")?
+            .error_for_status()
+            .context("ntfy rejected the message")?;
+
+        Ok(())
+    }
+}
+
+/// Sends notifications through the Pushover API
+pub struct PushoverSink {
+    client: reqwest::Client,
+    token: String,
+    user: String,
+}
+
+impl PushoverSink {
+    pub fn new(token: impl Into<String>, user: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token: token.into(),
+            user: user.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for PushoverSink {
+    fn name(&self) -> &str {
+        "pushover"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // High priority (1) bypasses quiet hours; emergency (2) would need acknowledging
+        let priority = match notification.priority {
+            Priority::Urgent => 1,
+            Priority::Normal => 0,
+        };
+
+        self.client
+            .post(PUSHOVER_API_URL)
+            .json(&json!({
+                "token": self.token,
+                "user": self.user,
+                "title": notification.title,
+                "message": notification.body,
+                "priority": priority,
+            }))
+            .send()
+            .await
+            .context("Pushover request failed")?
+            .error_for_status()
+            .context("Pushover rejected the message")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,473 @@
+//! Rules deciding which sinks a signal notification goes to
+//!
+//! A rule is `<conditions> -> <sinks>`, e.g.
+//! `strength >= VeryStrong and liquidity > 5000 -> log + email` or
+//! `escalated = true -> all`, `tier >= professional -> slack` or
+//! `watched = true and strength >= Moderate -> ntfy`.
+//! Conditions are `*` or `field op value` joined by `and`; sinks are joined
+//! by `+`, or are `all` / `none`. Rules are separated by `;` (or `,`, as
+//! config-file arrays are joined with commas) and the first match wins.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::SignalAlert;
+use crate::models::{LeagueTier, SignalStrength, SignalType, Venue};
+
+/// Signal attribute a condition tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Strength,
+    SignalType,
+    /// Market liquidity in USD
+    Liquidity,
+    /// Absolute net edge
+    Edge,
+    Venue,
+    Escalated,
+    /// Match is on the watchlist
+    Watched,
+    /// League tier of the match
+    Tier,
+}
+
+impl Field {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Field::Strength => "strength",
+            Field::SignalType => "type",
+            Field::Liquidity => "liquidity",
+            Field::Edge => "edge",
+            Field::Venue => "venue",
+            Field::Escalated => "escalated",
+            Field::Watched => "watched",
+            Field::Tier => "tier",
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strength" => Ok(Field::Strength),
+            "type" | "signal_type" => Ok(Field::SignalType),
+            "liquidity" => Ok(Field::Liquidity),
+            "edge" | "net_edge" => Ok(Field::Edge),
+            "venue" => Ok(Field::Venue),
+            "escalated" => Ok(Field::Escalated),
+            "watched" => Ok(Field::Watched),
+            "tier" | "league_tier" => Ok(Field::Tier),
+            other => anyhow::bail!("Unknown rule field: {}", other),
+        }
+    }
+}
+
+/// Comparison in a `field op value` condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    /// Operators, longest first so `>=` isn't read as `>`
+    const ALL: [(&'static str, Op); 7] = [
+        (">=", Op::Ge),
+        ("<=", Op::Le),
+        ("!=", Op::Ne),
+        ("==", Op::Eq),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+        ("=", Op::Eq),
+    ];
+
+    /// Split `field op value` at its first operator
+    pub(crate) fn split(condition: &str) -> Option<(&str, Op, &str)> {
+        let (index, symbol, op) = Op::ALL
+            .iter()
+            .filter_map(|(symbol, op)| condition.find(symbol).map(|i| (i, *symbol, *op)))
+            .min_by_key(|(i, _, _)| *i)?;
+        Some((
+            condition[..index].trim(),
+            op,
+            condition[index + symbol.len()..].trim(),
+        ))
+    }
+
+    /// Whether the operator needs ordered values
+    pub(crate) fn is_ordered(&self) -> bool {
+        matches!(self, Op::Gt | Op::Ge | Op::Lt | Op::Le)
+    }
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        }
+    }
+
+    /// Apply to unordered values, which only support `=` and `!=`
+    pub(crate) fn equals(&self, equal: bool) -> bool {
+        match self {
+            Op::Ne => !equal,
+            _ => equal,
+        }
+    }
+
+    pub(crate) fn compare<T: PartialOrd>(&self, actual: T, expected: T) -> bool {
+        match self {
+            Op::Eq => actual == expected,
+            Op::Ne => actual != expected,
+            Op::Gt => actual > expected,
+            Op::Ge => actual >= expected,
+            Op::Lt => actual < expected,
+            Op::Le => actual <= expected,
+        }
+    }
+}
+
+/// Right-hand side of a condition, parsed for its field
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Strength(SignalStrength),
+    SignalType(SignalType),
+    Number(f64),
+    Venue(Venue),
+    Bool(bool),
+    Tier(LeagueTier),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Strength(strength) => write!(f, "{}", strength),
+            Value::SignalType(signal_type) => write!(f, "{}", signal_type),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Venue(venue) => write!(f, "{}", venue),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Tier(tier) => write!(f, "{}", tier),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+impl Condition {
+    fn matches(&self, alert: &SignalAlert) -> bool {
+        match self.value {
+            Value::Strength(expected) => self.op.compare(alert.signal.strength, expected),
+            Value::SignalType(expected) => self.op.equals(alert.signal.signal_type == expected),
+            Value::Venue(expected) => self.op.equals(alert.market.venue == expected),
+            Value::Bool(expected) => {
+                let actual = match self.field {
+                    Field::Watched => alert.watched,
+                    _ => alert.signal.escalated,
+                };
+                self.op.equals(actual == expected)
+            }
+            // Matches without a known tier fail every tier condition
+            Value::Tier(expected) => snapshot_tier(&alert.signal.match_snapshot)
+                .is_some_and(|actual| self.op.compare(actual, expected)),
+            Value::Number(expected) => {
+                let actual = match self.field {
+                    Field::Liquidity => Some(alert.market.liquidity),
+                    Field::Edge => alert.signal.net_edge.map(f64::abs),
+                    _ => None,
+                };
+                actual.is_some_and(|actual| self.op.compare(actual, expected))
+            }
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, op, raw) =
+            Op::split(s).with_context(|| format!("Condition has no operator: {}", s))?;
+        let field: Field = field.parse()?;
+
+        let value = match field {
+            Field::Strength => Value::Strength(raw.parse()?),
+            Field::SignalType => Value::SignalType(raw.parse()?),
+            Field::Venue => Value::Venue(raw.parse()?),
+            Field::Escalated | Field::Watched => Value::Bool(
+                raw.parse()
+                    .with_context(|| format!("{} needs true or false: {}", field.as_str(), raw))?,
+            ),
+            Field::Tier => Value::Tier(raw.parse()?),
+            Field::Liquidity | Field::Edge => Value::Number(
+                raw.parse()
+                    .with_context(|| format!("{} needs a number: {}", field.as_str(), raw))?,
+            ),
+        };
+
+        if op.is_ordered()
+            && matches!(
+                field,
+                Field::SignalType | Field::Venue | Field::Escalated | Field::Watched
+            )
+        {
+            anyhow::bail!("{} only supports = and !=", field.as_str());
+        }
+
+        Ok(Self { field, op, value })
+    }
+}
+
+/// League tier from a signal's match snapshot, without parsing the rest
+fn snapshot_tier(snapshot: &str) -> Option<LeagueTier> {
+    #[derive(Deserialize)]
+    struct Snapshot {
+        league_tier: Option<LeagueTier>,
+    }
+
+    serde_json::from_str::<Snapshot>(snapshot).ok()?.league_tier
+}
+
+/// Sinks a matching rule delivers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Targets {
+    /// Every configured sink
+    All,
+    /// Stored in the database only
+    None,
+    Sinks(Vec<String>),
+}
+
+impl Targets {
+    /// Whether the named sink should receive the notification
+    pub fn includes(&self, sink: &str) -> bool {
+        match self {
+            Targets::All => true,
+            Targets::None => false,
+            Targets::Sinks(sinks) => sinks.iter().any(|s| s == sink),
+        }
+    }
+}
+
+/// One `conditions -> sinks` rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutingRule {
+    /// All must hold; empty matches every signal
+    conditions: Vec<Condition>,
+    pub targets: Targets,
+}
+
+impl RoutingRule {
+    pub fn matches(&self, alert: &SignalAlert) -> bool {
+        self.conditions.iter().all(|c| c.matches(alert))
+    }
+}
+
+impl FromStr for RoutingRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (conditions, targets) = s
+            .split_once("->")
+            .with_context(|| format!("Rule is missing `->`: {}", s))?;
+
+        let conditions = match conditions.trim() {
+            "*" | "" => Vec::new(),
+            conditions => conditions
+                .split(" and ")
+                .map(str::parse)
+                .collect::<Result<_>>()
+                .with_context(|| format!("Invalid rule: {}", s.trim()))?,
+        };
+
+        let targets = match targets.trim() {
+            "all" => Targets::All,
+            "none" => Targets::None,
+            sinks => Targets::Sinks(
+                sinks
+                    .split('+')
+                    .map(|sink| sink.trim().to_string())
+                    .filter(|sink| !sink.is_empty())
+                    .collect(),
+            ),
+        };
+        if targets == Targets::Sinks(Vec::new()) {
+            anyhow::bail!("Rule has no sinks: {}", s.trim());
+        }
+
+        Ok(Self {
+            conditions,
+            targets,
+        })
+    }
+}
+
+impl fmt::Display for RoutingRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.conditions.is_empty() {
+            f.write_str("*")?;
+        }
+        for (i, c) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" and ")?;
+            }
+            write!(f, "{} {} {}", c.field.as_str(), c.op.as_str(), c.value)?;
+        }
+
+        match &self.targets {
+            Targets::All => f.write_str(" -> all"),
+            Targets::None => f.write_str(" -> none"),
+            Targets::Sinks(sinks) => write!(f, " -> {}", sinks.join(" + ")),
+        }
+    }
+}
+
+/// Ordered routing rules for signal notifications; the first match wins
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoutingRules(pub Vec<RoutingRule>);
+
+impl RoutingRules {
+    /// Sinks for a signal; signals no rule matches aren't sent anywhere
+    pub fn route(&self, alert: &SignalAlert) -> &Targets {
+        self.0
+            .iter()
+            .find(|rule| rule.matches(alert))
+            .map(|rule| &rule.targets)
+            .unwrap_or(&Targets::None)
+    }
+
+    /// Sink names the rules refer to
+    pub fn sink_names(&self) -> impl Iterator<Item = &str> {
+        self.0
+            .iter()
+            .flat_map(|rule| match &rule.targets {
+                Targets::Sinks(sinks) => sinks.as_slice(),
+                _ => &[],
+            })
+            .map(String::as_str)
+    }
+}
+
+impl FromStr for RoutingRules {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split([';', ','])
+            .filter(|rule| !rule.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for RoutingRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<String> = self.0.iter().map(|rule| rule.to_string()).collect();
+        f.write_str(&rules.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Market, Signal};
+
+    fn alert(strength: SignalStrength, liquidity: f64) -> SignalAlert {
+        SignalAlert {
+            signal: Signal {
+                id: Some(1),
+                market_condition_id: "0xa".to_string(),
+                match_id: 7,
+                market_team_a_odds: 0.4,
+                team_a_win_prob: Some(0.6),
+                edge: Some(0.2),
+                net_edge: Some(-0.12),
+                strength,
+                signal_type: SignalType::TowerKill,
+                ..Default::default()
+            },
+            market: Market {
+                condition_id: "0xa".to_string(),
+                venue: Venue::Polymarket,
+                question: "Dota 2: Team Spirit vs OG".to_string(),
+                team_a: "Team Spirit".to_string(),
+                team_b: "OG".to_string(),
+                team_a_odds: 0.4,
+                team_b_odds: 0.6,
+                opening_team_a_odds: 0.45,
+                opened_at: None,
+                team_a_bid: None,
+                team_a_ask: None,
+                liquidity,
+                volume_24h: None,
+                end_date: None,
+                active: true,
+                url: None,
+                series_format: None,
+                game_number: None,
+                qualifiers: Vec::new(),
+                outcomes: Vec::new(),
+            },
+            watched: false,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules: RoutingRules =
+            "strength >= VeryStrong and liquidity > 5000 -> telegram + discord; \
+                                   strength >= Strong -> discord, * -> none"
+                .parse()
+                .unwrap();
+
+        let route = |strength, liquidity| rules.route(&alert(strength, liquidity)).clone();
+        assert_eq!(
+            route(SignalStrength::VeryStrong, 8000.0),
+            Targets::Sinks(vec!["telegram".to_string(), "discord".to_string()])
+        );
+        assert_eq!(
+            route(SignalStrength::VeryStrong, 1000.0),
+            Targets::Sinks(vec!["discord".to_string()])
+        );
+        assert_eq!(route(SignalStrength::Moderate, 8000.0), Targets::None);
+
+        let edge: RoutingRule = "edge >= 0.1 and type = TowerKill -> all".parse().unwrap();
+        assert!(edge.matches(&alert(SignalStrength::Weak, 0.0)));
+        assert_eq!(edge.to_string(), "edge >= 0.1 and type = TowerKill -> all");
+
+        let tier: RoutingRule = "tier >= Professional -> log".parse().unwrap();
+        assert_eq!(tier.to_string(), "tier >= professional -> log");
+        let mut premium = alert(SignalStrength::Weak, 0.0);
+        premium.signal.match_snapshot = r#"{"league_tier": "premium"}"#.to_string();
+        assert!(tier.matches(&premium));
+        assert!(!tier.matches(&alert(SignalStrength::Weak, 0.0)));
+
+        let watched: RoutingRule = "watched = true and strength >= Moderate -> log"
+            .parse()
+            .unwrap();
+        let mut on_watchlist = alert(SignalStrength::Moderate, 0.0);
+        assert!(!watched.matches(&on_watchlist));
+        on_watchlist.watched = true;
+        assert!(watched.matches(&on_watchlist));
+
+        assert!("type > TowerKill -> log".parse::<RoutingRule>().is_err());
+        assert!("strength >= Huge -> log".parse::<RoutingRule>().is_err());
+        assert!("strength >= Strong".parse::<RoutingRule>().is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{Notification, NotificationSink, Priority};
+
+/// Posts notifications to a Slack incoming webhook as Block Kit messages
+pub struct SlackSink {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackSink {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url: webhook_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for SlackSink {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        self.client
+            .post(&self.webhook_url)
+            .json(&slack_message(notification))
+            .send()
+            .await
+            .context("Slack webhook request failed")?
+            .error_for_status()
+            .context("Slack webhook rejected the message")?;
+
+        Ok(())
+    }
+}
+
+/// Block Kit payload; `text` is the fallback shown in push notifications
+fn slack_message(notification: &Notification) -> Value {
+    let title = match notification.priority {
+        Priority::Urgent => format!(":rotating_light: {}", notification.title),
+        Priority::Normal => notification.title.clone(),
+    };
+
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": title, "emoji": true },
+        }),
+        json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": notification.body },
+        }),
+    ];
+
+    if let Some(alert) = &notification.signal {
+        let (signal, market) = (&alert.signal, &alert.market);
+        let percent = |p: Option<f64>| {
+            p.map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_else(|| "n/a".to_string())
+        };
+        let field = |label: &str, value: String| {
+            json!({
+                "type": "mrkdwn",
+                "text": format!("*{}*\n{}", label, value),
+            })
+        };
+
+        blocks.push(json!({
+            "type": "section",
+            "fields": [
+                field("Strength", signal.strength.to_string()),
+                field("Type", signal.signal_type.to_string()),
+                field(
+                    &format!("{} price", market.team_a),
+                    format!("{:.1}%", market.team_a_odds * 100.0),
+                ),
+                field(
+                    &format!("Model {}", market.team_a),
+                    format!(
+                        "{} [{} - {}]",
+                        percent(signal.team_a_win_prob),
+                        percent(signal.team_a_prob_lower),
+                        percent(signal.team_a_prob_upper)
+                    ),
+                ),
+                field(
+                    "Net edge",
+                    signal
+                        .net_edge
+                        .map(|e| format!("{:+.1}%", e * 100.0))
+                        .unwrap_or_else(|| "n/a".to_string()),
+                ),
+                field(
+                    "Liquidity",
+                    format!("${:.0} on {}", market.liquidity, market.venue),
+                ),
+            ],
+        }));
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{
+                "type": "mrkdwn",
+                "text": format!(
+                    "Signal #{} | Match {} | `{}`",
+                    signal.id.unwrap_or_default(),
+                    signal.match_id,
+                    market.condition_id
+                ),
+            }],
+        }));
+    }
+
+    json!({
+        "text": format!("{}: {}", notification.title, notification.body),
+        "blocks": blocks,
+    })
+}
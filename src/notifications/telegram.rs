@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{truncate, Notification, NotificationSink, Priority};
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Telegram caps a message's text at this many characters
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// Sends notifications to a Telegram chat through a bot
+pub struct TelegramSink {
+    client: reqwest::Client,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(bot_token: impl Into<String>, chat_id: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            bot_token: bot_token.into(),
+            chat_id: chat_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for TelegramSink {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // The token is part of the URL, so errors are logged without it
+        self.client
+            .post(format!(
+                "{}/bot{}/sendMessage",
+                TELEGRAM_API_URL, self.bot_token
+            ))
+            .json(&json!({
+                "chat_id": self.chat_id,
+                "text": telegram_text(notification),
+                "disable_web_page_preview": true,
+            }))
+            .send()
+            .await
+            .map_err(|e| e.without_url())
+            .context("Telegram request failed")?
+            .error_for_status()
+            .map_err(|e| e.without_url())
+            .context("Telegram rejected the message")?;
+
+        Ok(())
+    }
+}
+
+/// Plain text, so team names and reasons need no Markdown escaping
+fn telegram_text(notification: &Notification) -> String {
+    let title = match notification.priority {
+        Priority::Urgent => format!("🚨 {}", notification.title),
+        Priority::Normal => notification.title.clone(),
+    };
+    truncate(
+        &format!("{}\n\n{}", title, notification.body),
+        MAX_MESSAGE_CHARS,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_digest_is_cut_to_the_message_limit() {
+        let digest = Notification::new(Priority::Normal, "Daily digest", "é".repeat(5000));
+        let text = telegram_text(&digest);
+        assert_eq!(text.chars().count(), MAX_MESSAGE_CHARS);
+        assert!(text.starts_with("Daily digest\n\né"));
+        assert!(text.ends_with('…'));
+
+        let exact = Notification::new(Priority::Normal, "a", "b".repeat(MAX_MESSAGE_CHARS - 3));
+        assert!(!telegram_text(&exact).ends_with('…'));
+
+        let urgent = Notification::new(Priority::Urgent, "RISK LIMIT", "Stake cap hit");
+        assert_eq!(telegram_text(&urgent), "🚨 RISK LIMIT\n\nStake cap hit");
+    }
+}
@@ -0,0 +1,218 @@
+//! Title and body templates for signal alerts
+//!
+//! Alerts render the `title` and `body` templates, or `<sink>.title` and
+//! `<sink>.body` when a sink has its own. Built-in defaults can be replaced
+//! by `<name>.j2` files in `NOTIFY_TEMPLATE_DIR`. Templates see `signal`,
+//! `market`, `state` (the parsed match snapshot, if any), `stake_team`,
+//! `stake` and `sink`, plus `pct`, `edge` and `usd` filters.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use minijinja::Environment;
+use serde::Serialize;
+
+use super::SignalAlert;
+use crate::models::{LiveMatchState, Market, Signal};
+use crate::trading::RiskLimits;
+
+const DEFAULT_TITLE: &str = "{% if signal.escalated %}ESCALATED {% endif %}\
+     {{ signal.strength }} {{ signal.signal_type }} | {{ market.team_a }} vs {{ market.team_b }}";
+
+const DEFAULT_BODY: &str = "Match {{ signal.match_id }} | {{ market.question }} \
+     {% if state and state.league_name %}| {{ state.league_name }} {% endif %}\
+     | Market: {{ signal.market_team_a_odds | pct }} \
+     | Model: {{ signal.team_a_win_prob | pct }} \
+     | Net edge: {{ signal.net_edge | edge }}\
+     {% if signal.reason %} | {{ signal.reason }}{% endif %}";
+
+const DEFAULT_EMAIL_BODY: &str = r#"{% include "body" %}
+
+Market: {{ market.question }} ({{ market.condition_id }})
+Venue: {{ market.venue }} | Liquidity: {{ market.liquidity | usd }}
+{{ market.team_a }} price: {{ market.team_a_odds | pct }} | {{ market.team_b }} price: {{ market.team_b_odds | pct }}
+Model ({{ market.team_a }}): {{ signal.team_a_win_prob | pct }} [{{ signal.team_a_prob_lower | pct }} - {{ signal.team_a_prob_upper | pct }}]
+Net edge: {{ signal.net_edge | edge }}
+{% if state and state.league_name %}League: {{ state.league_name }}{% if state.league_tier %} ({{ state.league_tier }}){% endif %}
+{% endif %}{% if state %}Game: {{ state.radiant.name }} {{ state.radiant.kills }}-{{ state.dire.kills }} {{ state.dire.name }} at {{ state.game_time // 60 }} min, Radiant gold lead {{ state.gold_lead }}
+{% endif %}{% if stake %}Recommended stake: {{ stake | usd }} on {{ stake_team }}
+{% endif %}{% if market.url %}{{ market.url }}
+{% endif %}Signal #{{ signal.id }} at {{ signal.created_at }}
+"#;
+
+/// Values a template can use
+#[derive(Serialize)]
+struct AlertContext<'a> {
+    signal: &'a Signal,
+    market: &'a Market,
+    state: Option<LiveMatchState>,
+    /// Team the edge favours
+    stake_team: Option<&'a str>,
+    /// Kelly stake in USD within the per-position limit
+    stake: Option<f64>,
+    sink: &'a str,
+}
+
+/// Compiled alert templates
+pub struct Templates {
+    env: Environment<'static>,
+    limits: RiskLimits,
+}
+
+impl Templates {
+    /// Built-in templates, overridden by any `*.j2` files in `dir`
+    pub fn load(dir: Option<&Path>, limits: RiskLimits) -> Result<Self> {
+        let mut env = Environment::new();
+        env.add_filter("pct", |p: Option<f64>| {
+            p.map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_else(|| "n/a".to_string())
+        });
+        env.add_filter("edge", |e: Option<f64>| {
+            e.map(|e| format!("{:+.1}%", e * 100.0))
+                .unwrap_or_else(|| "n/a".to_string())
+        });
+        env.add_filter("usd", |v: f64| format!("${:.0}", v));
+
+        env.add_template("title", DEFAULT_TITLE)?;
+        env.add_template("body", DEFAULT_BODY)?;
+        env.add_template("email.body", DEFAULT_EMAIL_BODY)?;
+
+        if let Some(dir) = dir {
+            let entries = fs::read_dir(dir)
+                .with_context(|| format!("Failed to read template dir {}", dir.display()))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("j2") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let source = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read template {}", path.display()))?;
+                env.add_template_owned(name.to_string(), source)
+                    .with_context(|| format!("Invalid template {}", path.display()))?;
+            }
+        }
+
+        Ok(Self { env, limits })
+    }
+
+    /// Title and body of an alert for `sink`
+    pub fn render(&self, sink: &str, alert: &SignalAlert) -> Result<(String, String)> {
+        let (signal, market) = (&alert.signal, &alert.market);
+
+        // Stake the side the net edge favours
+        let (stake_team, win_prob, price) = match signal.net_edge {
+            Some(e) if e < 0.0 => (
+                &market.team_b,
+                signal.team_a_win_prob.map(|p| 1.0 - p),
+                market.team_b_odds,
+            ),
+            _ => (&market.team_a, signal.team_a_win_prob, market.team_a_odds),
+        };
+        let stake = win_prob.and_then(|p| self.limits.recommended_stake(p, price));
+
+        let context = AlertContext {
+            signal,
+            market,
+            state: serde_json::from_str(&signal.match_snapshot).ok(),
+            stake_team: stake.map(|_| stake_team.as_str()),
+            stake,
+            sink,
+        };
+
+        let title = self.render_one(sink, "title", &context)?;
+        let body = self.render_one(sink, "body", &context)?;
+        Ok((title.trim().to_string(), body))
+    }
+
+    /// Render the sink's own template, else the shared one
+    fn render_one(&self, sink: &str, part: &str, context: &AlertContext<'_>) -> Result<String> {
+        let template = self
+            .env
+            .get_template(&format!("{}.{}", sink, part))
+            .or_else(|_| self.env.get_template(part))?;
+
+        template
+            .render(context)
+            .with_context(|| format!("Failed to render {} template for {}", part, sink))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SignalStrength, SignalType, Venue};
+
+    #[test]
+    fn test_sink_template_overrides_default() {
+        let dir = std::env::temp_dir().join(format!("notify-templates-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("ntfy.body.j2"),
+            "{{ stake_team }} {{ stake | usd }} @ {{ market.team_a_odds | pct }}",
+        )
+        .unwrap();
+
+        let limits = RiskLimits {
+            bankroll: 1000.0,
+            max_stake_fraction: 0.05,
+            max_match_exposure: 100.0,
+            max_daily_exposure: 300.0,
+            max_concurrent_positions: 5,
+        };
+        let templates = Templates::load(Some(&dir), limits).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let alert = SignalAlert {
+            signal: Signal {
+                id: Some(3),
+                market_condition_id: "0xa".to_string(),
+                match_id: 7,
+                market_team_a_odds: 0.4,
+                team_a_win_prob: Some(0.55),
+                team_a_prob_lower: Some(0.5),
+                team_a_prob_upper: Some(0.6),
+                edge: Some(0.15),
+                net_edge: Some(0.12),
+                strength: SignalStrength::Strong,
+                signal_type: SignalType::TowerKill,
+                ..Default::default()
+            },
+            market: Market {
+                condition_id: "0xa".to_string(),
+                venue: Venue::Polymarket,
+                question: "Dota 2: Team Spirit vs OG".to_string(),
+                team_a: "Team Spirit".to_string(),
+                team_b: "OG".to_string(),
+                team_a_odds: 0.4,
+                team_b_odds: 0.6,
+                opening_team_a_odds: 0.45,
+                opened_at: None,
+                team_a_bid: None,
+                team_a_ask: None,
+                liquidity: 8000.0,
+                volume_24h: None,
+                end_date: None,
+                active: true,
+                url: Some("https://polymarket.com/event/spirit-og".to_string()),
+                series_format: None,
+                game_number: None,
+                qualifiers: Vec::new(),
+                outcomes: Vec::new(),
+            },
+            watched: false,
+        };
+
+        let (title, body) = templates.render("ntfy", &alert).unwrap();
+        assert_eq!(title, "Strong TowerKill | Team Spirit vs OG");
+        assert_eq!(body, "Team Spirit $50 @ 40.0%");
+
+        let (_, email) = templates.render("email", &alert).unwrap();
+        assert!(email.starts_with("Match 7 | Dota 2: Team Spirit vs OG | Market: 40.0%"));
+        assert!(email.contains("Recommended stake: $50 on Team Spirit"));
+        assert!(email.contains("https://polymarket.com/event/spirit-og"));
+    }
+}
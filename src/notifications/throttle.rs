@@ -0,0 +1,181 @@
+//! Per-sink rate limits and duplicate suppression
+//!
+//! A teamfight can produce a signal every poll with near-identical content;
+//! each sink drops repeats of the same alert within the dedup window and
+//! caps how many notifications it sends per minute.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use super::{Notification, Priority};
+
+/// Window the per-minute rate limit is counted over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Per-sink overrides of the notification rate limit, as `sink=n` pairs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SinkRateLimits(pub BTreeMap<String, u32>);
+
+impl FromStr for SinkRateLimits {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (sink, limit) = pair
+                    .split_once('=')
+                    .with_context(|| format!("Expected sink=limit: {}", pair))?;
+                let limit = limit
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Invalid rate limit: {}", pair))?;
+                Ok((sink.trim().to_string(), limit))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for SinkRateLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self
+            .0
+            .iter()
+            .map(|(sink, limit)| format!("{}={}", sink, limit))
+            .collect();
+        f.write_str(&pairs.join(","))
+    }
+}
+
+/// Limits applied to every sink
+#[derive(Debug, Clone, Default)]
+pub struct ThrottleSettings {
+    /// Notifications per sink per minute; 0 is unlimited
+    pub rate_limit: u32,
+    /// Overrides of `rate_limit` by sink name
+    pub sink_rate_limits: SinkRateLimits,
+    /// Identical notifications to a sink within this window are dropped
+    pub dedup_window: Duration,
+}
+
+/// Why a notification wasn't sent to a sink
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suppressed {
+    Duplicate,
+    RateLimited,
+}
+
+impl fmt::Display for Suppressed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Suppressed::Duplicate => f.write_str("duplicate"),
+            Suppressed::RateLimited => f.write_str("rate limited"),
+        }
+    }
+}
+
+/// Send history per sink
+#[derive(Debug)]
+pub struct Throttle {
+    settings: ThrottleSettings,
+    /// Send times within the last minute, by sink
+    sent: HashMap<String, VecDeque<Instant>>,
+    /// Last send of each dedup key, by sink
+    seen: HashMap<(String, u64), Instant>,
+}
+
+impl Throttle {
+    pub fn new(settings: ThrottleSettings) -> Self {
+        Self {
+            settings,
+            sent: HashMap::new(),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Record a send to `sink` at `now`, or say why it should be skipped
+    ///
+    /// Urgent notifications are still deduplicated but never rate limited.
+    pub fn check(
+        &mut self,
+        sink: &str,
+        notification: &Notification,
+        now: Instant,
+    ) -> Result<(), Suppressed> {
+        let dedup_window = self.settings.dedup_window;
+        self.seen
+            .retain(|_, last| now.duration_since(*last) < dedup_window);
+
+        let key = (sink.to_string(), notification.dedup_key());
+        if self.seen.contains_key(&key) {
+            return Err(Suppressed::Duplicate);
+        }
+
+        let limit = self
+            .settings
+            .sink_rate_limits
+            .0
+            .get(sink)
+            .copied()
+            .unwrap_or(self.settings.rate_limit);
+        let sent = self.sent.entry(sink.to_string()).or_default();
+        while sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            sent.pop_front();
+        }
+        if limit > 0 && sent.len() >= limit as usize && notification.priority != Priority::Urgent {
+            return Err(Suppressed::RateLimited);
+        }
+
+        sent.push_back(now);
+        if !dedup_window.is_zero() {
+            self.seen.insert(key, now);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_and_rate_limit_per_sink() {
+        let mut throttle = Throttle::new(ThrottleSettings {
+            rate_limit: 2,
+            sink_rate_limits: "log=0".parse().unwrap(),
+            dedup_window: Duration::from_secs(300),
+        });
+        let start = Instant::now();
+        let note = |body: &str| Notification::new(Priority::Normal, "Title", body);
+
+        assert_eq!(throttle.check("slack", &note("a"), start), Ok(()));
+        assert_eq!(
+            throttle.check("slack", &note("a"), start),
+            Err(Suppressed::Duplicate)
+        );
+        assert_eq!(throttle.check("email", &note("a"), start), Ok(()));
+
+        assert_eq!(throttle.check("slack", &note("b"), start), Ok(()));
+        assert_eq!(
+            throttle.check("slack", &note("c"), start),
+            Err(Suppressed::RateLimited)
+        );
+        let urgent = Notification::new(Priority::Urgent, "Title", "c");
+        assert_eq!(throttle.check("slack", &urgent, start), Ok(()));
+        for body in ["c", "d", "e"] {
+            assert_eq!(throttle.check("log", &note(body), start), Ok(()));
+        }
+
+        let later = start + Duration::from_secs(301);
+        assert_eq!(throttle.check("slack", &note("a"), later), Ok(()));
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use tracing::info;
+
+use crate::db::HistoricalMatch;
+use crate::matching::TeamResolver;
+
+/// Rating assigned to teams with no history
+const INITIAL_RATING: f64 = 1500.0;
+
+/// Rating change per game for a fully unexpected result
+const K_FACTOR: f64 = 32.0;
+
+/// Elo ratings per team, keyed by normalized team name
+#[derive(Debug, Clone, Default)]
+pub struct EloRatings {
+    ratings: HashMap<String, f64>,
+    games: HashMap<String, u32>,
+}
+
+impl EloRatings {
+    /// Create an empty rating table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay historical matches in chronological order
+    pub fn from_matches(matches: &[HistoricalMatch], resolver: &TeamResolver) -> Self {
+        let mut ordered: Vec<&HistoricalMatch> = matches
+            .iter()
+            .filter(|m| m.radiant_team.is_some() && m.dire_team.is_some())
+            .collect();
+        ordered.sort_by_key(|m| (m.start_time, m.match_id));
+
+        let mut ratings = Self::new();
+
+        for m in ordered {
+            if let (Some(radiant), Some(dire)) = (&m.radiant_team, &m.dire_team) {
+                ratings.record_result(
+                    &resolver.normalize(radiant),
+                    &resolver.normalize(dire),
+                    m.radiant_win,
+                );
+            }
+        }
+
+        info!(
+            "Computed Elo ratings for {} teams from {} matches",
+            ratings.ratings.len(),
+            matches.len()
+        );
+
+        ratings
+    }
+
+    /// Update ratings with a single game result
+    pub fn record_result(&mut self, team_a: &str, team_b: &str, team_a_won: bool) {
+        let rating_a = self.rating(team_a);
+        let rating_b = self.rating(team_b);
+
+        let expected_a = expected_score(rating_a, rating_b);
+        let score_a = if team_a_won { 1.0 } else { 0.0 };
+        let delta = K_FACTOR * (score_a - expected_a);
+
+        self.ratings.insert(team_a.to_string(), rating_a + delta);
+        self.ratings.insert(team_b.to_string(), rating_b - delta);
+
+        *self.games.entry(team_a.to_string()).or_default() += 1;
+        *self.games.entry(team_b.to_string()).or_default() += 1;
+    }
+
+    /// Current rating for a team (initial rating if unknown)
+    pub fn rating(&self, team: &str) -> f64 {
+        self.ratings.get(team).copied().unwrap_or(INITIAL_RATING)
+    }
+
+    /// Number of rated games for a team
+    pub fn games_played(&self, team: &str) -> u32 {
+        self.games.get(team).copied().unwrap_or(0)
+    }
+
+    /// Probability that team A beats team B, if both teams have history
+    pub fn win_probability(&self, team_a: &str, team_b: &str) -> Option<f64> {
+        if self.games_played(team_a) == 0 || self.games_played(team_b) == 0 {
+            return None;
+        }
+
+        Some(expected_score(self.rating(team_a), self.rating(team_b)))
+    }
+}
+
+/// Expected score of A against B under the Elo logistic curve
+pub(crate) fn expected_score(rating_a: f64, rating_b: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((rating_b - rating_a) / 400.0))
+}
@@ -0,0 +1,188 @@
+//! Team form over their most recent matches
+//!
+//! Elo moves slowly by design; form picks up a roster change or a team
+//! finding a patch before the rating catches up. It covers the win rate
+//! and the average gold lead at 20 minutes over each team's last N games.
+
+use std::collections::HashMap;
+
+use tracing::info;
+
+use crate::db::HistoricalMatch;
+use crate::matching::TeamResolver;
+use crate::model::prior::sigmoid;
+
+/// Game minute the recent gold lead is measured at
+const GOLD_LEAD_MINUTE: usize = 20;
+
+/// Log-odds per unit of win rate difference (1.0 = won all vs lost all)
+const WIN_RATE_WEIGHT: f64 = 1.5;
+
+/// Log-odds per 1k difference in average gold lead at 20 minutes
+const GOLD_LEAD_WEIGHT: f64 = 0.1;
+
+/// Form over a team's last few matches
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TeamForm {
+    /// Share of the matches won
+    pub win_rate: f64,
+
+    /// Mean gold lead at 20 minutes from the team's side; `None` when no
+    /// match reached 20 minutes with gold data
+    pub avg_gold_lead_20: Option<f64>,
+
+    /// Matches the form covers
+    pub games: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FormGame {
+    won: bool,
+    gold_lead_20: Option<f64>,
+}
+
+/// Recent form per team, keyed by normalized team name
+#[derive(Debug, Clone, Default)]
+pub struct RecentForm {
+    teams: HashMap<String, TeamForm>,
+}
+
+impl RecentForm {
+    /// Form over each team's last `last_n` historical matches
+    pub fn from_matches(
+        matches: &[HistoricalMatch],
+        resolver: &TeamResolver,
+        last_n: usize,
+    ) -> Self {
+        let mut ordered: Vec<&HistoricalMatch> = matches.iter().collect();
+        ordered.sort_by_key(|m| std::cmp::Reverse((m.start_time, m.match_id)));
+
+        let mut games: HashMap<String, Vec<FormGame>> = HashMap::new();
+        for m in ordered {
+            let gold_lead_20 = serde_json::from_str::<Vec<f64>>(&m.radiant_gold_adv)
+                .ok()
+                .and_then(|lead| lead.get(GOLD_LEAD_MINUTE).copied());
+
+            let sides = [(&m.radiant_team, true), (&m.dire_team, false)];
+            for (team, radiant) in sides {
+                let Some(team) = team else {
+                    continue;
+                };
+                let team_games = games.entry(resolver.normalize(team)).or_default();
+                if team_games.len() < last_n {
+                    team_games.push(FormGame {
+                        won: m.radiant_win == radiant,
+                        gold_lead_20: gold_lead_20.map(|lead| if radiant { lead } else { -lead }),
+                    });
+                }
+            }
+        }
+
+        let teams: HashMap<String, TeamForm> = games
+            .into_iter()
+            .map(|(team, games)| (team, TeamForm::from_games(&games)))
+            .collect();
+
+        info!(
+            "Computed recent form for {} teams over their last {} matches",
+            teams.len(),
+            last_n
+        );
+
+        Self { teams }
+    }
+
+    /// Form of a team, if it has history
+    pub fn get(&self, team: &str) -> Option<TeamForm> {
+        self.teams.get(team).copied()
+    }
+
+    /// Probability that team A beats team B from form alone, if both teams
+    /// have history
+    ///
+    /// Win rate and gold lead differences add up in log-odds; the gold term
+    /// counts only when both teams have a 20 minute figure.
+    pub fn win_probability(&self, team_a: &str, team_b: &str) -> Option<f64> {
+        let (a, b) = (self.get(team_a)?, self.get(team_b)?);
+
+        let mut logit = WIN_RATE_WEIGHT * (a.win_rate - b.win_rate);
+        if let (Some(lead_a), Some(lead_b)) = (a.avg_gold_lead_20, b.avg_gold_lead_20) {
+            logit += GOLD_LEAD_WEIGHT * (lead_a - lead_b) / 1000.0;
+        }
+
+        Some(sigmoid(logit))
+    }
+}
+
+impl TeamForm {
+    fn from_games(games: &[FormGame]) -> Self {
+        let wins = games.iter().filter(|g| g.won).count();
+        let leads: Vec<f64> = games.iter().filter_map(|g| g.gold_lead_20).collect();
+
+        Self {
+            win_rate: wins as f64 / games.len().max(1) as f64,
+            avg_gold_lead_20: (!leads.is_empty())
+                .then(|| leads.iter().sum::<f64>() / leads.len() as f64),
+            games: games.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(
+        match_id: i64,
+        radiant: &str,
+        dire: &str,
+        radiant_win: bool,
+        lead_20: i32,
+    ) -> HistoricalMatch {
+        let mut gold = vec![0; 30];
+        gold[GOLD_LEAD_MINUTE] = lead_20;
+        HistoricalMatch {
+            id: None,
+            match_id,
+            radiant_team: Some(radiant.to_string()),
+            dire_team: Some(dire.to_string()),
+            radiant_win,
+            duration: 1800,
+            radiant_gold_adv: serde_json::to_string(&gold).unwrap(),
+            radiant_xp_adv: "[]".to_string(),
+            start_time: Some(match_id),
+            league_name: None,
+            patch: None,
+            fetched_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_form_counts_only_recent_matches() {
+        let resolver = TeamResolver::new();
+        let matches = [
+            // Too old to count with last_n = 2
+            game(1, "Falcons", "Liquid", false, -9000),
+            game(2, "Falcons", "Liquid", true, 4000),
+            game(3, "Liquid", "Falcons", false, -2000),
+        ];
+        let form = RecentForm::from_matches(&matches, &resolver, 2);
+
+        let falcons = form.get(&resolver.normalize("Falcons")).unwrap();
+        assert_eq!(falcons.games, 2);
+        assert_eq!(falcons.win_rate, 1.0);
+        assert_eq!(falcons.avg_gold_lead_20, Some(3000.0));
+
+        let p = form
+            .win_probability(
+                &resolver.normalize("Falcons"),
+                &resolver.normalize("Liquid"),
+            )
+            .unwrap();
+        assert!(p > 0.8);
+        assert_eq!(
+            form.win_probability(&resolver.normalize("Falcons"), "unknown"),
+            None
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Glicko-2 ratings, kept alongside Elo for their rating deviation
+//!
+//! Elo gives a team that played once last year the same weight as one that
+//! plays every week. Glicko-2 tracks a deviation per team that shrinks with
+//! games and grows while a team is idle, so the prior can say how sure it
+//! is. Each historical match is its own rating period; idle time inflates
+//! the deviation by the team's volatility once per `RATING_PERIOD_SECS`.
+
+use std::collections::HashMap;
+
+use tracing::info;
+
+use crate::db::HistoricalMatch;
+use crate::matching::TeamResolver;
+
+/// Rating and deviation given to teams with no history
+const INITIAL_RATING: f64 = 1500.0;
+const INITIAL_DEVIATION: f64 = 350.0;
+const INITIAL_VOLATILITY: f64 = 0.06;
+
+/// Constrains volatility changes; 0.3–1.2 per the Glicko-2 paper
+const TAU: f64 = 0.5;
+
+/// Conversion between the Glicko and Glicko-2 scales
+const SCALE: f64 = 173.7178;
+
+/// Idle time over which the deviation grows by one volatility step
+const RATING_PERIOD_SECS: f64 = 7.0 * 24.0 * 3600.0;
+
+/// Convergence tolerance of the volatility iteration
+const EPSILON: f64 = 1e-6;
+
+/// Combined deviation of two teams up to which a prior is fully trusted
+const SETTLED_DEVIATION: f64 = 150.0;
+
+/// Least confidence given to a pairing of unknown or long-idle teams
+const MIN_RATING_CONFIDENCE: f64 = 0.6;
+
+/// A team's Glicko-2 state, in Glicko-2 scale
+#[derive(Debug, Clone, Copy)]
+struct Rating {
+    mu: f64,
+    phi: f64,
+    sigma: f64,
+    /// Unix start time of the team's last rated game
+    last_played: Option<i64>,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            mu: 0.0,
+            phi: INITIAL_DEVIATION / SCALE,
+            sigma: INITIAL_VOLATILITY,
+            last_played: None,
+        }
+    }
+}
+
+impl Rating {
+    /// Deviation grown by idle time up to `now`, capped at the initial value
+    fn phi_at(&self, now: Option<i64>) -> f64 {
+        let periods = match (self.last_played, now) {
+            (Some(last), Some(now)) if now > last => (now - last) as f64 / RATING_PERIOD_SECS,
+            _ => 0.0,
+        };
+        (self.phi.powi(2) + periods * self.sigma.powi(2))
+            .sqrt()
+            .min(INITIAL_DEVIATION / SCALE)
+    }
+}
+
+/// Glicko-2 ratings per team, keyed by normalized team name
+#[derive(Debug, Clone, Default)]
+pub struct GlickoRatings {
+    ratings: HashMap<String, Rating>,
+}
+
+impl GlickoRatings {
+    /// Create an empty rating table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay historical matches in chronological order
+    pub fn from_matches(matches: &[HistoricalMatch], resolver: &TeamResolver) -> Self {
+        let mut ordered: Vec<&HistoricalMatch> = matches
+            .iter()
+            .filter(|m| m.radiant_team.is_some() && m.dire_team.is_some())
+            .collect();
+        ordered.sort_by_key(|m| (m.start_time, m.match_id));
+
+        let mut ratings = Self::new();
+
+        for m in ordered {
+            if let (Some(radiant), Some(dire)) = (&m.radiant_team, &m.dire_team) {
+                ratings.record_result(
+                    &resolver.normalize(radiant),
+                    &resolver.normalize(dire),
+                    m.radiant_win,
+                    m.start_time,
+                );
+            }
+        }
+
+        info!(
+            "Computed Glicko-2 ratings for {} teams from {} matches",
+            ratings.ratings.len(),
+            matches.len()
+        );
+
+        ratings
+    }
+
+    /// Update both teams with a single game played at `start_time`
+    pub fn record_result(
+        &mut self,
+        team_a: &str,
+        team_b: &str,
+        team_a_won: bool,
+        start_time: Option<i64>,
+    ) {
+        let a = self.ratings.get(team_a).copied().unwrap_or_default();
+        let b = self.ratings.get(team_b).copied().unwrap_or_default();
+        let score_a = if team_a_won { 1.0 } else { 0.0 };
+
+        let (a_phi, b_phi) = (a.phi_at(start_time), b.phi_at(start_time));
+        let a = Rating { phi: a_phi, ..a };
+        let b = Rating { phi: b_phi, ..b };
+
+        let updated_a = update(a, b, score_a, start_time);
+        let updated_b = update(b, a, 1.0 - score_a, start_time);
+        self.ratings.insert(team_a.to_string(), updated_a);
+        self.ratings.insert(team_b.to_string(), updated_b);
+    }
+
+    /// Rating on the Elo-like scale (initial rating if unknown)
+    pub fn rating(&self, team: &str) -> f64 {
+        self.ratings
+            .get(team)
+            .map(|r| INITIAL_RATING + r.mu * SCALE)
+            .unwrap_or(INITIAL_RATING)
+    }
+
+    /// Rating deviation at unix time `now`, grown by idle time since the
+    /// team's last game (initial deviation if unknown)
+    pub fn deviation(&self, team: &str, now: i64) -> f64 {
+        self.ratings
+            .get(team)
+            .map(|r| r.phi_at(Some(now)) * SCALE)
+            .unwrap_or(INITIAL_DEVIATION)
+    }
+
+    /// Combined deviation of a pairing at unix time `now`
+    pub fn pair_deviation(&self, team_a: &str, team_b: &str, now: i64) -> f64 {
+        self.deviation(team_a, now)
+            .hypot(self.deviation(team_b, now))
+    }
+}
+
+/// How far to trust history-based estimates for a pairing with a combined
+/// rating deviation, from `MIN_RATING_CONFIDENCE` for unknown teams to 1
+/// at `SETTLED_DEVIATION` or below
+pub fn rating_confidence(pair_deviation: f64) -> f64 {
+    if pair_deviation <= SETTLED_DEVIATION {
+        return 1.0;
+    }
+    let unknown = INITIAL_DEVIATION * std::f64::consts::SQRT_2;
+    let t = ((pair_deviation - SETTLED_DEVIATION) / (unknown - SETTLED_DEVIATION)).min(1.0);
+    1.0 - t * (1.0 - MIN_RATING_CONFIDENCE)
+}
+
+/// Reduction of an opponent's impact by its deviation
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi.powi(2) / std::f64::consts::PI.powi(2)).sqrt()
+}
+
+/// One-game rating period for `player` against `opponent` (steps 3–8 of
+/// the Glicko-2 paper)
+fn update(player: Rating, opponent: Rating, score: f64, start_time: Option<i64>) -> Rating {
+    let g_j = g(opponent.phi);
+    let expected = 1.0 / (1.0 + (-g_j * (player.mu - opponent.mu)).exp());
+    let v = 1.0 / (g_j.powi(2) * expected * (1.0 - expected));
+    let delta = v * g_j * (score - expected);
+
+    let sigma = new_volatility(player.phi, player.sigma, v, delta);
+    let phi_star = (player.phi.powi(2) + sigma.powi(2)).sqrt();
+    let phi = 1.0 / (1.0 / phi_star.powi(2) + 1.0 / v).sqrt();
+
+    Rating {
+        mu: player.mu + phi.powi(2) * g_j * (score - expected),
+        phi,
+        sigma,
+        last_played: start_time.or(player.last_played),
+    }
+}
+
+/// New volatility by the Illinois algorithm (step 5 of the paper)
+fn new_volatility(phi: f64, sigma: f64, v: f64, delta: f64) -> f64 {
+    let a = (sigma.powi(2)).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta.powi(2) - phi.powi(2) - v - ex) / (2.0 * (phi.powi(2) + v + ex).powi(2))
+            - (x - a) / TAU.powi(2)
+    };
+
+    let mut big_a = a;
+    let mut big_b = if delta.powi(2) > phi.powi(2) + v {
+        (delta.powi(2) - phi.powi(2) - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+
+    let mut f_a = f(big_a);
+    let mut f_b = f(big_b);
+    while (big_b - big_a).abs() > EPSILON {
+        let c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+        let f_c = f(c);
+        if f_c * f_b <= 0.0 {
+            big_a = big_b;
+            f_a = f_b;
+        } else {
+            f_a /= 2.0;
+        }
+        big_b = c;
+        f_b = f_c;
+    }
+
+    (big_a / 2.0).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 3600;
+
+    #[test]
+    fn test_deviation_shrinks_with_games_and_grows_idle() {
+        let mut ratings = GlickoRatings::new();
+        for day in 0..20 {
+            ratings.record_result("falcons", "liquid", day % 3 != 0, Some(day * DAY));
+        }
+
+        let now = 20 * DAY;
+        assert!(ratings.rating("falcons") > ratings.rating("liquid"));
+        assert!(ratings.deviation("falcons", now) < 150.0);
+        assert_eq!(ratings.deviation("unknown", now), INITIAL_DEVIATION);
+
+        // A year off brings a team back toward unknown
+        let idle = ratings.deviation("falcons", now + 365 * DAY);
+        assert!(idle > ratings.deviation("falcons", now));
+
+        assert_eq!(rating_confidence(100.0), 1.0);
+        let unknown = ratings.pair_deviation("unknown", "new", now);
+        assert!((rating_confidence(unknown) - MIN_RATING_CONFIDENCE).abs() < 1e-9);
+    }
+}
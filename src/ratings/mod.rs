@@ -0,0 +1,9 @@
+pub mod elo;
+pub mod form;
+pub mod glicko;
+pub mod outright;
+
+pub use elo::EloRatings;
+pub use form::{RecentForm, TeamForm};
+pub use glicko::{rating_confidence, GlickoRatings};
+pub use outright::{LiveSeries, OutrightModel};
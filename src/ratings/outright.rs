@@ -0,0 +1,109 @@
+//! Tournament and group winner probabilities
+//!
+//! Each contender's strength is its Elo rating on the Bradley-Terry scale,
+//! `10^(rating / 400)`, so any two contenders split their strengths in the
+//! same ratio as their Elo head-to-head expectation. A contender playing a
+//! live series has its strength scaled by how far the live win probability
+//! moved away from that expectation. Strengths are normalized over the
+//! contenders still listed in the market.
+
+use std::collections::HashMap;
+
+use super::elo::expected_score;
+use super::EloRatings;
+
+/// A contender's series in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveSeries {
+    /// Normalized opponent name
+    pub opponent: String,
+    /// Live probability the contender wins
+    pub win_prob: f64,
+}
+
+/// Outright winner model over Elo ratings
+pub struct OutrightModel<'a> {
+    ratings: &'a EloRatings,
+}
+
+impl<'a> OutrightModel<'a> {
+    pub fn new(ratings: &'a EloRatings) -> Self {
+        Self { ratings }
+    }
+
+    /// Win probability of each contender, in the given order
+    ///
+    /// `contenders` are normalized team names and `live` maps a contender to
+    /// the series it is playing now.
+    pub fn probabilities(
+        &self,
+        contenders: &[String],
+        live: &HashMap<String, LiveSeries>,
+    ) -> Vec<f64> {
+        let strengths: Vec<f64> = contenders
+            .iter()
+            .map(|team| {
+                let rating = self.ratings.rating(team);
+                let strength = 10f64.powf(rating / 400.0);
+                match live.get(team) {
+                    Some(series) => {
+                        let expected =
+                            expected_score(rating, self.ratings.rating(&series.opponent));
+                        strength * series.win_prob.clamp(0.0, 1.0) / expected
+                    }
+                    None => strength,
+                }
+            })
+            .collect();
+
+        let total: f64 = strengths.iter().sum();
+        if total <= 0.0 {
+            return vec![1.0 / contenders.len() as f64; contenders.len()];
+        }
+        strengths.iter().map(|s| s / total).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_series_shifts_outright_probabilities() {
+        let mut ratings = EloRatings::new();
+        for _ in 0..10 {
+            ratings.record_result("spirit", "og", true);
+        }
+        let model = OutrightModel::new(&ratings);
+        let contenders = vec!["spirit".to_string(), "og".to_string(), "tundra".to_string()];
+
+        let pre = model.probabilities(&contenders, &HashMap::new());
+        assert!((pre.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(pre[0] > pre[2] && pre[2] > pre[1]);
+
+        // Head-to-head shares match the Elo expectation
+        let h2h = pre[0] / (pre[0] + pre[1]);
+        assert!((h2h - ratings.win_probability("spirit", "og").unwrap()).abs() < 1e-9);
+
+        // OG is beating Spirit live
+        let live = HashMap::from([
+            (
+                "og".to_string(),
+                LiveSeries {
+                    opponent: "spirit".to_string(),
+                    win_prob: 0.9,
+                },
+            ),
+            (
+                "spirit".to_string(),
+                LiveSeries {
+                    opponent: "og".to_string(),
+                    win_prob: 0.1,
+                },
+            ),
+        ]);
+        let during = model.probabilities(&contenders, &live);
+        assert!(during[1] > pre[1]);
+        assert!(during[0] < pre[0]);
+    }
+}
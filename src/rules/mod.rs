@@ -0,0 +1,11 @@
+//! Custom signal rules layered over the built-in model
+//!
+//! `script` runs a user's Rhai hook on every match update, which can veto
+//! the built-in signal or emit signals of its own; `trigger` holds the
+//! `if ... then signal ...` rules configured without scripting.
+
+pub mod script;
+pub mod trigger;
+
+pub use script::{CustomSignal, ScriptOutcome, SignalScript};
+pub use trigger::{SignalRule, SignalRules};
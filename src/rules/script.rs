@@ -0,0 +1,281 @@
+//! Rhai hook for custom signal rules (`SIGNAL_SCRIPT`)
+//!
+//! The script defines `fn on_update(update, market, signal)`, called for
+//! every match update once the built-in signal is scored and before it's
+//! stored. All three arguments are object maps in their JSON shapes, with
+//! `update.previous_state` added. The hook returns `()` to keep the
+//! built-in signal, `#{veto: "reason"}` to store it as Neutral with no
+//! alert, or `#{signals: [#{strength: "Strong", reason: "..."}]}` to store
+//! extra `Script` signals alongside it; a custom signal may carry its own
+//! `team_a_prob`, which replaces the model's when grading its edge.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rhai::serde::to_dynamic;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::models::{
+    Deaths, LiveMatchState, Market, MatchUpdate, Momentum, Signal, SignalStrength, SignalType,
+    Teamfight,
+};
+
+/// Function the script must define
+pub const HOOK: &str = "on_update";
+
+/// Operations one call may run before it's aborted, so a runaway loop
+/// can't stall the signal processor
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Longest string a script may build, in bytes
+const MAX_STRING_SIZE: usize = 64 * 1024;
+
+/// Most elements in one array, and most properties in one object map; the
+/// market and signal maps have a few dozen
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+/// Deepest function call nesting, so recursion can't exhaust the stack
+const MAX_CALL_LEVELS: usize = 32;
+
+/// Deepest expression nesting, at top level and inside functions
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+
+/// What the hook decided for one update
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptOutcome {
+    /// Store the built-in signal as Neutral, for this reason
+    pub veto: Option<String>,
+
+    /// Extra signals to store after the built-in one
+    pub signals: Vec<CustomSignal>,
+}
+
+/// A signal emitted by a custom rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomSignal {
+    /// `Script` or `Rule`, by where it came from
+    pub signal_type: SignalType,
+    pub strength: SignalStrength,
+    pub reason: String,
+    /// The rule's own team A probability, when it has one
+    pub team_a_prob: Option<f64>,
+    /// Whether it backs team A, when the rule picks a side; the edge is
+    /// stored pointing at that side
+    pub team_a: Option<bool>,
+}
+
+/// Match update as the script sees it
+#[derive(Serialize)]
+struct ScriptUpdate<'a> {
+    market_condition_id: &'a str,
+    market_team_a_is_radiant: bool,
+    signal_type: SignalType,
+    state: &'a LiveMatchState,
+    previous_state: Option<&'a LiveMatchState>,
+    momentum: Momentum,
+    teamfight: Option<Teamfight>,
+    deaths: Deaths,
+}
+
+impl<'a> From<&'a MatchUpdate> for ScriptUpdate<'a> {
+    fn from(update: &'a MatchUpdate) -> Self {
+        Self {
+            market_condition_id: &update.market_condition_id,
+            market_team_a_is_radiant: update.market_team_a_is_radiant,
+            signal_type: update.signal_type,
+            state: &update.state,
+            previous_state: update.previous_state.as_deref(),
+            momentum: update.momentum,
+            teamfight: update.teamfight,
+            deaths: update.deaths,
+        }
+    }
+}
+
+/// A compiled signal script
+pub struct SignalScript {
+    engine: Engine,
+    ast: AST,
+}
+
+impl SignalScript {
+    /// Compile the script at `path` and check it defines the hook
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::compile(&source).with_context(|| format!("In {}", path.display()))
+    }
+
+    /// Compile a script from source
+    pub fn compile(source: &str) -> Result<Self> {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_string_size(MAX_STRING_SIZE)
+            .set_max_array_size(MAX_COLLECTION_SIZE)
+            .set_max_map_size(MAX_COLLECTION_SIZE)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+        engine.on_print(|text| info!("Signal script: {}", text));
+        engine.on_debug(|text, _, pos| debug!("Signal script {}: {}", pos, text));
+
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow!("Signal script doesn't compile: {}", e))?;
+        let defined = ast
+            .iter_functions()
+            .any(|f| f.name == HOOK && f.params.len() == 3);
+        if !defined {
+            anyhow::bail!(
+                "Signal script must define fn {}(update, market, signal)",
+                HOOK
+            );
+        }
+
+        Ok(Self { engine, ast })
+    }
+
+    /// Run the hook for one update and its built-in signal; this blocks for
+    /// up to `MAX_OPERATIONS`, so async callers should run it off the runtime
+    pub fn run(
+        &self,
+        update: &MatchUpdate,
+        market: &Market,
+        signal: &Signal,
+    ) -> Result<ScriptOutcome> {
+        let args = (
+            to_dynamic(ScriptUpdate::from(update))?,
+            to_dynamic(market)?,
+            to_dynamic(signal)?,
+        );
+        let result: Dynamic = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, HOOK, args)
+            .map_err(|e| anyhow!("{}", e))?;
+        parse_outcome(result)
+    }
+}
+
+/// Read the hook's return value
+fn parse_outcome(result: Dynamic) -> Result<ScriptOutcome> {
+    if result.is_unit() {
+        return Ok(ScriptOutcome::default());
+    }
+    let type_name = result.type_name();
+    let mut map = result
+        .try_cast::<Map>()
+        .ok_or_else(|| anyhow!("{} must return () or a map, not {}", HOOK, type_name))?;
+
+    let veto = match map.remove("veto") {
+        Some(reason) if reason.is_string() => Some(reason.into_string().unwrap_or_default()),
+        Some(veto) if veto.as_bool() == Ok(true) => Some("vetoed by script".to_string()),
+        _ => None,
+    };
+
+    let signals = match map.remove("signals") {
+        Some(signals) => signals
+            .try_cast::<Array>()
+            .context("signals must be an array")?
+            .into_iter()
+            .map(parse_signal)
+            .collect::<Result<_>>()?,
+        None => Vec::new(),
+    };
+
+    Ok(ScriptOutcome { veto, signals })
+}
+
+/// Read one `#{strength, reason, team_a_prob}` entry
+fn parse_signal(entry: Dynamic) -> Result<CustomSignal> {
+    let mut entry = entry
+        .try_cast::<Map>()
+        .context("each custom signal must be a map")?;
+
+    let strength = entry
+        .remove("strength")
+        .and_then(|s| s.into_string().ok())
+        .context("custom signal needs a strength")?;
+    let strength: SignalStrength = strength.parse()?;
+    let reason = entry
+        .remove("reason")
+        .and_then(|r| r.into_string().ok())
+        .unwrap_or_default();
+    let team_a_prob = match entry.remove("team_a_prob") {
+        Some(p) => {
+            let p = p
+                .as_float()
+                .map_err(|_| anyhow!("team_a_prob must be a number"))?;
+            if !(0.0..=1.0).contains(&p) {
+                anyhow::bail!("team_a_prob must be within 0-1, got {}", p);
+            }
+            Some(p)
+        }
+        None => None,
+    };
+
+    Ok(CustomSignal {
+        signal_type: SignalType::Script,
+        strength,
+        reason,
+        team_a_prob,
+        team_a: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_outcomes() {
+        assert!(SignalScript::compile("fn on_update(update) { () }").is_err());
+        assert!(SignalScript::compile("fn on_update(update, market, signal) {").is_err());
+        assert!(SignalScript::compile("fn on_update(update, market, signal) { () }").is_ok());
+
+        let engine = Engine::new();
+        let outcome = |source: &str| parse_outcome(engine.eval::<Dynamic>(source).unwrap());
+
+        assert_eq!(outcome("()").unwrap(), ScriptOutcome::default());
+        assert_eq!(
+            outcome(r#"#{veto: "stand-in carry"}"#).unwrap().veto,
+            Some("stand-in carry".to_string())
+        );
+
+        let scripted = outcome(
+            r#"#{signals: [#{strength: "Strong", reason: "roshan up", team_a_prob: 0.7}]}"#,
+        )
+        .unwrap();
+        assert_eq!(scripted.veto, None);
+        assert_eq!(
+            scripted.signals,
+            vec![CustomSignal {
+                signal_type: SignalType::Script,
+                strength: SignalStrength::Strong,
+                reason: "roshan up".to_string(),
+                team_a_prob: Some(0.7),
+                team_a: None,
+            }]
+        );
+
+        assert!(outcome("42").is_err());
+        assert!(outcome(r#"#{signals: [#{strength: "Huge"}]}"#).is_err());
+        assert!(outcome(r#"#{signals: [#{strength: "Weak", team_a_prob: 1.5}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_scripts_are_bounded() {
+        let script = SignalScript::compile("fn on_update(update, market, signal) { () }").unwrap();
+        let runs = |source: &str| script.engine.eval::<Dynamic>(source).is_ok();
+
+        assert!(runs(r#"let s = "x"; for i in 0..10 { s += s; } s"#));
+        assert!(!runs(r#"let s = "x"; loop { s += s; }"#));
+        assert!(!runs("let a = []; loop { a.push(1); }"));
+        assert!(!runs(
+            "let m = #{}; let i = 0; loop { m[`k${i}`] = i; i += 1; }"
+        ));
+        assert!(!runs("fn deeper(n) { deeper(n + 1) } deeper(0)"));
+    }
+}
@@ -0,0 +1,411 @@
+//! Declarative signal rules (`SIGNAL_RULES`)
+//!
+//! A rule is `if <conditions> then signal <strength>`, e.g.
+//! `if gold_lead > 15000 and game_time > 1800 and market_odds < 0.8 then signal Strong`.
+//! Conditions are `field op value` joined by `and`, read from one team's
+//! side: each rule is checked for team A, then team B, and a match emits a
+//! `Rule` signal backing that team. Rules are separated by `;` (or `,`, as
+//! config-file arrays are joined with commas) and the first match wins.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+
+use super::CustomSignal;
+use crate::models::{Market, MatchUpdate, Signal, SignalStrength, SignalType};
+use crate::notifications::routing::Op;
+
+/// Update attribute a condition tests, from the checked team's side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Gold lead over the other team
+    GoldLead,
+    KillLead,
+    /// Towers destroyed minus towers lost
+    TowerLead,
+    /// Barracks destroyed minus barracks lost
+    BarracksLead,
+    /// Game clock in seconds
+    GameTime,
+    /// The team's market price
+    MarketOdds,
+    /// The model's win probability for the team
+    ModelProb,
+    /// Net edge on the team
+    Edge,
+    /// Market liquidity in USD
+    Liquidity,
+    SignalType,
+}
+
+impl Field {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Field::GoldLead => "gold_lead",
+            Field::KillLead => "kill_lead",
+            Field::TowerLead => "tower_lead",
+            Field::BarracksLead => "barracks_lead",
+            Field::GameTime => "game_time",
+            Field::MarketOdds => "market_odds",
+            Field::ModelProb => "model_prob",
+            Field::Edge => "edge",
+            Field::Liquidity => "liquidity",
+            Field::SignalType => "type",
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gold_lead" => Ok(Field::GoldLead),
+            "kill_lead" | "kill_diff" => Ok(Field::KillLead),
+            "tower_lead" | "tower_diff" => Ok(Field::TowerLead),
+            "barracks_lead" | "barracks_diff" => Ok(Field::BarracksLead),
+            "game_time" => Ok(Field::GameTime),
+            "market_odds" | "odds" => Ok(Field::MarketOdds),
+            "model_prob" | "win_prob" => Ok(Field::ModelProb),
+            "edge" | "net_edge" => Ok(Field::Edge),
+            "liquidity" => Ok(Field::Liquidity),
+            "type" | "signal_type" => Ok(Field::SignalType),
+            other => anyhow::bail!("Unknown signal rule field: {}", other),
+        }
+    }
+}
+
+/// Right-hand side of a condition, parsed for its field
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Number(f64),
+    SignalType(SignalType),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::SignalType(signal_type) => write!(f, "{}", signal_type),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+impl Condition {
+    fn matches(&self, side: &Side<'_>) -> bool {
+        match self.value {
+            Value::SignalType(expected) => self.op.equals(side.signal.signal_type == expected),
+            // Probabilities the model couldn't give fail every condition
+            Value::Number(expected) => side
+                .number(self.field)
+                .is_some_and(|actual| self.op.compare(actual, expected)),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, op, raw) =
+            Op::split(s).with_context(|| format!("Condition has no operator: {}", s))?;
+        let field: Field = field.parse()?;
+
+        let value = match field {
+            Field::SignalType => {
+                if op.is_ordered() {
+                    anyhow::bail!("{} only supports = and !=", field.as_str());
+                }
+                Value::SignalType(raw.parse()?)
+            }
+            _ => Value::Number(
+                raw.parse()
+                    .with_context(|| format!("{} needs a number: {}", field.as_str(), raw))?,
+            ),
+        };
+
+        Ok(Self { field, op, value })
+    }
+}
+
+/// An update and its built-in signal seen from one team's side
+struct Side<'a> {
+    update: &'a MatchUpdate,
+    market: &'a Market,
+    signal: &'a Signal,
+    team_a: bool,
+}
+
+impl Side<'_> {
+    fn number(&self, field: Field) -> Option<f64> {
+        let state = &self.update.state;
+        // Radiant-centric counts, flipped when this side plays Dire
+        let radiant = self.team_a == self.update.market_team_a_is_radiant;
+        let (us, them) = if radiant {
+            (&state.radiant, &state.dire)
+        } else {
+            (&state.dire, &state.radiant)
+        };
+        // Team A-centric prices and probabilities, flipped for team B
+        let team_a = |p: f64| if self.team_a { p } else { 1.0 - p };
+
+        match field {
+            Field::GoldLead => Some(if radiant {
+                state.gold_lead as f64
+            } else {
+                -state.gold_lead as f64
+            }),
+            Field::KillLead => Some((us.kills - them.kills).into()),
+            Field::TowerLead => Some((us.towers_killed - them.towers_killed).into()),
+            Field::BarracksLead => Some((us.barracks_killed - them.barracks_killed).into()),
+            Field::GameTime => Some(state.game_time.into()),
+            Field::MarketOdds => Some(team_a(self.market.team_a_odds)),
+            Field::ModelProb => self.signal.team_a_win_prob.map(team_a),
+            Field::Edge => self
+                .signal
+                .net_edge
+                .map(|e| if self.team_a { e } else { -e }),
+            Field::Liquidity => Some(self.market.liquidity),
+            Field::SignalType => None,
+        }
+    }
+}
+
+/// One `if conditions then signal strength` rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalRule {
+    /// All must hold
+    conditions: Vec<Condition>,
+    pub strength: SignalStrength,
+}
+
+impl FromStr for SignalRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = s.trim();
+        let body = rule.strip_prefix("if ").unwrap_or(rule);
+        let (conditions, action) = body
+            .rsplit_once(" then ")
+            .with_context(|| format!("Rule is missing `then`: {}", rule))?;
+
+        let strength: SignalStrength = action
+            .trim()
+            .strip_prefix("signal ")
+            .with_context(|| format!("Rule must end in `then signal <strength>`: {}", rule))?
+            .trim()
+            .parse()?;
+        if strength == SignalStrength::Neutral {
+            anyhow::bail!("Rule signals must be stronger than Neutral: {}", rule);
+        }
+
+        let conditions: Vec<Condition> = conditions
+            .split(" and ")
+            .map(str::parse)
+            .collect::<Result<_>>()
+            .with_context(|| format!("Invalid rule: {}", rule))?;
+
+        Ok(Self {
+            conditions,
+            strength,
+        })
+    }
+}
+
+impl fmt::Display for SignalRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("if ")?;
+        for (i, c) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" and ")?;
+            }
+            write!(f, "{} {} {}", c.field.as_str(), c.op.as_str(), c.value)?;
+        }
+        write!(f, " then signal {}", self.strength)
+    }
+}
+
+/// Ordered signal rules; the first match wins
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignalRules(pub Vec<SignalRule>);
+
+impl SignalRules {
+    /// Signal from the first rule that holds for either team, team A first
+    pub fn evaluate(
+        &self,
+        update: &MatchUpdate,
+        market: &Market,
+        signal: &Signal,
+    ) -> Option<CustomSignal> {
+        self.0.iter().find_map(|rule| {
+            [true, false].into_iter().find_map(|team_a| {
+                let side = Side {
+                    update,
+                    market,
+                    signal,
+                    team_a,
+                };
+                rule.conditions
+                    .iter()
+                    .all(|c| c.matches(&side))
+                    .then(|| CustomSignal {
+                        signal_type: SignalType::Rule,
+                        strength: rule.strength,
+                        reason: format!(
+                            "{}: {}",
+                            if team_a {
+                                &market.team_a
+                            } else {
+                                &market.team_b
+                            },
+                            rule
+                        ),
+                        team_a_prob: None,
+                        team_a: Some(team_a),
+                    })
+            })
+        })
+    }
+}
+
+impl FromStr for SignalRules {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split([';', ','])
+            .filter(|rule| !rule.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for SignalRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<String> = self.0.iter().map(|rule| rule.to_string()).collect();
+        f.write_str(&rules.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::models::{LiveMatchState, Venue};
+
+    fn update(gold_lead: i64, game_time: i32, team_a_is_radiant: bool) -> MatchUpdate {
+        MatchUpdate {
+            market_condition_id: "0xa".to_string(),
+            state: Arc::new(LiveMatchState {
+                match_id: 7,
+                gold_lead,
+                game_time,
+                ..Default::default()
+            }),
+            market_team_a_is_radiant: team_a_is_radiant,
+            previous_state: None,
+            momentum: Default::default(),
+            teamfight: None,
+            deaths: Default::default(),
+            signal_type: SignalType::GoldSwing,
+        }
+    }
+
+    fn market(team_a_odds: f64) -> Market {
+        Market {
+            condition_id: "0xa".to_string(),
+            venue: Venue::Polymarket,
+            question: "Dota 2: Team Spirit vs OG".to_string(),
+            team_a: "Team Spirit".to_string(),
+            team_b: "OG".to_string(),
+            team_a_odds,
+            team_b_odds: 1.0 - team_a_odds,
+            opening_team_a_odds: 0.5,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 5000.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    fn signal() -> Signal {
+        Signal {
+            market_condition_id: "0xa".to_string(),
+            match_id: 7,
+            market_team_a_odds: 0.7,
+            signal_type: SignalType::GoldSwing,
+            team_a_is_radiant: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rules_match_from_either_side() {
+        let rules: SignalRules = "if gold_lead > 15000 and game_time > 1800 and market_odds < 0.8 \
+                                  then signal Strong; type = GoldSwing and model_prob > 0.5 \
+                                  then signal Weak"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rules.to_string(),
+            "if gold_lead > 15000 and game_time > 1800 and market_odds < 0.8 then signal Strong; \
+             if type = GoldSwing and model_prob > 0.5 then signal Weak"
+        );
+
+        let fired = rules
+            .evaluate(&update(16000, 1900, true), &market(0.7), &signal())
+            .unwrap();
+        assert_eq!(fired.signal_type, SignalType::Rule);
+        assert_eq!(fired.strength, SignalStrength::Strong);
+        assert_eq!(fired.team_a, Some(true));
+        assert!(fired
+            .reason
+            .starts_with("Team Spirit: if gold_lead > 15000"));
+
+        // Radiant's lead belongs to OG when team A plays Dire, and OG's
+        // price is team A's complement
+        let fired = rules
+            .evaluate(&update(16000, 1900, false), &market(0.3), &signal())
+            .unwrap();
+        assert!(fired.reason.starts_with("OG: "));
+        assert_eq!(fired.team_a, Some(false));
+
+        assert_eq!(
+            rules.evaluate(&update(16000, 1200, true), &market(0.7), &signal()),
+            None
+        );
+        // Without a model probability the second rule can't hold
+        assert_eq!(
+            rules.evaluate(&update(16000, 1900, true), &market(0.9), &signal()),
+            None
+        );
+
+        assert!("if gold_lead > 1 then signal Neutral"
+            .parse::<SignalRules>()
+            .is_err());
+        assert!("if gold_lead > lots then signal Strong"
+            .parse::<SignalRules>()
+            .is_err());
+        assert!("if type > GoldSwing then signal Strong"
+            .parse::<SignalRules>()
+            .is_err());
+        assert!("if gold_lead > 1".parse::<SignalRules>().is_err());
+    }
+}
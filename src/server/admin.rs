@@ -0,0 +1,153 @@
+//! Authenticated endpoints for changing runtime state
+//!
+//! Mounted under `/admin` only when an admin-scope token is configured in
+//! `API_TOKENS`; every request must send one as `Authorization: Bearer <token>`.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::auth::{require_scope, Scope};
+use super::AppState;
+use crate::matching::TeamAliases;
+use crate::models::MutedMarket;
+
+/// Body of `POST /admin/aliases`
+#[derive(Debug, Deserialize)]
+struct NewAlias {
+    alias: String,
+    canonical: String,
+}
+
+/// Body of `POST /admin/mutes`
+#[derive(Debug, Deserialize)]
+struct NewMute {
+    market: String,
+    reason: Option<String>,
+}
+
+/// Response of `POST /admin/aliases/reload`
+#[derive(Debug, Serialize)]
+struct Reloaded {
+    mappings: usize,
+}
+
+/// Build the admin routes, all behind the admin scope check
+pub fn router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/aliases", get(aliases).post(add_alias))
+        .route("/aliases/{alias}", delete(remove_alias))
+        .route("/aliases/reload", post(reload_aliases))
+        .route("/mutes", get(mutes).post(mute_market))
+        .route("/mutes/{market}", delete(unmute_market))
+        .route_layer(middleware::from_fn_with_state(
+            (Arc::clone(&state.api_tokens), Scope::Admin),
+            require_scope,
+        ))
+}
+
+/// Team alias entries as currently configured
+async fn aliases(State(state): State<AppState>) -> Json<TeamAliases> {
+    Json(state.team_resolver.entries())
+}
+
+/// Map an alias to a team, effective for the next match attempt
+async fn add_alias(
+    State(state): State<AppState>,
+    Json(body): Json<NewAlias>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state
+        .team_resolver
+        .add_alias(&body.alias, &body.canonical)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(edit_error)
+}
+
+/// Remove an alias from whichever team has it
+async fn remove_alias(
+    State(state): State<AppState>,
+    Path(alias): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match state.team_resolver.remove_alias(&alias) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, format!("No alias {}", alias))),
+        Err(e) => Err(edit_error(e)),
+    }
+}
+
+/// Re-read the aliases file after editing it by hand
+async fn reload_aliases(
+    State(state): State<AppState>,
+) -> Result<Json<Reloaded>, (StatusCode, String)> {
+    state
+        .team_resolver
+        .reload()
+        .map(|mappings| Json(Reloaded { mappings }))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))
+}
+
+/// Muted markets, most recently muted first
+async fn mutes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MutedMarket>>, (StatusCode, String)> {
+    state
+        .signal_store
+        .get_muted_markets()
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))
+}
+
+/// Stop generating signals for a market; its updates are still stored
+async fn mute_market(
+    State(state): State<AppState>,
+    Json(body): Json<NewMute>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let market = body.market.trim();
+    if market.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "market is required".to_string()));
+    }
+
+    let mute = MutedMarket {
+        market_condition_id: market.to_string(),
+        reason: body.reason.filter(|r| !r.trim().is_empty()),
+        muted_at: Utc::now(),
+    };
+    state
+        .signal_store
+        .mute_market(&mute)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))
+}
+
+/// Resume signal generation for a market
+async fn unmute_market(
+    State(state): State<AppState>,
+    Path(market): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match state.signal_store.unmute_market(&market).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Market {} isn't muted", market),
+        )),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e))),
+    }
+}
+
+/// 500 when the aliases file couldn't be written, else 400
+fn edit_error(e: anyhow::Error) -> (StatusCode, String) {
+    let status = if e.chain().any(|cause| cause.is::<std::io::Error>()) {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    (status, format!("{:#}", e))
+}
@@ -0,0 +1,161 @@
+//! Bearer-token authentication for the HTTP and gRPC servers
+//!
+//! Tokens come from `API_TOKENS` as `token=read|admin` pairs. With none
+//! configured every read endpoint is open and `/admin` isn't served; once
+//! any is configured, everything but `/healthz` needs one.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// What a token may do; admin tokens can also read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    /// Signal, stats and GraphQL queries, and the gRPC service
+    Read,
+    /// Everything, including `/admin`
+    Admin,
+}
+
+impl FromStr for Scope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "read" => Ok(Scope::Read),
+            "admin" => Ok(Scope::Admin),
+            other => anyhow::bail!("Scope must be read or admin: {}", other),
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::Read => write!(f, "read"),
+            Scope::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+/// Configured API tokens and their scopes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiTokens(pub Vec<(String, Scope)>);
+
+impl ApiTokens {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether any token may use `/admin`
+    pub fn has_admin(&self) -> bool {
+        self.0.iter().any(|(_, scope)| *scope == Scope::Admin)
+    }
+
+    /// Scope of a presented token, if it's configured
+    pub fn scope(&self, token: &str) -> Option<Scope> {
+        // Check every token so the time taken doesn't reveal which matched
+        self.0
+            .iter()
+            .filter(|(known, _)| constant_time_eq(known.as_bytes(), token.as_bytes()))
+            .map(|(_, scope)| *scope)
+            .fold(None, |best, scope| best.max(Some(scope)))
+    }
+
+    /// Whether a request with this `Authorization` header value may act
+    /// with `required` scope; anything goes when no tokens are configured
+    pub fn allows(&self, authorization: Option<&str>, required: Scope) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| self.scope(token.trim()))
+            .is_some_and(|scope| scope >= required)
+    }
+}
+
+impl FromStr for ApiTokens {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                // Split on the last '=' so tokens may contain one
+                let (token, scope) = pair
+                    .rsplit_once('=')
+                    .context("Expected token=read or token=admin")?;
+                let token = token.trim();
+                anyhow::ensure!(!token.is_empty(), "Empty API token");
+                Ok((token.to_string(), scope.parse()?))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for ApiTokens {
+    /// Scopes only; tokens are redacted
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self
+            .0
+            .iter()
+            .map(|(_, scope)| format!("***={}", scope))
+            .collect();
+        f.write_str(&pairs.join(","))
+    }
+}
+
+/// 401 unless the request's bearer token has the route's scope
+pub async fn require_scope(
+    State((tokens, required)): State<(Arc<ApiTokens>, Scope)>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    if tokens.allows(authorization, required) {
+        Ok(next.run(request).await)
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Compare without returning early, so response time doesn't leak a prefix
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_scopes() {
+        let tokens: ApiTokens = "dash=read, ops=admin".parse().unwrap();
+        assert_eq!(tokens.to_string(), "***=read,***=admin");
+
+        assert!(tokens.allows(Some("Bearer dash"), Scope::Read));
+        assert!(!tokens.allows(Some("Bearer dash"), Scope::Admin));
+        assert!(tokens.allows(Some("Bearer ops"), Scope::Admin));
+        assert!(tokens.allows(Some("Bearer ops"), Scope::Read));
+        assert!(!tokens.allows(Some("Bearer other"), Scope::Read));
+        assert!(!tokens.allows(Some("dash"), Scope::Read));
+        assert!(!tokens.allows(None, Scope::Read));
+
+        assert!(ApiTokens::default().allows(None, Scope::Admin));
+        assert!("dash=write".parse::<ApiTokens>().is_err());
+        assert!("dash".parse::<ApiTokens>().is_err());
+    }
+}
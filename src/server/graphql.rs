@@ -0,0 +1,389 @@
+//! GraphQL query API over stored signals and historical matches
+
+use std::sync::Arc;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, InputObject, Object, Result, Schema,
+};
+use chrono::{DateTime, Utc};
+
+use super::clamp_limit;
+use crate::db::{
+    HistoricalMatch, HistoricalMatchFilter, HistoricalStore, MatchOutcome, SignalFilter,
+    SignalStore,
+};
+use crate::models::{LiveMatchState, Signal};
+
+/// Deepest selection set a query may nest
+const MAX_DEPTH: usize = 8;
+
+pub type SignalSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the schema over the given stores
+pub fn schema(
+    signal_store: Arc<SignalStore>,
+    historical_store: Arc<HistoricalStore>,
+) -> SignalSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(signal_store)
+        .data(historical_store)
+        .limit_depth(MAX_DEPTH)
+        .finish()
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "crate::models::SignalStrength")]
+enum SignalStrength {
+    Neutral,
+    Weak,
+    Moderate,
+    Strong,
+    VeryStrong,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "crate::models::SignalType")]
+enum SignalType {
+    Snapshot,
+    GoldSwing,
+    Teamfight,
+    TowerKill,
+    BarracksKill,
+    MegaCreeps,
+    Script,
+    Rule,
+    SettlementLag,
+    TerminalState,
+}
+
+/// Result of the match a signal was taken from
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "crate::db::MatchOutcome")]
+enum Outcome {
+    RadiantWin,
+    DireWin,
+    /// No historical record of the match yet
+    Pending,
+}
+
+/// Signal filters; omitted fields match everything
+#[derive(InputObject, Default)]
+struct SignalFilterInput {
+    match_id: Option<i64>,
+    market_condition_id: Option<String>,
+    /// Substring of either team name, case-insensitive
+    team: Option<String>,
+    min_strength: Option<SignalStrength>,
+    /// Empty or omitted matches every type
+    #[graphql(default)]
+    signal_types: Vec<SignalType>,
+    /// Minimum net edge on either side
+    min_net_edge: Option<f64>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    outcome: Option<Outcome>,
+}
+
+impl From<SignalFilterInput> for SignalFilter {
+    fn from(input: SignalFilterInput) -> Self {
+        SignalFilter {
+            match_id: input.match_id,
+            market_condition_id: input.market_condition_id,
+            team: input.team,
+            min_strength: input.min_strength.map(Into::into),
+            signal_types: input.signal_types.into_iter().map(Into::into).collect(),
+            min_net_edge: input.min_net_edge,
+            since: input.since,
+            until: input.until,
+            outcome: input.outcome.map(MatchOutcome::from),
+        }
+    }
+}
+
+/// Historical match filters; omitted fields match everything
+#[derive(InputObject, Default)]
+struct HistoricalMatchFilterInput {
+    /// Substring of either team name, case-insensitive
+    team: Option<String>,
+    /// Substring of the league name, case-insensitive
+    league: Option<String>,
+    start_after: Option<DateTime<Utc>>,
+    start_before: Option<DateTime<Utc>>,
+    radiant_win: Option<bool>,
+}
+
+impl From<HistoricalMatchFilterInput> for HistoricalMatchFilter {
+    fn from(input: HistoricalMatchFilterInput) -> Self {
+        HistoricalMatchFilter {
+            team: input.team,
+            league: input.league,
+            start_after: input.start_after.map(|t| t.timestamp()),
+            start_before: input.start_before.map(|t| t.timestamp()),
+            radiant_win: input.radiant_win,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A signal by id
+    async fn signal(&self, ctx: &Context<'_>, id: i64) -> Result<Option<SignalObject>> {
+        let store = ctx.data::<Arc<SignalStore>>()?;
+        Ok(store.get_signal(id).await?.map(SignalObject::new))
+    }
+
+    /// Newest signals matching the filter
+    async fn signals(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<SignalFilterInput>,
+        #[graphql(default = 50)] limit: i64,
+    ) -> Result<Vec<SignalObject>> {
+        let store = ctx.data::<Arc<SignalStore>>()?;
+        let filter = SignalFilter::from(filter.unwrap_or_default());
+
+        Ok(store
+            .query_signals(&filter, clamp_limit(Some(limit)))
+            .await?
+            .into_iter()
+            .map(SignalObject::new)
+            .collect())
+    }
+
+    /// A finished match by OpenDota match id
+    async fn historical_match(
+        &self,
+        ctx: &Context<'_>,
+        match_id: i64,
+    ) -> Result<Option<HistoricalMatchObject>> {
+        let store = ctx.data::<Arc<HistoricalStore>>()?;
+        Ok(store.get_match(match_id).await?.map(HistoricalMatchObject))
+    }
+
+    /// Most recent finished matches matching the filter
+    async fn historical_matches(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<HistoricalMatchFilterInput>,
+        #[graphql(default = 50)] limit: i64,
+    ) -> Result<Vec<HistoricalMatchObject>> {
+        let store = ctx.data::<Arc<HistoricalStore>>()?;
+        let filter = HistoricalMatchFilter::from(filter.unwrap_or_default());
+
+        Ok(store
+            .query_matches(&filter, clamp_limit(Some(limit)))
+            .await?
+            .into_iter()
+            .map(HistoricalMatchObject)
+            .collect())
+    }
+}
+
+/// A stored match snapshot and the model's read of it
+struct SignalObject {
+    signal: Signal,
+    /// Parsed `match_snapshot`, if it is valid JSON
+    state: Option<LiveMatchState>,
+}
+
+impl SignalObject {
+    fn new(signal: Signal) -> Self {
+        let state = serde_json::from_str(&signal.match_snapshot).ok();
+        Self { signal, state }
+    }
+}
+
+#[Object(name = "Signal")]
+impl SignalObject {
+    async fn id(&self) -> Option<i64> {
+        self.signal.id
+    }
+
+    async fn market_condition_id(&self) -> &str {
+        &self.signal.market_condition_id
+    }
+
+    async fn match_id(&self) -> i64 {
+        self.signal.match_id
+    }
+
+    async fn radiant_team(&self) -> Option<&str> {
+        self.state.as_ref().map(|s| s.radiant.name.as_str())
+    }
+
+    async fn dire_team(&self) -> Option<&str> {
+        self.state.as_ref().map(|s| s.dire.name.as_str())
+    }
+
+    async fn league_name(&self) -> Option<&str> {
+        self.state.as_ref()?.league_name.as_deref()
+    }
+
+    /// Game clock in seconds at the snapshot
+    async fn game_time(&self) -> Option<i32> {
+        self.state.as_ref().map(|s| s.game_time)
+    }
+
+    async fn market_team_a_odds(&self) -> f64 {
+        self.signal.market_team_a_odds
+    }
+
+    async fn team_a_prior_prob(&self) -> Option<f64> {
+        self.signal.team_a_prior_prob
+    }
+
+    async fn sharp_team_a_prob(&self) -> Option<f64> {
+        self.signal.sharp_team_a_prob
+    }
+
+    async fn team_a_win_prob(&self) -> Option<f64> {
+        self.signal.team_a_win_prob
+    }
+
+    async fn team_a_prob_lower(&self) -> Option<f64> {
+        self.signal.team_a_prob_lower
+    }
+
+    async fn team_a_prob_upper(&self) -> Option<f64> {
+        self.signal.team_a_prob_upper
+    }
+
+    async fn edge(&self) -> Option<f64> {
+        self.signal.edge
+    }
+
+    async fn net_edge(&self) -> Option<f64> {
+        self.signal.net_edge
+    }
+
+    async fn strength(&self) -> SignalStrength {
+        self.signal.strength.into()
+    }
+
+    async fn signal_type(&self) -> SignalType {
+        self.signal.signal_type.into()
+    }
+
+    /// Part of a run of Strong+ signals on the same side of the market
+    async fn escalated(&self) -> bool {
+        self.signal.escalated
+    }
+
+    /// Team that took more kills in a teamfight; null for an even trade
+    async fn fight_winner(&self) -> Option<&str> {
+        self.signal.fight_winner.as_deref()
+    }
+
+    /// Median game seconds left in similar historical games
+    async fn expected_remaining_secs(&self) -> Option<i32> {
+        self.signal.expected_remaining_secs
+    }
+
+    async fn model_version(&self) -> Option<&str> {
+        self.signal.model_version.as_deref()
+    }
+
+    /// Raw `LiveMatchState` JSON
+    /// Model input features at signal time as JSON, Radiant perspective
+    async fn features(&self) -> Option<&str> {
+        self.signal.features.as_deref()
+    }
+
+    /// Features that moved the live model most for team A
+    async fn reason(&self) -> Option<&str> {
+        self.signal.reason.as_deref()
+    }
+
+    /// Whether the market's team A played Radiant
+    async fn team_a_is_radiant(&self) -> Option<bool> {
+        self.signal.team_a_is_radiant
+    }
+
+    /// Whether the side the net edge favoured won; null until graded
+    async fn correct(&self) -> Option<bool> {
+        self.signal.correct
+    }
+
+    async fn graded_at(&self) -> Option<DateTime<Utc>> {
+        self.signal.graded_at
+    }
+
+    async fn match_snapshot(&self) -> &str {
+        &self.signal.match_snapshot
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.signal.created_at
+    }
+
+    /// The finished match, once it has been fetched into history
+    async fn historical_match(&self, ctx: &Context<'_>) -> Result<Option<HistoricalMatchObject>> {
+        let store = ctx.data::<Arc<HistoricalStore>>()?;
+        Ok(store
+            .get_match(self.signal.match_id)
+            .await?
+            .map(HistoricalMatchObject))
+    }
+}
+
+/// A finished pro match
+struct HistoricalMatchObject(HistoricalMatch);
+
+#[Object(name = "HistoricalMatch")]
+impl HistoricalMatchObject {
+    async fn match_id(&self) -> i64 {
+        self.0.match_id
+    }
+
+    async fn radiant_team(&self) -> Option<&str> {
+        self.0.radiant_team.as_deref()
+    }
+
+    async fn dire_team(&self) -> Option<&str> {
+        self.0.dire_team.as_deref()
+    }
+
+    async fn radiant_win(&self) -> bool {
+        self.0.radiant_win
+    }
+
+    async fn outcome(&self) -> Outcome {
+        if self.0.radiant_win {
+            Outcome::RadiantWin
+        } else {
+            Outcome::DireWin
+        }
+    }
+
+    /// Duration in seconds
+    async fn duration(&self) -> i32 {
+        self.0.duration
+    }
+
+    async fn start_time(&self) -> Option<DateTime<Utc>> {
+        self.0
+            .start_time
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+    }
+
+    async fn league_name(&self) -> Option<&str> {
+        self.0.league_name.as_deref()
+    }
+
+    /// OpenDota patch ID
+    async fn patch(&self) -> Option<i32> {
+        self.0.patch
+    }
+
+    /// Radiant gold advantage per minute
+    async fn radiant_gold_adv(&self) -> Vec<i64> {
+        serde_json::from_str(&self.0.radiant_gold_adv).unwrap_or_default()
+    }
+
+    /// Radiant experience advantage per minute
+    async fn radiant_xp_adv(&self) -> Vec<i64> {
+        serde_json::from_str(&self.0.radiant_xp_adv).unwrap_or_default()
+    }
+}
@@ -0,0 +1,333 @@
+//! gRPC signal service with live streaming for trading bots
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use super::auth::{ApiTokens, Scope};
+use super::clamp_limit;
+use crate::db::{MatchOutcome, SignalFilter, SignalStore};
+use crate::models::{LiveMatchState, Signal, SignalStrength, SignalType};
+
+use proto::signal_service_server::SignalServiceServer;
+
+pub mod proto {
+    tonic::include_proto!("esport_signal.v1");
+}
+
+/// Signals buffered per subscriber before the stream applies backpressure
+const STREAM_BUFFER: usize = 64;
+
+/// Serves stored signals and streams new ones as they are written
+pub struct SignalService {
+    signal_store: Arc<SignalStore>,
+    signal_feed: broadcast::Sender<Signal>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl SignalService {
+    /// Create a service over the store and the signal processor's feed
+    pub fn new(
+        signal_store: Arc<SignalStore>,
+        signal_feed: broadcast::Sender<Signal>,
+        shutdown: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            signal_store,
+            signal_feed,
+            shutdown,
+        }
+    }
+}
+
+/// Serve gRPC on `addr` until shutdown is signaled; calls need a read-scope
+/// `authorization` bearer token when any are configured
+pub async fn serve(
+    addr: &str,
+    service: SignalService,
+    tokens: Arc<ApiTokens>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<()> {
+    let socket_addr = addr
+        .parse()
+        .with_context(|| format!("Invalid gRPC address: {}", addr))?;
+    info!("gRPC server listening on {}", addr);
+
+    tonic::transport::Server::builder()
+        .add_service(SignalServiceServer::with_interceptor(
+            service,
+            TokenCheck(tokens),
+        ))
+        .serve_with_shutdown(socket_addr, async move {
+            shutdown.changed().await.ok();
+        })
+        .await
+        .context("gRPC server failed")
+}
+
+/// Rejects calls without a read-scope token
+#[derive(Clone)]
+struct TokenCheck(Arc<ApiTokens>);
+
+impl Interceptor for TokenCheck {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        if self.0.allows(authorization, Scope::Read) {
+            Ok(request)
+        } else {
+            Err(Status::unauthenticated("Missing or invalid API token"))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl proto::signal_service_server::SignalService for SignalService {
+    type SubscribeSignalsStream = ReceiverStream<Result<proto::Signal, Status>>;
+
+    async fn subscribe_signals(
+        &self,
+        request: Request<proto::SubscribeSignalsRequest>,
+    ) -> Result<Response<Self::SubscribeSignalsStream>, Status> {
+        let filter = SignalFilter::from(request.into_inner().filter.unwrap_or_default());
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let mut feed = self.signal_feed.subscribe();
+        let mut shutdown = self.shutdown.clone();
+
+        // End the stream on shutdown so graceful shutdown isn't held open
+        tokio::spawn(async move {
+            loop {
+                let received = tokio::select! {
+                    received = feed.recv() => received,
+                    _ = shutdown.changed() => break,
+                    _ = tx.closed() => break,
+                };
+
+                match received {
+                    Ok(signal) => {
+                        if filter.matches(&signal) && tx.send(Ok(to_proto(&signal))).await.is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("gRPC subscriber lagged, skipped {} signals", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn get_signal(
+        &self,
+        request: Request<proto::GetSignalRequest>,
+    ) -> Result<Response<proto::Signal>, Status> {
+        let id = request.into_inner().id;
+
+        match self.signal_store.get_signal(id).await {
+            Ok(Some(signal)) => Ok(Response::new(to_proto(&signal))),
+            Ok(None) => Err(Status::not_found(format!("Signal {} not found", id))),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn list_signals(
+        &self,
+        request: Request<proto::ListSignalsRequest>,
+    ) -> Result<Response<proto::ListSignalsResponse>, Status> {
+        let request = request.into_inner();
+        let filter = SignalFilter::from(request.filter.unwrap_or_default());
+        let limit = clamp_limit(Some(request.limit));
+
+        let page = self
+            .signal_store
+            .query_signals_page(&filter, request.before, limit)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::ListSignalsResponse {
+            signals: page.signals.iter().map(to_proto).collect(),
+            next_cursor: page.next_cursor,
+        }))
+    }
+}
+
+impl From<proto::SignalFilter> for SignalFilter {
+    fn from(filter: proto::SignalFilter) -> Self {
+        let min_strength = match filter.min_strength() {
+            proto::SignalStrength::Unspecified => None,
+            proto::SignalStrength::Neutral => Some(SignalStrength::Neutral),
+            proto::SignalStrength::Weak => Some(SignalStrength::Weak),
+            proto::SignalStrength::Moderate => Some(SignalStrength::Moderate),
+            proto::SignalStrength::Strong => Some(SignalStrength::Strong),
+            proto::SignalStrength::VeryStrong => Some(SignalStrength::VeryStrong),
+        };
+        let outcome = match filter.outcome() {
+            proto::MatchOutcome::Unspecified => None,
+            proto::MatchOutcome::RadiantWin => Some(MatchOutcome::RadiantWin),
+            proto::MatchOutcome::DireWin => Some(MatchOutcome::DireWin),
+            proto::MatchOutcome::Pending => Some(MatchOutcome::Pending),
+        };
+
+        let signal_types = filter
+            .signal_types()
+            .filter_map(|signal_type| match signal_type {
+                proto::SignalType::Unspecified => None,
+                proto::SignalType::Snapshot => Some(SignalType::Snapshot),
+                proto::SignalType::GoldSwing => Some(SignalType::GoldSwing),
+                proto::SignalType::Teamfight => Some(SignalType::Teamfight),
+                proto::SignalType::TowerKill => Some(SignalType::TowerKill),
+                proto::SignalType::BarracksKill => Some(SignalType::BarracksKill),
+                proto::SignalType::MegaCreeps => Some(SignalType::MegaCreeps),
+                proto::SignalType::Script => Some(SignalType::Script),
+                proto::SignalType::Rule => Some(SignalType::Rule),
+                proto::SignalType::SettlementLag => Some(SignalType::SettlementLag),
+                proto::SignalType::TerminalState => Some(SignalType::TerminalState),
+            })
+            .collect();
+
+        SignalFilter {
+            match_id: filter.match_id,
+            market_condition_id: filter.market_condition_id,
+            team: filter.team,
+            min_strength,
+            signal_types,
+            min_net_edge: filter.min_net_edge,
+            since: filter.since.and_then(from_timestamp),
+            until: filter.until.and_then(from_timestamp),
+            outcome,
+        }
+    }
+}
+
+fn to_proto(signal: &Signal) -> proto::Signal {
+    let state = serde_json::from_str::<LiveMatchState>(&signal.match_snapshot).ok();
+    let strength = match signal.strength {
+        SignalStrength::Neutral => proto::SignalStrength::Neutral,
+        SignalStrength::Weak => proto::SignalStrength::Weak,
+        SignalStrength::Moderate => proto::SignalStrength::Moderate,
+        SignalStrength::Strong => proto::SignalStrength::Strong,
+        SignalStrength::VeryStrong => proto::SignalStrength::VeryStrong,
+    };
+    let signal_type = match signal.signal_type {
+        SignalType::Snapshot => proto::SignalType::Snapshot,
+        SignalType::GoldSwing => proto::SignalType::GoldSwing,
+        SignalType::Teamfight => proto::SignalType::Teamfight,
+        SignalType::TowerKill => proto::SignalType::TowerKill,
+        SignalType::BarracksKill => proto::SignalType::BarracksKill,
+        SignalType::MegaCreeps => proto::SignalType::MegaCreeps,
+        SignalType::Script => proto::SignalType::Script,
+        SignalType::Rule => proto::SignalType::Rule,
+        SignalType::SettlementLag => proto::SignalType::SettlementLag,
+        SignalType::TerminalState => proto::SignalType::TerminalState,
+    };
+
+    proto::Signal {
+        id: signal.id.unwrap_or_default(),
+        market_condition_id: signal.market_condition_id.clone(),
+        match_id: signal.match_id,
+        radiant_team: state
+            .as_ref()
+            .map(|s| s.radiant.name.clone())
+            .unwrap_or_default(),
+        dire_team: state
+            .as_ref()
+            .map(|s| s.dire.name.clone())
+            .unwrap_or_default(),
+        market_team_a_odds: signal.market_team_a_odds,
+        team_a_prior_prob: signal.team_a_prior_prob,
+        sharp_team_a_prob: signal.sharp_team_a_prob,
+        team_a_win_prob: signal.team_a_win_prob,
+        team_a_prob_lower: signal.team_a_prob_lower,
+        team_a_prob_upper: signal.team_a_prob_upper,
+        edge: signal.edge,
+        net_edge: signal.net_edge,
+        strength: strength.into(),
+        signal_type: signal_type.into(),
+        escalated: signal.escalated,
+        fight_winner: signal.fight_winner.clone(),
+        expected_remaining_secs: signal.expected_remaining_secs,
+        model_version: signal.model_version.clone(),
+        features: signal.features.clone(),
+        reason: signal.reason.clone(),
+        team_a_is_radiant: signal.team_a_is_radiant,
+        correct: signal.correct,
+        graded_at: signal.graded_at.map(|at| prost_types::Timestamp {
+            seconds: at.timestamp(),
+            nanos: at.timestamp_subsec_nanos() as i32,
+        }),
+        match_snapshot: signal.match_snapshot.clone(),
+        created_at: Some(prost_types::Timestamp {
+            seconds: signal.created_at.timestamp(),
+            nanos: signal.created_at.timestamp_subsec_nanos() as i32,
+        }),
+    }
+}
+
+fn from_timestamp(ts: prost_types::Timestamp) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use super::proto::signal_service_server::SignalService as _;
+    use super::*;
+
+    fn signal(id: i64, strength: SignalStrength) -> Signal {
+        Signal {
+            id: Some(id),
+            market_condition_id: "0xa".to_string(),
+            match_id: 7,
+            market_team_a_odds: 0.4,
+            team_a_win_prob: Some(0.6),
+            edge: Some(0.2),
+            net_edge: Some(0.15),
+            strength,
+            signal_type: SignalType::TowerKill,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_streams_filtered_signals() {
+        let store = Arc::new(SignalStore::new("sqlite::memory:").await.unwrap());
+        let (feed, _) = broadcast::channel(16);
+        let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+        let service = SignalService::new(store, feed.clone(), shutdown_rx);
+
+        let request = proto::SubscribeSignalsRequest {
+            filter: Some(proto::SignalFilter {
+                min_strength: proto::SignalStrength::Strong.into(),
+                ..Default::default()
+            }),
+        };
+        let mut stream = service
+            .subscribe_signals(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner();
+
+        feed.send(signal(1, SignalStrength::Weak)).unwrap();
+        feed.send(signal(2, SignalStrength::VeryStrong)).unwrap();
+
+        let received = stream.next().await.unwrap().unwrap();
+        assert_eq!(received.id, 2);
+        assert_eq!(received.strength(), proto::SignalStrength::VeryStrong);
+        assert_eq!(received.signal_type(), proto::SignalType::TowerKill);
+    }
+}
@@ -0,0 +1,201 @@
+//! Prometheus text exposition of the health registry for `GET /metrics`
+
+use std::fmt::Write;
+
+use crate::health::{HealthReport, LATENCY_BUCKETS};
+
+/// Render a health report in the Prometheus text format
+pub fn render(report: &HealthReport) -> String {
+    let mut out = String::new();
+
+    gauge(
+        &mut out,
+        "esport_signal_healthy",
+        "1 while every worker ticks and the database is reachable",
+        report.healthy,
+    );
+    gauge(
+        &mut out,
+        "esport_signal_database_up",
+        "1 while the database answers",
+        report.database,
+    );
+
+    header(
+        &mut out,
+        "esport_signal_matched_markets",
+        "Markets matched to a live match at the last poll",
+        "gauge",
+    );
+    writeln!(
+        out,
+        "esport_signal_matched_markets {}",
+        report.matched_markets
+    )
+    .ok();
+
+    if let Some(live_matches) = report.live_matches {
+        header(
+            &mut out,
+            "esport_signal_live_matches",
+            "Matches the last live poll returned",
+            "gauge",
+        );
+        writeln!(out, "esport_signal_live_matches {}", live_matches).ok();
+    }
+
+    header(
+        &mut out,
+        "esport_signal_signals_total",
+        "Signals by processor stage (generated, suppressed, stored), type and strength",
+        "counter",
+    );
+    for ((stage, signal_type, strength), count) in &report.signals {
+        writeln!(
+            out,
+            "esport_signal_signals_total{{stage=\"{}\",type=\"{}\",strength=\"{}\"}} {}",
+            stage.as_str(),
+            label(signal_type),
+            label(strength),
+            count
+        )
+        .ok();
+    }
+
+    header(
+        &mut out,
+        "esport_signal_upstream_requests_total",
+        "Calls to an upstream API, failed ones included",
+        "counter",
+    );
+    for (name, upstream) in &report.upstreams {
+        writeln!(
+            out,
+            "esport_signal_upstream_requests_total{{upstream=\"{}\"}} {}",
+            label(name),
+            upstream.calls
+        )
+        .ok();
+    }
+
+    header(
+        &mut out,
+        "esport_signal_upstream_errors_total",
+        "Failed calls to an upstream API",
+        "counter",
+    );
+    for (name, upstream) in &report.upstreams {
+        writeln!(
+            out,
+            "esport_signal_upstream_errors_total{{upstream=\"{}\"}} {}",
+            label(name),
+            upstream.errors
+        )
+        .ok();
+    }
+
+    header(
+        &mut out,
+        "esport_signal_upstream_request_duration_seconds",
+        "Duration of calls to an upstream API",
+        "histogram",
+    );
+    for (name, upstream) in &report.upstreams {
+        let name = label(name);
+        let latency = &upstream.latency;
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(latency.buckets) {
+            writeln!(
+                out,
+                "esport_signal_upstream_request_duration_seconds_bucket{{upstream=\"{}\",le=\"{}\"}} {}",
+                name, bound, count
+            )
+            .ok();
+        }
+        writeln!(
+            out,
+            "esport_signal_upstream_request_duration_seconds_bucket{{upstream=\"{}\",le=\"+Inf\"}} {}",
+            name, latency.count
+        )
+        .ok();
+        writeln!(
+            out,
+            "esport_signal_upstream_request_duration_seconds_sum{{upstream=\"{}\"}} {}",
+            name, latency.sum_secs
+        )
+        .ok();
+        writeln!(
+            out,
+            "esport_signal_upstream_request_duration_seconds_count{{upstream=\"{}\"}} {}",
+            name, latency.count
+        )
+        .ok();
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    writeln!(out, "# HELP {} {}", name, help).ok();
+    writeln!(out, "# TYPE {} {}", name, kind).ok();
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: bool) {
+    header(out, name, help, "gauge");
+    writeln!(out, "{} {}", name, u8::from(value)).ok();
+}
+
+/// Escape a label value
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::health::{Health, SignalStage};
+
+    #[test]
+    fn test_render_upstream_metrics() {
+        let health = Health::new();
+        health.upstream_ok("opendota", Duration::from_millis(300));
+        health.upstream_error("opendota", Duration::from_secs(12), "timeout");
+        health.upstream_ok("polymarket", Duration::from_millis(50));
+
+        let text = render(&health.report(true));
+        assert!(text.contains("esport_signal_healthy 1\n"));
+        assert!(text.contains("esport_signal_upstream_requests_total{upstream=\"opendota\"} 2\n"));
+        assert!(text.contains("esport_signal_upstream_errors_total{upstream=\"opendota\"} 1\n"));
+        assert!(text.contains(
+            "esport_signal_upstream_request_duration_seconds_bucket{upstream=\"opendota\",le=\"0.5\"} 1\n"
+        ));
+        assert!(text.contains(
+            "esport_signal_upstream_request_duration_seconds_bucket{upstream=\"opendota\",le=\"30\"} 2\n"
+        ));
+        assert!(text.contains(
+            "esport_signal_upstream_request_duration_seconds_count{upstream=\"polymarket\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_signal_counts() {
+        let health = Health::new();
+        health.matched_markets(3);
+        health.signal_counted(SignalStage::Generated, "TowerKill", "Strong");
+        health.signal_counted(SignalStage::Generated, "TowerKill", "Strong");
+        health.signal_counted(SignalStage::Stored, "TowerKill", "Neutral");
+
+        let text = render(&health.report(true));
+        assert!(text.contains("esport_signal_matched_markets 3\n"));
+        assert!(text.contains(
+            "esport_signal_signals_total{stage=\"generated\",type=\"TowerKill\",strength=\"Strong\"} 2\n"
+        ));
+        assert!(text.contains(
+            "esport_signal_signals_total{stage=\"stored\",type=\"TowerKill\",strength=\"Neutral\"} 1\n"
+        ));
+    }
+}
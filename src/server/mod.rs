@@ -0,0 +1,235 @@
+//! HTTP server for health checks, signal listing, the GraphQL query API and
+//! admin endpoints
+
+pub mod admin;
+pub mod auth;
+pub mod graphql;
+pub mod grpc;
+pub mod metrics;
+pub mod rate_limit;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_graphql::http::GraphiQLSource;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::middleware;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::info;
+
+use crate::analytics::{SignalStats, StatsGroup};
+use crate::db::{SignalFilter, SignalPage, SignalStore};
+use crate::health::{Health, HealthReport};
+use crate::matching::TeamResolver;
+use auth::Scope;
+use graphql::SignalSchema;
+use rate_limit::RateLimiter;
+
+pub use auth::ApiTokens;
+
+/// Rows a list endpoint returns when no limit is given
+const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// Most rows a list endpoint returns at once
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// State shared by request handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub health: Health,
+    pub signal_store: Arc<SignalStore>,
+    pub graphql: SignalSchema,
+    pub team_resolver: Arc<TeamResolver>,
+
+    /// Tokens for everything but `/healthz`; all open when empty
+    pub api_tokens: Arc<ApiTokens>,
+
+    /// Request limits for everything but `/healthz`
+    pub rate_limiter: Arc<RateLimiter>,
+}
+
+/// Build the HTTP routes
+pub fn router(state: AppState) -> Router {
+    let mut api = Router::new()
+        .route("/signals", get(signals))
+        .route("/stats", get(stats))
+        .route("/graphql", get(graphiql).post(graphql_query));
+    if state.api_tokens.has_admin() {
+        api = api.nest("/admin", admin::router(&state));
+    }
+    if !state.api_tokens.is_empty() {
+        api = api.route_layer(middleware::from_fn_with_state(
+            (Arc::clone(&state.api_tokens), Scope::Read),
+            auth::require_scope,
+        ));
+    }
+    // Outermost, so rejected tokens count toward the limit too
+    api = api.route_layer(middleware::from_fn_with_state(
+        state.clone(),
+        rate_limit::limit_requests,
+    ));
+
+    // Health probes and scrapes stay open
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(prometheus_metrics))
+        .merge(api)
+        .with_state(state)
+}
+
+/// Serve HTTP on `addr` until shutdown is signaled
+pub async fn serve(addr: &str, state: AppState, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind HTTP server to {}", addr))?;
+    info!("HTTP server listening on {}", addr);
+
+    axum::serve(
+        listener,
+        router(state).into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        shutdown.changed().await.ok();
+    })
+    .await
+    .context("HTTP server failed")
+}
+
+/// 200 with the report when healthy, 503 otherwise
+async fn healthz(State(state): State<AppState>) -> (StatusCode, Json<HealthReport>) {
+    let database = state.signal_store.ping().await.is_ok();
+    let report = state.health.report(database);
+
+    let status = if report.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+/// Upstream and health metrics in the Prometheus text format
+async fn prometheus_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let database = state.signal_store.ping().await.is_ok();
+    let report = state.health.report(database);
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(&report),
+    )
+}
+
+/// Execute a GraphQL query
+async fn graphql_query(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(state.graphql.execute(request).await)
+}
+
+/// In-browser GraphQL IDE
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+/// Query string of `GET /signals`
+#[derive(Debug, Deserialize)]
+struct SignalsParams {
+    match_id: Option<i64>,
+    market: Option<String>,
+    team: Option<String>,
+    min_strength: Option<String>,
+    /// Comma-separated signal types
+    signal_type: Option<String>,
+    min_edge: Option<f64>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    outcome: Option<String>,
+    /// `next_cursor` of the previous page
+    before: Option<i64>,
+    limit: Option<i64>,
+}
+
+impl SignalsParams {
+    fn filter(&self) -> Result<SignalFilter> {
+        Ok(SignalFilter {
+            match_id: self.match_id,
+            market_condition_id: self.market.clone(),
+            team: self.team.clone(),
+            min_strength: self.min_strength.as_deref().map(str::parse).transpose()?,
+            signal_types: self
+                .signal_type
+                .iter()
+                .flat_map(|types| types.split(','))
+                .map(|t| t.trim().parse())
+                .collect::<Result<_>>()?,
+            min_net_edge: self.min_edge,
+            since: self.since,
+            until: self.until,
+            outcome: self.outcome.as_deref().map(str::parse).transpose()?,
+        })
+    }
+}
+
+/// Newest signals matching the query, one keyset page at a time
+async fn signals(
+    State(state): State<AppState>,
+    Query(params): Query<SignalsParams>,
+) -> Result<Json<SignalPage>, (StatusCode, String)> {
+    let filter = params
+        .filter()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    state
+        .signal_store
+        .query_signals_page(&filter, params.before, clamp_limit(params.limit))
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))
+}
+
+/// Grouping of `GET /stats`
+#[derive(Debug, Deserialize)]
+struct StatsParams {
+    group: Option<String>,
+}
+
+/// Counts, average edge and confidence of signals matching the query, per group
+async fn stats(
+    State(state): State<AppState>,
+    Query(params): Query<SignalsParams>,
+    Query(stats_params): Query<StatsParams>,
+) -> Result<Json<Vec<SignalStats>>, (StatusCode, String)> {
+    let bad_request = |e: anyhow::Error| (StatusCode::BAD_REQUEST, e.to_string());
+    let filter = params.filter().map_err(bad_request)?;
+    let group: StatsGroup = stats_params
+        .group
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(bad_request)?
+        .unwrap_or_default();
+
+    state
+        .signal_store
+        .signal_stats(&filter, group)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))
+}
+
+/// Page size for list endpoints: 50 by default, at most 500
+pub(crate) fn clamp_limit(limit: Option<i64>) -> i64 {
+    match limit {
+        Some(limit) if limit > 0 => limit.min(MAX_PAGE_SIZE),
+        _ => DEFAULT_PAGE_SIZE,
+    }
+}
@@ -0,0 +1,137 @@
+//! Per-client request limits for the HTTP API
+//!
+//! Clients are told apart by API token when they send a configured one, else
+//! by IP address, and each may make `API_RATE_LIMIT` requests per minute.
+//! Behind a reverse proxy every client shares the proxy's IP.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::AppState;
+
+/// Span requests are counted over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Clients tracked before idle ones are forgotten
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// Request times within the last minute, by client
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Requests per client per minute; 0 is unlimited
+    limit: u32,
+    requests: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a request from `client` at `now`, or return how long until it
+    /// may make another
+    pub fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+        let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+
+        if requests.len() >= MAX_TRACKED_CLIENTS && !requests.contains_key(client) {
+            requests.retain(|_, times| {
+                times
+                    .back()
+                    .is_some_and(|t| now.duration_since(*t) < RATE_WINDOW)
+            });
+        }
+
+        let times = requests.entry(client.to_string()).or_default();
+        while times
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            times.pop_front();
+        }
+        if times.len() >= self.limit as usize {
+            let oldest = times.front().copied().unwrap_or(now);
+            return Err(RATE_WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+
+        times.push_back(now);
+        Ok(())
+    }
+}
+
+/// 429 with `Retry-After` once a client is over its limit
+pub async fn limit_requests(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| state.api_tokens.scope(token).is_some());
+    let client = match token {
+        Some(token) => format!("token:{}", token),
+        None => request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| format!("ip:{}", addr.ip()))
+            .unwrap_or_else(|| "ip:unknown".to_string()),
+    };
+
+    match state.rate_limiter.check(&client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            let mut response =
+                (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs.max(1)));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_per_client() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert_eq!(limiter.check("ip:10.0.0.1", start), Ok(()));
+        assert_eq!(
+            limiter.check("ip:10.0.0.1", start + Duration::from_secs(20)),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.check("ip:10.0.0.1", start + Duration::from_secs(30)),
+            Err(Duration::from_secs(30))
+        );
+        assert_eq!(limiter.check("token:dash", start), Ok(()));
+
+        // The first request has left the window
+        assert_eq!(
+            limiter.check("ip:10.0.0.1", start + Duration::from_secs(60)),
+            Ok(())
+        );
+        assert!(RateLimiter::new(0).check("ip:10.0.0.1", start).is_ok());
+    }
+}
@@ -0,0 +1,35 @@
+//! In-process broadcast of stored signals
+//!
+//! Feeds live subscribers in the same process, such as gRPC
+//! `SubscribeSignals` streams and the NATS producer.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use super::SignalSink;
+use crate::models::Signal;
+
+/// Sink that republishes signals on a broadcast channel
+pub struct FeedSink {
+    sender: broadcast::Sender<Signal>,
+}
+
+impl FeedSink {
+    pub fn new(sender: broadcast::Sender<Signal>) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl SignalSink for FeedSink {
+    fn name(&self) -> &str {
+        "signal_feed"
+    }
+
+    async fn emit(&self, signal: &Signal) -> Result<()> {
+        // Sending fails only when nobody is subscribed
+        self.sender.send(signal.clone()).ok();
+        Ok(())
+    }
+}
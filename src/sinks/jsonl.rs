@@ -0,0 +1,67 @@
+//! JSON-lines stream of stored signals on stdout (`--output jsonl`)
+//!
+//! One signal per line, in the `/signals` row shape, flushed as it's
+//! written so `esport-signal --output jsonl | jq` sees signals live. Logs
+//! go to stderr in this mode so stdout carries nothing else.
+
+use std::io::ErrorKind;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::io::{self, AsyncWriteExt, Stdout};
+use tokio::sync::Mutex;
+use tracing::error;
+
+use super::SignalSink;
+use crate::models::Signal;
+
+/// Sink that writes each signal to stdout as a JSON line
+pub struct JsonlSink {
+    /// None once the reader went away
+    stdout: Mutex<Option<Stdout>>,
+}
+
+impl JsonlSink {
+    pub fn new() -> Self {
+        Self {
+            stdout: Mutex::new(Some(io::stdout())),
+        }
+    }
+}
+
+impl Default for JsonlSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SignalSink for JsonlSink {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    async fn emit(&self, signal: &Signal) -> Result<()> {
+        let mut line = serde_json::to_string(signal)?;
+        line.push('\n');
+
+        let mut stdout = self.stdout.lock().await;
+        let Some(out) = stdout.as_mut() else {
+            return Ok(());
+        };
+        let written = async {
+            out.write_all(line.as_bytes()).await?;
+            out.flush().await
+        };
+        match written.await {
+            Ok(()) => Ok(()),
+            // A closed pipe (e.g. `| head`) ends the output, not the service
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                error!("Stopped writing signals to stdout: {}", e);
+                *stdout = None;
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
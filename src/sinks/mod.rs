@@ -0,0 +1,285 @@
+//! Outputs every stored signal is fanned out to
+//!
+//! The signal processor hands each stored signal, with its id, to
+//! `SignalSinks`, which queues a copy for every registered `SignalSink`.
+//! Each sink drains its own bounded queue on its own task, in order, so a
+//! slow or unreachable sink only drops its own signals and never holds up
+//! the processor. A new output is a `SignalSink` impl plus a line in
+//! `SignalSinks::from_config` (or `main`, for command-line outputs).
+//!
+//! The database stays the record and is written before sinks see a signal;
+//! alerts go through the notifier, whose routing rules need the market.
+//! On shutdown, `SignalSinks::close` delivers what's queued and flushes
+//! each sink's own buffer.
+
+pub mod feed;
+pub mod jsonl;
+pub mod mqtt;
+pub mod redis_pubsub;
+pub mod timeseries;
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::health::Health;
+use crate::models::Signal;
+
+pub use self::feed::FeedSink;
+pub use self::jsonl::JsonlSink;
+pub use self::mqtt::MqttSink;
+pub use self::redis_pubsub::RedisSink;
+pub use self::timeseries::TimeSeriesSink;
+
+/// Signals queued per sink before new ones are dropped
+pub const SINK_QUEUE_CAPACITY: usize = 256;
+
+/// An output for stored signals
+#[async_trait]
+pub trait SignalSink: Send + Sync {
+    /// Short name used in logs and reported as an upstream in `/healthz`
+    fn name(&self) -> &str;
+
+    /// Deliver one stored signal
+    async fn emit(&self, signal: &Signal) -> Result<()>;
+
+    /// Deliver anything the sink still buffers; called once on shutdown,
+    /// after the last signal
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct SinkQueue {
+    name: String,
+    sender: mpsc::Sender<Signal>,
+}
+
+/// Fans stored signals out to the registered sinks; clones share the
+/// sinks' queues
+#[derive(Clone)]
+pub struct SignalSinks {
+    queues: Vec<SinkQueue>,
+    health: Health,
+    /// Tells the delivery tasks to finish their queues and flush
+    closing: Arc<watch::Sender<bool>>,
+    /// Delivery tasks, awaited by `close`
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl Default for SignalSinks {
+    fn default() -> Self {
+        Self::new(Health::default())
+    }
+}
+
+impl SignalSinks {
+    /// Create an empty registry reporting deliveries to `health`
+    pub fn new(health: Health) -> Self {
+        Self {
+            queues: Vec::new(),
+            health,
+            closing: Arc::new(watch::channel(false).0),
+            tasks: Arc::default(),
+        }
+    }
+
+    /// Create a registry with the sinks configured in `config`
+    pub fn from_config(config: &Config, health: Health) -> Self {
+        let mut sinks = Self::new(health);
+
+        if let Some(url) = &config.tsdb_write_url {
+            sinks = sinks.with_sink(TimeSeriesSink::new(url.clone(), config.tsdb_token.clone()));
+        }
+        if let Some(url) = &config.redis_url {
+            sinks = sinks.with_sink(RedisSink::new(url.clone(), config.redis_channel.clone()));
+        }
+        if let Some(url) = &config.mqtt_url {
+            match mqtt::mqtt_options(url, &config.mqtt_client_id) {
+                Ok(options) => {
+                    sinks = sinks.with_sink(MqttSink::new(options, config.mqtt_topic.clone()));
+                }
+                Err(e) => warn!("MQTT output disabled: {:#}", e),
+            }
+        }
+
+        sinks
+    }
+
+    /// Register a sink and start delivering to it; needs a Tokio runtime
+    pub fn with_sink(mut self, sink: impl SignalSink + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel(SINK_QUEUE_CAPACITY);
+        let name = sink.name().to_string();
+        info!("Signal sink registered: {}", name);
+        let task = tokio::spawn(deliver(
+            Arc::new(sink),
+            receiver,
+            self.closing.subscribe(),
+            self.health.clone(),
+        ));
+        self.tasks.lock().unwrap().push(task);
+        self.queues.push(SinkQueue { name, sender });
+        self
+    }
+
+    /// Names of the registered sinks
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.queues.iter().map(|queue| queue.name.as_str())
+    }
+
+    /// Queue a stored signal for every sink, dropping it for any sink whose
+    /// queue is full
+    pub fn emit(&self, signal: &Signal) {
+        for queue in &self.queues {
+            match queue.sender.try_send(signal.clone()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    warn!("{} sink is behind, dropped a signal", queue.name);
+                }
+                Err(TrySendError::Closed(_)) => {}
+            }
+        }
+    }
+
+    /// Stop taking signals, deliver the ones already queued, flush every
+    /// sink and wait for all of it; signals emitted after this are dropped
+    pub async fn close(&self) {
+        self.closing.send_replace(true);
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        for task in tasks {
+            task.await.ok();
+        }
+    }
+}
+
+/// Deliver queued signals to one sink until the registry is dropped or
+/// closed, then flush it
+async fn deliver(
+    sink: Arc<dyn SignalSink>,
+    mut receiver: mpsc::Receiver<Signal>,
+    mut closing: watch::Receiver<bool>,
+    health: Health,
+) {
+    loop {
+        tokio::select! {
+            signal = receiver.recv() => match signal {
+                Some(signal) => emit(sink.as_ref(), &signal, &health).await,
+                None => break,
+            },
+            // Only ever set to true, or dropped along with every registry
+            _ = closing.changed() => {
+                // Refuse new signals but deliver the queued ones
+                receiver.close();
+                while let Some(signal) = receiver.recv().await {
+                    emit(sink.as_ref(), &signal, &health).await;
+                }
+                break;
+            }
+        }
+    }
+
+    let started = Instant::now();
+    match sink.flush().await {
+        Ok(()) => {}
+        Err(e) => {
+            health.upstream_error(sink.name(), started.elapsed(), format!("{:#}", e));
+            warn!("{} sink failed to flush: {:#}", sink.name(), e);
+        }
+    }
+}
+
+/// Hand one signal to a sink and report the delivery
+async fn emit(sink: &dyn SignalSink, signal: &Signal, health: &Health) {
+    let started = Instant::now();
+    match sink.emit(signal).await {
+        Ok(()) => health.upstream_ok(sink.name(), started.elapsed()),
+        Err(e) => {
+            health.upstream_error(sink.name(), started.elapsed(), format!("{:#}", e));
+            warn!("{} sink failed: {:#}", sink.name(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Records delivered ids, and -1 once flushed
+    struct Recording(Arc<Mutex<Vec<i64>>>);
+
+    #[async_trait]
+    impl SignalSink for Recording {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn emit(&self, signal: &Signal) -> Result<()> {
+            self.0.lock().unwrap().push(signal.id.unwrap_or_default());
+            Ok(())
+        }
+
+        async fn flush(&self) -> Result<()> {
+            self.0.lock().unwrap().push(-1);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sinks_get_every_signal_in_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sinks = SignalSinks::default().with_sink(Recording(seen.clone()));
+        assert_eq!(sinks.names().collect::<Vec<_>>(), ["recording"]);
+
+        for id in 1..=3 {
+            sinks.emit(&Signal {
+                id: Some(id),
+                market_condition_id: "0xabc".to_string(),
+                match_id: 7,
+                market_team_a_odds: 0.55,
+                ..Default::default()
+            });
+        }
+        drop(sinks);
+
+        for _ in 0..50 {
+            if seen.lock().unwrap().len() == 4 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*seen.lock().unwrap(), [1, 2, 3, -1]);
+    }
+
+    #[tokio::test]
+    async fn test_close_delivers_queued_signals_then_flushes() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sinks = SignalSinks::default().with_sink(Recording(seen.clone()));
+        // A clone still holds the queues open, as a worker would
+        let held = sinks.clone();
+
+        for id in 1..=3 {
+            sinks.emit(&Signal {
+                id: Some(id),
+                ..Default::default()
+            });
+        }
+        sinks.close().await;
+        assert_eq!(*seen.lock().unwrap(), [1, 2, 3, -1]);
+
+        held.emit(&Signal {
+            id: Some(4),
+            ..Default::default()
+        });
+        assert_eq!(*seen.lock().unwrap(), [1, 2, 3, -1]);
+    }
+}
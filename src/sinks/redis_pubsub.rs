@@ -0,0 +1,66 @@
+//! Redis pub/sub output for stored signals
+//!
+//! Every stored signal is published as JSON (the same shape as `/signals`
+//! rows) on one channel, so local bots and dashboards can `SUBSCRIBE`
+//! instead of polling the database. Pub/sub doesn't keep messages: a
+//! signal published while Redis is down or nobody is listening is gone,
+//! and the database stays the record.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use super::SignalSink;
+use crate::models::Signal;
+
+/// Sink that publishes each signal to a Redis channel
+pub struct RedisSink {
+    redis_url: String,
+    channel: String,
+    /// Connected on first use; the manager reconnects by itself once it
+    /// has connected, so only the first connect is retried here
+    conn: Mutex<Option<ConnectionManager>>,
+}
+
+impl RedisSink {
+    /// Create a sink publishing to `channel` on the server at `redis_url`
+    pub fn new(redis_url: String, channel: String) -> Self {
+        Self {
+            redis_url,
+            channel,
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// Open a connection manager to the configured server
+    async fn connect(&self) -> Result<ConnectionManager> {
+        let client = redis::Client::open(self.redis_url.as_str())?;
+        Ok(ConnectionManager::new(client).await?)
+    }
+}
+
+#[async_trait]
+impl SignalSink for RedisSink {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    async fn emit(&self, signal: &Signal) -> Result<()> {
+        let payload = serde_json::to_string(signal)?;
+
+        let mut conn = self.conn.lock().await;
+        let conn = match conn.as_mut() {
+            Some(conn) => conn,
+            None => conn.insert(self.connect().await?),
+        };
+        let receivers: i64 = conn.publish(&self.channel, payload).await?;
+        debug!(
+            "Published signal for market {} to {} Redis subscribers",
+            signal.market_condition_id, receivers
+        );
+        Ok(())
+    }
+}
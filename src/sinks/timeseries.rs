@@ -0,0 +1,207 @@
+//! Export of market odds and model probabilities as time-series points
+//!
+//! Every stored signal becomes one point in the InfluxDB line protocol,
+//! which InfluxDB (`/api/v2/write`) and VictoriaMetrics (`/write`) both
+//! accept, so Grafana can chart model against market for each match.
+
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use super::SignalSink;
+use crate::models::{LiveMatchState, Signal};
+
+/// Measurement every point is written to
+const MEASUREMENT: &str = "esport_signal";
+
+/// Points kept while the database is unreachable; the oldest go first
+const MAX_PENDING_POINTS: usize = 10_000;
+
+/// Line-protocol point for a signal, timestamped in nanoseconds
+///
+/// Tagged by market, match, teams and strength; fields are the market and
+/// model probabilities for team A, the net edge and the game clock.
+pub fn line_protocol(signal: &Signal) -> Option<String> {
+    let timestamp = signal.created_at.timestamp_nanos_opt()?;
+    let state = serde_json::from_str::<LiveMatchState>(&signal.match_snapshot).ok();
+
+    let mut tags = vec![
+        ("market", signal.market_condition_id.clone()),
+        ("match_id", signal.match_id.to_string()),
+        ("strength", signal.strength.to_string()),
+    ];
+    if let Some(state) = &state {
+        tags.push(("radiant", state.radiant.name.clone()));
+        tags.push(("dire", state.dire.name.clone()));
+    }
+
+    let mut fields = vec![format!("market_team_a_odds={}", signal.market_team_a_odds)];
+    let probabilities = [
+        ("team_a_win_prob", signal.team_a_win_prob),
+        ("team_a_prior_prob", signal.team_a_prior_prob),
+        ("sharp_team_a_prob", signal.sharp_team_a_prob),
+        ("team_a_prob_lower", signal.team_a_prob_lower),
+        ("team_a_prob_upper", signal.team_a_prob_upper),
+        ("net_edge", signal.net_edge),
+    ];
+    for (name, value) in probabilities {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            fields.push(format!("{}={}", name, value));
+        }
+    }
+    if let Some(state) = &state {
+        fields.push(format!("game_time={}i", state.game_time));
+        fields.push(format!("gold_lead={}i", state.gold_lead));
+    }
+
+    let tags: String = tags
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!(",{}={}", key, escape_tag(value)))
+        .collect();
+    Some(format!(
+        "{}{} {} {}",
+        MEASUREMENT,
+        tags,
+        fields.join(","),
+        timestamp
+    ))
+}
+
+/// Escape commas, spaces and equals signs in a tag value
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | ' ' | '=' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Sink that writes each signal to a time-series database
+pub struct TimeSeriesSink {
+    client: Client,
+    write_url: String,
+    token: Option<String>,
+    /// Points not written yet, retried with the next signal
+    pending: Mutex<VecDeque<String>>,
+}
+
+impl TimeSeriesSink {
+    /// Create a sink posting line protocol to `write_url`, with
+    /// `Authorization: Token <token>` when a token is given
+    pub fn new(write_url: String, token: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            write_url,
+            token,
+            pending: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Post a batch of points
+    async fn write(&self, lines: &[String]) -> Result<()> {
+        let mut request = self.client.post(&self.write_url).body(lines.join("\n"));
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("Token {}", token));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("{} - {}", status, text);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SignalSink for TimeSeriesSink {
+    fn name(&self) -> &str {
+        "tsdb"
+    }
+
+    async fn emit(&self, signal: &Signal) -> Result<()> {
+        let Some(line) = line_protocol(signal) else {
+            return Ok(());
+        };
+
+        let mut pending = self.pending.lock().await;
+        if pending.len() == MAX_PENDING_POINTS {
+            pending.pop_front();
+        }
+        pending.push_back(line);
+
+        self.write(pending.make_contiguous()).await?;
+        debug!("Wrote {} time-series points", pending.len());
+        pending.clear();
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let mut pending = self.pending.lock().await;
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        self.write(pending.make_contiguous()).await?;
+        info!("Flushed {} pending time-series points", pending.len());
+        pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::models::{SignalStrength, TeamState};
+
+    #[test]
+    fn test_line_protocol() {
+        let state = LiveMatchState {
+            match_id: 7,
+            radiant: TeamState {
+                name: "Team Spirit".to_string(),
+                ..Default::default()
+            },
+            dire: TeamState {
+                name: "Liquid".to_string(),
+                ..Default::default()
+            },
+            gold_lead: -1200,
+            game_time: 900,
+            ..Default::default()
+        };
+        let signal = Signal {
+            id: Some(1),
+            market_condition_id: "0xabc".to_string(),
+            match_id: 7,
+            market_team_a_odds: 0.55,
+            team_a_prior_prob: Some(0.5),
+            team_a_win_prob: Some(0.6),
+            edge: Some(0.05),
+            net_edge: Some(0.03),
+            strength: SignalStrength::Moderate,
+            team_a_is_radiant: Some(true),
+            match_snapshot: serde_json::to_string(&state).unwrap(),
+            created_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            line_protocol(&signal).unwrap(),
+            "esport_signal,market=0xabc,match_id=7,strength=Moderate,radiant=Team\\ Spirit,dire=Liquid \
+             market_team_a_odds=0.55,team_a_win_prob=0.6,team_a_prior_prob=0.5,net_edge=0.03,\
+             game_time=900i,gold_lead=-1200i 1772366400000000000"
+        );
+    }
+}
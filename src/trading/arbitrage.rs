@@ -0,0 +1,112 @@
+use chrono::Utc;
+
+use super::TradingCosts;
+use crate::models::ArbitrageSignal;
+
+/// Finds locked-in profit between a Polymarket market and a Betfair back price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArbitrageDetector {
+    /// Polymarket fees and slippage
+    pub costs: TradingCosts,
+
+    /// Betfair commission on net winnings
+    pub betfair_commission: f64,
+
+    /// Smallest profit per $1 payout worth flagging
+    pub min_profit: f64,
+}
+
+/// One side of a match priced on both venues
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArbitrageLeg<'a> {
+    /// Team bought on Polymarket
+    pub polymarket_team: &'a str,
+    pub polymarket_price: f64,
+
+    /// Opposing team backed on Betfair
+    pub betfair_team: &'a str,
+    pub betfair_back_odds: f64,
+    pub betfair_available: f64,
+}
+
+impl ArbitrageDetector {
+    /// Betfair back stake that returns $1 (after commission) if the selection wins
+    pub fn betfair_stake(&self, back_odds: f64) -> f64 {
+        1.0 / (1.0 + (back_odds - 1.0) * (1.0 - self.betfair_commission))
+    }
+
+    /// Guaranteed profit per $1 of payout from buying one team on Polymarket
+    /// and backing the other on Betfair
+    pub fn profit(&self, polymarket_price: f64, liquidity: f64, back_odds: f64) -> f64 {
+        if back_odds <= 1.0 {
+            return f64::NEG_INFINITY;
+        }
+
+        1.0 - self.costs.cost_per_share(polymarket_price, liquidity) - self.betfair_stake(back_odds)
+    }
+
+    /// Arbitrage on a leg if its profit clears `min_profit`
+    pub fn check(
+        &self,
+        market_condition_id: &str,
+        betfair_market_id: &str,
+        leg: ArbitrageLeg<'_>,
+        liquidity: f64,
+    ) -> Option<ArbitrageSignal> {
+        let profit = self.profit(leg.polymarket_price, liquidity, leg.betfair_back_odds);
+        if profit < self.min_profit {
+            return None;
+        }
+
+        Some(ArbitrageSignal {
+            id: None,
+            market_condition_id: market_condition_id.to_string(),
+            betfair_market_id: betfair_market_id.to_string(),
+            polymarket_team: leg.polymarket_team.to_string(),
+            polymarket_cost: self.costs.cost_per_share(leg.polymarket_price, liquidity),
+            betfair_team: leg.betfair_team.to_string(),
+            betfair_back_odds: leg.betfair_back_odds,
+            betfair_available: leg.betfair_available,
+            betfair_stake: self.betfair_stake(leg.betfair_back_odds),
+            profit,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commission_and_fees_decide_arbitrage() {
+        let detector = ArbitrageDetector {
+            costs: TradingCosts::new(0.0, 0.0),
+            betfair_commission: 0.05,
+            min_profit: 0.005,
+        };
+
+        // Buy A at 0.40, back B at 2.0: B stake = 1 / 1.95
+        let profit = detector.profit(0.40, 10_000.0, 2.0);
+        assert!((profit - (0.60 - 1.0 / 1.95)).abs() < 1e-9);
+
+        // Prices that sum to one are no arbitrage once commission is paid
+        assert!(detector.profit(0.50, 10_000.0, 2.0) < 0.0);
+
+        let leg = ArbitrageLeg {
+            polymarket_team: "OG",
+            polymarket_price: 0.40,
+            betfair_team: "Team Spirit",
+            betfair_back_odds: 2.0,
+            betfair_available: 250.0,
+        };
+        let arb = detector.check("0xa", "1.234", leg, 10_000.0).unwrap();
+        assert_eq!(arb.betfair_team, "Team Spirit");
+
+        let thin = ArbitrageLeg {
+            betfair_back_odds: 1.70,
+            ..leg
+        };
+        assert!(detector.check("0xa", "1.234", thin, 10_000.0).is_none());
+    }
+}
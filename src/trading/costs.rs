@@ -0,0 +1,104 @@
+/// Share of the stake/liquidity ratio paid as price impact on average
+///
+/// Walking a book moves the price roughly in proportion to size over depth;
+/// the average fill sits halfway along that move.
+const SLIPPAGE_FACTOR: f64 = 0.5;
+
+/// Upper bound on estimated slippage per share
+const MAX_SLIPPAGE: f64 = 0.10;
+
+/// Trading fees and execution costs used to turn gross edge into net edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradingCosts {
+    /// Polymarket fee rate, charged as `rate * min(price, 1 - price)` per share
+    pub fee_rate: f64,
+
+    /// Stake in USD used to estimate slippage
+    pub reference_stake: f64,
+}
+
+impl TradingCosts {
+    /// Create a cost model
+    pub fn new(fee_rate: f64, reference_stake: f64) -> Self {
+        Self {
+            fee_rate,
+            reference_stake,
+        }
+    }
+
+    /// Fee per share at a given price
+    pub fn fee(&self, price: f64) -> f64 {
+        self.fee_rate * price.min(1.0 - price).max(0.0)
+    }
+
+    /// Estimated slippage per share for the reference stake
+    pub fn slippage(&self, liquidity: f64) -> f64 {
+        if liquidity <= 0.0 {
+            return MAX_SLIPPAGE;
+        }
+
+        (SLIPPAGE_FACTOR * self.reference_stake / liquidity).min(MAX_SLIPPAGE)
+    }
+
+    /// All-in cost of one share quoted at `price`
+    pub fn cost_per_share(&self, price: f64, liquidity: f64) -> f64 {
+        price + self.fee(price) + self.slippage(liquidity)
+    }
+
+    /// Net expected value per share on the better side, each side bought at
+    /// its own price
+    ///
+    /// Positive = back team A, negative = back team B, 0.0 when neither side
+    /// clears fees and slippage.
+    pub fn net_edge(
+        &self,
+        team_a_prob: f64,
+        team_a_price: f64,
+        team_b_price: f64,
+        liquidity: f64,
+    ) -> f64 {
+        let team_a_net = team_a_prob - self.cost_per_share(team_a_price, liquidity);
+        let team_b_net = (1.0 - team_a_prob) - self.cost_per_share(team_b_price, liquidity);
+
+        if team_a_net > 0.0 {
+            team_a_net
+        } else if team_b_net > 0.0 {
+            -team_b_net
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Default for TradingCosts {
+    fn default() -> Self {
+        Self::new(0.0, 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_costs_eat_small_edges() {
+        let costs = TradingCosts::new(0.02, 500.0);
+
+        // 3% gross edge on a $5k market: 1% fee + 5% slippage wipes it out
+        assert_eq!(costs.net_edge(0.63, 0.60, 0.40, 5_000.0), 0.0);
+
+        // 15% gross edge survives
+        let net = costs.net_edge(0.75, 0.60, 0.40, 5_000.0);
+        assert!(net > 0.0 && net < 0.15);
+    }
+
+    #[test]
+    fn test_net_edge_sign_follows_side() {
+        let costs = TradingCosts::default();
+        assert!(costs.net_edge(0.30, 0.50, 0.50, 100_000.0) < 0.0);
+        assert!(costs.net_edge(0.70, 0.50, 0.50, 100_000.0) > 0.0);
+
+        // Team B priced at its own ask, not one minus team A's
+        assert_eq!(costs.net_edge(0.45, 0.50, 0.56, 100_000.0), 0.0);
+    }
+}
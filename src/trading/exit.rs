@@ -0,0 +1,61 @@
+use crate::models::{ExitReason, SignalStrength};
+
+/// Minimum strength for a signal to count as an entry worth exiting later
+pub const ENTRY_MIN_STRENGTH: SignalStrength = SignalStrength::Strong;
+
+/// An edge that was signaled and has not been exited yet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpenEntry {
+    /// Signal that opened the edge
+    pub signal_id: i64,
+
+    /// Net edge at entry (positive = team A side)
+    pub net_edge: f64,
+}
+
+impl OpenEntry {
+    /// Decide whether the current net edge means the entry should be closed
+    ///
+    /// Reversed when the model now favours the other side; collapsed when
+    /// the edge on the entry side is at or below `exit_threshold`.
+    pub fn check_exit(&self, current_net_edge: f64, exit_threshold: f64) -> Option<ExitReason> {
+        let entry_side = self.net_edge.signum();
+        let edge_on_entry_side = current_net_edge * entry_side;
+
+        if edge_on_entry_side < 0.0 {
+            Some(ExitReason::Reversed)
+        } else if edge_on_entry_side <= exit_threshold {
+            Some(ExitReason::Collapsed)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_reasons() {
+        let entry = OpenEntry {
+            signal_id: 1,
+            net_edge: 0.12,
+        };
+
+        assert_eq!(entry.check_exit(0.08, 0.01), None);
+        assert_eq!(entry.check_exit(0.005, 0.01), Some(ExitReason::Collapsed));
+        assert_eq!(entry.check_exit(0.0, 0.01), Some(ExitReason::Collapsed));
+        assert_eq!(entry.check_exit(-0.03, 0.01), Some(ExitReason::Reversed));
+
+        let team_b_entry = OpenEntry {
+            signal_id: 2,
+            net_edge: -0.12,
+        };
+        assert_eq!(team_b_entry.check_exit(-0.10, 0.01), None);
+        assert_eq!(
+            team_b_entry.check_exit(0.02, 0.01),
+            Some(ExitReason::Reversed)
+        );
+    }
+}
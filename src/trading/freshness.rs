@@ -0,0 +1,33 @@
+/// Age of live data up to which it counts as current
+const FRESH_DATA_SECS: f64 = 5.0;
+
+/// Age of live data at which confidence bottoms out
+const STALE_DATA_SECS: f64 = 120.0;
+
+/// Confidence given to live data `STALE_DATA_SECS` or more old
+const MIN_FRESHNESS_CONFIDENCE: f64 = 0.5;
+
+/// How much to trust a signal given the age of its live data in seconds,
+/// from 1.0 up to `FRESH_DATA_SECS` down to `MIN_FRESHNESS_CONFIDENCE`
+///
+/// The market keeps moving while the data ages, so an edge against a
+/// minute-old game state is partly the game having moved on.
+pub fn freshness_confidence(age_secs: f64) -> f64 {
+    let t = ((age_secs - FRESH_DATA_SECS) / (STALE_DATA_SECS - FRESH_DATA_SECS)).clamp(0.0, 1.0);
+    1.0 - t * (1.0 - MIN_FRESHNESS_CONFIDENCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freshness_confidence() {
+        assert_eq!(freshness_confidence(3.0), 1.0);
+        assert!(freshness_confidence(60.0) < freshness_confidence(3.0));
+        assert!(freshness_confidence(60.0) > MIN_FRESHNESS_CONFIDENCE);
+        assert_eq!(freshness_confidence(600.0), MIN_FRESHNESS_CONFIDENCE);
+        // Clock skew can make data look like it's from the future
+        assert_eq!(freshness_confidence(-2.0), 1.0);
+    }
+}
@@ -0,0 +1,16 @@
+pub mod arbitrage;
+pub mod costs;
+pub mod exit;
+pub mod freshness;
+pub mod risk;
+pub mod sharp;
+pub mod stop_loss;
+pub mod volume;
+
+pub use arbitrage::{ArbitrageDetector, ArbitrageLeg};
+pub use costs::TradingCosts;
+pub use exit::OpenEntry;
+pub use freshness::freshness_confidence;
+pub use risk::{LimitBreach, RiskEngine, RiskLimits};
+pub use sharp::sharp_confidence;
+pub use volume::volume_confidence;
@@ -0,0 +1,286 @@
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::Utc;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::db::PositionStore;
+use crate::models::Position;
+use crate::notifications::{Notification, Notifier, Priority};
+
+/// Limits every new position must satisfy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskLimits {
+    /// Bankroll in USD that stake fractions are measured against
+    pub bankroll: f64,
+
+    /// Maximum cost of a single position as a fraction of bankroll
+    pub max_stake_fraction: f64,
+
+    /// Maximum open USD exposure on one match
+    pub max_match_exposure: f64,
+
+    /// Maximum USD staked on positions opened in one UTC day
+    pub max_daily_exposure: f64,
+
+    /// Maximum number of positions held at once
+    pub max_concurrent_positions: usize,
+}
+
+impl RiskLimits {
+    /// Build limits from the application config
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            bankroll: config.bankroll,
+            max_stake_fraction: config.max_stake_fraction,
+            max_match_exposure: config.max_match_exposure,
+            max_daily_exposure: config.max_daily_exposure,
+            max_concurrent_positions: config.max_concurrent_positions,
+        }
+    }
+
+    /// Every limit a proposed position would breach
+    ///
+    /// `open` are positions still holding shares; `opened_today` are all
+    /// positions opened since UTC midnight, whatever their state.
+    pub fn check(
+        &self,
+        proposed: &Position,
+        open: &[Position],
+        opened_today: &[Position],
+    ) -> Vec<LimitBreach> {
+        let mut breaches = Vec::new();
+        let stake = proposed.cost_basis();
+
+        let max_stake = self.bankroll * self.max_stake_fraction;
+        if stake > max_stake {
+            breaches.push(LimitBreach::Stake {
+                stake,
+                limit: max_stake,
+            });
+        }
+
+        let match_exposure: f64 = open
+            .iter()
+            .filter(|p| same_match(p, proposed))
+            .map(|p| p.open_exposure())
+            .sum::<f64>()
+            + stake;
+        if match_exposure > self.max_match_exposure {
+            breaches.push(LimitBreach::MatchExposure {
+                exposure: match_exposure,
+                limit: self.max_match_exposure,
+            });
+        }
+
+        let daily_exposure: f64 = opened_today.iter().map(|p| p.cost_basis()).sum::<f64>() + stake;
+        if daily_exposure > self.max_daily_exposure {
+            breaches.push(LimitBreach::DailyExposure {
+                exposure: daily_exposure,
+                limit: self.max_daily_exposure,
+            });
+        }
+
+        if open.len() + 1 > self.max_concurrent_positions {
+            breaches.push(LimitBreach::ConcurrentPositions {
+                count: open.len() + 1,
+                limit: self.max_concurrent_positions,
+            });
+        }
+
+        breaches
+    }
+
+    /// Kelly stake in USD for buying a side priced at `price` that wins with
+    /// `win_prob`, capped at the per-position limit; `None` without an edge
+    pub fn recommended_stake(&self, win_prob: f64, price: f64) -> Option<f64> {
+        if !(0.0..1.0).contains(&price) || win_prob <= price {
+            return None;
+        }
+
+        let kelly_fraction = (win_prob - price) / (1.0 - price);
+        Some(self.bankroll * kelly_fraction.min(self.max_stake_fraction))
+    }
+}
+
+/// Positions on the same match, or the same market when the match is unknown
+fn same_match(a: &Position, b: &Position) -> bool {
+    match (a.match_id, b.match_id) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.market_condition_id == b.market_condition_id,
+    }
+}
+
+/// A risk limit that a proposed position would exceed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitBreach {
+    Stake { stake: f64, limit: f64 },
+    MatchExposure { exposure: f64, limit: f64 },
+    DailyExposure { exposure: f64, limit: f64 },
+    ConcurrentPositions { count: usize, limit: usize },
+}
+
+impl fmt::Display for LimitBreach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitBreach::Stake { stake, limit } => {
+                write!(f, "stake ${:.2} exceeds ${:.2} per position", stake, limit)
+            }
+            LimitBreach::MatchExposure { exposure, limit } => {
+                write!(f, "match exposure ${:.2} exceeds ${:.2}", exposure, limit)
+            }
+            LimitBreach::DailyExposure { exposure, limit } => {
+                write!(f, "daily exposure ${:.2} exceeds ${:.2}", exposure, limit)
+            }
+            LimitBreach::ConcurrentPositions { count, limit } => {
+                write!(f, "{} concurrent positions exceeds {}", count, limit)
+            }
+        }
+    }
+}
+
+/// Gatekeeper that records positions only when they satisfy the risk limits
+pub struct RiskEngine {
+    limits: RiskLimits,
+    position_store: Arc<PositionStore>,
+    notifier: Notifier,
+}
+
+impl RiskEngine {
+    /// Create a new risk engine
+    pub fn new(limits: RiskLimits, position_store: Arc<PositionStore>, notifier: Notifier) -> Self {
+        Self {
+            limits,
+            position_store,
+            notifier,
+        }
+    }
+
+    /// Limits breached by a proposed position given the current book
+    pub async fn check(&self, proposed: &Position) -> Result<Vec<LimitBreach>> {
+        let open = self.position_store.get_open_positions().await?;
+
+        let midnight = Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc();
+        let opened_today = self
+            .position_store
+            .get_positions_opened_since(midnight)
+            .await?;
+
+        Ok(self.limits.check(proposed, &open, &opened_today))
+    }
+
+    /// Record a position if it is within limits
+    ///
+    /// Breaches are logged and notified; the position is rejected unless
+    /// `force` is set.
+    pub async fn open_position(&self, position: &Position, force: bool) -> Result<i64> {
+        let breaches = self.check(position).await?;
+
+        if !breaches.is_empty() {
+            let summary = breaches
+                .iter()
+                .map(|b| b.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            warn!(
+                "RISK LIMIT | Market {} | {} | {}",
+                position.market_condition_id,
+                if force { "overridden" } else { "rejected" },
+                summary
+            );
+
+            self.notifier
+                .notify(Notification::new(
+                    Priority::Urgent,
+                    format!("Risk limit breached on {}", position.market_condition_id),
+                    format!(
+                        "{} {} @ {:.3} x {} {}: {}",
+                        position.side,
+                        position.market_condition_id,
+                        position.entry_price,
+                        position.shares,
+                        if force { "recorded anyway" } else { "rejected" },
+                        summary
+                    ),
+                ))
+                .await;
+
+            if !force {
+                anyhow::bail!("Position rejected by risk limits: {}", summary);
+            }
+        }
+
+        self.position_store.insert_position(position).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PositionSide;
+
+    fn limits() -> RiskLimits {
+        RiskLimits {
+            bankroll: 1000.0,
+            max_stake_fraction: 0.05,
+            max_match_exposure: 80.0,
+            max_daily_exposure: 120.0,
+            max_concurrent_positions: 2,
+        }
+    }
+
+    fn position(market: &str, match_id: i64, stake: f64) -> Position {
+        let mut position = Position::open(market, PositionSide::TeamA, 0.5, stake / 0.5);
+        position.match_id = Some(match_id);
+        position
+    }
+
+    #[test]
+    fn test_within_limits() {
+        let proposed = position("0xa", 1, 40.0);
+        assert!(limits().check(&proposed, &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_breaches() {
+        let limits = limits();
+
+        let too_big = position("0xa", 1, 60.0);
+        assert_eq!(
+            limits.check(&too_big, &[], &[]),
+            vec![LimitBreach::Stake {
+                stake: 60.0,
+                limit: 50.0
+            }]
+        );
+
+        // Positions in different markets on the same match share exposure
+        let held = vec![position("0xa", 1, 45.0), position("0xb", 2, 45.0)];
+        let proposed = position("0xc", 1, 45.0);
+        let breaches = limits.check(&proposed, &held, &held);
+        assert!(matches!(breaches[0], LimitBreach::MatchExposure { .. }));
+        assert!(matches!(breaches[1], LimitBreach::DailyExposure { .. }));
+        assert!(matches!(
+            breaches[2],
+            LimitBreach::ConcurrentPositions { count: 3, limit: 2 }
+        ));
+    }
+
+    #[test]
+    fn test_recommended_stake() {
+        let limits = limits();
+
+        // 2% Kelly fraction is under the 5% cap
+        let stake = limits.recommended_stake(0.51, 0.5).unwrap();
+        assert!((stake - 20.0).abs() < 1e-9);
+        assert_eq!(limits.recommended_stake(0.7, 0.5), Some(50.0));
+        assert_eq!(limits.recommended_stake(0.45, 0.5), None);
+    }
+}
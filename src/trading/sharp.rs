@@ -0,0 +1,47 @@
+/// Confidence given to an edge a sharp book prices the other way by
+/// `SHARP_DISAGREEMENT` or more
+const MIN_SHARP_CONFIDENCE: f64 = 0.5;
+
+/// How far, in probability, a sharp book leaning against the model takes
+/// confidence down to `MIN_SHARP_CONFIDENCE`
+const SHARP_DISAGREEMENT: f64 = 0.10;
+
+/// How much to trust the model's edge given a sharp book's price, from 1.0
+/// when the book sits on the model's side of the market (or isn't quoted)
+/// down to `MIN_SHARP_CONFIDENCE`
+///
+/// Sharp books move first on public information, so a Polymarket price the
+/// book agrees with is more likely right than the model.
+pub fn sharp_confidence(model_prob: f64, sharp_prob: Option<f64>, market_prob: f64) -> f64 {
+    let Some(sharp_prob) = sharp_prob else {
+        return 1.0;
+    };
+    let model_lean = model_prob - market_prob;
+    let sharp_lean = sharp_prob - market_prob;
+    if model_lean * sharp_lean >= 0.0 {
+        return 1.0;
+    }
+
+    let t = (sharp_lean.abs() / SHARP_DISAGREEMENT).min(1.0);
+    1.0 - t * (1.0 - MIN_SHARP_CONFIDENCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharp_confidence() {
+        assert_eq!(sharp_confidence(0.7, None, 0.6), 1.0);
+        // The book agrees the market is too low on team A
+        assert_eq!(sharp_confidence(0.7, Some(0.65), 0.6), 1.0);
+        assert_eq!(sharp_confidence(0.7, Some(0.6), 0.6), 1.0);
+        // ... or is too high, against a model backing team B
+        assert_eq!(sharp_confidence(0.4, Some(0.55), 0.6), 1.0);
+
+        let mild = sharp_confidence(0.7, Some(0.57), 0.6);
+        assert!(mild < 1.0 && mild > MIN_SHARP_CONFIDENCE);
+        assert_eq!(sharp_confidence(0.7, Some(0.4), 0.6), MIN_SHARP_CONFIDENCE);
+        assert_eq!(sharp_confidence(0.4, Some(0.8), 0.6), MIN_SHARP_CONFIDENCE);
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::models::{Position, PositionSide};
+
+/// Why a position's stop-loss fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// Unrealized loss on the shares held exceeds the configured fraction
+    LossLimit,
+    /// The model no longer thinks the held side is worth its entry price
+    ModelBelowEntry,
+}
+
+impl StopReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopReason::LossLimit => "LossLimit",
+            StopReason::ModelBelowEntry => "ModelBelowEntry",
+        }
+    }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Price of the held side given team A's price
+pub fn side_price(side: PositionSide, team_a_price: f64) -> f64 {
+    match side {
+        PositionSide::TeamA => team_a_price,
+        PositionSide::TeamB => 1.0 - team_a_price,
+    }
+}
+
+/// Unrealized loss on the shares held as a fraction of their cost (negative = gain)
+pub fn unrealized_loss_fraction(position: &Position, current_price: f64) -> f64 {
+    if position.entry_price <= 0.0 {
+        return 0.0;
+    }
+
+    (position.entry_price - current_price) / position.entry_price
+}
+
+/// Decide whether an open position should be stopped out
+///
+/// `current_price` and `model_prob` are for the held side. The loss limit is
+/// checked first since it reflects money already lost.
+pub fn check_stop(
+    position: &Position,
+    current_price: f64,
+    model_prob: Option<f64>,
+    max_loss_fraction: f64,
+) -> Option<StopReason> {
+    if !position.status.is_active() {
+        return None;
+    }
+
+    if unrealized_loss_fraction(position, current_price) >= max_loss_fraction {
+        Some(StopReason::LossLimit)
+    } else if model_prob.is_some_and(|p| p < position.entry_price) {
+        Some(StopReason::ModelBelowEntry)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_reasons() {
+        let position = Position::open("0xa", PositionSide::TeamB, 0.50, 100.0);
+
+        assert_eq!(check_stop(&position, 0.45, Some(0.60), 0.3), None);
+        assert_eq!(
+            check_stop(&position, 0.35, Some(0.60), 0.3),
+            Some(StopReason::LossLimit)
+        );
+        assert_eq!(
+            check_stop(&position, 0.48, Some(0.47), 0.3),
+            Some(StopReason::ModelBelowEntry)
+        );
+
+        // Team B's price and model probability mirror team A's
+        assert!((side_price(PositionSide::TeamB, 0.7) - 0.3).abs() < 1e-9);
+    }
+}
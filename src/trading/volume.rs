@@ -0,0 +1,37 @@
+/// Confidence given to a market that barely trades
+///
+/// A thin market's quote can be hours old, so part of any edge against it is
+/// the quote lagging rather than the model being right.
+const MIN_VOLUME_CONFIDENCE: f64 = 0.5;
+
+/// How much to trust a market's quote given its 24h volume, from
+/// `MIN_VOLUME_CONFIDENCE` up to 1.0 at `full_volume` USD
+///
+/// Grows with the square root of volume, so the first few thousand dollars
+/// matter most. Markets with no volume figure (Azuro) and a `full_volume`
+/// of 0 get full confidence.
+pub fn volume_confidence(volume_24h: Option<f64>, full_volume: f64) -> f64 {
+    match volume_24h {
+        Some(volume) if full_volume > 0.0 => (volume.max(0.0) / full_volume)
+            .sqrt()
+            .clamp(MIN_VOLUME_CONFIDENCE, 1.0),
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_confidence() {
+        assert_eq!(
+            volume_confidence(Some(0.0), 10_000.0),
+            MIN_VOLUME_CONFIDENCE
+        );
+        assert!((volume_confidence(Some(4_900.0), 10_000.0) - 0.7).abs() < 1e-9);
+        assert_eq!(volume_confidence(Some(50_000.0), 10_000.0), 1.0);
+        assert_eq!(volume_confidence(None, 10_000.0), 1.0);
+        assert_eq!(volume_confidence(Some(0.0), 0.0), 1.0);
+    }
+}
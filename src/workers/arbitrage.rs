@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use crate::api::betfair::{BetfairMatchOdds, BetfairRunner};
+use crate::api::BetfairClient;
+use crate::config::SharedTuning;
+use crate::db::SignalStore;
+use crate::health::Health;
+use crate::matching::TeamResolver;
+use crate::models::{ActiveMarkets, ArbitrageSignal, Market};
+use crate::notifications::{Notification, Notifier, Priority};
+use crate::trading::{ArbitrageDetector, ArbitrageLeg, TradingCosts};
+
+use super::retune;
+
+/// Name reported to the health registry
+const WORKER: &str = "arbitrage";
+
+/// Upstream API name reported to the health registry
+const UPSTREAM: &str = "betfair";
+
+/// Worker that compares Polymarket prices with Betfair and flags arbitrage
+pub struct ArbitrageWorker {
+    client: BetfairClient,
+    active_markets: Arc<ActiveMarkets>,
+    team_resolver: Arc<TeamResolver>,
+    signal_store: Arc<SignalStore>,
+    notifier: Notifier,
+    betfair_commission: f64,
+    tuning: SharedTuning,
+    /// Markets currently in arbitrage, so each opportunity is emitted once
+    open_arbs: Mutex<HashSet<String>>,
+    health: Health,
+}
+
+impl ArbitrageWorker {
+    /// Create a new arbitrage worker
+    pub fn new(
+        client: BetfairClient,
+        active_markets: Arc<ActiveMarkets>,
+        team_resolver: Arc<TeamResolver>,
+        signal_store: Arc<SignalStore>,
+        notifier: Notifier,
+        betfair_commission: f64,
+        tuning: SharedTuning,
+    ) -> Self {
+        Self {
+            client,
+            active_markets,
+            team_resolver,
+            signal_store,
+            notifier,
+            betfair_commission,
+            tuning,
+            open_arbs: Mutex::new(HashSet::new()),
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks and upstream calls to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        let poll_interval = Duration::from_secs(self.tuning.load().arbitrage_poll_interval);
+        info!(
+            "Arbitrage detector started (interval: {:?}, commission: {:.1}%)",
+            poll_interval,
+            self.betfair_commission * 100.0
+        );
+
+        let mut interval = time::interval(poll_interval);
+        self.health.register_worker(WORKER, Some(poll_interval));
+
+        loop {
+            interval.tick().await;
+            retune(
+                &mut interval,
+                self.tuning.load().arbitrage_poll_interval,
+                "Arbitrage",
+            );
+            self.health.worker_tick(WORKER, Some(interval.period()));
+
+            if self.active_markets.is_empty() {
+                debug!("No active markets, skipping Betfair fetch");
+                continue;
+            }
+
+            let started = Instant::now();
+            match self.client.fetch_dota2_match_odds().await {
+                Ok(betfair_markets) => {
+                    self.health.upstream_ok(UPSTREAM, started.elapsed());
+                    self.scan(&betfair_markets).await;
+                }
+                Err(e) => {
+                    self.health.upstream_error(UPSTREAM, started.elapsed(), &e);
+                    error!("Failed to fetch Betfair odds: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Check every active market that Betfair also prices
+    async fn scan(&self, betfair_markets: &[BetfairMatchOdds]) {
+        let detector = self.detector();
+        let found: Vec<ArbitrageSignal> = self
+            .active_markets
+            .iter()
+            .filter(|market| !market.is_outright())
+            .filter_map(|market| self.best_arbitrage(&detector, &market, betfair_markets))
+            .collect();
+
+        let mut open_arbs = self.open_arbs.lock().await;
+        let current: HashSet<String> = found
+            .iter()
+            .map(|arb| arb.market_condition_id.clone())
+            .collect();
+
+        for arb in found {
+            if open_arbs.contains(&arb.market_condition_id) {
+                continue;
+            }
+            self.emit(&arb).await;
+        }
+
+        *open_arbs = current;
+    }
+
+    /// Detector built from the current tuning
+    fn detector(&self) -> ArbitrageDetector {
+        let tuning = self.tuning.load();
+        ArbitrageDetector {
+            costs: TradingCosts::new(tuning.trading_fee_rate, tuning.reference_stake),
+            betfair_commission: self.betfair_commission,
+            min_profit: tuning.arbitrage_min_profit,
+        }
+    }
+
+    /// Most profitable arbitrage for a market, if any
+    fn best_arbitrage(
+        &self,
+        detector: &ArbitrageDetector,
+        market: &Market,
+        betfair_markets: &[BetfairMatchOdds],
+    ) -> Option<ArbitrageSignal> {
+        let (betfair, runner_a, runner_b) = betfair_markets
+            .iter()
+            .find_map(|b| self.match_runners(market, b).map(|(a, b_)| (b, a, b_)))?;
+
+        // Buy A on Polymarket + back B on Betfair, or the mirror, at the
+        // price a buy actually fills at
+        let legs = [
+            (market.team_a_buy_price(), &market.team_a, runner_b),
+            (market.team_b_buy_price(), &market.team_b, runner_a),
+        ];
+
+        legs.into_iter()
+            .filter_map(|(price, team, runner)| {
+                let leg = ArbitrageLeg {
+                    polymarket_team: team,
+                    polymarket_price: price,
+                    betfair_team: &runner.name,
+                    betfair_back_odds: runner.best_back_odds?,
+                    betfair_available: runner.best_back_size,
+                };
+                detector.check(
+                    &market.condition_id,
+                    &betfair.market_id,
+                    leg,
+                    market.liquidity,
+                )
+            })
+            .max_by(|a, b| a.profit.total_cmp(&b.profit))
+    }
+
+    /// Betfair runners for the market's team A and team B
+    fn match_runners<'a>(
+        &self,
+        market: &Market,
+        betfair: &'a BetfairMatchOdds,
+    ) -> Option<(&'a BetfairRunner, &'a BetfairRunner)> {
+        let find = |team: &str| {
+            betfair
+                .runners
+                .iter()
+                .find(|r| self.team_resolver.names_match(&r.name, team))
+        };
+
+        Some((find(&market.team_a)?, find(&market.team_b)?))
+    }
+
+    /// Log, store and notify a new arbitrage
+    async fn emit(&self, arb: &ArbitrageSignal) {
+        warn!(
+            "ARBITRAGE | Market {} | Buy {} on Polymarket @ {:.3} | Back {} on Betfair @ {:.2} (${:.0} available) | Profit: {:.2}%",
+            arb.market_condition_id,
+            arb.polymarket_team,
+            arb.polymarket_cost,
+            arb.betfair_team,
+            arb.betfair_back_odds,
+            arb.betfair_available,
+            arb.profit * 100.0
+        );
+
+        if let Err(e) = self.signal_store.insert_arbitrage_signal(arb).await {
+            error!("Failed to store arbitrage signal: {}", e);
+        }
+
+        self.notifier
+            .notify(Notification::new(
+                Priority::Urgent,
+                format!("Arbitrage: {:.2}% locked in", arb.profit * 100.0),
+                format!(
+                    "Buy {} on Polymarket at {:.3} (all-in) and back {} on Betfair at {:.2} with {:.3} per $1 payout (market {}, Betfair {})",
+                    arb.polymarket_team,
+                    arb.polymarket_cost,
+                    arb.betfair_team,
+                    arb.betfair_back_odds,
+                    arb.betfair_stake,
+                    arb.market_condition_id,
+                    arb.betfair_market_id
+                ),
+            ))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Tuning};
+    use crate::models::Venue;
+
+    fn runner(name: &str, back_odds: f64) -> BetfairRunner {
+        BetfairRunner {
+            selection_id: 1,
+            name: name.to_string(),
+            best_back_odds: Some(back_odds),
+            best_back_size: 1000.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arbitrage_prices_the_polymarket_leg_at_the_ask() {
+        let worker = ArbitrageWorker::new(
+            BetfairClient::new("http://localhost", "", ""),
+            Arc::default(),
+            Arc::new(TeamResolver::new()),
+            Arc::new(SignalStore::new("sqlite::memory:").await.unwrap()),
+            Notifier::new(Vec::new()),
+            0.02,
+            Tuning::shared(&Config::load(None).unwrap()),
+        );
+        let detector = worker.detector();
+
+        let mut market = Market {
+            condition_id: "0xa".to_string(),
+            venue: Venue::Polymarket,
+            question: "Dota 2: Team Spirit vs OG".to_string(),
+            team_a: "Team Spirit".to_string(),
+            team_b: "OG".to_string(),
+            team_a_odds: 0.45,
+            team_b_odds: 0.55,
+            opening_team_a_odds: 0.45,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 50_000.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        };
+        let betfair = [BetfairMatchOdds {
+            market_id: "1.23".to_string(),
+            event_name: "Team Spirit v OG".to_string(),
+            runners: vec![runner("Team Spirit", 1.8), runner("OG", 2.3)],
+        }];
+
+        // The midpoint plus backing OG at 2.3 costs under $1
+        let arb = worker.best_arbitrage(&detector, &market, &betfair).unwrap();
+        assert_eq!(arb.polymarket_team, "Team Spirit");
+
+        // ... but team A's ask doesn't
+        market.team_a_bid = Some(0.40);
+        market.team_a_ask = Some(0.58);
+        assert!(worker
+            .best_arbitrage(&detector, &market, &betfair)
+            .is_none());
+    }
+}
@@ -0,0 +1,348 @@
+//! Places Azuro bets on strong signals (needs the `azuro` feature)
+//!
+//! Each stored signal on an Azuro market at `AZURO_BET_MIN_STRENGTH` or
+//! stronger backs the side its net edge favours with a Kelly stake capped
+//! at `AZURO_MAX_BET`, once per market. The bet must pass the risk limits
+//! before it is signed, and is recorded as a position once the relayer
+//! accepts it.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, error, info, warn};
+
+use crate::api::azuro::WINNER_OUTCOME_IDS;
+use crate::api::{AzuroBetClient, BetOrder};
+use crate::db::PositionStore;
+use crate::health::Health;
+use crate::models::{ActiveMarkets, Market, Position, PositionSide, Signal, SignalStrength, Venue};
+use crate::notifications::{Notification, Notifier, Priority};
+use crate::trading::{RiskEngine, RiskLimits};
+
+/// Name reported to the health registry
+const WORKER: &str = "azuro_bets";
+
+/// Upstream API name reported to the health registry
+const UPSTREAM: &str = "azuro_relayer";
+
+/// Smallest stake worth a bet, in USD
+const MIN_BET: f64 = 1.0;
+
+/// When and how much to bet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BetSettings {
+    /// Weakest signal that places a bet
+    pub min_strength: SignalStrength,
+    /// Largest stake in USD
+    pub max_bet: f64,
+    /// Fraction the odds may drop below the quote before the bet is refused
+    pub max_slippage: f64,
+}
+
+/// Worker that turns strong Azuro signals into bets
+pub struct AzuroBetWorker {
+    client: AzuroBetClient,
+    active_markets: Arc<ActiveMarkets>,
+    position_store: Arc<PositionStore>,
+    risk: RiskEngine,
+    limits: RiskLimits,
+    settings: BetSettings,
+    signals: broadcast::Receiver<Signal>,
+    notifier: Notifier,
+    health: Health,
+}
+
+impl AzuroBetWorker {
+    /// Create a worker betting on signals from the stored-signal feed
+    pub fn new(
+        client: AzuroBetClient,
+        active_markets: Arc<ActiveMarkets>,
+        position_store: Arc<PositionStore>,
+        limits: RiskLimits,
+        settings: BetSettings,
+        signals: broadcast::Receiver<Signal>,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            client,
+            active_markets,
+            risk: RiskEngine::new(limits, Arc::clone(&position_store), notifier.clone()),
+            position_store,
+            limits,
+            settings,
+            signals,
+            notifier,
+            health: Health::default(),
+        }
+    }
+
+    /// Report bets and relayer calls to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop until the signal feed closes
+    pub async fn run(mut self) {
+        info!(
+            "Azuro bet worker started (bettor {}, {}+ signals, max ${:.0})",
+            self.client.bettor(),
+            self.settings.min_strength,
+            self.settings.max_bet
+        );
+        self.health.register_worker(WORKER, None);
+
+        loop {
+            let signal = match self.signals.recv().await {
+                Ok(signal) => signal,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Azuro bet worker fell behind, skipped {} signals", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            self.health.worker_tick(WORKER, None);
+
+            if let Err(e) = self.consider(&signal).await {
+                error!(
+                    "Azuro bet on market {} failed: {:#}",
+                    signal.market_condition_id, e
+                );
+                self.notifier
+                    .notify(Notification::new(
+                        Priority::Urgent,
+                        format!("Azuro bet failed on {}", signal.market_condition_id),
+                        format!("Signal {}: {:#}", signal.id.unwrap_or_default(), e),
+                    ))
+                    .await;
+            }
+        }
+
+        info!("Azuro bet worker stopped");
+    }
+
+    /// Bet on a signal if it qualifies and the book allows it
+    async fn consider(&self, signal: &Signal) -> Result<()> {
+        let Some(market) = self
+            .active_markets
+            .get(&signal.market_condition_id)
+            .map(|m| m.clone())
+        else {
+            return Ok(());
+        };
+        let Some((position, order)) = plan_bet(signal, &market, &self.limits, &self.settings)
+        else {
+            return Ok(());
+        };
+
+        // One bet per market, including ones placed before a restart
+        if !self
+            .position_store
+            .get_positions_for_market(&market.condition_id)
+            .await?
+            .is_empty()
+        {
+            debug!(
+                "Azuro market {} already has a position",
+                market.condition_id
+            );
+            return Ok(());
+        }
+
+        let breaches = self.risk.check(&position).await?;
+        if !breaches.is_empty() {
+            let summary: Vec<String> = breaches.iter().map(|b| b.to_string()).collect();
+            info!(
+                "Azuro bet on {} skipped by risk limits: {}",
+                market.condition_id,
+                summary.join("; ")
+            );
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        let order_id = match self.client.place_bet(&order).await {
+            Ok(order_id) => {
+                self.health.upstream_ok(UPSTREAM, started.elapsed());
+                order_id
+            }
+            Err(e) => {
+                self.health.upstream_error(UPSTREAM, started.elapsed(), &e);
+                return Err(e);
+            }
+        };
+
+        let team = match position.side {
+            PositionSide::TeamA => &market.team_a,
+            PositionSide::TeamB => &market.team_b,
+        };
+        info!(
+            "AZURO BET | Order {} | Market {} | {} ${:.2} @ {:.2} (min {:.2}) | Signal {}",
+            order_id,
+            market.condition_id,
+            team,
+            order.amount,
+            1.0 / position.entry_price,
+            order.min_odds,
+            signal.id.unwrap_or_default()
+        );
+
+        let id = self
+            .position_store
+            .insert_position(&position)
+            .await
+            .with_context(|| format!("Azuro order {} placed but not recorded", order_id))?;
+
+        self.notifier
+            .notify(Notification::new(
+                Priority::Normal,
+                format!("Azuro bet placed: {} vs {}", market.team_a, market.team_b),
+                format!(
+                    "Backed {} with ${:.2} at odds {:.2} (min {:.2}) | Order {} | Position {} | Signal {}",
+                    team,
+                    order.amount,
+                    1.0 / position.entry_price,
+                    order.min_odds,
+                    order_id,
+                    id,
+                    signal.id.unwrap_or_default()
+                ),
+            ))
+            .await;
+
+        Ok(())
+    }
+}
+
+/// The position and bet order a signal calls for; `None` when it isn't an
+/// Azuro signal at the minimum strength with an edge worth a bet
+fn plan_bet(
+    signal: &Signal,
+    market: &Market,
+    limits: &RiskLimits,
+    settings: &BetSettings,
+) -> Option<(Position, BetOrder)> {
+    if signal.venue != Venue::Azuro
+        || market.venue != Venue::Azuro
+        || signal.strength < settings.min_strength
+    {
+        return None;
+    }
+    let net_edge = signal.net_edge.filter(|e| *e != 0.0)?;
+    let team_a_prob = signal.team_a_win_prob?;
+
+    let side = PositionSide::from_edge(net_edge);
+    let (win_prob, price, outcome) = match side {
+        PositionSide::TeamA => (team_a_prob, market.team_a_buy_price(), WINNER_OUTCOME_IDS.0),
+        PositionSide::TeamB => (
+            1.0 - team_a_prob,
+            market.team_b_buy_price(),
+            WINNER_OUTCOME_IDS.1,
+        ),
+    };
+
+    let stake = limits
+        .recommended_stake(win_prob, price)?
+        .min(settings.max_bet);
+    let min_odds = (1.0 - settings.max_slippage) / price;
+    if stake < MIN_BET || min_odds <= 1.0 {
+        return None;
+    }
+
+    let mut position = Position::open(&market.condition_id, side, price, stake / price);
+    position.signal_id = signal.id;
+    position.match_id = Some(signal.match_id);
+
+    let order = BetOrder {
+        condition_id: market.condition_id.clone(),
+        outcome_id: outcome.parse().ok()?,
+        amount: stake,
+        min_odds,
+    };
+    Some((position, order))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market() -> Market {
+        Market {
+            condition_id: "100100000000000015915436730000000000000437469831".to_string(),
+            venue: Venue::Azuro,
+            question: "Team Spirit - OG".to_string(),
+            team_a: "Team Spirit".to_string(),
+            team_b: "OG".to_string(),
+            team_a_odds: 0.625,
+            team_b_odds: 0.375,
+            opening_team_a_odds: 0.625,
+            opened_at: None,
+            // Decimal odds 1.5 / 2.5
+            team_a_bid: Some(0.6),
+            team_a_ask: Some(1.0 / 1.5),
+            liquidity: 5000.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    fn limits() -> RiskLimits {
+        RiskLimits {
+            bankroll: 1000.0,
+            max_stake_fraction: 0.05,
+            max_match_exposure: 100.0,
+            max_daily_exposure: 500.0,
+            max_concurrent_positions: 5,
+        }
+    }
+
+    const SETTINGS: BetSettings = BetSettings {
+        min_strength: SignalStrength::VeryStrong,
+        max_bet: 30.0,
+        max_slippage: 0.02,
+    };
+
+    #[test]
+    fn test_plan_bet_backs_the_edge_side_at_the_book_price() {
+        // Model has OG at 55% against a 40% book price
+        let signal = Signal {
+            id: Some(9),
+            venue: Venue::Azuro,
+            match_id: 7,
+            team_a_win_prob: Some(0.45),
+            net_edge: Some(-0.12),
+            strength: SignalStrength::VeryStrong,
+            ..Default::default()
+        };
+
+        let (position, order) = plan_bet(&signal, &market(), &limits(), &SETTINGS).unwrap();
+        assert_eq!(position.side, PositionSide::TeamB);
+        assert!((position.entry_price - 0.4).abs() < 1e-9);
+        assert_eq!(position.signal_id, Some(9));
+        assert_eq!(position.match_id, Some(7));
+        assert_eq!(order.outcome_id, 30);
+        // Kelly is 25% of bankroll, capped at 5% then at the max bet
+        assert_eq!(order.amount, 30.0);
+        assert!((order.min_odds - 2.45).abs() < 1e-9);
+
+        let weaker = Signal {
+            strength: SignalStrength::Strong,
+            ..signal.clone()
+        };
+        assert!(plan_bet(&weaker, &market(), &limits(), &SETTINGS).is_none());
+
+        let polymarket = Signal {
+            venue: Venue::Polymarket,
+            ..signal
+        };
+        assert!(plan_bet(&polymarket, &market(), &limits(), &SETTINGS).is_none());
+    }
+}
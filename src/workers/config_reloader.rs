@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use crate::config::{Config, SharedTuning, Tuning};
+use crate::health::Health;
+
+/// Name reported to the health registry
+const WORKER: &str = "config_reloader";
+
+/// Worker that watches the config file and swaps in changed tuning values
+///
+/// Only `Tuning` fields apply live; other settings still need a restart.
+pub struct ConfigReloaderWorker {
+    config_path: PathBuf,
+    tuning: SharedTuning,
+    check_interval: Duration,
+    health: Health,
+}
+
+impl ConfigReloaderWorker {
+    /// Create a new config reloader worker
+    pub fn new(config_path: PathBuf, tuning: SharedTuning, check_interval_secs: u64) -> Self {
+        Self {
+            config_path,
+            tuning,
+            check_interval: Duration::from_secs(check_interval_secs),
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        info!(
+            "Config reloader started for {} (interval: {:?})",
+            self.config_path.display(),
+            self.check_interval
+        );
+
+        let mut last_modified = self.modified_time();
+
+        let mut interval = time::interval(self.check_interval);
+        interval.tick().await; // Skip first tick (config already loaded at startup)
+        self.health
+            .register_worker(WORKER, Some(self.check_interval));
+
+        loop {
+            interval.tick().await;
+            self.health.worker_tick(WORKER, Some(self.check_interval));
+
+            let modified = self.modified_time();
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+
+            debug!("Config file {} changed", self.config_path.display());
+
+            // Only record the new mtime once the file parses, so a half-written
+            // file is retried on the next tick instead of being skipped
+            if self.reload() {
+                last_modified = modified;
+            }
+        }
+    }
+
+    /// Load the config file and swap in its tuning, keeping the current values on failure
+    fn reload(&self) -> bool {
+        let config = match Config::load(Some(&self.config_path)) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to reload config: {:#}", e);
+                return false;
+            }
+        };
+
+        let problems = config.validate();
+        if !problems.is_empty() {
+            for problem in &problems {
+                warn!("Invalid config: {}", problem);
+            }
+            warn!("Keeping previous tuning, will retry on next change");
+            return true;
+        }
+
+        let tuning = Tuning::from_config(&config);
+        if **self.tuning.load() != tuning {
+            info!("Reloaded tuning: {:?}", tuning);
+            self.tuning.store(Arc::new(tuning));
+        }
+        true
+    }
+
+    /// Last modification time of the config file
+    fn modified_time(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.config_path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+}
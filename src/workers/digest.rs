@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{NaiveTime, Utc};
+use tracing::{error, info};
+
+use crate::db::{SignalFilter, SignalStore};
+use crate::health::Health;
+use crate::notifications::{build_digest, next_digest_at, Notifier, DIGEST_MIN_STRENGTH};
+
+/// Name reported to the health registry
+const WORKER: &str = "digest";
+
+/// Span of signals each digest covers
+const DIGEST_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most signals fetched for one digest
+const MAX_DIGEST_SIGNALS: i64 = 5000;
+
+/// Worker that sends the digest sinks a summary of the day's Strong+
+/// signals once a day
+pub struct DigestWorker {
+    signal_store: Arc<SignalStore>,
+    notifier: Notifier,
+    at: NaiveTime,
+    health: Health,
+}
+
+impl DigestWorker {
+    /// Create a new digest worker sending at `at` UTC
+    pub fn new(signal_store: Arc<SignalStore>, notifier: Notifier, at: NaiveTime) -> Self {
+        Self {
+            signal_store,
+            notifier,
+            at,
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        info!("Digest worker started (daily at {} UTC)", self.at);
+        self.health.register_worker(WORKER, Some(DIGEST_PERIOD));
+
+        loop {
+            let now = Utc::now();
+            let next = next_digest_at(now, self.at);
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+            self.health.worker_tick(WORKER, Some(DIGEST_PERIOD));
+
+            if let Err(e) = self.send().await {
+                error!("Daily digest failed: {}", e);
+            }
+        }
+    }
+
+    /// Send the digest for the day up to now
+    async fn send(&self) -> anyhow::Result<()> {
+        let until = Utc::now();
+        let filter = SignalFilter::default()
+            .min_strength(DIGEST_MIN_STRENGTH)
+            .since(until - DIGEST_PERIOD)
+            .until(until);
+        let signals = self
+            .signal_store
+            .query_signals(&filter, MAX_DIGEST_SIGNALS)
+            .await?;
+
+        match build_digest(&signals, until) {
+            Some(digest) => {
+                info!("Sending daily digest: {}", digest.title);
+                self.notifier.send_digest(&digest).await;
+            }
+            None => info!("No Strong+ signals today, digest skipped"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::time;
+use tracing::{error, info};
+
+use crate::analytics::SettledSignal;
+use crate::db::SignalStore;
+use crate::health::Health;
+
+/// Name reported to the health registry
+const WORKER: &str = "grader";
+
+/// Signals graded per query
+const BATCH_SIZE: i64 = 500;
+
+/// Worker that grades signals once their match has a result in
+/// `historical_matches`
+pub struct SignalGraderWorker {
+    signal_store: Arc<SignalStore>,
+    check_interval: Duration,
+    health: Health,
+}
+
+impl SignalGraderWorker {
+    /// Create a new signal grader worker
+    pub fn new(signal_store: Arc<SignalStore>, check_interval_secs: u64) -> Self {
+        Self {
+            signal_store,
+            check_interval: Duration::from_secs(check_interval_secs),
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        info!(
+            "Signal grader started (interval: {:?})",
+            self.check_interval
+        );
+
+        let mut interval = time::interval(self.check_interval);
+        self.health
+            .register_worker(WORKER, Some(self.check_interval));
+
+        loop {
+            interval.tick().await;
+            self.health.worker_tick(WORKER, Some(self.check_interval));
+
+            match self.grade().await {
+                Ok((0, _)) => {}
+                Ok((graded, correct)) => {
+                    info!("Graded {} signals ({} correct)", graded, correct)
+                }
+                Err(e) => error!("Signal grading failed: {}", e),
+            }
+        }
+    }
+
+    /// Grade every settled signal not yet graded; returns (graded, correct)
+    async fn grade(&self) -> anyhow::Result<(usize, usize)> {
+        let mut graded = 0;
+        let mut correct = 0;
+
+        loop {
+            let batch = self.signal_store.get_ungraded_signals(BATCH_SIZE).await?;
+            let done = (batch.len() as i64) < BATCH_SIZE;
+
+            let graded_at = Utc::now();
+            for (signal, radiant_win) in batch {
+                let Some(id) = signal.id else {
+                    continue;
+                };
+                let hit = SettledSignal::new(signal, radiant_win).and_then(|s| s.hit());
+                self.signal_store.set_grade(id, hit, graded_at).await?;
+
+                graded += 1;
+                if hit == Some(true) {
+                    correct += 1;
+                }
+            }
+
+            if done {
+                return Ok((graded, correct));
+            }
+        }
+    }
+}
@@ -1,113 +1,525 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use tokio::sync::{mpsc, RwLock};
+use chrono::Utc;
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time;
 use tracing::{debug, error, info, warn};
 
-use crate::api::LiveDataClient;
-use crate::matching::TeamResolver;
-use crate::models::{ActiveMarkets, LiveMatchCache, MatchUpdate};
+use crate::api::{LiveDataClient, RateQuota};
+use crate::config::SharedTuning;
+use crate::health::Health;
+use crate::matching::{SeriesTracker, TeamResolver};
+use crate::models::{
+    ActiveMarkets, DetectorChain, LeagueTier, LiveMatchCache, LiveMatchState, Market, MatchUpdate,
+};
+use crate::notifications::{Notification, Notifier, Priority};
+
+use super::{retune, UpdateBus};
+
+/// Name reported to the health registry
+const WORKER: &str = "live_fetcher";
+
+/// Upstream API name reported to the health registry
+const UPSTREAM: &str = "opendota";
+
+/// Upstream of the per-match queries, reported to the health registry
+const MATCH_UPSTREAM: &str = "stratz";
+
+/// A market following a live game
+#[derive(Debug, Clone)]
+struct Follower {
+    condition_id: String,
+    team_a_is_radiant: bool,
+}
+
+/// What a match's polling task follows, refreshed by every discovery poll
+#[derive(Debug, Clone)]
+struct Followed {
+    /// State from the discovery poll, for the series details the match
+    /// query lacks
+    discovered: Arc<LiveMatchState>,
+    followers: Vec<Follower>,
+    watched: bool,
+}
+
+/// A game's own polling task; dropping it stops the task
+struct MatchTask {
+    followed: watch::Sender<Followed>,
+    handle: JoinHandle<()>,
+}
+
+impl Drop for MatchTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
 
 /// Worker that fetches live match data for active markets
+///
+/// Every poll discovers the live games and matches markets to them. When
+/// the client can query one match at a time, each matched game then gets
+/// its own task that fetches just that game at its own cadence and
+/// publishes its updates; otherwise the discovery poll publishes them.
 pub struct LiveFetcherWorker {
     client: LiveDataClient,
-    active_markets: Arc<RwLock<ActiveMarkets>>,
-    match_cache: Arc<RwLock<LiveMatchCache>>,
+    active_markets: Arc<ActiveMarkets>,
+    /// Recent states of live games; only this worker reads or writes it,
+    /// and other workers get them through the updates it publishes
+    match_cache: std::sync::Mutex<LiveMatchCache>,
     team_resolver: Arc<TeamResolver>,
-    update_tx: mpsc::Sender<MatchUpdate>,
-    poll_interval: Duration,
+    /// Game numbers of live series, for game-level markets
+    series: Mutex<SeriesTracker>,
+    /// Consumers of the match updates
+    updates: UpdateBus,
+    tuning: SharedTuning,
+    /// A watched match was live at the last poll
+    watching: AtomicBool,
+    /// OpenDota requests the last poll made
+    poll_requests: AtomicU64,
+    /// Polling is slowed down to stay inside the OpenDota quota
+    throttled: AtomicBool,
+    /// Warns when the quota slows polling down or a pro match has no market
+    notifier: Option<Notifier>,
+    /// Tracked pro matches already alerted for having no market
+    no_market_alerted: Mutex<HashSet<i64>>,
+    /// Classifies each update's event
+    detectors: DetectorChain,
+    /// Polling task of each matched game, by match_id
+    match_tasks: Mutex<HashMap<i64, MatchTask>>,
+    health: Health,
 }
 
 impl LiveFetcherWorker {
     /// Create a new live fetcher worker
     pub fn new(
         client: LiveDataClient,
-        active_markets: Arc<RwLock<ActiveMarkets>>,
-        match_cache: Arc<RwLock<LiveMatchCache>>,
+        active_markets: Arc<ActiveMarkets>,
         team_resolver: Arc<TeamResolver>,
-        update_tx: mpsc::Sender<MatchUpdate>,
-        poll_interval_secs: u64,
+        updates: UpdateBus,
+        tuning: SharedTuning,
     ) -> Self {
         Self {
             client,
             active_markets,
-            match_cache,
+            match_cache: std::sync::Mutex::default(),
             team_resolver,
-            update_tx,
-            poll_interval: Duration::from_secs(poll_interval_secs),
+            series: Mutex::new(SeriesTracker::new()),
+            updates,
+            tuning,
+            watching: AtomicBool::new(false),
+            poll_requests: AtomicU64::new(1),
+            throttled: AtomicBool::new(false),
+            notifier: None,
+            no_market_alerted: Mutex::new(HashSet::new()),
+            detectors: DetectorChain::default(),
+            match_tasks: Mutex::new(HashMap::new()),
+            health: Health::default(),
         }
     }
 
-    /// Run the worker loop
-    pub async fn run(&self) {
-        info!("Live fetcher started (interval: {:?})", self.poll_interval);
+    /// Send a warning when the OpenDota quota slows polling down, and
+    /// `NO_MARKET_ALERT_MINUTES` alerts
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Classify updates with these detectors instead of the standard ones
+    pub fn with_detectors(mut self, detectors: DetectorChain) -> Self {
+        self.detectors = detectors;
+        self
+    }
 
-        let mut interval = time::interval(self.poll_interval);
+    /// Report ticks and upstream calls to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop until shutdown is signaled
+    ///
+    /// The update sender is dropped with the worker once its match tasks
+    /// stop, letting the processor drain.
+    pub async fn run(self: Arc<Self>, mut shutdown: watch::Receiver<bool>) {
+        let poll_interval = Duration::from_secs(self.tuning.load().live_match_poll_interval);
+        info!("Live fetcher started (interval: {:?})", poll_interval);
+
+        let mut interval = time::interval(poll_interval);
+        self.health.register_worker(WORKER, Some(poll_interval));
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+            let secs = self.paced_interval_secs().await;
+            retune(&mut interval, secs, "Live fetch");
+            self.health.worker_tick(WORKER, Some(interval.period()));
             self.fetch().await;
         }
+
+        self.match_tasks.lock().await.clear();
+        info!("Live fetcher stopped");
     }
 
-    /// Perform a single fetch cycle
-    async fn fetch(&self) {
-        // Check if we have any active markets
-        let markets = self.active_markets.read().await;
-        if markets.is_empty() {
+    /// Poll interval from tuning, shortened while a watched match is live
+    /// and has no task polling it faster on its own
+    fn poll_interval_secs(&self) -> u64 {
+        self.match_interval_secs(
+            self.watching.load(Ordering::Relaxed) && !self.client.has_match_query(),
+        )
+    }
+
+    /// Poll interval of a game from tuning, shortened while it's watched
+    fn match_interval_secs(&self, watched: bool) -> u64 {
+        let tuning = self.tuning.load();
+        if watched {
+            tuning
+                .watch_poll_interval
+                .min(tuning.live_match_poll_interval)
+        } else {
+            tuning.live_match_poll_interval
+        }
+    }
+
+    /// Poll interval stretched to keep the last poll's OpenDota requests
+    /// inside the quota left, warning when polling first slows down
+    async fn paced_interval_secs(&self) -> u64 {
+        let base = self.poll_interval_secs();
+        let Some(quota) = self.client.quota() else {
+            return base;
+        };
+
+        let calls = self.poll_requests.load(Ordering::Relaxed);
+        let calls = u32::try_from(calls).unwrap_or(u32::MAX);
+        let now = Utc::now();
+        let pace = |quota: RateQuota| {
+            quota
+                .paced_interval(Duration::from_secs(base), calls, now)
+                .as_secs()
+                .max(base)
+        };
+        let paced = pace(quota);
+
+        // Sitting out a spent minute window is routine; a stretched daily
+        // pace means the rest of the day polls slower
+        let daily = RateQuota {
+            remaining_minute: None,
+            ..quota
+        };
+        let throttled = pace(daily) > base;
+        if self.throttled.swap(throttled, Ordering::Relaxed) != throttled {
+            if throttled {
+                warn!(
+                    "OpenDota quota running low ({}), polling every {}s",
+                    quota, paced
+                );
+                if let Some(notifier) = &self.notifier {
+                    notifier
+                        .notify(Notification::new(
+                            Priority::Normal,
+                            "OpenDota quota running low",
+                            format!(
+                                "{}. Live data now polls every {}s instead of {}s.",
+                                quota, paced, base
+                            ),
+                        ))
+                        .await;
+                }
+            } else {
+                info!("OpenDota quota recovered, polling every {}s", base);
+            }
+        }
+        paced
+    }
+
+    /// Record whether a watched match is live, logging changes
+    fn set_watching(&self, watching: bool) {
+        if self.watching.swap(watching, Ordering::Relaxed) != watching {
+            if watching {
+                info!("Watched match live, polling faster");
+            } else {
+                info!("No watched match live, polling at the normal interval");
+            }
+        }
+    }
+
+    /// Perform a single discovery poll
+    async fn fetch(self: &Arc<Self>) {
+        let tuning = self.tuning.load_full();
+
+        // Check if we have any active markets, or pro matches to track anyway
+        let market_count = self.active_markets.len();
+        if market_count == 0 && !tuning.track_all_pro_matches {
             debug!("No active markets, skipping live data fetch");
+            self.set_watching(false);
+            self.health.matched_markets(0);
+            self.match_tasks.lock().await.clear();
             return;
         }
 
-        let market_count = markets.len();
-        drop(markets); // Release lock before API call
-
         debug!("Fetching live matches for {} active markets", market_count);
 
         // Fetch all live matches
-        let live_matches = match self.client.fetch_live_matches().await {
-            Ok(matches) => matches,
+        let started = Instant::now();
+        let requests = self.client.requests();
+        let result = self.client.fetch_live_matches().await;
+        self.poll_requests
+            .store(self.client.requests() - requests, Ordering::Relaxed);
+        let mut live_matches = match result {
+            Ok(matches) => {
+                self.health.upstream_ok(UPSTREAM, started.elapsed());
+                self.health.live_matches(matches.len());
+                matches
+            }
             Err(e) => {
+                self.health.upstream_error(UPSTREAM, started.elapsed(), &e);
                 error!("Failed to fetch live matches: {}", e);
                 return;
             }
         };
 
+        self.evict_stale_matches();
+
+        live_matches.retain(|m| !tuning.blacklist.blocks_match(m, &self.team_resolver));
+
         if live_matches.is_empty() {
             debug!("No live matches found");
+            self.set_watching(false);
+            self.match_tasks.lock().await.clear();
             return;
         }
 
         debug!("Found {} live matches", live_matches.len());
 
-        // Match markets to live games
-        let markets = self.active_markets.read().await;
-        let mut cache = self.match_cache.write().await;
+        self.series
+            .lock()
+            .await
+            .assign(&mut live_matches, &self.team_resolver, Instant::now());
 
-        for market in markets.values() {
-            if let Some(match_result) =
-                self.team_resolver.match_market_to_live(market, &live_matches)
-            {
-                let match_id = match_result.match_state.match_id;
+        // Shared from here on by every market that follows the game
+        let live_matches: Vec<Arc<LiveMatchState>> =
+            live_matches.into_iter().map(Arc::new).collect();
+
+        // Match markets to live games on a snapshot, so the scanner's writes
+        // don't wait on name resolution. Outrights span many matches and
+        // aren't priced from one game
+        let markets: Vec<Market> = self
+            .active_markets
+            .iter()
+            .filter(|m| !m.is_outright())
+            .map(|m| m.value().clone())
+            .collect();
+
+        let matched: Vec<_> = markets
+            .iter()
+            .filter_map(|market| {
+                // A pinned market only follows its pinned match
+                match tuning.match_pins.get(&market.condition_id) {
+                    Some(pin) => self.team_resolver.match_pinned(market, pin, &live_matches),
+                    None => self
+                        .team_resolver
+                        .match_market_to_live(market, &live_matches),
+                }
+                .map(|match_result| (market, match_result))
+            })
+            .collect();
+        self.health.matched_markets(matched.len());
 
-                // Get previous state for comparison
-                let previous_state = cache.get(&match_id).cloned();
+        // Several markets (series, map, handicap) can follow one game
+        let mut games: HashMap<i64, Followed> = HashMap::new();
+        for (market, match_result) in matched {
+            let state = match_result.match_state;
+            games
+                .entry(state.match_id)
+                .or_insert_with(|| Followed {
+                    watched: tuning.watchlist.includes(&state, &self.team_resolver),
+                    discovered: Arc::clone(&state),
+                    followers: Vec::new(),
+                })
+                .followers
+                .push(Follower {
+                    condition_id: market.condition_id.clone(),
+                    team_a_is_radiant: match_result.market_team_a_is_radiant,
+                });
+        }
+        self.set_watching(games.values().any(|game| game.watched));
+
+        // Pro matches no market follows yet are cached too, so momentum and
+        // a previous state are there the moment a market appears mid-game
+        let unmarketed: Vec<Arc<LiveMatchState>> = if tuning.track_all_pro_matches {
+            live_matches
+                .iter()
+                .filter(|m| {
+                    m.league_tier >= Some(LeagueTier::Professional)
+                        && !games.contains_key(&m.match_id)
+                })
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        {
+            let mut cache = self.match_cache.lock().unwrap();
+            for state in &unmarketed {
+                cache.insert(state.match_id, Arc::clone(state));
+            }
+        }
+
+        if self.client.has_match_query() {
+            self.follow(games).await;
+        } else {
+            for game in games.into_values() {
+                self.publish(game.discovered, &game.followers).await;
+            }
+        }
+
+        self.alert_no_market(&unmarketed, tuning.no_market_alert_minutes)
+            .await;
+    }
+
+    /// Start a polling task for each newly matched game, hand the running
+    /// ones their current markets, and stop those of games no market follows
+    async fn follow(self: &Arc<Self>, games: HashMap<i64, Followed>) {
+        let mut tasks = self.match_tasks.lock().await;
+        tasks.retain(|match_id, _| games.contains_key(match_id));
+
+        for (match_id, game) in games {
+            if let Some(task) = tasks.get(&match_id) {
+                task.followed.send_replace(game);
+                continue;
+            }
+            debug!("Polling match {} on its own", match_id);
+            let (followed, receiver) = watch::channel(game);
+            let handle = tokio::spawn(Arc::clone(self).poll_match(match_id, receiver));
+            tasks.insert(match_id, MatchTask { followed, handle });
+        }
+    }
 
-                // Update cache
-                cache.insert(match_id, match_result.match_state.clone());
+    /// Fetch one game at its own cadence and publish its updates, until
+    /// its task is dropped
+    async fn poll_match(self: Arc<Self>, match_id: i64, followed: watch::Receiver<Followed>) {
+        let secs = self.match_interval_secs(followed.borrow().watched);
+        let mut interval = time::interval(Duration::from_secs(secs));
+        let name = format!("Match {} fetch", match_id);
 
-                // Send update to signal processor
-                let update = MatchUpdate {
-                    market_condition_id: market.condition_id.clone(),
-                    state: match_result.match_state,
-                    previous_state,
-                };
+        loop {
+            interval.tick().await;
+            let game = followed.borrow().clone();
+            retune(&mut interval, self.match_interval_secs(game.watched), &name);
 
-                if let Err(e) = self.update_tx.send(update).await {
-                    warn!("Failed to send match update: {}", e);
+            let started = Instant::now();
+            match self.client.fetch_match(match_id).await {
+                Ok(Some(mut state)) => {
+                    self.health.upstream_ok(MATCH_UPSTREAM, started.elapsed());
+                    // The match query has no series details
+                    state.series_id = state.series_id.or(game.discovered.series_id);
+                    state.game_number = game.discovered.game_number;
+                    self.publish(Arc::new(state), &game.followers).await;
+                }
+                // Discovery stops the task once the game leaves the live feed
+                Ok(None) => debug!("Match {} no longer live", match_id),
+                Err(e) => {
+                    self.health
+                        .upstream_error(MATCH_UPSTREAM, started.elapsed(), &e);
+                    warn!("Failed to fetch match {}: {}", match_id, e);
                 }
             }
         }
     }
+
+    /// Publish an update for each market following a game, all diffed
+    /// against the game's state from the previous poll
+    async fn publish(&self, state: Arc<LiveMatchState>, followers: &[Follower]) {
+        let updates: Vec<MatchUpdate> = {
+            let mut cache = self.match_cache.lock().unwrap();
+            let match_id = state.match_id;
+            let previous_state = cache.get(&match_id).cloned();
+            cache.insert(match_id, Arc::clone(&state));
+
+            let teamfight = cache.teamfight(&match_id);
+            let deaths = cache.deaths(&match_id);
+            let momentum = cache.momentum(&match_id);
+            let signal_type = self.detectors.detect(
+                previous_state.as_deref(),
+                &state,
+                teamfight.as_ref(),
+                deaths,
+            );
+            followers
+                .iter()
+                .map(|follower| MatchUpdate {
+                    market_condition_id: follower.condition_id.clone(),
+                    state: Arc::clone(&state),
+                    market_team_a_is_radiant: follower.team_a_is_radiant,
+                    previous_state: previous_state.clone(),
+                    momentum,
+                    teamfight,
+                    deaths,
+                    signal_type,
+                })
+                .collect()
+        };
+
+        // Publish with no lock held: a full consumer queue waits here
+        for update in updates {
+            self.updates.publish(update).await;
+        }
+    }
+
+    /// Alert once per match when a tracked pro match has run `minutes` of
+    /// game time without a market; 0 is off
+    async fn alert_no_market(&self, unmarketed: &[Arc<LiveMatchState>], minutes: u64) {
+        let mut alerted = self.no_market_alerted.lock().await;
+        // Forget matches that ended or found a market
+        alerted.retain(|match_id| unmarketed.iter().any(|m| m.match_id == *match_id));
+
+        let Some(notifier) = self.notifier.as_ref().filter(|_| minutes > 0) else {
+            return;
+        };
+        for state in unmarketed {
+            if i64::from(state.game_time) < minutes as i64 * 60 || !alerted.insert(state.match_id) {
+                continue;
+            }
+
+            let teams = format!("{} vs {}", state.radiant.name, state.dire.name);
+            info!(
+                "Live pro match {} ({}) has no market",
+                state.match_id, teams
+            );
+            notifier
+                .notify(Notification::new(
+                    Priority::Normal,
+                    format!("No market for live match: {}", teams),
+                    format!(
+                        "Match {} ({}) is {} min in with no active market.",
+                        state.match_id,
+                        state.league_name.as_deref().unwrap_or("unknown league"),
+                        state.game_time / 60
+                    ),
+                ))
+                .await;
+        }
+    }
+
+    /// Drop cached matches that ended or went silent, and keep the cache
+    /// within its size cap
+    fn evict_stale_matches(&self) {
+        let tuning = self.tuning.load();
+        let ttl = chrono::Duration::minutes(tuning.match_cache_ttl_minutes as i64);
+
+        let mut cache = self.match_cache.lock().unwrap();
+        let evicted = cache.evict(Utc::now(), ttl, tuning.match_cache_max_entries);
+        if evicted > 0 {
+            debug!(
+                "Evicted {} stale matches from cache ({} left)",
+                evicted,
+                cache.len()
+            );
+        }
+    }
 }
@@ -1,80 +1,375 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use tokio::sync::RwLock;
+use chrono::Utc;
+use tokio::sync::watch;
 use tokio::time;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
-use crate::api::PolymarketClient;
-use crate::models::ActiveMarkets;
+use crate::api::MarketSource;
+use crate::config::SharedTuning;
+use crate::health::Health;
+use crate::matching::TeamResolver;
+use crate::model::PreGamePrior;
+use crate::models::{ActiveMarkets, Market};
+use crate::notifications::{Notification, Notifier, Priority};
+use crate::ratings::{EloRatings, RecentForm};
 
-/// Worker that periodically scans Polymarket for active Dota 2 markets
+use super::retune;
+
+/// Name reported to the health registry
+const WORKER: &str = "market_scanner";
+
+/// Worker that periodically scans every market source for active Dota 2 markets
 pub struct MarketScannerWorker {
-    client: PolymarketClient,
-    active_markets: Arc<RwLock<ActiveMarkets>>,
-    scan_interval: Duration,
+    sources: Vec<Box<dyn MarketSource>>,
+    active_markets: Arc<ActiveMarkets>,
+    tuning: SharedTuning,
+    /// Matches blacklisted and watched team names
+    team_resolver: Arc<TeamResolver>,
+    /// Alerts on new markets for watched fixtures
+    notifier: Option<Notifier>,
+    /// Ratings and form for the prior in new-market alerts
+    ratings: Arc<EloRatings>,
+    form: Arc<RecentForm>,
+    /// A scan has run, so later markets are new rather than pre-existing
+    scanned: AtomicBool,
+    health: Health,
 }
 
 impl MarketScannerWorker {
     /// Create a new market scanner worker
     pub fn new(
-        client: PolymarketClient,
-        active_markets: Arc<RwLock<ActiveMarkets>>,
-        scan_interval_secs: u64,
+        source: Box<dyn MarketSource>,
+        active_markets: Arc<ActiveMarkets>,
+        tuning: SharedTuning,
     ) -> Self {
         Self {
-            client,
+            sources: vec![source],
             active_markets,
-            scan_interval: Duration::from_secs(scan_interval_secs),
+            tuning,
+            team_resolver: Arc::new(TeamResolver::new()),
+            notifier: None,
+            ratings: Arc::default(),
+            form: Arc::default(),
+            scanned: AtomicBool::new(false),
+            health: Health::default(),
         }
     }
 
-    /// Run the worker loop
-    pub async fn run(&self) {
-        info!(
-            "Market scanner started (interval: {:?})",
-            self.scan_interval
-        );
+    /// Report ticks and upstream calls to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Match blacklisted and watched teams through these aliases
+    pub fn with_team_resolver(mut self, team_resolver: Arc<TeamResolver>) -> Self {
+        self.team_resolver = team_resolver;
+        self
+    }
+
+    /// Alert when a market appears for a watched fixture
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Quote the pre-game prior from these ratings in new-market alerts
+    pub fn with_ratings(mut self, ratings: Arc<EloRatings>, form: Arc<RecentForm>) -> Self {
+        self.ratings = ratings;
+        self.form = form;
+        self
+    }
+
+    /// Also scan an additional venue
+    pub fn with_source(mut self, source: Box<dyn MarketSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Run the worker loop until shutdown is signaled
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        let scan_interval = Duration::from_secs(self.tuning.load().polymarket_scan_interval);
+        info!("Market scanner started (interval: {:?})", scan_interval);
 
         // Run initial scan immediately
+        self.health.register_worker(WORKER, Some(scan_interval));
         self.scan().await;
 
         // Then run on interval
-        let mut interval = time::interval(self.scan_interval);
+        let mut interval = time::interval(scan_interval);
         interval.tick().await; // Skip first tick (already ran)
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+            retune(
+                &mut interval,
+                self.tuning.load().polymarket_scan_interval,
+                "Market scan",
+            );
+            self.health.worker_tick(WORKER, Some(interval.period()));
             self.scan().await;
         }
+
+        info!("Market scanner stopped");
     }
 
     /// Perform a single market scan
     async fn scan(&self) {
-        info!("Scanning Polymarket for Dota 2 markets...");
-
-        match self.client.fetch_dota2_markets().await {
-            Ok(markets) => {
-                let count = markets.len();
-
-                // Update shared state
-                let mut active = self.active_markets.write().await;
-                active.clear();
-
-                for market in markets {
-                    info!(
-                        "Found market: {} - {} vs {} (liquidity: ${:.2})",
-                        market.condition_id, market.team_a, market.team_b, market.liquidity
-                    );
-                    active.insert(market.condition_id.clone(), market);
+        info!("Scanning prediction markets for Dota 2 markets...");
+
+        let mut markets = Vec::new();
+        let mut failed_venues = Vec::new();
+
+        for source in &self.sources {
+            let upstream = source.venue().as_str().to_lowercase();
+            let started = Instant::now();
+            match source.fetch_markets().await {
+                Ok(found) => {
+                    self.health.upstream_ok(&upstream, started.elapsed());
+                    markets.extend(found);
                 }
+                Err(e) => {
+                    self.health.upstream_error(&upstream, started.elapsed(), &e);
+                    error!("Failed to scan {} markets: {}", source.venue(), e);
+                    failed_venues.push(source.venue());
+                }
+            }
+        }
+
+        if !failed_venues.is_empty() {
+            warn!("Will retry {:?} on next interval", failed_venues);
+        }
 
-                info!("Market scan complete: {} active markets", count);
+        let tuning = self.tuning.load_full();
+        let blacklist = &tuning.blacklist;
+        markets.retain(|market| {
+            let blocked = blacklist.blocks_market(market, &self.team_resolver);
+            if blocked {
+                debug!(
+                    "Skipping blacklisted market {}: {}",
+                    market.condition_id, market.question
+                );
             }
-            Err(e) => {
-                error!("Failed to scan markets: {}", e);
-                warn!("Will retry on next interval");
+            !blocked
+        });
+
+        let count = markets.len();
+        // Markets already listed when the service starts aren't news
+        let first_scan = !self.scanned.swap(true, Ordering::Relaxed);
+        let mut watched = Vec::new();
+
+        // Update shared state one market at a time, so workers reading other
+        // markets never wait on the whole scan
+        let mut listed = HashSet::new();
+        for mut market in markets {
+            // Keep the first-seen price as the opening line; the guard is
+            // dropped before the insert below, which locks the same shard
+            let existing = self
+                .active_markets
+                .get(&market.condition_id)
+                .map(|existing| (existing.opening_team_a_odds, existing.opened_at));
+            match existing {
+                Some((opening_team_a_odds, opened_at)) => {
+                    market.opening_team_a_odds = opening_team_a_odds;
+                    market.opened_at = opened_at;
+                }
+                None => {
+                    market.opened_at = Some(Utc::now());
+                    if !first_scan
+                        && tuning
+                            .watchlist
+                            .includes_market(&market, &self.team_resolver)
+                    {
+                        watched.push(market.clone());
+                    }
+                }
             }
+
+            if market.is_outright() {
+                info!(
+                    "Found {} outright: {} - {} (liquidity: ${:.2})",
+                    market.venue, market.condition_id, market.question, market.liquidity
+                );
+            } else {
+                info!(
+                    "Found {} market: {} - {} vs {} (liquidity: ${:.2})",
+                    market.venue,
+                    market.condition_id,
+                    market.team_a,
+                    market.team_b,
+                    market.liquidity
+                );
+            }
+            listed.insert(market.condition_id.clone());
+            self.active_markets
+                .insert(market.condition_id.clone(), market);
+        }
+
+        // Drop delisted markets, but keep the last known markets of venues
+        // that failed this scan
+        self.active_markets.retain(|condition_id, market| {
+            listed.contains(condition_id)
+                || (failed_venues.contains(&market.venue)
+                    && !blacklist.blocks_market(market, &self.team_resolver))
+        });
+
+        info!("Market scan complete: {} active markets", count);
+
+        for market in watched {
+            self.alert_new_market(&market).await;
+        }
+    }
+
+    /// Alert on a new market for a watched fixture with its opening odds
+    /// and the prior from ratings and form, before the market moves
+    async fn alert_new_market(&self, market: &Market) {
+        info!(
+            "New market for watched fixture: {} vs {} ({})",
+            market.team_a, market.team_b, market.condition_id
+        );
+        let Some(notifier) = &self.notifier else {
+            return;
+        };
+
+        let team_a = self.team_resolver.normalize(&market.team_a);
+        let team_b = self.team_resolver.normalize(&market.team_b);
+        // Without the opening price, which is what the alert compares to
+        let prior = PreGamePrior {
+            elo: self.ratings.win_probability(&team_a, &team_b),
+            form: self.form.win_probability(&team_a, &team_b),
+            ..Default::default()
+        };
+        let pct = |p: Option<f64>| {
+            p.map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_else(|| "n/a".to_string())
+        };
+
+        let mut body = format!(
+            "{} opened at {:.1}% for {} ({:.1}% for {}), liquidity ${:.0}.",
+            market.venue,
+            market.opening_team_a_odds * 100.0,
+            market.team_a,
+            market.team_b_odds * 100.0,
+            market.team_b,
+            market.liquidity
+        );
+        if prior.elo.is_some() || prior.form.is_some() {
+            let probability = prior.probability();
+            body.push_str(&format!(
+                "\nPrior for {}: {:.1}% ({:+.1} vs open; Elo {}, form {}).",
+                market.team_a,
+                probability * 100.0,
+                (probability - market.opening_team_a_odds) * 100.0,
+                pct(prior.elo),
+                pct(prior.form)
+            ));
+        } else {
+            body.push_str("\nNo rating history for either team.");
         }
+        if let Some(url) = &market.url {
+            body.push_str(&format!("\n{}", url));
+        }
+
+        notifier
+            .notify(Notification::new(
+                Priority::Normal,
+                format!("New market: {} vs {}", market.team_a, market.team_b),
+                body,
+            ))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::config::{Config, Tuning};
+    use crate::models::Venue;
+
+    /// Returns one scan's markets per call, or fails on `None`
+    struct Scripted(Mutex<Vec<Option<Vec<Market>>>>);
+
+    #[async_trait]
+    impl MarketSource for Scripted {
+        fn venue(&self) -> Venue {
+            Venue::Polymarket
+        }
+
+        async fn fetch_markets(&self) -> Result<Vec<Market>> {
+            self.0
+                .lock()
+                .unwrap()
+                .remove(0)
+                .ok_or_else(|| anyhow::anyhow!("venue down"))
+        }
+    }
+
+    fn market(condition_id: &str, team_a_odds: f64) -> Market {
+        Market {
+            condition_id: condition_id.to_string(),
+            venue: Venue::Polymarket,
+            question: "Dota 2: Team Spirit vs OG".to_string(),
+            team_a: "Team Spirit".to_string(),
+            team_b: "OG".to_string(),
+            team_a_odds,
+            team_b_odds: 1.0 - team_a_odds,
+            opening_team_a_odds: team_a_odds,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 5000.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scans_update_shared_markets_in_place() {
+        let scans = vec![
+            Some(vec![market("0xa", 0.5), market("0xb", 0.5)]),
+            Some(vec![market("0xa", 0.6), market("0xc", 0.4)]),
+            None,
+        ];
+        let active = Arc::new(ActiveMarkets::new());
+        let scanner = MarketScannerWorker::new(
+            Box::new(Scripted(Mutex::new(scans))),
+            Arc::clone(&active),
+            Tuning::shared(&Config::load(None).unwrap()),
+        );
+
+        scanner.scan().await;
+        let opened_at = active.get("0xa").unwrap().opened_at;
+        assert!(opened_at.is_some());
+
+        // Delisted markets go; listed ones keep their opening line
+        scanner.scan().await;
+        let mut listed: Vec<String> = active.iter().map(|m| m.key().clone()).collect();
+        listed.sort();
+        assert_eq!(listed, ["0xa", "0xc"]);
+        let a = active.get("0xa").unwrap().clone();
+        assert_eq!((a.team_a_odds, a.opening_team_a_odds), (0.6, 0.5));
+        assert_eq!(a.opened_at, opened_at);
+
+        // A failed venue keeps its last known markets
+        scanner.scan().await;
+        assert_eq!(active.len(), 2);
     }
 }
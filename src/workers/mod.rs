@@ -1,7 +1,56 @@
+pub mod arbitrage;
+#[cfg(feature = "azuro")]
+pub mod azuro_bets;
+pub mod config_reloader;
+pub mod digest;
+pub mod grader;
 pub mod live_fetcher;
 pub mod market_scanner;
+pub mod model_reloader;
+#[cfg(feature = "nats")]
+pub mod nats_producer;
+pub mod outright;
+pub mod settlement;
+pub mod sharp_odds;
 pub mod signal_processor;
+pub mod stop_loss;
+pub mod updates;
+pub mod watchdog;
 
+pub use arbitrage::ArbitrageWorker;
+#[cfg(feature = "azuro")]
+pub use azuro_bets::{AzuroBetWorker, BetSettings};
+pub use config_reloader::ConfigReloaderWorker;
+pub use digest::DigestWorker;
+pub use grader::SignalGraderWorker;
 pub use live_fetcher::LiveFetcherWorker;
 pub use market_scanner::MarketScannerWorker;
-pub use signal_processor::SignalProcessorWorker;
+pub use model_reloader::ModelReloaderWorker;
+#[cfg(feature = "nats")]
+pub use nats_producer::NatsProducerWorker;
+pub use outright::OutrightWorker;
+pub use settlement::SettlementWatchWorker;
+pub use sharp_odds::SharpOddsWorker;
+pub use signal_processor::{SignalProcessorWorker, SignalSettings};
+pub use stop_loss::StopLossWorker;
+pub use updates::{UpdateBus, UpdateReceiver, DEFAULT_UPDATE_CAPACITY};
+pub use watchdog::NoDataWatchdogWorker;
+
+use std::time::Duration;
+
+use tokio::time::{self, Instant, Interval};
+use tracing::info;
+
+/// Restart `interval` with a new period if its tuned value changed
+pub(crate) fn retune(interval: &mut Interval, secs: u64, name: &str) {
+    let period = Duration::from_secs(secs);
+    if interval.period() != period {
+        info!(
+            "{} interval changed: {:?} -> {:?}",
+            name,
+            interval.period(),
+            period
+        );
+        *interval = time::interval_at(Instant::now() + period, period);
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use crate::health::Health;
+use crate::model::{self, ProbabilityModel, SharedModel};
+
+/// Name reported to the health registry
+const WORKER: &str = "model_reloader";
+
+/// Worker that watches the model file and swaps in new weights when it changes
+pub struct ModelReloaderWorker {
+    model_path: PathBuf,
+    model: SharedModel,
+    check_interval: Duration,
+    health: Health,
+}
+
+impl ModelReloaderWorker {
+    /// Create a new model reloader worker
+    pub fn new(model_path: PathBuf, model: SharedModel, check_interval_secs: u64) -> Self {
+        Self {
+            model_path,
+            model,
+            check_interval: Duration::from_secs(check_interval_secs),
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        info!(
+            "Model reloader started for {} (interval: {:?})",
+            self.model_path.display(),
+            self.check_interval
+        );
+
+        let mut last_modified = self.modified_time();
+
+        let mut interval = time::interval(self.check_interval);
+        interval.tick().await; // Skip first tick (model already loaded at startup)
+        self.health
+            .register_worker(WORKER, Some(self.check_interval));
+
+        loop {
+            interval.tick().await;
+            self.health.worker_tick(WORKER, Some(self.check_interval));
+
+            let modified = self.modified_time();
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+
+            debug!("Model file {} changed", self.model_path.display());
+
+            // Only record the new mtime once the file parses, so a half-written
+            // file is retried on the next tick instead of being skipped
+            if self.reload().await {
+                last_modified = modified;
+            }
+        }
+    }
+
+    /// Load the model file and swap it in, keeping the current model on failure
+    async fn reload(&self) -> bool {
+        let path = self.model_path.clone();
+
+        let loaded = tokio::task::spawn_blocking(move || model::load_model(&path)).await;
+
+        match loaded {
+            Ok(Ok(new_model)) => {
+                let new_model: Arc<dyn ProbabilityModel> = Arc::from(new_model);
+                let mut current = self.model.write().await;
+                info!(
+                    "Reloaded model: {} -> {}",
+                    current.version(),
+                    new_model.version()
+                );
+                *current = new_model;
+                true
+            }
+            Ok(Err(e)) => {
+                error!("Failed to reload model: {:#}", e);
+                warn!("Keeping previous model, will retry on next change");
+                false
+            }
+            Err(e) => {
+                error!("Model reload task failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Last modification time of the model file
+    fn modified_time(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.model_path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+}
@@ -0,0 +1,195 @@
+//! NATS producer for stored signals and raw match updates
+//!
+//! Lets execution and analytics run as separate services: each stored
+//! signal goes to `<prefix>.signals.<match_id>` and each live match update
+//! to `<prefix>.match_updates.<match_id>`, both as JSON, so a consumer can
+//! take everything (`<prefix>.signals.>`) or follow one game. Core NATS
+//! doesn't keep messages; put a JetStream stream on the subjects when
+//! consumers need replay.
+
+use std::time::Instant;
+
+use async_nats::{Client, ConnectOptions};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use crate::health::Health;
+use crate::models::{Deaths, LiveMatchState, MatchUpdate, Momentum, Signal, SignalType, Teamfight};
+use crate::workers::UpdateReceiver;
+
+/// Name reported to the health registry
+const WORKER: &str = "nats_producer";
+
+/// Upstream API name reported to the health registry
+const UPSTREAM: &str = "nats";
+
+/// Match update as published, without the previous state
+#[derive(Debug, Serialize)]
+pub struct UpdateMessage<'a> {
+    pub market_condition_id: &'a str,
+    pub market_team_a_is_radiant: bool,
+    pub signal_type: SignalType,
+    pub state: &'a LiveMatchState,
+    pub momentum: Momentum,
+    pub teamfight: Option<Teamfight>,
+    pub deaths: Deaths,
+}
+
+impl<'a> From<&'a MatchUpdate> for UpdateMessage<'a> {
+    fn from(update: &'a MatchUpdate) -> Self {
+        Self {
+            market_condition_id: &update.market_condition_id,
+            market_team_a_is_radiant: update.market_team_a_is_radiant,
+            signal_type: update.signal_type,
+            state: &update.state,
+            momentum: update.momentum,
+            teamfight: update.teamfight,
+            deaths: update.deaths,
+        }
+    }
+}
+
+/// Worker that publishes signals and match updates to NATS subjects
+pub struct NatsProducerWorker {
+    nats_url: String,
+    subject_prefix: String,
+    signals: broadcast::Receiver<Signal>,
+    updates: UpdateReceiver,
+    health: Health,
+}
+
+impl NatsProducerWorker {
+    /// Create a worker publishing under `subject_prefix` on the server at
+    /// `nats_url`
+    pub fn new(
+        nats_url: String,
+        subject_prefix: String,
+        signals: broadcast::Receiver<Signal>,
+        updates: UpdateReceiver,
+    ) -> Self {
+        Self {
+            nats_url,
+            subject_prefix,
+            signals,
+            updates,
+            health: Health::default(),
+        }
+    }
+
+    /// Report publishes to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop until the signal feed or the update bus closes
+    pub async fn run(mut self) {
+        // Keeps retrying in the background, so a server that starts after
+        // us still gets everything from then on
+        let client = match ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect(self.nats_url.as_str())
+            .await
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("NATS producer not started: {}", e);
+                return;
+            }
+        };
+        info!(
+            "NATS producer started (subjects {}.signals.*, {}.match_updates.*)",
+            self.subject_prefix, self.subject_prefix
+        );
+        self.health.register_worker(WORKER, None);
+
+        loop {
+            let (subject, payload) = tokio::select! {
+                received = self.signals.recv() => match received {
+                    Ok(signal) => (
+                        self.subject("signals", signal.match_id),
+                        serde_json::to_vec(&signal),
+                    ),
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("NATS producer fell behind, skipped {} signals", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                update = self.updates.recv() => match update {
+                    Some(update) => (
+                        self.subject("match_updates", update.state.match_id),
+                        serde_json::to_vec(&UpdateMessage::from(&update)),
+                    ),
+                    None => break,
+                },
+            };
+            self.health.worker_tick(WORKER, None);
+
+            match payload {
+                Ok(payload) => self.publish(&client, subject, payload).await,
+                Err(e) => warn!("Failed to serialize {} for NATS: {}", subject, e),
+            }
+        }
+
+        if let Err(e) = client.flush().await {
+            warn!("Failed to flush NATS messages: {}", e);
+        }
+        info!("NATS producer stopped");
+    }
+
+    /// Subject for one kind of message about a match
+    fn subject(&self, kind: &str, match_id: i64) -> String {
+        format!("{}.{}.{}", self.subject_prefix, kind, match_id)
+    }
+
+    async fn publish(&self, client: &Client, subject: String, payload: Vec<u8>) {
+        let started = Instant::now();
+        match client.publish(subject, payload.into()).await {
+            Ok(()) => self.health.upstream_ok(UPSTREAM, started.elapsed()),
+            Err(e) => {
+                let e = anyhow::Error::new(e);
+                self.health.upstream_error(UPSTREAM, started.elapsed(), &e);
+                warn!("Failed to publish to NATS: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::models::TeamState;
+
+    #[test]
+    fn test_update_message_carries_event_and_state() {
+        let state = |towers_killed| LiveMatchState {
+            match_id: 42,
+            radiant: TeamState {
+                towers_killed,
+                ..Default::default()
+            },
+            game_time: 600,
+            ..Default::default()
+        };
+        let update = MatchUpdate {
+            market_condition_id: "0xabc".to_string(),
+            state: Arc::new(state(1)),
+            market_team_a_is_radiant: true,
+            previous_state: Some(Arc::new(state(0))),
+            momentum: Default::default(),
+            teamfight: None,
+            deaths: Default::default(),
+            signal_type: SignalType::TowerKill,
+        };
+
+        let json = serde_json::to_value(UpdateMessage::from(&update)).unwrap();
+        assert_eq!(json["market_condition_id"], "0xabc");
+        assert_eq!(json["signal_type"], "TowerKill");
+        assert_eq!(json["state"]["match_id"], 42);
+        assert!(json.get("previous_state").is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use tokio::time;
+use tracing::{debug, error, info};
+
+use crate::config::SharedTuning;
+use crate::db::SignalStore;
+use crate::health::Health;
+use crate::matching::TeamResolver;
+use crate::models::ActiveMarkets;
+use crate::ratings::{EloRatings, LiveSeries, OutrightModel};
+
+use super::retune;
+
+/// Name reported to the health registry
+const WORKER: &str = "outright";
+
+/// Signals older than this don't count as a series in progress
+const LIVE_SIGNAL_MAX_AGE_SECS: i64 = 120;
+
+/// Worker that prices multi-outcome markets with the outright model
+///
+/// Outright prices only change on a market scan, so it runs on the scan
+/// interval.
+pub struct OutrightWorker {
+    active_markets: Arc<ActiveMarkets>,
+    signal_store: Arc<SignalStore>,
+    ratings: Arc<EloRatings>,
+    team_resolver: Arc<TeamResolver>,
+    tuning: SharedTuning,
+    health: Health,
+}
+
+impl OutrightWorker {
+    /// Create a new outright worker
+    pub fn new(
+        active_markets: Arc<ActiveMarkets>,
+        signal_store: Arc<SignalStore>,
+        ratings: Arc<EloRatings>,
+        team_resolver: Arc<TeamResolver>,
+        tuning: SharedTuning,
+    ) -> Self {
+        Self {
+            active_markets,
+            signal_store,
+            ratings,
+            team_resolver,
+            tuning,
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        let scan_interval = Duration::from_secs(self.tuning.load().polymarket_scan_interval);
+        info!("Outright pricer started (interval: {:?})", scan_interval);
+
+        let mut interval = time::interval(scan_interval);
+        self.health.register_worker(WORKER, Some(scan_interval));
+
+        loop {
+            interval.tick().await;
+            retune(
+                &mut interval,
+                self.tuning.load().polymarket_scan_interval,
+                "Outright pricing",
+            );
+            self.health.worker_tick(WORKER, Some(interval.period()));
+
+            if let Err(e) = self.price().await {
+                error!("Failed to price outright markets: {}", e);
+            }
+        }
+    }
+
+    /// Log model and market probability for every contender of every outright
+    async fn price(&self) -> Result<()> {
+        // Copied out, as pricing awaits the signal store
+        let markets = ActiveMarkets::clone(&self.active_markets);
+        if !markets.iter().any(|m| m.is_outright()) {
+            debug!("No outright markets, skipping");
+            return Ok(());
+        }
+
+        let live = self.live_series(&markets).await?;
+        let model = OutrightModel::new(&self.ratings);
+
+        for market in markets.iter().filter(|m| m.is_outright()) {
+            let contenders: Vec<String> = market
+                .outcomes
+                .iter()
+                .map(|o| self.team_resolver.normalize(&o.name))
+                .collect();
+            let probabilities = model.probabilities(&contenders, &live);
+
+            for (outcome, prob) in market.outcomes.iter().zip(probabilities) {
+                info!(
+                    "Outright | {} | {} | Market: {:.1}% | Model: {:.1}% | Edge: {:+.1}%{}",
+                    market.question,
+                    outcome.name,
+                    outcome.price * 100.0,
+                    prob * 100.0,
+                    (prob - outcome.price) * 100.0,
+                    if live.contains_key(&self.team_resolver.normalize(&outcome.name)) {
+                        " | live"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Series in progress, from the latest signal of each match market
+    async fn live_series(&self, markets: &ActiveMarkets) -> Result<HashMap<String, LiveSeries>> {
+        let since = Utc::now() - chrono::Duration::seconds(LIVE_SIGNAL_MAX_AGE_SECS);
+        let signals = self
+            .signal_store
+            .get_latest_signal_per_market(since)
+            .await?;
+
+        let mut live = HashMap::new();
+        for signal in signals {
+            let (Some(market), Some(prob)) = (
+                markets.get(&signal.market_condition_id),
+                signal.team_a_win_prob,
+            ) else {
+                continue;
+            };
+            // Game-level markets say nothing about the series
+            if market.is_outright() || market.game_number.is_some() {
+                continue;
+            }
+
+            let team_a = self.team_resolver.normalize(&market.team_a);
+            let team_b = self.team_resolver.normalize(&market.team_b);
+            live.insert(
+                team_a.clone(),
+                LiveSeries {
+                    opponent: team_b.clone(),
+                    win_prob: prob,
+                },
+            );
+            live.insert(
+                team_b,
+                LiveSeries {
+                    opponent: team_a,
+                    win_prob: 1.0 - prob,
+                },
+            );
+        }
+
+        Ok(live)
+    }
+}
@@ -0,0 +1,449 @@
+//! Finished games whose markets still trade short of settled
+//!
+//! The settlement watch follows the match update bus like the watchdog. A
+//! market whose game has been missing from the live feed for a while is
+//! looked up in OpenDota match details until the result is in; if the
+//! market is still active and prices the winner below
+//! `SETTLEMENT_LAG_MAX_PRICE`, a `SettlementLag` signal backing the winner
+//! is stored, alerted and handed to the sinks. Only markets that settle on
+//! that one game (its game number, or a BO1) count: a series isn't decided
+//! by a single game, and a market without a format can't be told apart.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use super::UpdateReceiver;
+use crate::api::LiveDataClient;
+use crate::config::SharedTuning;
+use crate::db::SignalStore;
+use crate::health::{Health, SignalStage};
+use crate::models::{
+    ActiveMarkets, LiveMatchState, Market, MatchUpdate, SeriesFormat, Signal, SignalStrength,
+    SignalType,
+};
+use crate::notifications::{Notification, Notifier};
+use crate::sinks::SignalSinks;
+use crate::trading::TradingCosts;
+
+/// Name reported to the health registry
+const WORKER: &str = "settlement_watch";
+
+/// Upstream API name reported to the health registry
+const UPSTREAM: &str = "opendota";
+
+/// How often silent games are looked up
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Time out of the live feed after which a game counts as over rather
+/// than between polls
+const SILENCE_SECS: i64 = 90;
+
+/// Time out of the live feed after which the result is no longer looked up
+const GIVE_UP_SECS: i64 = 3 * 60 * 60;
+
+/// Last update of one market's game
+#[derive(Debug, Clone)]
+struct Followed {
+    state: Arc<LiveMatchState>,
+    market_team_a_is_radiant: bool,
+    last_update: DateTime<Utc>,
+}
+
+/// Markets whose games were last seen live, by condition_id
+#[derive(Debug, Default)]
+pub struct SettlementWatch {
+    markets: HashMap<String, Followed>,
+}
+
+impl SettlementWatch {
+    /// Note a market's update
+    pub fn record(&mut self, update: &MatchUpdate, now: DateTime<Utc>) {
+        self.markets.insert(
+            update.market_condition_id.clone(),
+            Followed {
+                state: Arc::clone(&update.state),
+                market_team_a_is_radiant: update.market_team_a_is_radiant,
+                last_update: now,
+            },
+        );
+    }
+
+    /// Matches out of the live feed long enough to look up, forgetting
+    /// markets whose result never arrived
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<i64> {
+        self.markets
+            .retain(|_, f| (now - f.last_update).num_seconds() < GIVE_UP_SECS);
+
+        let mut due: Vec<i64> = self
+            .markets
+            .values()
+            .filter(|f| (now - f.last_update).num_seconds() >= SILENCE_SECS)
+            .map(|f| f.state.match_id)
+            .collect();
+        due.sort_unstable();
+        due.dedup();
+        due
+    }
+
+    /// Settlement lag signals for a finished match's markets, each with the
+    /// market it's about; the match's markets are no longer followed
+    pub fn settle(
+        &mut self,
+        match_id: i64,
+        radiant_win: bool,
+        active: &ActiveMarkets,
+        max_price: f64,
+        costs: &TradingCosts,
+        now: DateTime<Utc>,
+    ) -> Vec<(Signal, Market)> {
+        let finished: Vec<(String, Followed)> = self
+            .markets
+            .iter()
+            .filter(|(_, f)| f.state.match_id == match_id)
+            .map(|(id, f)| (id.clone(), f.clone()))
+            .collect();
+
+        let mut signals = Vec::new();
+        for (condition_id, followed) in finished {
+            self.markets.remove(&condition_id);
+
+            let Some(market) = active.get(&condition_id) else {
+                continue;
+            };
+            if !settles_on_game(&market, &followed.state) {
+                continue;
+            }
+
+            let team_a_won = followed.market_team_a_is_radiant == radiant_win;
+            let (winner, price) = if team_a_won {
+                (&market.team_a, market.team_a_buy_price())
+            } else {
+                (&market.team_b, market.team_b_buy_price())
+            };
+            if price >= max_price {
+                continue;
+            }
+
+            let p = if team_a_won { 1.0 } else { 0.0 };
+            let strength = SignalStrength::from_interval_with_confidence(
+                p,
+                p,
+                costs.cost_per_share(market.team_a_buy_price(), market.liquidity),
+                costs.cost_per_share(market.team_b_buy_price(), market.liquidity),
+                1.0,
+            );
+            let signal = Signal {
+                id: None,
+                market_condition_id: condition_id,
+                venue: market.venue,
+                match_id,
+                market_team_a_odds: market.team_a_odds,
+                team_a_prior_prob: None,
+                sharp_team_a_prob: None,
+                team_a_win_prob: Some(p),
+                team_a_prob_lower: Some(p),
+                team_a_prob_upper: Some(p),
+                confidence: Some(1.0),
+                edge: Some(p - market.team_a_odds),
+                net_edge: Some(costs.net_edge(
+                    p,
+                    market.team_a_buy_price(),
+                    market.team_b_buy_price(),
+                    market.liquidity,
+                )),
+                strength,
+                signal_type: SignalType::SettlementLag,
+                escalated: false,
+                fight_winner: None,
+                expected_remaining_secs: Some(0),
+                model_version: None,
+                features: None,
+                reason: Some(format!(
+                    "{} won match {}; market still prices them at {:.1}%",
+                    winner,
+                    match_id,
+                    price * 100.0
+                )),
+                team_a_is_radiant: Some(followed.market_team_a_is_radiant),
+                correct: None,
+                graded_at: None,
+                match_snapshot: serde_json::to_string(&*followed.state).unwrap_or_default(),
+                created_at: now,
+            };
+            signals.push((signal, market.clone()));
+        }
+        signals
+    }
+}
+
+/// Whether the market is decided by this game alone
+fn settles_on_game(market: &Market, state: &LiveMatchState) -> bool {
+    if market.is_outright() {
+        return false;
+    }
+    match market.game_number {
+        Some(game) => state.game_number == Some(game),
+        None => market.series_format == Some(SeriesFormat::Bo1),
+    }
+}
+
+/// Worker that signals finished games whose markets haven't settled
+pub struct SettlementWatchWorker {
+    watch: SettlementWatch,
+    client: LiveDataClient,
+    active_markets: Arc<ActiveMarkets>,
+    signal_store: Arc<SignalStore>,
+    tuning: SharedTuning,
+    /// Winner's price at or above which the market counts as settled
+    max_price: f64,
+    /// Outputs every stored signal is fanned out to
+    sinks: SignalSinks,
+    /// Signal alerts, routed by the notifier's rules
+    notifier: Option<Notifier>,
+    update_rx: UpdateReceiver,
+    health: Health,
+}
+
+impl SettlementWatchWorker {
+    /// Create a worker signaling markets that price a finished game's
+    /// winner below `max_price`
+    pub fn new(
+        client: LiveDataClient,
+        active_markets: Arc<ActiveMarkets>,
+        signal_store: Arc<SignalStore>,
+        tuning: SharedTuning,
+        max_price: f64,
+        update_rx: UpdateReceiver,
+    ) -> Self {
+        Self {
+            watch: SettlementWatch::default(),
+            client,
+            active_markets,
+            signal_store,
+            tuning,
+            max_price,
+            sinks: SignalSinks::default(),
+            notifier: None,
+            update_rx,
+            health: Health::default(),
+        }
+    }
+
+    /// Hand every stored signal, with its id, to these sinks
+    pub fn with_sinks(mut self, sinks: SignalSinks) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    /// Alert on stored signals through the notifier's routing rules
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Report ticks and upstream calls to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run until the update bus closes
+    pub async fn run(mut self) {
+        info!(
+            "Settlement watch started (winner priced below {:.0}%)",
+            self.max_price * 100.0
+        );
+
+        let mut interval = time::interval(CHECK_INTERVAL);
+        self.health.register_worker(WORKER, Some(CHECK_INTERVAL));
+
+        loop {
+            tokio::select! {
+                update = self.update_rx.recv() => match update {
+                    Some(update) => self.watch.record(&update, Utc::now()),
+                    None => break,
+                },
+                _ = interval.tick() => {
+                    self.health.worker_tick(WORKER, Some(CHECK_INTERVAL));
+                    self.check().await;
+                }
+            }
+        }
+
+        info!("Settlement watch stopped, update bus closed");
+    }
+
+    /// Look up the games that left the live feed and signal unsettled markets
+    async fn check(&mut self) {
+        for match_id in self.watch.due(Utc::now()) {
+            let started = std::time::Instant::now();
+            let radiant_win = match self.client.fetch_match_result(match_id).await {
+                Ok(Some(radiant_win)) => radiant_win,
+                Ok(None) => {
+                    self.health.upstream_ok(UPSTREAM, started.elapsed());
+                    debug!("No result yet for match {}", match_id);
+                    continue;
+                }
+                Err(e) => {
+                    self.health.upstream_error(UPSTREAM, started.elapsed(), &e);
+                    warn!("Failed to fetch result of match {}: {:#}", match_id, e);
+                    continue;
+                }
+            };
+            self.health.upstream_ok(UPSTREAM, started.elapsed());
+
+            let tuning = self.tuning.load_full();
+            let costs = TradingCosts::new(tuning.trading_fee_rate, tuning.reference_stake);
+            let signals = self.watch.settle(
+                match_id,
+                radiant_win,
+                &self.active_markets,
+                self.max_price,
+                &costs,
+                Utc::now(),
+            );
+
+            for (signal, market) in signals {
+                self.store(signal, market, &tuning.disabled_signal_types)
+                    .await;
+            }
+        }
+    }
+
+    /// Store a signal, then alert on it and hand it to the sinks
+    async fn store(&self, signal: Signal, market: Market, disabled: &[SignalType]) {
+        let (signal_type, strength) = (signal.signal_type, signal.strength);
+        self.health
+            .signal_counted(SignalStage::Generated, signal_type, strength);
+        if disabled.contains(&signal_type) {
+            self.health
+                .signal_counted(SignalStage::Suppressed, signal_type, strength);
+            return;
+        }
+
+        warn!(
+            "SETTLEMENT LAG | Match {} | Market {} | {}",
+            signal.match_id,
+            signal.market_condition_id,
+            signal.reason.as_deref().unwrap_or_default()
+        );
+
+        match self.signal_store.insert_signal(&signal).await {
+            Ok(id) => {
+                self.health
+                    .signal_counted(SignalStage::Stored, signal_type, strength);
+                let signal = Signal {
+                    id: Some(id),
+                    ..signal
+                };
+
+                if let Some(notifier) = &self.notifier {
+                    let notifier = notifier.clone();
+                    let notification = Notification::for_signal(signal.clone(), market);
+                    tokio::spawn(async move { notifier.notify(notification).await });
+                }
+                self.sinks.emit(&signal);
+            }
+            Err(e) => error!("Failed to store settlement lag signal: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Venue;
+
+    fn update(market: &str, match_id: i64, game_number: Option<u32>) -> MatchUpdate {
+        MatchUpdate {
+            market_condition_id: market.to_string(),
+            state: Arc::new(LiveMatchState {
+                match_id,
+                game_time: 2400,
+                game_number,
+                ..Default::default()
+            }),
+            market_team_a_is_radiant: false,
+            previous_state: None,
+            momentum: Default::default(),
+            teamfight: None,
+            deaths: Default::default(),
+            signal_type: SignalType::Snapshot,
+        }
+    }
+
+    fn market(condition_id: &str, team_a_odds: f64, game_number: Option<u32>) -> Market {
+        Market {
+            condition_id: condition_id.to_string(),
+            venue: Venue::Polymarket,
+            question: String::new(),
+            team_a: "Spirit".to_string(),
+            team_b: "Liquid".to_string(),
+            team_a_odds,
+            team_b_odds: 1.0 - team_a_odds,
+            opening_team_a_odds: 0.5,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 10_000.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: Some(SeriesFormat::Bo3),
+            game_number,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_settlement_lag_on_game_markets() {
+        let start = Utc::now();
+        let mut watch = SettlementWatch::default();
+        watch.record(&update("0xgame2", 7, Some(2)), start);
+        watch.record(&update("0xseries", 7, Some(2)), start);
+        watch.record(&update("0xsettled", 8, Some(1)), start);
+
+        // Still between polls
+        assert!(watch.due(start + chrono::Duration::seconds(30)).is_empty());
+        let later = start + chrono::Duration::seconds(120);
+        assert_eq!(watch.due(later), vec![7, 8]);
+
+        let active: ActiveMarkets = [
+            ("0xgame2".to_string(), market("0xgame2", 0.3, Some(2))),
+            ("0xseries".to_string(), market("0xseries", 0.3, None)),
+            ("0xsettled".to_string(), market("0xsettled", 0.02, Some(1))),
+        ]
+        .into_iter()
+        .collect();
+        let costs = TradingCosts::new(0.0, 0.0);
+
+        // Team A is Dire, so a Dire win is team A's, still priced at 30%
+        let signals = watch.settle(7, false, &active, 0.95, &costs, later);
+        assert_eq!(signals.len(), 1);
+        let (signal, _) = &signals[0];
+        assert_eq!(signal.market_condition_id, "0xgame2");
+        assert_eq!(signal.signal_type, SignalType::SettlementLag);
+        assert_eq!(signal.team_a_win_prob, Some(1.0));
+        assert_eq!(signal.strength, SignalStrength::VeryStrong);
+        assert_eq!(watch.due(later), vec![8]);
+
+        // Radiant (team B) won and is already priced at 98%
+        assert!(watch
+            .settle(8, true, &active, 0.95, &costs, later)
+            .is_empty());
+        assert!(watch.due(later).is_empty());
+
+        // Results that never arrive are given up on
+        watch.record(&update("0xgame2", 9, Some(3)), start);
+        assert!(watch
+            .due(start + chrono::Duration::seconds(GIVE_UP_SECS))
+            .is_empty());
+    }
+}
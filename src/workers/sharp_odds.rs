@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use tokio::sync::RwLock;
+use tokio::time;
+use tracing::{debug, error, info};
+
+use crate::api::pinnacle::MoneyLine;
+use crate::api::PinnacleClient;
+use crate::config::SharedTuning;
+use crate::health::Health;
+use crate::matching::TeamResolver;
+use crate::models::{ActiveMarkets, Market, SharpOddsCache, SharpQuote};
+
+use super::retune;
+
+/// Source tag recorded with Pinnacle quotes
+const SOURCE: &str = "pinnacle";
+
+/// Name reported to the health registry
+const WORKER: &str = "sharp_odds";
+
+/// Worker that polls a sharp sportsbook and maps its prices onto active markets
+pub struct SharpOddsWorker {
+    client: PinnacleClient,
+    active_markets: Arc<ActiveMarkets>,
+    team_resolver: Arc<TeamResolver>,
+    sharp_odds: Arc<RwLock<SharpOddsCache>>,
+    tuning: SharedTuning,
+    health: Health,
+}
+
+impl SharpOddsWorker {
+    /// Create a new sharp odds worker
+    pub fn new(
+        client: PinnacleClient,
+        active_markets: Arc<ActiveMarkets>,
+        team_resolver: Arc<TeamResolver>,
+        sharp_odds: Arc<RwLock<SharpOddsCache>>,
+        tuning: SharedTuning,
+    ) -> Self {
+        Self {
+            client,
+            active_markets,
+            team_resolver,
+            sharp_odds,
+            tuning,
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks and upstream calls to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop
+    pub async fn run(&self) {
+        let poll_interval = Duration::from_secs(self.tuning.load().sharp_odds_poll_interval);
+        info!("Sharp odds fetcher started (interval: {:?})", poll_interval);
+
+        let mut interval = time::interval(poll_interval);
+        self.health.register_worker(WORKER, Some(poll_interval));
+
+        loop {
+            interval.tick().await;
+            retune(
+                &mut interval,
+                self.tuning.load().sharp_odds_poll_interval,
+                "Sharp odds",
+            );
+            self.health.worker_tick(WORKER, Some(interval.period()));
+
+            if self.active_markets.is_empty() {
+                debug!("No active markets, skipping sharp odds fetch");
+                continue;
+            }
+
+            let started = Instant::now();
+            let lines = match self.client.fetch_dota2_moneylines().await {
+                Ok(lines) => {
+                    self.health.upstream_ok(SOURCE, started.elapsed());
+                    lines
+                }
+                Err(e) => {
+                    self.health.upstream_error(SOURCE, started.elapsed(), &e);
+                    error!("Failed to fetch sharp odds: {}", e);
+                    continue;
+                }
+            };
+
+            let fetched_at = Utc::now();
+            let (quotes, market_count): (SharpOddsCache, usize) = {
+                let markets = &self.active_markets;
+                let quotes = markets
+                    .iter()
+                    .filter(|market| !market.is_outright())
+                    .filter_map(|market| {
+                        let team_a_prob = self.team_a_prob(&market, &lines)?;
+                        Some((
+                            market.condition_id.clone(),
+                            SharpQuote {
+                                source: SOURCE.to_string(),
+                                team_a_prob,
+                                fetched_at,
+                            },
+                        ))
+                    })
+                    .collect();
+                (quotes, markets.len())
+            };
+
+            debug!(
+                "Matched {} of {} markets to sharp lines",
+                quotes.len(),
+                market_count
+            );
+
+            *self.sharp_odds.write().await = quotes;
+        }
+    }
+
+    /// Team A probability from the line on the same two teams, in either order
+    fn team_a_prob(&self, market: &Market, lines: &[MoneyLine]) -> Option<f64> {
+        let team_a = self.team_resolver.normalize(&market.team_a);
+        let team_b = self.team_resolver.normalize(&market.team_b);
+
+        lines.iter().find_map(|line| {
+            let home = self.team_resolver.normalize(&line.home);
+            let away = self.team_resolver.normalize(&line.away);
+
+            if home == team_a && away == team_b {
+                Some(line.home_prob)
+            } else if home == team_b && away == team_a {
+                Some(1.0 - line.home_prob)
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -1,49 +1,227 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use chrono::Utc;
-use tokio::sync::{mpsc, RwLock};
-use tracing::{error, info, warn};
+use chrono::{Duration, Utc};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error, info, warn};
 
+use crate::config::{SharedTuning, Tuning};
 use crate::db::SignalStore;
-use crate::models::{ActiveMarkets, MatchUpdate, Signal};
+use crate::health::{Health, SignalStage};
+use crate::matching::TeamResolver;
+use crate::model::{
+    generate_reason, prior, Contribution, DurationEstimate, DurationModel, MatchFeatures,
+    PreGamePrior, Prediction, SharedModel, MEGA_CREEPS_PREDICTION, TERMINAL_STATE_PREDICTION,
+};
+use crate::models::{
+    ActiveMarkets, ClosingLine, ExitSignal, LiveMatchState, Market, MatchUpdate, SharpOddsCache,
+    Signal, SignalStrength, SignalType,
+};
+use crate::notifications::{Notification, Notifier};
+use crate::ratings::{EloRatings, GlickoRatings, RecentForm};
+use crate::rules::{CustomSignal, ScriptOutcome, SignalScript};
+use crate::sinks::SignalSinks;
+use crate::trading::exit::ENTRY_MIN_STRENGTH;
+use crate::trading::{
+    freshness_confidence, sharp_confidence, volume_confidence, OpenEntry, TradingCosts,
+};
+
+use super::UpdateReceiver;
+
+/// Name reported to the health registry
+const WORKER: &str = "signal_processor";
+
+/// Sharp quotes older than this are ignored
+const SHARP_QUOTE_MAX_AGE_SECS: i64 = 120;
+
+/// Confidence kept per stand-in playing, since the model and prior were
+/// fitted on regular rosters
+const STAND_IN_CONFIDENCE: f64 = 0.9;
+
+/// Tuning parameters for signal generation
+#[derive(Debug, Clone, Copy)]
+pub struct SignalSettings {
+    /// Game minutes after which the pre-game prior carries half its weight
+    pub prior_half_life_minutes: f64,
+
+    /// Fees and slippage deducted from gross edge
+    pub costs: TradingCosts,
+
+    /// Net edge on the entry side at or below which an exit is signaled
+    pub exit_edge_threshold: f64,
+
+    /// Consecutive Strong+ signals on one side that escalate; 0 is off
+    pub escalation_streak: u32,
+
+    /// 24h volume in USD at which a market's quote is fully trusted
+    pub full_confidence_volume: f64,
+
+    /// Seconds the live data source lags the game
+    pub live_data_delay_secs: f64,
+
+    /// Winner's price below which a terminal state is signaled Very Strong
+    pub terminal_state_max_price: f64,
+}
+
+impl SignalSettings {
+    /// Build settings from the current tuning
+    pub fn from_tuning(tuning: &Tuning) -> Self {
+        Self {
+            prior_half_life_minutes: tuning.prior_half_life_minutes,
+            costs: TradingCosts::new(tuning.trading_fee_rate, tuning.reference_stake),
+            exit_edge_threshold: tuning.exit_edge_threshold,
+            escalation_streak: tuning.escalation_streak,
+            full_confidence_volume: tuning.full_confidence_volume,
+            live_data_delay_secs: tuning.live_data_delay_secs,
+            terminal_state_max_price: tuning.terminal_state_max_price,
+        }
+    }
+}
+
+/// Run of Strong+ signals on one side of a market
+#[derive(Debug, Clone, Copy)]
+struct Streak {
+    team_a: bool,
+    count: u32,
+}
 
 /// Worker that processes match updates and stores snapshots
 pub struct SignalProcessorWorker {
-    active_markets: Arc<RwLock<ActiveMarkets>>,
+    active_markets: Arc<ActiveMarkets>,
     signal_store: Arc<SignalStore>,
-    update_rx: mpsc::Receiver<MatchUpdate>,
+    model: SharedModel,
+    ratings: Arc<EloRatings>,
+    /// Teams' recent form; empty unless given
+    form: Arc<RecentForm>,
+    /// Glicko-2 ratings, for the prior's uncertainty
+    glicko: Option<Arc<GlickoRatings>>,
+    /// Historical game lengths; no duration estimates unless given
+    durations: Option<Arc<DurationModel>>,
+    /// Markets already warned about closing before their game ends
+    end_date_warned: Mutex<HashSet<String>>,
+    team_resolver: Arc<TeamResolver>,
+    tuning: SharedTuning,
+    /// Strong edges awaiting an exit, by market condition_id
+    open_entries: Mutex<HashMap<String, OpenEntry>>,
+    /// Current Strong+ runs, by market condition_id
+    streaks: Mutex<HashMap<String, Streak>>,
+    /// Sharp sportsbook quotes, when a feed is configured
+    sharp_odds: Option<Arc<RwLock<SharpOddsCache>>>,
+    /// Outputs every stored signal is fanned out to
+    sinks: SignalSinks,
+    /// Custom rules run on every update, when `SIGNAL_SCRIPT` is set
+    script: Option<Arc<SignalScript>>,
+    /// Signal alerts, routed by the notifier's rules
+    notifier: Option<Notifier>,
+    update_rx: UpdateReceiver,
+    health: Health,
 }
 
 impl SignalProcessorWorker {
     /// Create a new signal processor worker
     pub fn new(
-        active_markets: Arc<RwLock<ActiveMarkets>>,
+        active_markets: Arc<ActiveMarkets>,
         signal_store: Arc<SignalStore>,
-        update_rx: mpsc::Receiver<MatchUpdate>,
+        model: SharedModel,
+        ratings: Arc<EloRatings>,
+        team_resolver: Arc<TeamResolver>,
+        tuning: SharedTuning,
+        update_rx: UpdateReceiver,
     ) -> Self {
         Self {
             active_markets,
             signal_store,
+            model,
+            ratings,
+            form: Arc::default(),
+            glicko: None,
+            durations: None,
+            end_date_warned: Mutex::new(HashSet::new()),
+            team_resolver,
+            tuning,
+            open_entries: Mutex::new(HashMap::new()),
+            streaks: Mutex::new(HashMap::new()),
+            sharp_odds: None,
+            sinks: SignalSinks::default(),
+            script: None,
+            notifier: None,
             update_rx,
+            health: Health::default(),
         }
     }
 
-    /// Run the worker loop
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Blend teams' recent form into the pre-game prior
+    pub fn with_recent_form(mut self, form: Arc<RecentForm>) -> Self {
+        self.form = form;
+        self
+    }
+
+    /// Grade edges weaker when either team's rating is uncertain
+    pub fn with_glicko_ratings(mut self, glicko: Arc<GlickoRatings>) -> Self {
+        self.glicko = Some(glicko);
+        self
+    }
+
+    /// Estimate time left in each game, weighting lineup scaling by the
+    /// expected end and warning on markets that close first
+    pub fn with_duration_model(mut self, durations: Arc<DurationModel>) -> Self {
+        self.durations = Some(durations);
+        self
+    }
+
+    /// Record sharp sportsbook quotes alongside each signal and grade edges
+    /// they disagree with weaker
+    pub fn with_sharp_odds(mut self, sharp_odds: Arc<RwLock<SharpOddsCache>>) -> Self {
+        self.sharp_odds = Some(sharp_odds);
+        self
+    }
+
+    /// Hand every stored signal, with its id, to these sinks
+    pub fn with_sinks(mut self, sinks: SignalSinks) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    /// Let a script veto built-in signals and emit its own
+    pub fn with_script(mut self, script: Arc<SignalScript>) -> Self {
+        self.script = Some(script);
+        self
+    }
+
+    /// Alert on stored signals through the notifier's routing rules
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Run the worker loop until every sender is dropped and the channel is drained
     pub async fn run(mut self) {
         info!("Signal processor started");
+        self.health.register_worker(WORKER, None);
 
         while let Some(update) = self.update_rx.recv().await {
             self.process_update(update).await;
+            self.forget_closed_markets().await;
+            self.health.worker_tick(WORKER, None);
         }
 
-        warn!("Signal processor channel closed");
+        info!("Signal processor drained, channel closed");
     }
 
     /// Process a match update and store snapshot
     async fn process_update(&self, update: MatchUpdate) {
-        let markets = self.active_markets.read().await;
-
-        let market = match markets.get(&update.market_condition_id) {
+        // Cloned so the scanner can refresh markets while this update is stored
+        let market = self
+            .active_markets
+            .get(&update.market_condition_id)
+            .map(|market| market.clone());
+        let market = match &market {
             Some(m) => m,
             None => {
                 warn!(
@@ -54,36 +232,810 @@ impl SignalProcessorWorker {
             }
         };
 
+        // Snapshot the current model and tuning so a concurrent reload can't mix versions
+        let model = self.model.read().await.clone();
+        let tuning = self.tuning.load_full();
+        let settings = SignalSettings::from_tuning(&tuning);
+
+        let stand_ins = update.state.stand_ins();
+        let pre_game_prior = self
+            .pre_game_prior(market, &update.state)
+            .with_stand_ins(stand_ins);
+        let team_a_prior_prob = pre_game_prior.probability();
+        let sharp_team_a_prob = self.sharp_team_a_prob(&update.market_condition_id).await;
+
+        let duration = self
+            .durations
+            .as_ref()
+            .and_then(|d| d.estimate(update.state.game_time, update.state.gold_lead));
+        if let Some(duration) = duration {
+            self.check_end_date(market, duration).await;
+        }
+
+        // Model probability is Radiant-centric; flip it if team A is Dire
+        let features = MatchFeatures::from_update(&update);
+        let features = match duration {
+            Some(d) => features.with_expected_end(
+                &update.state,
+                (update.state.game_time + d.median_secs) as f64 / 60.0,
+            ),
+            None => features,
+        };
+        let live_team_a_prob = match model.predict(&features) {
+            Ok(radiant) if update.market_team_a_is_radiant => Some(radiant),
+            Ok(radiant) => Some(radiant.flip()),
+            Err(e) => {
+                warn!(
+                    "Model prediction failed for match {}: {}",
+                    update.state.match_id, e
+                );
+                None
+            }
+        };
+
+        // Update the pre-game prior with live evidence (bounds included)
+        let prediction = live_team_a_prob.map(|live| {
+            live.map(|p| {
+                prior::combine(
+                    team_a_prior_prob,
+                    p,
+                    features.game_time_min,
+                    settings.prior_half_life_minutes,
+                )
+            })
+        });
+
+        // Mega creeps are near-terminal; floor the side that has them, and
+        // the side a push from winning higher still
+        let terminal = update.state.terminal_state(update.deaths);
+        let floor = match terminal {
+            Some(radiant) => Some((radiant, TERMINAL_STATE_PREDICTION)),
+            None => update
+                .state
+                .mega_creeps()
+                .map(|radiant| (radiant, MEGA_CREEPS_PREDICTION)),
+        };
+        let prediction = match floor {
+            Some((radiant, floor)) => prediction.map(|p| {
+                if radiant == update.market_team_a_is_radiant {
+                    p.at_least(floor)
+                } else {
+                    p.flip().at_least(floor).flip()
+                }
+            }),
+            None => prediction,
+        };
+
+        let team_a_win_prob = prediction.map(|p| p.probability);
+        let edge = team_a_win_prob.map(|p| p - market.team_a_odds);
+
+        // Judge edge against the all-in price, not the midpoint
+        let costs = &settings.costs;
+        let net_edge = team_a_win_prob.map(|p| {
+            costs.net_edge(
+                p,
+                market.team_a_buy_price(),
+                market.team_b_buy_price(),
+                market.liquidity,
+            )
+        });
+        // Thin markets, stale data, stand-in lineups, uncertain ratings and
+        // a sharp book pricing the other way grade weaker
+        let data_age_secs = (Utc::now() - update.state.updated_at).num_milliseconds() as f64
+            / 1000.0
+            + settings.live_data_delay_secs;
+        let confidence = volume_confidence(market.volume_24h, settings.full_confidence_volume)
+            * freshness_confidence(data_age_secs)
+            * STAND_IN_CONFIDENCE.powi(stand_ins)
+            * pre_game_prior.confidence()
+            * team_a_win_prob
+                .map(|p| sharp_confidence(p, sharp_team_a_prob, market.team_a_odds))
+                .unwrap_or(1.0);
+        let strength = prediction
+            .map(|p| grade(p, market, costs, confidence))
+            .unwrap_or(SignalStrength::Neutral);
+
+        // A terminal state the market still prices short of won is as
+        // urgent as signals get, whatever the model's confidence
+        let signal_type = update.signal_type;
+        let terminal_lag = terminal
+            .filter(|_| signal_type == SignalType::TerminalState)
+            .and_then(|radiant| {
+                let (team, price) = if radiant == update.market_team_a_is_radiant {
+                    (&market.team_a, market.team_a_buy_price())
+                } else {
+                    (&market.team_b, market.team_b_buy_price())
+                };
+                (price < settings.terminal_state_max_price).then(|| {
+                    format!(
+                        "{} is a push from winning; market still prices them at {:.1}%",
+                        team,
+                        price * 100.0
+                    )
+                })
+            });
+        let strength = if terminal_lag.is_some() {
+            SignalStrength::VeryStrong
+        } else {
+            strength
+        };
+
+        // Attribution is Radiant-centric too
+        let contributions = model.contributions(&features);
+        let reason = if update.market_team_a_is_radiant {
+            generate_reason(&contributions)
+        } else {
+            let flipped: Vec<_> = contributions.into_iter().map(Contribution::flip).collect();
+            generate_reason(&flipped)
+        };
+        let reason = match (terminal_lag, reason) {
+            (Some(lag), Some(reason)) => Some(format!("{} | {}", lag, reason)),
+            (lag, reason) => lag.or(reason),
+        };
+
+        let fight_winner = update
+            .teamfight
+            .filter(|_| signal_type == SignalType::Teamfight)
+            .and_then(|fight| fight.radiant_won())
+            .map(|radiant| {
+                if radiant {
+                    update.state.radiant.name.clone()
+                } else {
+                    update.state.dire.name.clone()
+                }
+            });
+
+        // The market's event stands in when the league lookup found nothing
+        let mut snapshot = Arc::clone(&update.state);
+        if snapshot.league_name.is_none() {
+            if let Some(event) = market.event_name() {
+                Arc::make_mut(&mut snapshot).league_name = Some(event.to_string());
+            }
+        }
+
         // Create signal (match snapshot)
         let signal = Signal {
             id: None,
             market_condition_id: update.market_condition_id.clone(),
+            venue: market.venue,
             match_id: update.state.match_id,
             market_team_a_odds: market.team_a_odds,
-            match_snapshot: serde_json::to_string(&update.state).unwrap_or_default(),
+            team_a_prior_prob: Some(team_a_prior_prob),
+            sharp_team_a_prob,
+            team_a_win_prob,
+            team_a_prob_lower: prediction.map(|p| p.lower),
+            team_a_prob_upper: prediction.map(|p| p.upper),
+            confidence: Some(confidence),
+            edge,
+            net_edge,
+            strength,
+            signal_type,
+            escalated: false,
+            fight_winner,
+            expected_remaining_secs: duration.map(|d| d.median_secs),
+            model_version: Some(model.version().to_string()),
+            features: serde_json::to_string(&features).ok(),
+            reason,
+            team_a_is_radiant: Some(update.market_team_a_is_radiant),
+            correct: None,
+            graded_at: None,
+            match_snapshot: serde_json::to_string(&*snapshot).unwrap_or_default(),
             created_at: Utc::now(),
         };
 
+        self.health
+            .signal_counted(SignalStage::Generated, signal_type, strength);
+
         // Log
         info!(
-            "Snapshot | Match {} | {} vs {} | Score: {}-{} | Gold: {}k | Market: {:.1}%",
+            "Snapshot | Match {} | {} | {} vs {} | Score: {}-{} | Gold: {}k | Market: {:.1}%{}{} | Sharp: {} | Model: {} | {} {}{}{}",
             signal.match_id,
+            market.question,
             update.state.radiant.name,
             update.state.dire.name,
             update.state.radiant.kills,
             update.state.dire.kills,
             update.state.gold_lead / 1000,
             market.team_a_odds * 100.0,
+            market
+                .spread()
+                .map(|s| format!(" (spread {:.1})", s * 100.0))
+                .unwrap_or_default(),
+            if confidence < 1.0 {
+                format!(" (confidence {:.2})", confidence)
+            } else {
+                String::new()
+            },
+            sharp_team_a_prob
+                .map(|p| format!(
+                    "{:.1}% ({:+.1})",
+                    p * 100.0,
+                    (p - market.team_a_odds) * 100.0
+                ))
+                .unwrap_or_else(|| "n/a".to_string()),
+            prediction
+                .map(|p| format!(
+                    "{:.1}% [{:.1}-{:.1}]",
+                    p.probability * 100.0,
+                    p.lower * 100.0,
+                    p.upper * 100.0
+                ))
+                .unwrap_or_else(|| "n/a".to_string()),
+            strength,
+            signal.signal_type,
+            signal
+                .fight_winner
+                .as_ref()
+                .map(|team| format!(" (won by {})", team))
+                .unwrap_or_default(),
+            if features.performance_diff != 0.0 {
+                format!(
+                    " | Radiant farm vs usual: {:+.0}% relative to Dire",
+                    features.performance_diff * 100.0
+                )
+            } else {
+                String::new()
+            },
         );
 
+        // The last live price before the market closes is its closing line
+        let closing_line = ClosingLine {
+            market_condition_id: signal.market_condition_id.clone(),
+            team_a_odds: signal.market_team_a_odds,
+            recorded_at: signal.created_at,
+        };
+        if let Err(e) = self.signal_store.upsert_closing_line(&closing_line).await {
+            error!("Failed to record closing line: {}", e);
+        }
+
+        let outcome = self.run_script(&update, market, &signal).await;
+        let custom: Vec<_> = outcome
+            .signals
+            .iter()
+            .chain(&tuning.signal_rules.evaluate(&update, market, &signal))
+            .map(|custom| custom_signal(&signal, custom, market, costs))
+            .collect();
+
+        // Only the built-in signal counts toward escalation and entries, once
+        // per update
+        self.store_signal(signal, outcome.veto, true, &update, market, &tuning)
+            .await;
+        for signal in custom {
+            self.health
+                .signal_counted(SignalStage::Generated, signal.signal_type, signal.strength);
+            self.store_signal(signal, None, false, &update, market, &tuning)
+                .await;
+        }
+    }
+
+    /// Run the signal script, if any, on a blocking thread so a slow
+    /// script can't stall the runtime; a failing script keeps the built-in
+    /// signal as it is
+    async fn run_script(
+        &self,
+        update: &MatchUpdate,
+        market: &Market,
+        signal: &Signal,
+    ) -> ScriptOutcome {
+        let Some(script) = self.script.clone() else {
+            return ScriptOutcome::default();
+        };
+        let (update, market, signal) = (update.clone(), market.clone(), signal.clone());
+        let match_id = update.state.match_id;
+
+        match tokio::task::spawn_blocking(move || script.run(&update, &market, &signal)).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(e)) => {
+                warn!("Signal script failed for match {}: {:#}", match_id, e);
+                ScriptOutcome::default()
+            }
+            Err(e) => {
+                error!("Signal script task failed for match {}: {}", match_id, e);
+                ScriptOutcome::default()
+            }
+        }
+    }
+
+    /// Store a signal unless its type is disabled, then alert on it and
+    /// hand it to the sinks; muted markets and script vetoes store it as
+    /// Neutral without an alert. Only a `built_in` signal extends or ends the
+    /// market's Strong+ run and opens or exits its entry; script and rule
+    /// signals may point away from the model's edge
+    async fn store_signal(
+        &self,
+        signal: Signal,
+        veto: Option<String>,
+        built_in: bool,
+        update: &MatchUpdate,
+        market: &Market,
+        tuning: &Tuning,
+    ) {
+        let signal_type = signal.signal_type;
+        let strength = signal.strength;
+
+        if tuning.disabled_signal_types.contains(&signal_type) {
+            debug!("Dropped disabled {} signal", signal_type);
+            self.health
+                .signal_counted(SignalStage::Suppressed, signal_type, strength);
+            return;
+        }
+
+        // A muted market keeps its snapshots but generates no signals
+        let muted = match self
+            .signal_store
+            .is_market_muted(&signal.market_condition_id)
+            .await
+        {
+            Ok(muted) => muted,
+            Err(e) => {
+                error!("{:#}", e);
+                false
+            }
+        };
+        let signal = if muted {
+            debug!(
+                "Market {} is muted, storing {} as a Neutral snapshot",
+                signal.market_condition_id, signal.strength
+            );
+            self.health
+                .signal_counted(SignalStage::Suppressed, signal_type, strength);
+            Signal {
+                strength: SignalStrength::Neutral,
+                ..signal
+            }
+        } else if let Some(veto) = &veto {
+            debug!(
+                "Script vetoed {} signal for market {}: {}",
+                signal.strength, signal.market_condition_id, veto
+            );
+            self.health
+                .signal_counted(SignalStage::Suppressed, signal_type, strength);
+            let reason = match signal.reason {
+                Some(reason) => format!("{} | vetoed: {}", reason, veto),
+                None => format!("vetoed: {}", veto),
+            };
+            Signal {
+                strength: SignalStrength::Neutral,
+                reason: Some(reason),
+                ..signal
+            }
+        } else {
+            signal
+        };
+        let suppressed = muted || veto.is_some();
+
+        let escalated = built_in && self.extend_streak(&signal, tuning.escalation_streak).await;
+        if escalated {
+            warn!(
+                "ESCALATED | Match {} | Market {} | {} {} | Net edge: {}",
+                signal.match_id,
+                signal.market_condition_id,
+                signal.strength,
+                signal.signal_type,
+                signal
+                    .net_edge
+                    .map(|e| format!("{:+.1}%", e * 100.0))
+                    .unwrap_or_else(|| "n/a".to_string()),
+            );
+        }
+        let signal = Signal {
+            escalated,
+            ..signal
+        };
+
         // Store in database
         match self.signal_store.insert_signal(&signal).await {
             Ok(id) => {
                 info!("Stored snapshot id: {}", id);
+                self.health
+                    .signal_counted(SignalStage::Stored, signal_type, signal.strength);
+                if built_in && !suppressed {
+                    self.track_entry(id, &signal, tuning.exit_edge_threshold)
+                        .await;
+                }
+
+                let signal = Signal {
+                    id: Some(id),
+                    ..signal
+                };
+
+                // Deliver in the background so slow sinks don't hold up updates
+                if let Some(notifier) = self.notifier.as_ref().filter(|_| !suppressed) {
+                    let notifier = notifier.clone();
+                    let watched = tuning
+                        .watchlist
+                        .includes(&update.state, &self.team_resolver);
+                    let notification =
+                        Notification::for_signal(signal.clone(), market.clone()).watched(watched);
+                    tokio::spawn(async move { notifier.notify(notification).await });
+                }
+
+                self.sinks.emit(&signal);
             }
             Err(e) => {
                 error!("Failed to store snapshot: {}", e);
             }
         }
     }
+
+    /// Drop runs, open entries and end-date warnings of markets that have
+    /// left the active set (closed or resolved), so they don't pile up
+    async fn forget_closed_markets(&self) {
+        let active = |condition_id: &String| {
+            self.active_markets
+                .get(condition_id)
+                .is_some_and(|market| market.active)
+        };
+
+        self.streaks.lock().await.retain(|id, _| active(id));
+        self.open_entries.lock().await.retain(|id, _| active(id));
+        self.end_date_warned.lock().await.retain(|id| active(id));
+    }
+
+    /// Count the signal toward its market's Strong+ run and report whether
+    /// the run has reached `required`; weaker or flat signals end the run
+    async fn extend_streak(&self, signal: &Signal, required: u32) -> bool {
+        let mut streaks = self.streaks.lock().await;
+
+        let side = signal.net_edge.filter(|e| *e != 0.0).map(|e| e > 0.0);
+        match side {
+            Some(team_a) if signal.strength >= SignalStrength::Strong => {
+                let streak = streaks
+                    .entry(signal.market_condition_id.clone())
+                    .or_insert(Streak { team_a, count: 0 });
+                if streak.team_a != team_a {
+                    *streak = Streak { team_a, count: 0 };
+                }
+                streak.count += 1;
+
+                required > 0 && streak.count >= required
+            }
+            _ => {
+                streaks.remove(&signal.market_condition_id);
+                false
+            }
+        }
+    }
+
+    /// Emit an exit for the market's open entry if its edge is gone, and
+    /// record this signal as a new entry if it is strong enough
+    async fn track_entry(&self, signal_id: i64, signal: &Signal, exit_edge_threshold: f64) {
+        let Some(net_edge) = signal.net_edge else {
+            return;
+        };
+
+        let mut entries = self.open_entries.lock().await;
+
+        if let Some(entry) = entries.get(&signal.market_condition_id).copied() {
+            let Some(reason) = entry.check_exit(net_edge, exit_edge_threshold) else {
+                return;
+            };
+
+            entries.remove(&signal.market_condition_id);
+
+            let exit = ExitSignal {
+                id: None,
+                entry_signal_id: entry.signal_id,
+                trigger_signal_id: signal_id,
+                market_condition_id: signal.market_condition_id.clone(),
+                match_id: signal.match_id,
+                reason,
+                entry_net_edge: entry.net_edge,
+                current_net_edge: net_edge,
+                market_team_a_odds: signal.market_team_a_odds,
+                created_at: Utc::now(),
+            };
+
+            warn!(
+                "EXIT | Match {} | Market {} | Entry signal {} | {} | Net edge: {:+.1}% -> {:+.1}%",
+                exit.match_id,
+                exit.market_condition_id,
+                exit.entry_signal_id,
+                exit.reason,
+                exit.entry_net_edge * 100.0,
+                exit.current_net_edge * 100.0,
+            );
+
+            match self.signal_store.insert_exit_signal(&exit).await {
+                Ok(id) => info!("Stored exit signal id: {}", id),
+                Err(e) => error!("Failed to store exit signal: {}", e),
+            }
+        }
+
+        // A reversal strong enough on its own opens a new entry on the other side
+        if signal.strength >= ENTRY_MIN_STRENGTH && net_edge != 0.0 {
+            entries.insert(
+                signal.market_condition_id.clone(),
+                OpenEntry {
+                    signal_id,
+                    net_edge,
+                },
+            );
+        }
+    }
+
+    /// Fresh sharp-book probability for the market's team A, if quoted
+    async fn sharp_team_a_prob(&self, market_condition_id: &str) -> Option<f64> {
+        let sharp_odds = self.sharp_odds.as_ref()?.read().await;
+        let quote = sharp_odds.get(market_condition_id)?;
+
+        (Utc::now() - quote.fetched_at <= Duration::seconds(SHARP_QUOTE_MAX_AGE_SECS))
+            .then_some(quote.team_a_prob)
+    }
+
+    /// Warn once per market whose end date falls before the game has most
+    /// likely finished, since it may close or resolve before the result
+    async fn check_end_date(&self, market: &Market, duration: DurationEstimate) {
+        let Some(end_date) = market.end_date else {
+            return;
+        };
+        if Utc::now() + Duration::seconds(duration.late_secs as i64) <= end_date {
+            return;
+        }
+
+        if self
+            .end_date_warned
+            .lock()
+            .await
+            .insert(market.condition_id.clone())
+        {
+            warn!(
+                "Market {} ends at {} but the game likely runs {}-{} more min: {}",
+                market.condition_id,
+                end_date,
+                duration.median_secs / 60,
+                duration.late_secs / 60,
+                market.question
+            );
+        }
+    }
+
+    /// Pre-game prior for the market's team A from Elo, recent form and the
+    /// opening price, if it was seen before the game began
+    fn pre_game_prior(&self, market: &Market, state: &LiveMatchState) -> PreGamePrior {
+        let team_a = self.team_resolver.normalize(&market.team_a);
+        let team_b = self.team_resolver.normalize(&market.team_b);
+
+        PreGamePrior {
+            elo: self.ratings.win_probability(&team_a, &team_b),
+            market_open: pre_match_opening(market, state),
+            form: self.form.win_probability(&team_a, &team_b),
+            rating_deviation: self
+                .glicko
+                .as_ref()
+                .map(|glicko| glicko.pair_deviation(&team_a, &team_b, Utc::now().timestamp())),
+        }
+    }
+}
+
+/// Strength of a prediction against the market's all-in prices, with the
+/// edge scaled by `confidence`; Strong+ must also clear the ask on the side
+/// it would buy
+fn grade(p: Prediction, market: &Market, costs: &TradingCosts, confidence: f64) -> SignalStrength {
+    let quoted = SignalStrength::from_interval_with_confidence(
+        p.lower,
+        p.upper,
+        costs.cost_per_share(market.team_a_odds, market.liquidity),
+        costs.cost_per_share(market.team_b_odds, market.liquidity),
+        confidence,
+    );
+    let at_spread = SignalStrength::from_interval_with_confidence(
+        p.lower,
+        p.upper,
+        costs.cost_per_share(market.team_a_buy_price(), market.liquidity),
+        costs.cost_per_share(market.team_b_buy_price(), market.liquidity),
+        confidence,
+    );
+    quoted.capped_by_spread(at_spread)
+}
+
+/// The market's opening price, unless the scanner first saw it after the
+/// game began; that's an in-play price and says nothing about the prior
+fn pre_match_opening(market: &Market, state: &LiveMatchState) -> Option<f64> {
+    let started_at = state.updated_at - Duration::seconds(state.game_time.into());
+    match market.opened_at {
+        Some(opened_at) if opened_at > started_at => {
+            debug!(
+                "Market {} opened {}s into match {}, leaving its price out of the prior",
+                market.condition_id,
+                (opened_at - started_at).num_seconds(),
+                state.match_id
+            );
+            None
+        }
+        _ => Some(market.opening_team_a_odds),
+    }
+}
+
+/// A script's or rule's signal: the built-in one relabelled, graded on the
+/// custom probability when there is one and pointed at the side it backs
+fn custom_signal(
+    base: &Signal,
+    custom: &CustomSignal,
+    market: &Market,
+    costs: &TradingCosts,
+) -> Signal {
+    let mut signal = Signal {
+        strength: custom.strength,
+        signal_type: custom.signal_type,
+        fight_winner: None,
+        reason: Some(format!(
+            "{}: {}",
+            custom.signal_type.as_str().to_lowercase(),
+            custom.reason
+        )),
+        ..base.clone()
+    };
+    if let Some(p) = custom.team_a_prob {
+        signal.team_a_win_prob = Some(p);
+        signal.team_a_prob_lower = Some(p);
+        signal.team_a_prob_upper = Some(p);
+        signal.edge = Some(p - market.team_a_odds);
+        signal.net_edge = Some(costs.net_edge(
+            p,
+            market.team_a_buy_price(),
+            market.team_b_buy_price(),
+            market.liquidity,
+        ));
+    }
+    // A rule backing a team keeps the model's edge size, but its alerts and
+    // routing must follow the rule's side
+    if let Some(team_a) = custom.team_a {
+        let toward = |e: f64| if team_a { e.abs() } else { -e.abs() };
+        signal.edge = signal.edge.map(toward);
+        signal.net_edge = signal.net_edge.map(toward);
+    }
+    signal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::model::{LogisticModel, ProbabilityModel};
+    use crate::models::Venue;
+    use crate::workers::UpdateBus;
+
+    fn market() -> Market {
+        Market {
+            condition_id: "0xa".to_string(),
+            venue: Venue::Polymarket,
+            question: "Dota 2: Team Spirit vs OG".to_string(),
+            team_a: "Team Spirit".to_string(),
+            team_b: "OG".to_string(),
+            team_a_odds: 0.6,
+            team_b_odds: 0.4,
+            opening_team_a_odds: 0.6,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 5000.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_rule_signal_points_at_its_team() {
+        let market = market();
+        // The model leans team A
+        let base = Signal {
+            team_a_win_prob: Some(0.65),
+            edge: Some(0.05),
+            net_edge: Some(0.03),
+            ..Default::default()
+        };
+        let rule = |team_a| CustomSignal {
+            signal_type: SignalType::Rule,
+            strength: SignalStrength::Strong,
+            reason: "OG: if gold_lead > 15000 then signal Strong".to_string(),
+            team_a_prob: None,
+            team_a: Some(team_a),
+        };
+        let costs = TradingCosts::default();
+
+        let team_b = custom_signal(&base, &rule(false), &market, &costs);
+        assert_eq!(team_b.signal_type, SignalType::Rule);
+        assert_eq!(team_b.strength, SignalStrength::Strong);
+        assert_eq!(team_b.edge, Some(-0.05));
+        assert_eq!(team_b.net_edge, Some(-0.03));
+
+        let team_a = custom_signal(&base, &rule(true), &market, &costs);
+        assert_eq!(team_a.net_edge, Some(0.03));
+    }
+
+    #[test]
+    fn test_opening_price_only_counts_before_the_game() {
+        let state = LiveMatchState {
+            game_time: 600,
+            ..Default::default()
+        };
+        let opened = |mins_before_now: i64| Market {
+            opening_team_a_odds: 0.55,
+            opened_at: Some(state.updated_at - Duration::minutes(mins_before_now)),
+            ..market()
+        };
+
+        assert_eq!(pre_match_opening(&opened(60), &state), Some(0.55));
+        // First seen 5 minutes into the game
+        assert_eq!(pre_match_opening(&opened(5), &state), None);
+        let unknown = Market {
+            opened_at: None,
+            ..opened(5)
+        };
+        assert_eq!(pre_match_opening(&unknown, &state), Some(0.55));
+    }
+
+    #[test]
+    fn test_sharp_book_against_the_model_grades_weaker() {
+        let market = market();
+        let costs = TradingCosts::default();
+        let prediction = Prediction {
+            probability: 0.75,
+            lower: 0.73,
+            upper: 0.77,
+        };
+        let strength = |sharp| {
+            let confidence = sharp_confidence(prediction.probability, sharp, market.team_a_odds);
+            grade(prediction, &market, &costs, confidence)
+        };
+
+        assert_eq!(strength(None), SignalStrength::Strong);
+        // Pinnacle also has team A above the market
+        assert_eq!(strength(Some(0.66)), SignalStrength::Strong);
+        // Pinnacle has them below it: not worth an alert or an entry
+        assert_eq!(strength(Some(0.52)), SignalStrength::Moderate);
+    }
+
+    #[tokio::test]
+    async fn test_closed_markets_are_forgotten() {
+        let active_markets: Arc<ActiveMarkets> = Arc::default();
+        active_markets.insert("0xa".to_string(), market());
+        let model: Arc<dyn ProbabilityModel> = Arc::new(LogisticModel::default());
+        let worker = SignalProcessorWorker::new(
+            Arc::clone(&active_markets),
+            Arc::new(SignalStore::new("sqlite::memory:").await.unwrap()),
+            Arc::new(RwLock::new(model)),
+            Arc::default(),
+            Arc::new(TeamResolver::new()),
+            Tuning::shared(&Config::load(None).unwrap()),
+            UpdateBus::new().subscribe("signals", 1),
+        );
+
+        for id in ["0xa", "0xb"] {
+            let entry = OpenEntry {
+                signal_id: 1,
+                net_edge: 0.06,
+            };
+            let streak = Streak {
+                team_a: true,
+                count: 2,
+            };
+            worker
+                .open_entries
+                .lock()
+                .await
+                .insert(id.to_string(), entry);
+            worker.streaks.lock().await.insert(id.to_string(), streak);
+            worker.end_date_warned.lock().await.insert(id.to_string());
+        }
+
+        // 0xb resolved and left the active set
+        worker.forget_closed_markets().await;
+        let kept = |ids: Vec<&String>| ids == vec!["0xa"];
+        assert!(kept(worker.open_entries.lock().await.keys().collect()));
+        assert!(kept(worker.streaks.lock().await.keys().collect()));
+        assert!(kept(worker.end_date_warned.lock().await.iter().collect()));
+
+        // ... and so does 0xa once it's inactive
+        active_markets.get_mut("0xa").unwrap().active = false;
+        worker.forget_closed_markets().await;
+        assert!(worker.open_entries.lock().await.is_empty());
+    }
 }
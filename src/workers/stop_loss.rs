@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{watch, Mutex};
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use crate::config::SharedTuning;
+use crate::db::{PositionStore, SignalStore};
+use crate::health::Health;
+use crate::models::{ActiveMarkets, Position};
+use crate::notifications::{Notification, Notifier, Priority};
+use crate::trading::stop_loss::{self, StopReason};
+
+use super::retune;
+
+/// Name reported to the health registry
+const WORKER: &str = "stop_loss";
+
+/// Worker that watches open positions and alerts when they should be cut
+pub struct StopLossWorker {
+    position_store: Arc<PositionStore>,
+    signal_store: Arc<SignalStore>,
+    active_markets: Arc<ActiveMarkets>,
+    notifier: Notifier,
+    auto_exit: bool,
+    tuning: SharedTuning,
+    /// Positions already alerted, so each stop fires once
+    alerted: Mutex<HashSet<i64>>,
+    health: Health,
+}
+
+impl StopLossWorker {
+    /// Create a new stop-loss worker
+    pub fn new(
+        position_store: Arc<PositionStore>,
+        signal_store: Arc<SignalStore>,
+        active_markets: Arc<ActiveMarkets>,
+        notifier: Notifier,
+        auto_exit: bool,
+        tuning: SharedTuning,
+    ) -> Self {
+        Self {
+            position_store,
+            signal_store,
+            active_markets,
+            notifier,
+            auto_exit,
+            tuning,
+            alerted: Mutex::new(HashSet::new()),
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run the worker loop until shutdown is signaled
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
+        let tuning = self.tuning.load();
+        let check_interval = Duration::from_secs(tuning.stop_loss_check_interval);
+        info!(
+            "Stop-loss monitor started (max loss: {:.0}%, auto exit: {}, interval: {:?})",
+            tuning.stop_loss_threshold * 100.0,
+            self.auto_exit,
+            check_interval
+        );
+
+        let mut interval = time::interval(check_interval);
+        self.health.register_worker(WORKER, Some(check_interval));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.changed() => break,
+            }
+            retune(
+                &mut interval,
+                self.tuning.load().stop_loss_check_interval,
+                "Stop-loss",
+            );
+            self.health.worker_tick(WORKER, Some(interval.period()));
+
+            if let Err(e) = self.check_positions().await {
+                error!("Stop-loss check failed: {}", e);
+            }
+        }
+
+        info!("Stop-loss monitor stopped");
+    }
+
+    /// Check every open position against current odds and the latest model estimate
+    async fn check_positions(&self) -> anyhow::Result<()> {
+        let max_loss_fraction = self.tuning.load().stop_loss_threshold;
+        let positions = self.position_store.get_open_positions().await?;
+        debug!("Checking {} open positions", positions.len());
+
+        for position in positions {
+            let Some(id) = position.id else {
+                continue;
+            };
+
+            if self.alerted.lock().await.contains(&id) {
+                continue;
+            }
+
+            let team_a_price = match self.active_markets.get(&position.market_condition_id) {
+                Some(market) if !market.is_outright() => market.team_a_odds,
+                _ => continue,
+            };
+
+            let latest = self
+                .signal_store
+                .get_signals_for_market(&position.market_condition_id, 1)
+                .await?;
+            let team_a_prob = latest.first().and_then(|s| s.team_a_win_prob);
+
+            let current_price = stop_loss::side_price(position.side, team_a_price);
+            let model_prob = team_a_prob.map(|p| stop_loss::side_price(position.side, p));
+
+            if let Some(reason) =
+                stop_loss::check_stop(&position, current_price, model_prob, max_loss_fraction)
+            {
+                self.alerted.lock().await.insert(id);
+                self.trigger(&position, reason, current_price, model_prob)
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notify about a stopped position and optionally close it at the current price
+    async fn trigger(
+        &self,
+        position: &Position,
+        reason: StopReason,
+        current_price: f64,
+        model_prob: Option<f64>,
+    ) {
+        let id = position.id.unwrap_or_default();
+        let loss = stop_loss::unrealized_loss_fraction(position, current_price);
+
+        warn!(
+            "STOP LOSS | Position {} | Market {} | {} | Entry: {:.3} | Now: {:.3} ({:+.1}%) | Model: {}",
+            id,
+            position.market_condition_id,
+            reason,
+            position.entry_price,
+            current_price,
+            -loss * 100.0,
+            model_prob
+                .map(|p| format!("{:.1}%", p * 100.0))
+                .unwrap_or_else(|| "n/a".to_string()),
+        );
+
+        self.notifier
+            .notify(Notification::new(
+                Priority::Urgent,
+                format!("Stop-loss: position {} ({})", id, reason),
+                format!(
+                    "{} {} held {} @ {:.3}, now {:.3} ({:+.1}%){}",
+                    position.market_condition_id,
+                    position.side,
+                    position.remaining_shares,
+                    position.entry_price,
+                    current_price,
+                    -loss * 100.0,
+                    if self.auto_exit { ", closing" } else { "" }
+                ),
+            ))
+            .await;
+
+        if self.auto_exit {
+            match self
+                .position_store
+                .close_position(id, position.remaining_shares, current_price)
+                .await
+            {
+                Ok(closed) => info!(
+                    "Closed position {} at {:.3}, realized PnL: ${:+.2}",
+                    id, current_price, closed.realized_pnl
+                ),
+                Err(e) => error!("Failed to close position {}: {}", id, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Fan-out of live match updates
+//!
+//! The live fetcher publishes each `MatchUpdate` once; every consumer that
+//! subscribed at startup gets its own bounded queue, so a new consumer only
+//! needs a `subscribe` call in `main` and a slow one can't reorder another's
+//! updates.
+//!
+//! Queues hand out event updates (towers, barracks, teamfights) before
+//! periodic ones, so when a consumer falls behind during many simultaneous
+//! games the price-moving moments aren't stuck behind routine snapshots.
+//! A full queue drops its oldest periodic update to make room, since the
+//! next poll supersedes it; event updates are never dropped, and the
+//! publisher waits instead when a queue holds nothing else.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+use tracing::debug;
+
+use crate::health::Health;
+use crate::models::MatchUpdate;
+
+/// Queue size per subscriber when none is given
+pub const DEFAULT_UPDATE_CAPACITY: usize = 100;
+
+#[derive(Default)]
+struct QueueState {
+    events: VecDeque<MatchUpdate>,
+    periodic: VecDeque<MatchUpdate>,
+    /// The bus was dropped; nothing more will arrive
+    closed: bool,
+    /// The consumer stopped; nothing more is delivered
+    receiver_dropped: bool,
+}
+
+impl QueueState {
+    fn len(&self) -> usize {
+        self.events.len() + self.periodic.len()
+    }
+}
+
+/// One subscriber's bounded queue
+struct Queue {
+    name: String,
+    state: Mutex<QueueState>,
+    capacity: usize,
+    health: Health,
+    /// Wakes the receiver when an update arrives or the bus closes
+    available: Notify,
+    /// Wakes the publisher when an update is taken off a full queue
+    space: Notify,
+}
+
+impl Queue {
+    fn report_depth(&self, depth: usize) {
+        self.health.queue_depth(&self.name, depth, self.capacity);
+    }
+}
+
+struct Subscriber {
+    queue: Arc<Queue>,
+}
+
+/// Publishes match updates to every subscriber's queue
+///
+/// Subscribe before handing the bus to the publisher; a subscriber's queue
+/// closes once the bus is dropped and the queue is drained.
+#[derive(Default)]
+pub struct UpdateBus {
+    subscribers: Vec<Subscriber>,
+    health: Health,
+}
+
+/// Receiving end of a subscriber's queue
+pub struct UpdateReceiver {
+    queue: Arc<Queue>,
+}
+
+impl UpdateBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report queue depth, drops and send failures of subscribers added
+    /// afterwards to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Add a consumer with its own queue of `capacity` updates
+    pub fn subscribe(&mut self, name: &str, capacity: usize) -> UpdateReceiver {
+        let queue = Arc::new(Queue {
+            name: name.to_string(),
+            state: Mutex::new(QueueState::default()),
+            capacity: capacity.max(1),
+            health: self.health.clone(),
+            available: Notify::new(),
+            space: Notify::new(),
+        });
+        queue.report_depth(0);
+        self.subscribers.push(Subscriber {
+            queue: Arc::clone(&queue),
+        });
+        UpdateReceiver { queue }
+    }
+
+    /// Queue an update for every subscriber still listening, waiting only
+    /// while a queue is full of event updates
+    pub async fn publish(&self, update: MatchUpdate) {
+        let Some((last, rest)) = self.subscribers.split_last() else {
+            return;
+        };
+
+        let is_event = update.is_event();
+        for subscriber in rest {
+            subscriber.send(update.clone(), is_event).await;
+        }
+        last.send(update, is_event).await;
+    }
+}
+
+impl Drop for UpdateBus {
+    fn drop(&mut self) {
+        for subscriber in &self.subscribers {
+            subscriber.queue.state.lock().unwrap().closed = true;
+            subscriber.queue.available.notify_one();
+        }
+    }
+}
+
+impl Subscriber {
+    async fn send(&self, update: MatchUpdate, is_event: bool) {
+        let queue = &self.queue;
+        loop {
+            {
+                let mut state = queue.state.lock().unwrap();
+                if state.receiver_dropped {
+                    drop(state);
+                    debug!(
+                        "Match update for stopped consumer {} not delivered",
+                        queue.name
+                    );
+                    queue.health.queue_send_failed(&queue.name);
+                    return;
+                }
+
+                // Make room by dropping the stalest periodic update
+                let mut dropped = false;
+                if state.len() >= queue.capacity {
+                    dropped = state.periodic.pop_front().is_some();
+                }
+
+                if state.len() < queue.capacity {
+                    if is_event {
+                        state.events.push_back(update);
+                    } else {
+                        state.periodic.push_back(update);
+                    }
+                    let depth = state.len();
+                    drop(state);
+
+                    if dropped {
+                        debug!("{} queue full, dropped oldest periodic update", queue.name);
+                        queue.health.queue_dropped(&queue.name);
+                    }
+                    queue.report_depth(depth);
+                    queue.available.notify_one();
+                    return;
+                }
+
+                // Full of events: a periodic update can wait for the next poll
+                if !is_event {
+                    drop(state);
+                    debug!(
+                        "{} queue full of events, dropped periodic update",
+                        queue.name
+                    );
+                    queue.health.queue_dropped(&queue.name);
+                    return;
+                }
+            }
+            queue.space.notified().await;
+        }
+    }
+}
+
+impl UpdateReceiver {
+    /// Next update, events first; `None` once the bus is dropped and the
+    /// queue is empty
+    pub async fn recv(&mut self) -> Option<MatchUpdate> {
+        loop {
+            {
+                let mut state = self.queue.state.lock().unwrap();
+                let next = match state.events.pop_front() {
+                    Some(update) => Some(update),
+                    None => state.periodic.pop_front(),
+                };
+                if let Some(update) = next {
+                    let depth = state.len();
+                    drop(state);
+                    self.queue.report_depth(depth);
+                    self.queue.space.notify_one();
+                    return Some(update);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.queue.available.notified().await;
+        }
+    }
+}
+
+impl Drop for UpdateReceiver {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock().unwrap();
+        state.receiver_dropped = true;
+        state.events.clear();
+        state.periodic.clear();
+        drop(state);
+        self.queue.space.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LiveMatchState, SignalType, TeamState};
+
+    fn update(market: &str, towers_before: i32, towers_now: i32) -> MatchUpdate {
+        let state = |towers_killed| LiveMatchState {
+            match_id: 1,
+            radiant: TeamState {
+                towers_killed,
+                ..Default::default()
+            },
+            game_time: 600,
+            ..Default::default()
+        };
+        MatchUpdate {
+            market_condition_id: market.to_string(),
+            state: Arc::new(state(towers_now)),
+            market_team_a_is_radiant: true,
+            previous_state: Some(Arc::new(state(towers_before))),
+            momentum: Default::default(),
+            teamfight: None,
+            deaths: Default::default(),
+            signal_type: if towers_now > towers_before {
+                SignalType::TowerKill
+            } else {
+                SignalType::Snapshot
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_jump_queued_periodic_updates() {
+        let mut bus = UpdateBus::new();
+        let mut rx = bus.subscribe("test", 10);
+
+        bus.publish(update("periodic-1", 0, 0)).await;
+        bus.publish(update("periodic-2", 0, 0)).await;
+        bus.publish(update("tower", 0, 1)).await;
+        drop(bus);
+
+        let mut order = Vec::new();
+        while let Some(update) = rx.recv().await {
+            order.push(update.market_condition_id);
+        }
+        assert_eq!(order, ["tower", "periodic-1", "periodic-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_oldest_periodic_update() {
+        let health = Health::new();
+        let mut bus = UpdateBus::new().with_health(health.clone());
+        let mut rx = bus.subscribe("test", 2);
+
+        bus.publish(update("periodic-1", 0, 0)).await;
+        bus.publish(update("tower-1", 0, 1)).await;
+        bus.publish(update("tower-2", 0, 1)).await;
+        // Full of events: periodic updates are dropped, not queued
+        bus.publish(update("periodic-2", 0, 0)).await;
+        drop(bus);
+
+        let mut order = Vec::new();
+        while let Some(update) = rx.recv().await {
+            order.push(update.market_condition_id);
+        }
+        assert_eq!(order, ["tower-1", "tower-2"]);
+
+        let status = &health.report(true).queues["test"];
+        assert_eq!(status.dropped, 2);
+        assert_eq!(status.peak_depth, 2);
+        assert_eq!(status.depth, 0);
+    }
+}
@@ -0,0 +1,308 @@
+//! Alerts when live data stops arriving for games that should still be on
+//!
+//! The watchdog follows the match update bus like the signal processor. Each
+//! update starts a market's expected window, which runs until the game's
+//! median end from the duration model. A market silent for `NO_DATA_ALERT_MINUTES`
+//! within that window gets one alert per match until data resumes; if the
+//! last live poll returned no matches at all, the alert blames the feed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::time;
+use tracing::{info, warn};
+
+use super::UpdateReceiver;
+use crate::health::Health;
+use crate::model::DurationModel;
+use crate::models::{ActiveMarkets, MatchUpdate};
+use crate::notifications::{Notification, Notifier, Priority};
+
+/// Name reported to the health registry
+const WORKER: &str = "watchdog";
+
+/// How often markets are checked for silence
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Game length assumed when the duration model has no estimate
+const FALLBACK_GAME_SECS: i32 = 45 * 60;
+
+/// Last update of one market and when its game should end
+#[derive(Debug, Clone)]
+struct Tracked {
+    match_id: i64,
+    teams: String,
+    last_update: DateTime<Utc>,
+    expected_end: DateTime<Utc>,
+    alerted: bool,
+}
+
+/// Markets whose games are expected to be live, by condition_id
+#[derive(Debug, Default)]
+pub struct NoDataWatch {
+    silence: chrono::Duration,
+    markets: HashMap<String, Tracked>,
+}
+
+impl NoDataWatch {
+    pub fn new(silence: Duration) -> Self {
+        Self {
+            silence: chrono::Duration::from_std(silence).unwrap_or(chrono::Duration::MAX),
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Note a market's update, `remaining_secs` before its game should end
+    pub fn record(&mut self, update: &MatchUpdate, remaining_secs: i32, now: DateTime<Utc>) {
+        self.markets.insert(
+            update.market_condition_id.clone(),
+            Tracked {
+                match_id: update.state.match_id,
+                teams: format!(
+                    "{} vs {}",
+                    update.state.radiant.name, update.state.dire.name
+                ),
+                last_update: now,
+                expected_end: now + chrono::Duration::seconds(remaining_secs.max(0) as i64),
+                alerted: false,
+            },
+        );
+    }
+
+    /// Alerts for markets newly silent within their window; `live_matches`
+    /// is the size of the last live poll, if any succeeded
+    pub fn check(
+        &mut self,
+        now: DateTime<Utc>,
+        active: &ActiveMarkets,
+        live_matches: Option<usize>,
+    ) -> Vec<Notification> {
+        // Games past their window have most likely ended
+        self.markets
+            .retain(|id, tracked| active.contains_key(id) && now < tracked.expected_end);
+
+        // Newly silent markets, grouped by match
+        let mut silent: BTreeMap<i64, Vec<(&String, &Tracked)>> = BTreeMap::new();
+        for (id, tracked) in &mut self.markets {
+            if !tracked.alerted && now - tracked.last_update >= self.silence {
+                tracked.alerted = true;
+                silent
+                    .entry(tracked.match_id)
+                    .or_default()
+                    .push((id, tracked));
+            }
+        }
+        if silent.is_empty() {
+            return Vec::new();
+        }
+
+        let describe = |match_id: &i64, markets: &[(&String, &Tracked)]| {
+            let tracked = markets[0].1;
+            let mut ids: Vec<&str> = markets.iter().map(|(id, _)| id.as_str()).collect();
+            ids.sort_unstable();
+            format!(
+                "Match {} ({}): silent for {} min, expected until {} UTC; markets {}",
+                match_id,
+                tracked.teams,
+                (now - tracked.last_update).num_minutes(),
+                tracked.expected_end.format("%H:%M"),
+                ids.join(", ")
+            )
+        };
+
+        if live_matches == Some(0) {
+            let lines: Vec<String> = silent
+                .iter()
+                .map(|(match_id, markets)| describe(match_id, markets))
+                .collect();
+            return vec![Notification::new(
+                Priority::Urgent,
+                format!(
+                    "Live feed returns no matches while {} games should be live",
+                    silent.len()
+                ),
+                lines.join("\n"),
+            )];
+        }
+
+        silent
+            .iter()
+            .map(|(match_id, markets)| {
+                Notification::new(
+                    Priority::Normal,
+                    format!("No live data for match {}", match_id),
+                    describe(match_id, markets),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Worker that alerts when matched markets stop getting live updates
+pub struct NoDataWatchdogWorker {
+    watch: NoDataWatch,
+    active_markets: Arc<ActiveMarkets>,
+    durations: Arc<DurationModel>,
+    notifier: Notifier,
+    update_rx: UpdateReceiver,
+    health: Health,
+}
+
+impl NoDataWatchdogWorker {
+    /// Create a watchdog alerting after `silence` without updates
+    pub fn new(
+        silence: Duration,
+        active_markets: Arc<ActiveMarkets>,
+        durations: Arc<DurationModel>,
+        notifier: Notifier,
+        update_rx: UpdateReceiver,
+    ) -> Self {
+        Self {
+            watch: NoDataWatch::new(silence),
+            active_markets,
+            durations,
+            notifier,
+            update_rx,
+            health: Health::default(),
+        }
+    }
+
+    /// Report ticks to a shared health registry
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Run until the update bus closes
+    pub async fn run(mut self) {
+        info!(
+            "No-data watchdog started (alerts after {} min of silence)",
+            self.watch.silence.num_minutes()
+        );
+
+        let mut interval = time::interval(CHECK_INTERVAL);
+        self.health.register_worker(WORKER, Some(CHECK_INTERVAL));
+
+        loop {
+            tokio::select! {
+                update = self.update_rx.recv() => match update {
+                    Some(update) => {
+                        let remaining = self
+                            .durations
+                            .estimate(update.state.game_time, update.state.gold_lead)
+                            .map(|d| d.median_secs)
+                            .unwrap_or(FALLBACK_GAME_SECS - update.state.game_time);
+                        self.watch.record(&update, remaining, Utc::now());
+                    }
+                    None => break,
+                },
+                _ = interval.tick() => {
+                    self.health.worker_tick(WORKER, Some(CHECK_INTERVAL));
+                    let live_matches = self.health.report(true).live_matches;
+                    let alerts = self.watch.check(Utc::now(), &self.active_markets, live_matches);
+
+                    for alert in alerts {
+                        warn!("{}: {}", alert.title, alert.body);
+                        self.notifier.notify(alert).await;
+                    }
+                }
+            }
+        }
+
+        info!("No-data watchdog stopped, update bus closed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::{LiveMatchState, Market, SignalType, TeamState, Venue};
+
+    fn update(market: &str, match_id: i64) -> MatchUpdate {
+        MatchUpdate {
+            market_condition_id: market.to_string(),
+            state: Arc::new(LiveMatchState {
+                match_id,
+                radiant: TeamState {
+                    name: "Spirit".to_string(),
+                    ..Default::default()
+                },
+                dire: TeamState {
+                    name: "Liquid".to_string(),
+                    ..Default::default()
+                },
+                game_time: 900,
+                ..Default::default()
+            }),
+            market_team_a_is_radiant: true,
+            previous_state: None,
+            momentum: Default::default(),
+            teamfight: None,
+            deaths: Default::default(),
+            signal_type: SignalType::Snapshot,
+        }
+    }
+
+    fn market(id: &str) -> Market {
+        Market {
+            condition_id: id.to_string(),
+            venue: Venue::Polymarket,
+            question: String::new(),
+            team_a: "Spirit".to_string(),
+            team_b: "Liquid".to_string(),
+            team_a_odds: 0.5,
+            team_b_odds: 0.5,
+            opening_team_a_odds: 0.5,
+            opened_at: None,
+            team_a_bid: None,
+            team_a_ask: None,
+            liquidity: 0.0,
+            volume_24h: None,
+            end_date: None,
+            active: true,
+            url: None,
+            series_format: None,
+            game_number: None,
+            qualifiers: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_alerts_once_per_silent_match_in_window() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let minutes = |m: i64| start + chrono::Duration::minutes(m);
+        let active: ActiveMarkets = ["game", "series", "other"]
+            .into_iter()
+            .map(|id| (id.to_string(), market(id)))
+            .collect();
+
+        let mut watch = NoDataWatch::new(Duration::from_secs(5 * 60));
+        watch.record(&update("game", 1), 30 * 60, start);
+        watch.record(&update("series", 1), 30 * 60, start);
+        watch.record(&update("other", 2), 30 * 60, start);
+        watch.record(&update("other", 2), 30 * 60, minutes(4));
+
+        assert!(watch.check(minutes(4), &active, Some(3)).is_empty());
+
+        // Both markets of match 1 share one alert; match 2 is still updating
+        let alerts = watch.check(minutes(6), &active, Some(3));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].title, "No live data for match 1");
+        assert!(alerts[0].body.ends_with("markets game, series"));
+        assert!(watch.check(minutes(7), &active, Some(3)).is_empty());
+
+        // An empty feed is blamed instead
+        let alerts = watch.check(minutes(10), &active, Some(0));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].priority, Priority::Urgent);
+
+        // Past its window a silent game has most likely ended
+        watch.record(&update("game", 1), 10 * 60, minutes(10));
+        assert!(watch.check(minutes(21), &active, Some(3)).is_empty());
+    }
+}